@@ -7,10 +7,11 @@ fn main() {
     tauri_build::build()
 }
 
-/// Generate tray menu translations from frontend locale files.
+/// Generate tray menu and notification translations from frontend locale
+/// files.
 ///
 /// Source of truth: src/i18n/locales/*/translation.json
-/// The English "tray" section defines the struct fields.
+/// The English "tray" and "notifications" sections define the struct fields.
 fn generate_tray_translations() {
     use std::collections::BTreeMap;
     use std::fs;
@@ -22,7 +23,7 @@ fn generate_tray_translations() {
     println!("cargo:rerun-if-changed=../src/i18n/locales");
 
     // Collect all locale translations
-    let mut translations: BTreeMap<String, serde_json::Value> = BTreeMap::new();
+    let mut locales: BTreeMap<String, serde_json::Value> = BTreeMap::new();
 
     for entry in fs::read_dir(locales_dir).unwrap().flatten() {
         let path = entry.path();
@@ -37,41 +38,75 @@ fn generate_tray_translations() {
 
         let content = fs::read_to_string(&json_path).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
-
-        if let Some(tray) = parsed.get("tray").cloned() {
-            translations.insert(lang, tray);
-        }
+        locales.insert(lang, parsed);
     }
 
+    // Generate code
+    let mut out = String::from(
+        "// Auto-generated from src/i18n/locales/*/translation.json - do not edit\n\n",
+    );
+    let tray_fields = generate_section(&mut out, &locales, "tray", "TrayStrings", "TRANSLATIONS");
+    let notification_fields = generate_section(
+        &mut out,
+        &locales,
+        "notifications",
+        "NotificationStrings",
+        "NOTIFICATION_TRANSLATIONS",
+    );
+
+    fs::write(Path::new(&out_dir).join("tray_translations.rs"), out).unwrap();
+
+    println!(
+        "cargo:warning=Generated tray translations: {} languages, {} tray and {} notification fields",
+        locales.len(),
+        tray_fields,
+        notification_fields
+    );
+}
+
+/// Append a struct with one field per English key of `section`, and a static
+/// map from language to its strings. Returns the number of fields.
+fn generate_section(
+    out: &mut String,
+    locales: &std::collections::BTreeMap<String, serde_json::Value>,
+    section: &str,
+    struct_name: &str,
+    static_name: &str,
+) -> usize {
     // English defines the schema
-    let english = translations.get("en").unwrap().as_object().unwrap();
+    let english = locales["en"][section].as_object().unwrap();
     let fields: Vec<_> = english
         .keys()
         .map(|k| (camel_to_snake(k), k.clone()))
         .collect();
 
-    // Generate code
-    let mut out = String::from(
-        "// Auto-generated from src/i18n/locales/*/translation.json - do not edit\n\n",
-    );
-
     // Struct
-    out.push_str("#[derive(Debug, Clone)]\npub struct TrayStrings {\n");
+    out.push_str(&format!(
+        "#[derive(Debug, Clone)]\npub struct {struct_name} {{\n"
+    ));
     for (rust_field, _) in &fields {
         out.push_str(&format!("    pub {rust_field}: String,\n"));
     }
     out.push_str("}\n\n");
 
     // Static map
-    out.push_str(
-        "pub static TRANSLATIONS: Lazy<HashMap<&'static str, TrayStrings>> = Lazy::new(|| {\n",
-    );
+    out.push_str(&format!(
+        "pub static {static_name}: Lazy<HashMap<&'static str, {struct_name}>> = Lazy::new(|| {{\n"
+    ));
     out.push_str("    let mut m = HashMap::new();\n");
 
-    for (lang, tray) in &translations {
-        out.push_str(&format!("    m.insert(\"{lang}\", TrayStrings {{\n"));
+    for (lang, parsed) in locales {
+        let Some(strings) = parsed.get(section) else {
+            continue;
+        };
+        out.push_str(&format!("    m.insert(\"{lang}\", {struct_name} {{\n"));
         for (rust_field, json_key) in &fields {
-            let val = tray.get(json_key).and_then(|v| v.as_str()).unwrap_or("");
+            // Missing keys fall back to English
+            let val = strings
+                .get(json_key)
+                .or_else(|| english.get(json_key))
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
             out.push_str(&format!(
                 "        {rust_field}: \"{}\".to_string(),\n",
                 escape_string(val)
@@ -80,15 +115,8 @@ fn generate_tray_translations() {
         out.push_str("    });\n");
     }
 
-    out.push_str("    m\n});\n");
-
-    fs::write(Path::new(&out_dir).join("tray_translations.rs"), out).unwrap();
-
-    println!(
-        "cargo:warning=Generated tray translations: {} languages, {} fields",
-        translations.len(),
-        fields.len()
-    );
+    out.push_str("    m\n});\n\n");
+    fields.len()
 }
 
 fn camel_to_snake(s: &str) -> String {
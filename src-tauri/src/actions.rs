@@ -1,13 +1,21 @@
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::list_input_devices;
 use crate::cloud_stt::realtime::{RealtimeStreamingSession, SessionConfig};
 use crate::managers::audio::AudioRecordingManager;
-use crate::managers::history::HistoryManager;
+use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::managers::model::{ModelInfo, ModelManager};
 use crate::managers::transcription::TranscriptionManager;
-use crate::settings::{get_settings, AppSettings};
+use crate::meeting::MeetingRecorder;
+use crate::output_sinks::{self, TranscriptOutput};
+use crate::pipeline_error::{self, PipelineError};
+use crate::settings::{self, get_settings, AppSettings};
 use crate::shortcut;
-use crate::tray::{change_tray_icon, TrayIconState};
+use crate::tray::{self, change_tray_icon, TrayIconState};
+use crate::tray_i18n::{get_notification_translations, NotificationStrings};
 use crate::utils::{
-    self, show_processing_overlay, show_recording_overlay, show_transcribing_overlay,
+    self, show_processing_overlay, show_recording_overlay, show_transcribing_overlay, UndoError,
+    UndoRefusal,
 };
 use crate::TranscriptionCoordinator;
 use ferrous_opencc::{config::BuiltinConfig, OpenCC};
@@ -19,6 +27,7 @@ use std::time::Instant;
 use tauri::AppHandle;
 use tauri::Emitter;
 use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
 use tokio::sync::Mutex as TokioMutex;
 
 /// Managed state holding the active streaming session (if any).
@@ -27,6 +36,51 @@ pub type ActiveStreamingState = Arc<TokioMutex<Option<RealtimeStreamingSession>>
 /// Managed state tracking when the user pressed the record key.
 pub type RecordingStartTime = Arc<std::sync::Mutex<Option<Instant>>>;
 
+/// Resolves once the most recently stopped recording has been processed.
+/// Hands-free utterances are recorded back to back, and each waits for the
+/// one before it so they are pasted in the order they were spoken.
+static LAST_PROCESSED: Lazy<std::sync::Mutex<Option<tokio::sync::oneshot::Receiver<()>>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Shows the partial results of the streaming session being recorded.
+static STREAMING_PREVIEW: Lazy<std::sync::Mutex<Option<StreamingPreview>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// The task showing a streaming session's partial results in the overlay
+/// and, with the input method paste method, as preedit text. Dropping it
+/// stops the task and clears the preedit.
+struct StreamingPreview {
+    task: tauri::async_runtime::JoinHandle<()>,
+    preedit: bool,
+}
+
+impl StreamingPreview {
+    /// Stop showing partial results, so none arrives after the final text.
+    /// The preedit stays until the preview is dropped.
+    async fn stop(&mut self) {
+        self.task.abort();
+        let _ = (&mut self.task).await;
+    }
+}
+
+impl Drop for StreamingPreview {
+    fn drop(&mut self) {
+        self.task.abort();
+        if self.preedit {
+            crate::input_method::show_preedit("");
+        }
+    }
+}
+
+/// Stop showing the partial results of the recording in progress, when it
+/// is cancelled.
+pub fn stop_streaming_preview() {
+    STREAMING_PREVIEW
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+}
+
 /// Drop guard that notifies the [`TranscriptionCoordinator`] when the
 /// transcription pipeline finishes — whether it completes normally or panics.
 struct FinishGuard(AppHandle);
@@ -91,6 +145,255 @@ async fn maybe_convert_chinese_variant(
     }
 }
 
+/// Whether the post-processing provider is verified. If not, prompts are
+/// treated as absent so post-processing is skipped entirely.
+fn post_process_ready(settings: &AppSettings) -> bool {
+    let provider_id = &settings.post_process_provider_id;
+    provider_id == "apple_intelligence"
+        || settings
+            .post_process_verified_providers
+            .contains(provider_id)
+}
+
+/// The post-processing prompt of `binding_id`.
+fn post_process_prompt_for(settings: &AppSettings, binding_id: &str) -> Option<String> {
+    if !post_process_ready(settings) {
+        return None;
+    }
+    settings
+        .bindings
+        .get(binding_id)
+        .and_then(|b| b.post_process_prompt_id.clone())
+}
+
+/// The binding that recorded `entry`, if it post-processes its dictations.
+/// The prompt-cycling and re-run actions act on its prompt, or on the
+/// globally selected prompt when there is none.
+fn post_processing_binding_of<'a>(
+    settings: &AppSettings,
+    entry: Option<&'a HistoryEntry>,
+) -> Option<&'a str> {
+    entry
+        .and_then(|entry| entry.binding_id.as_deref())
+        .filter(|id| {
+            settings
+                .bindings
+                .get(*id)
+                .is_some_and(|b| b.post_process_prompt_id.is_some())
+        })
+}
+
+/// The latest history entry, logging failures.
+fn latest_entry(app: &AppHandle) -> Option<HistoryEntry> {
+    match app.state::<Arc<HistoryManager>>().get_latest_entry() {
+        Ok(entry) => entry,
+        Err(e) => {
+            error!("Failed to fetch last transcription entry: {}", e);
+            None
+        }
+    }
+}
+
+/// Count words for speaking stats: whitespace splitting (works for most
+/// languages) plus character count for CJK scripts where words are not
+/// whitespace-delimited.
+pub fn count_words(transcription: &str) -> i32 {
+    let ws_words = transcription.split_whitespace().count();
+    let cjk_chars = transcription
+        .chars()
+        .filter(|c| {
+            matches!(*c,
+                '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+                | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+                | '\u{3040}'..='\u{309F}' // Hiragana
+                | '\u{30A0}'..='\u{30FF}' // Katakana
+                | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+            )
+        })
+        .count();
+    let words = if cjk_chars > 0 {
+        // For CJK-heavy text, each character ≈ one word.
+        // Whitespace-split tokens that are purely CJK are
+        // already counted as one, so add the extra chars.
+        ws_words + cjk_chars.saturating_sub(ws_words.min(cjk_chars))
+    } else {
+        ws_words
+    };
+    words as i32
+}
+
+/// The text a transcription becomes after conversions and post-processing.
+struct ProcessedTranscription {
+    /// The text to paste or deliver
+    text: String,
+    /// `text`, if it differs from the raw transcription
+    post_processed_text: Option<String>,
+    /// The post-processing prompt that produced `text`
+    post_process_prompt: Option<String>,
+    /// Why post-processing failed; `text` is then left unprocessed
+    post_process_error: Option<String>,
+}
+
+/// Convert the Chinese variant and apply the binding's post-processing prompt.
+/// Dictations and transcriptions of stored recordings both go through here so
+/// they end up with the same text.
+async fn process_transcription(
+    app: &AppHandle,
+    settings: &AppSettings,
+    transcription: &str,
+    post_process_prompt_id: Option<&str>,
+) -> ProcessedTranscription {
+    let mut final_text = transcription.to_string();
+    let mut post_processed_text = None;
+    let mut post_process_prompt = None;
+    let mut post_process_error = None;
+
+    // First, check if Chinese variant conversion is needed
+    if let Some(converted_text) = maybe_convert_chinese_variant(settings, transcription).await {
+        final_text = converted_text;
+    }
+
+    // Then apply LLM post-processing if the binding has a prompt
+    let processed = match post_process_prompt_id {
+        Some(pid) => crate::post_process::post_process_transcription(settings, &final_text, pid)
+            .await
+            .unwrap_or_else(|e| {
+                post_process_error = Some(e);
+                None
+            }),
+        None => None,
+    };
+    if let Some(result) = processed {
+        let tps_display = result
+            .stats
+            .tokens_per_second
+            .map(|tps| format!("{:.1} tok/s", tps))
+            .unwrap_or_else(|| "N/A".to_string());
+        info!(
+            "Post-processing completed: model='{}', elapsed={}ms, tps={}, prompt_id='{}'",
+            result.stats.model,
+            result.stats.elapsed_ms,
+            tps_display,
+            post_process_prompt_id.unwrap_or("unknown"),
+        );
+
+        let _ = app.emit("post-process-stats", &result.stats);
+
+        post_processed_text = Some(result.text.clone());
+        final_text = result.text;
+
+        if let Some(pid) = post_process_prompt_id {
+            if let Some(prompt) = settings.post_process_prompts.iter().find(|p| p.id == pid) {
+                post_process_prompt = Some(prompt.prompt.clone());
+            }
+        }
+    } else if final_text != transcription {
+        post_processed_text = Some(final_text.clone());
+    }
+
+    ProcessedTranscription {
+        text: final_text,
+        post_processed_text,
+        post_process_prompt,
+        post_process_error,
+    }
+}
+
+/// Transcribe the audio of a stored history entry and mark the entry done,
+/// or failed if transcription errors. The text goes through the same
+/// post-processing as a dictation with the entry's binding, but nothing is
+/// pasted.
+pub async fn transcribe_history_entry(
+    app: &AppHandle,
+    entry: &HistoryEntry,
+) -> anyhow::Result<HistoryEntry> {
+    let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+
+    let samples = hm.load_audio(&entry.file_name)?;
+    let duration_ms = (samples.len() as i64 * 1000) / WHISPER_SAMPLE_RATE as i64;
+
+    tm.initiate_model_load();
+    match tm.transcribe(samples).await {
+        Ok(transcription) => {
+            let settings = get_settings(app);
+            let post_process_prompt_id = entry
+                .binding_id
+                .as_deref()
+                .and_then(|binding_id| post_process_prompt_for(&settings, binding_id));
+            let processed = process_transcription(
+                app,
+                &settings,
+                &transcription,
+                post_process_prompt_id.as_deref(),
+            )
+            .await;
+            if let Some(e) = processed.post_process_error {
+                warn!(
+                    "Post-processing history entry {} failed, keeping the raw text: {}",
+                    entry.id, e
+                );
+            }
+            let word_count = count_words(&transcription);
+            hm.complete_entry(
+                entry.id,
+                transcription,
+                processed.post_processed_text,
+                processed.post_process_prompt,
+                word_count,
+                duration_ms,
+            )
+            .await
+        }
+        Err(err) => {
+            hm.mark_failed(entry.id, &err.to_string()).await?;
+            Err(err)
+        }
+    }
+}
+
+/// Retry the history entries left `pending` by a crash or forced quit.
+pub fn recover_pending_transcriptions(app: &AppHandle) {
+    let hm = app.state::<Arc<HistoryManager>>();
+    let pending = match hm.get_pending_entries() {
+        Ok(pending) => pending,
+        Err(e) => {
+            error!("Failed to look up pending transcriptions: {}", e);
+            return;
+        }
+    };
+    if pending.is_empty() {
+        return;
+    }
+
+    info!("Recovering {} unfinished transcription(s)", pending.len());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+        for entry in pending {
+            // Mark the entry first: if transcribing it is what crashed the app,
+            // it stays failed for a manual retry instead of crashing every start.
+            if let Err(e) = hm
+                .mark_failed(entry.id, "Transcription was interrupted")
+                .await
+            {
+                error!(
+                    "Failed to mark history entry {} as attempted, not retrying it: {}",
+                    entry.id, e
+                );
+                continue;
+            }
+            match transcribe_history_entry(&app, &entry).await {
+                Ok(_) => info!("Recovered transcription for history entry {}", entry.id),
+                Err(e) => warn!(
+                    "Could not recover transcription for history entry {}: {}",
+                    entry.id, e
+                ),
+            }
+        }
+    });
+}
+
 impl ShortcutAction for TranscribeAction {
     fn start(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
         let start_time = Instant::now();
@@ -116,8 +419,11 @@ impl ShortcutAction for TranscribeAction {
         let is_always_on = settings.always_on_microphone;
         debug!("Microphone mode - always_on: {}", is_always_on);
 
-        // Check if cloud realtime streaming should be used
+        // Check if cloud realtime streaming should be used. Hands-free
+        // utterances overlap with the processing of the previous one, which
+        // a single streaming session can't, so they are transcribed in batch.
         let use_streaming = settings.stt_provider_id != "local"
+            && !rm.is_hands_free()
             && settings
                 .stt_realtime_enabled
                 .get(&settings.stt_provider_id)
@@ -131,11 +437,17 @@ impl ShortcutAction for TranscribeAction {
             // Create a channel for streaming transcription deltas → overlay
             let (delta_tx, mut delta_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
             let app_for_delta = app.clone();
-            tauri::async_runtime::spawn(async move {
+            let preedit = crate::input_method::is_selected(app);
+            let task = tauri::async_runtime::spawn(async move {
                 while let Some(text) = delta_rx.recv().await {
                     crate::overlay::emit_streaming_text(&app_for_delta, &text);
+                    if preedit {
+                        crate::input_method::show_preedit(&text);
+                    }
                 }
             });
+            *STREAMING_PREVIEW.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(StreamingPreview { task, preedit });
 
             // Spawn the WS connection async — frames buffer in the channel until ready
             let streaming_state = app.state::<ActiveStreamingState>();
@@ -184,8 +496,7 @@ impl ShortcutAction for TranscribeAction {
             None
         };
 
-        let mut recording_started = false;
-        if is_always_on {
+        let start_result = if is_always_on {
             // Always-on mode: Play audio feedback immediately, then apply mute after sound finishes
             debug!("Always-on mode: Playing audio feedback immediately");
             let rm_clone = Arc::clone(&rm);
@@ -195,15 +506,15 @@ impl ShortcutAction for TranscribeAction {
                 rm_clone.apply_mute();
             });
 
-            recording_started = rm.try_start_recording(&binding_id, stream_tap_tx);
-            debug!("Recording started: {}", recording_started);
+            let result = rm.try_start_recording(&binding_id, stream_tap_tx);
+            debug!("Recording started: {:?}", result);
+            result
         } else {
             // On-demand mode: Start recording first, then play audio feedback, then apply mute
             debug!("On-demand mode: Starting recording first, then audio feedback");
             let recording_start_time = Instant::now();
-            let started = rm.try_start_recording(&binding_id, stream_tap_tx);
-            if started {
-                recording_started = true;
+            let result = rm.try_start_recording(&binding_id, stream_tap_tx);
+            if let Ok(true) = result {
                 debug!("Recording started in {:?}", recording_start_time.elapsed());
                 let app_clone = app.clone();
                 let rm_clone = Arc::clone(&rm);
@@ -216,7 +527,16 @@ impl ShortcutAction for TranscribeAction {
             } else {
                 debug!("Failed to start recording");
             }
-        }
+            result
+        };
+
+        let recording_started = match start_result {
+            Ok(started) => started,
+            Err(e) => {
+                pipeline_error::report(app, &PipelineError::recording(e));
+                false
+            }
+        };
 
         if recording_started {
             shortcut::register_cancel_shortcut(app);
@@ -261,49 +581,86 @@ impl ShortcutAction for TranscribeAction {
 
         let binding_id = binding_id.to_string();
 
-        // Look up the post-processing prompt for this binding.
-        // If the provider is not verified, treat as no prompt so we skip post-processing entirely.
-        let settings_snapshot = get_settings(app);
-        let provider_id = &settings_snapshot.post_process_provider_id;
-        let provider_ready = provider_id == "apple_intelligence"
-            || settings_snapshot
-                .post_process_verified_providers
-                .contains(provider_id);
-        let post_process_prompt_id = if provider_ready {
-            settings_snapshot
-                .bindings
-                .get(&binding_id)
-                .and_then(|b| b.post_process_prompt_id.clone())
-        } else {
-            None
-        };
+        // Stop right away so hands-free mode can start the next recording
+        // while this one is processed.
+        // stop_recording drops the stream tap (via the recorder's Cmd::Stop),
+        // which signals end-of-audio to the streaming sender task.
+        let stop_recording_time = Instant::now();
+        let samples = rm.stop_recording(&binding_id);
+        debug!("Recording stopped in {:?}", stop_recording_time.elapsed());
+
+        let (processed_tx, processed_rx) = tokio::sync::oneshot::channel::<()>();
+        let previous = LAST_PROCESSED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(processed_rx);
+
+        let mut streaming_preview = STREAMING_PREVIEW
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
+        let post_process_prompt_id = post_process_prompt_for(&get_settings(app), &binding_id);
+
+        // Output sinks of this binding, and whether they replace pasting
+        let (sinks, skip_paste) = get_settings(app)
+            .bindings
+            .get(&binding_id)
+            .map(|b| {
+                (
+                    b.output_sinks.clone(),
+                    b.skip_paste && !b.output_sinks.is_empty(),
+                )
+            })
+            .unwrap_or_default();
 
         tauri::async_runtime::spawn(async move {
             let _guard = FinishGuard(ah.clone());
+            // Dropped when this task ends, letting the next recording go on
+            let _processed = processed_tx;
             let binding_id = binding_id.clone();
             debug!(
                 "Starting async transcription task for binding: {}",
                 binding_id
             );
 
-            let stop_recording_time = Instant::now();
-            // stop_recording drops the stream tap (via the recorder's Cmd::Stop),
-            // which signals end-of-audio to the streaming sender task.
-            if let Some(samples) = rm.stop_recording(&binding_id) {
-                debug!(
-                    "Recording stopped and samples retrieved in {:?}, sample count: {}",
-                    stop_recording_time.elapsed(),
-                    samples.len()
-                );
+            if let Some(samples) = samples {
+                debug!("Processing recording of {} samples", samples.len());
+
+                // Note the app in front before the user switches away
+                let frontmost_app = if sinks.is_empty() {
+                    None
+                } else {
+                    tauri::async_runtime::spawn_blocking(output_sinks::frontmost_app)
+                        .await
+                        .ok()
+                        .flatten()
+                };
+
+                // Persist the audio before transcribing so an error or crash
+                // during transcription never loses the dictation.
+                let pending_entry = if samples.is_empty() {
+                    None
+                } else {
+                    match hm.create_pending_entry(&samples, Some(&binding_id)).await {
+                        Ok(entry) => Some(entry),
+                        Err(e) => {
+                            error!("Failed to save recording to history: {}", e);
+                            None
+                        }
+                    }
+                };
+
+                if let Some(previous) = previous {
+                    let _ = previous.await;
+                }
 
                 // Check if we have an active streaming session
                 let session = streaming_state.lock().await.take();
 
                 let transcription_time = Instant::now();
 
-                // Returns (result, samples_for_history). In the streaming
-                // success path `samples` is not consumed so we avoid a clone.
-                let (transcription_result, samples_for_history) = if let Some(session) = session {
+                let transcription_result = if let Some(session) = session {
                     debug!("Finishing realtime streaming session...");
                     match session.finish().await {
                         Ok(transcript) => {
@@ -320,28 +677,32 @@ impl ShortcutAction for TranscribeAction {
                             };
                             let filtered =
                                 crate::audio_toolkit::filter_transcription_output(&corrected);
-                            (Ok(filtered), samples)
+                            Ok(filtered)
                         }
                         Err(e) => {
                             let err_msg = e.to_string();
                             if err_msg.contains("No audio received") {
                                 debug!("Streaming session returned no audio – treating as empty transcription.");
-                                (Ok(String::new()), samples)
+                                Ok(String::new())
                             } else {
                                 warn!(
                                     "Streaming session failed: {e}. Falling back to batch transcription."
                                 );
-                                let samples_for_history = samples.clone();
-                                (tm.transcribe(samples).await, samples_for_history)
+                                tm.transcribe(samples).await
                             }
                         }
                     }
                 } else {
                     // Batch path (no streaming session or streaming failed to start)
-                    let samples_for_history = samples.clone();
-                    (tm.transcribe(samples).await, samples_for_history)
+                    tm.transcribe(samples).await
                 };
 
+                // The preedit is cleared when the preview is dropped at the
+                // end of this task, whichever way it ends
+                if let Some(preview) = streaming_preview.as_mut() {
+                    preview.stop().await;
+                }
+
                 match transcription_result {
                     Ok(transcription) => {
                         debug!(
@@ -351,140 +712,126 @@ impl ShortcutAction for TranscribeAction {
                         );
                         if !transcription.is_empty() {
                             let settings = get_settings(&ah);
-                            let mut final_text = transcription.clone();
-                            let mut post_processed_text: Option<String> = None;
-                            let mut post_process_prompt: Option<String> = None;
-
-                            // First, check if Chinese variant conversion is needed
-                            if let Some(converted_text) =
-                                maybe_convert_chinese_variant(&settings, &transcription).await
-                            {
-                                final_text = converted_text;
-                            }
 
-                            // Then apply LLM post-processing if this binding has a prompt
-                            if post_process_prompt_id.is_some() {
+                            // Show that the text is being rewritten before the LLM call
+                            if post_process_prompt_id.is_some() && !rm.is_recording() {
                                 show_processing_overlay(&ah);
                             }
-                            let processed = if let Some(ref pid) = post_process_prompt_id {
-                                crate::post_process::post_process_transcription(
-                                    &settings,
-                                    &final_text,
-                                    pid,
-                                )
-                                .await
-                            } else {
-                                None
-                            };
-                            if let Some(result) = processed {
-                                let tps_display = result
-                                    .stats
-                                    .tokens_per_second
-                                    .map(|tps| format!("{:.1} tok/s", tps))
-                                    .unwrap_or_else(|| "N/A".to_string());
-                                info!(
-                                    "Post-processing completed: model='{}', elapsed={}ms, tps={}, prompt_id='{}'",
-                                    result.stats.model,
-                                    result.stats.elapsed_ms,
-                                    tps_display,
-                                    post_process_prompt_id.as_deref().unwrap_or("unknown"),
-                                );
-
-                                let _ = ah.emit("post-process-stats", &result.stats);
+                            let ProcessedTranscription {
+                                text: final_text,
+                                post_processed_text,
+                                post_process_prompt,
+                                post_process_error,
+                            } = process_transcription(
+                                &ah,
+                                &settings,
+                                &transcription,
+                                post_process_prompt_id.as_deref(),
+                            )
+                            .await;
+                            // Paste the unprocessed text but let the user know
+                            let post_process_failed = post_process_error.is_some();
+                            if let Some(e) = post_process_error {
+                                pipeline_error::report(&ah, &PipelineError::post_processing(e));
+                            }
 
-                                post_processed_text = Some(result.text.clone());
-                                final_text = result.text;
+                            if !sinks.is_empty() {
+                                let output = TranscriptOutput {
+                                    text: final_text.clone(),
+                                    raw_text: transcription.clone(),
+                                    prompt: post_process_prompt.clone(),
+                                    duration_ms: speaking_duration_ms,
+                                    app: frontmost_app,
+                                    binding_id: binding_id.clone(),
+                                    timestamp: chrono::Local::now(),
+                                };
+                                let ah_clone = ah.clone();
+                                tauri::async_runtime::spawn(async move {
+                                    if let Err(e) = output_sinks::deliver(&sinks, &output).await {
+                                        pipeline_error::report(
+                                            &ah_clone,
+                                            &PipelineError::output(e),
+                                        );
+                                    }
+                                });
+                            }
 
-                                if let Some(ref pid) = post_process_prompt_id {
-                                    if let Some(prompt) =
-                                        settings.post_process_prompts.iter().find(|p| p.id == *pid)
+                            // Store the transcription on the pending history entry
+                            let history_id = pending_entry.as_ref().map(|entry| entry.id);
+                            if let Some(entry) = pending_entry {
+                                let word_count = count_words(&transcription);
+                                let hm_clone = Arc::clone(&hm);
+                                let transcription_for_history = transcription.clone();
+                                tauri::async_runtime::spawn(async move {
+                                    if let Err(e) = hm_clone
+                                        .complete_entry(
+                                            entry.id,
+                                            transcription_for_history,
+                                            post_processed_text,
+                                            post_process_prompt,
+                                            word_count,
+                                            speaking_duration_ms,
+                                        )
+                                        .await
                                     {
-                                        post_process_prompt = Some(prompt.prompt.clone());
+                                        error!("Failed to save transcription to history: {}", e);
                                     }
-                                }
-                            } else if final_text != transcription {
-                                post_processed_text = Some(final_text.clone());
+                                });
                             }
 
-                            // Save to history
-                            // Count words: use whitespace splitting (works for most
-                            // languages) plus character count for CJK scripts where
-                            // words are not whitespace-delimited.
-                            let word_count = {
-                                let ws_words = transcription.split_whitespace().count();
-                                let cjk_chars = transcription
-                                    .chars()
-                                    .filter(|c| {
-                                        matches!(*c,
-                                            '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
-                                            | '\u{3400}'..='\u{4DBF}' // CJK Extension A
-                                            | '\u{3040}'..='\u{309F}' // Hiragana
-                                            | '\u{30A0}'..='\u{30FF}' // Katakana
-                                            | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
-                                        )
-                                    })
-                                    .count();
-                                if cjk_chars > 0 {
-                                    // For CJK-heavy text, each character ≈ one word.
-                                    // Whitespace-split tokens that are purely CJK are
-                                    // already counted as one, so add the extra chars.
-                                    ws_words + cjk_chars.saturating_sub(ws_words.min(cjk_chars))
-                                } else {
-                                    ws_words
-                                }
-                            } as i32;
-                            let hm_clone = Arc::clone(&hm);
-                            let transcription_for_history = transcription.clone();
-                            tauri::async_runtime::spawn(async move {
-                                if let Err(e) = hm_clone
-                                    .save_transcription(
-                                        samples_for_history,
-                                        transcription_for_history,
-                                        post_processed_text,
-                                        post_process_prompt,
-                                        word_count,
-                                        speaking_duration_ms,
-                                    )
-                                    .await
-                                {
-                                    error!("Failed to save transcription to history: {}", e);
-                                }
-                            });
+                            if skip_paste {
+                                // The sinks received the text; there's nothing to paste
+                                show_idle(&ah, !post_process_failed);
+                                return;
+                            }
 
                             // Paste the final text
                             let ah_clone = ah.clone();
                             let paste_time = Instant::now();
                             ah.run_on_main_thread(move || {
-                                match utils::paste(final_text, ah_clone.clone()) {
-                                    Ok(()) => debug!(
-                                        "Text pasted successfully in {:?}",
-                                        paste_time.elapsed()
-                                    ),
-                                    Err(e) => error!("Failed to paste transcription: {}", e),
+                                match utils::paste(final_text, ah_clone.clone(), history_id) {
+                                    Ok(()) => {
+                                        debug!(
+                                            "Text pasted successfully in {:?}",
+                                            paste_time.elapsed()
+                                        );
+                                        // Leave a post-processing error on screen until it hides itself
+                                        show_idle(&ah_clone, !post_process_failed);
+                                    }
+                                    Err(e) => {
+                                        pipeline_error::report(&ah_clone, &PipelineError::paste(e));
+                                        show_idle(&ah_clone, false);
+                                    }
                                 }
-                                utils::hide_recording_overlay(&ah_clone);
-                                change_tray_icon(&ah_clone, TrayIconState::Idle);
                             })
                             .unwrap_or_else(|e| {
                                 error!("Failed to run paste on main thread: {:?}", e);
-                                utils::hide_recording_overlay(&ah);
-                                change_tray_icon(&ah, TrayIconState::Idle);
+                                show_idle(&ah, true);
                             });
                         } else {
-                            utils::hide_recording_overlay(&ah);
-                            change_tray_icon(&ah, TrayIconState::Idle);
+                            // Nothing was said; don't keep an empty history entry
+                            if let Some(entry) = pending_entry {
+                                if let Err(e) = hm.discard_pending_entry(entry.id).await {
+                                    error!("Failed to remove empty history entry: {}", e);
+                                }
+                            }
+                            show_idle(&ah, true);
                         }
                     }
                     Err(err) => {
-                        debug!("Global Shortcut Transcription error: {}", err);
-                        utils::hide_recording_overlay(&ah);
-                        change_tray_icon(&ah, TrayIconState::Idle);
+                        pipeline_error::report(&ah, &PipelineError::transcription(&err));
+                        // The audio stays in history so the dictation can be retried
+                        if let Some(entry) = pending_entry {
+                            if let Err(e) = hm.mark_failed(entry.id, &err.to_string()).await {
+                                error!("Failed to mark history entry as failed: {}", e);
+                            }
+                        }
+                        show_idle(&ah, false);
                     }
                 }
             } else {
                 debug!("No samples retrieved from recording stop");
-                utils::hide_recording_overlay(&ah);
-                change_tray_icon(&ah, TrayIconState::Idle);
+                show_idle(&ah, true);
             }
         });
 
@@ -508,6 +855,423 @@ impl ShortcutAction for CancelAction {
     }
 }
 
+// Toggle Hands-Free Action
+struct ToggleHandsFreeAction;
+
+impl ShortcutAction for ToggleHandsFreeAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        if let Some(coordinator) = app.try_state::<TranscriptionCoordinator>() {
+            coordinator.toggle_hands_free();
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Hands-free mode is toggled on press only
+    }
+}
+
+// Meeting Action
+struct MeetingAction;
+
+impl ShortcutAction for MeetingAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let Some(meeting) = app.try_state::<MeetingRecorder>() else {
+            return;
+        };
+        if let Err(e) = meeting.toggle() {
+            pipeline_error::report(app, &PipelineError::recording(e.to_string()));
+        }
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Meetings run until the shortcut is pressed again
+    }
+}
+
+// Re-paste Last Action
+struct RepasteLastAction;
+
+impl ShortcutAction for RepasteLastAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let entry = match app.state::<Arc<HistoryManager>>().get_latest_entry() {
+            Ok(Some(entry)) => entry,
+            Ok(None) => {
+                debug!("No transcription to paste again");
+                return;
+            }
+            Err(e) => {
+                error!("Failed to fetch last transcription entry: {}", e);
+                return;
+            }
+        };
+        paste_on_main_thread(
+            app,
+            tray::last_transcript_text(&entry).to_string(),
+            Some(entry.id),
+        );
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Pasting happens on press only
+    }
+}
+
+// Copy Last Action
+struct CopyLastAction;
+
+impl ShortcutAction for CopyLastAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        tray::copy_last_transcript(app);
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Copying happens on press only
+    }
+}
+
+// Undo Last Insertion Action
+struct UndoInsertionAction;
+
+impl ShortcutAction for UndoInsertionAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let ah = app.clone();
+        app.run_on_main_thread(move || match utils::undo_last_insertion(&ah) {
+            Ok(Some(id)) => {
+                let hm = Arc::clone(&ah.state::<Arc<HistoryManager>>());
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = hm.set_retracted(id).await {
+                        error!("Failed to mark history entry {} as retracted: {}", id, e);
+                    }
+                });
+            }
+            Ok(None) => {}
+            // Usually nothing to undo, so no error sound or overlay
+            Err(e) => {
+                warn!("Failed to undo last insertion: {}", e);
+                let strings = notification_strings(&ah);
+                let body = match e {
+                    UndoError::Refused(UndoRefusal::NothingToUndo) => strings.undo_nothing_to_undo,
+                    UndoError::Refused(UndoRefusal::Submitted) => strings.undo_submitted,
+                    UndoError::Refused(UndoRefusal::TooOld) => strings.undo_too_old,
+                    UndoError::Refused(UndoRefusal::WindowChanged) => strings.undo_window_changed,
+                    UndoError::Refused(UndoRefusal::NotSupported) => strings.undo_not_supported,
+                    UndoError::Failed(e) => e,
+                };
+                notify(&ah, &strings.nothing_undone, &body);
+            }
+        })
+        .unwrap_or_else(|e| error!("Failed to run undo on main thread: {:?}", e));
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Undoing happens on press only
+    }
+}
+
+// Cycle Prompt Action
+struct CyclePromptAction;
+
+impl ShortcutAction for CyclePromptAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let entry = latest_entry(app);
+        let mut settings = get_settings(app);
+        let ids: Vec<String> = settings
+            .post_process_prompts
+            .iter()
+            .map(|p| p.id.clone())
+            .collect();
+        let binding_id = post_processing_binding_of(&settings, entry.as_ref()).map(String::from);
+        let current = match &binding_id {
+            Some(id) => settings.bindings[id].post_process_prompt_id.clone(),
+            None => settings.post_process_selected_prompt_id.clone(),
+        };
+        let Some(next) = next_in_cycle(&ids, current.as_deref()).cloned() else {
+            debug!("No post-processing prompts to cycle through");
+            return;
+        };
+        if let Some(id) = &binding_id {
+            if let Some(binding) = settings.bindings.get_mut(id) {
+                binding.post_process_prompt_id = Some(next.clone());
+            }
+        }
+        // The post-processing shortcut and the selected prompt go together
+        let global = match binding_id.as_deref() {
+            Some(id) => id == POST_PROCESS_BINDING,
+            None => true,
+        };
+        if global {
+            settings.post_process_selected_prompt_id = Some(next.clone());
+        }
+        let name = settings
+            .post_process_prompts
+            .iter()
+            .find(|p| p.id == next)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| next.clone());
+        let bindings = settings.bindings.clone();
+        settings::write_settings(app, settings);
+
+        if binding_id.is_some() {
+            emit_setting_changed(app, "bindings", &bindings);
+        }
+        if global {
+            emit_setting_changed(app, "post_process_selected_prompt_id", &next);
+        }
+        notify_switched(app, |s| &s.prompt_switched, &name);
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Prompts are cycled on press only
+    }
+}
+
+// Cycle Microphone Action
+struct CycleMicrophoneAction;
+
+impl ShortcutAction for CycleMicrophoneAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let current = crate::commands::audio::get_effective_microphone_name(app.clone())
+            .unwrap_or_else(|_| "Default".to_string());
+        let microphones: Vec<String> = std::iter::once("Default".to_string())
+            .chain(
+                list_input_devices()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|d| d.name)
+                    .filter(|name| name != "Default" && name != "default"),
+            )
+            .collect();
+        let Some(next) = next_in_cycle(&microphones, Some(current.as_str())).cloned() else {
+            return;
+        };
+
+        // The chosen microphone goes to the top of the priority list
+        let mut settings = get_settings(app);
+        settings.microphone_priority.retain(|name| *name != next);
+        settings.microphone_priority.insert(0, next.clone());
+        let priority = settings.microphone_priority.clone();
+        settings::write_settings(app, settings);
+
+        // A recording in progress keeps its microphone
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        if let Err(e) = rm.restart_idle_stream() {
+            error!("Failed to switch microphone: {}", e);
+        }
+
+        emit_setting_changed(app, "microphone_priority", &priority);
+        notify_switched(app, |s| &s.microphone_switched, &next);
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Microphones are cycled on press only
+    }
+}
+
+// Switch Model Action
+struct SwitchModelAction;
+
+impl ShortcutAction for SwitchModelAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let mut models: Vec<ModelInfo> = app
+            .state::<Arc<ModelManager>>()
+            .get_available_models()
+            .into_iter()
+            .filter(|m| m.is_downloaded)
+            .collect();
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+        let ids: Vec<String> = models.iter().map(|m| m.id.clone()).collect();
+
+        let mut settings = get_settings(app);
+        let current = settings.selected_model.as_str();
+        let Some(next) = next_in_cycle(&ids, Some(current)).cloned() else {
+            debug!("No downloaded models to switch between");
+            return;
+        };
+        if next == settings.selected_model {
+            return;
+        }
+        settings.selected_model = next.clone();
+        settings::write_settings(app, settings);
+
+        let name = models
+            .iter()
+            .find(|m| m.id == next)
+            .map(|m| m.name.clone())
+            .unwrap_or_else(|| next.clone());
+        notify_switched(app, |s| &s.model_switched, &name);
+
+        // Loading can take seconds; don't hold up the shortcut handler
+        let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+        std::thread::spawn(move || {
+            if let Err(e) = tm.load_model(&next) {
+                error!("Failed to load model '{}': {}", next, e);
+            }
+        });
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Models are switched on press only
+    }
+}
+
+// Toggle Always-On Microphone Action
+struct ToggleAlwaysOnAction;
+
+impl ShortcutAction for ToggleAlwaysOnAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let always_on = !get_settings(app).always_on_microphone;
+        if let Err(e) = crate::commands::audio::update_microphone_mode(app.clone(), always_on) {
+            error!("{}", e);
+            return;
+        }
+
+        let _ = app.emit(
+            "settings-changed",
+            serde_json::json!({
+                "setting": "always_on_microphone",
+                "value": always_on
+            }),
+        );
+        let strings = notification_strings(app);
+        let state = if always_on { &strings.on } else { &strings.off };
+        notify_switched(app, |s| &s.always_on_microphone, state);
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // The microphone mode is toggled on press only
+    }
+}
+
+// Re-run Post-Processing Action
+struct RerunPostProcessAction;
+
+impl ShortcutAction for RerunPostProcessAction {
+    fn start(&self, app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        let settings = get_settings(app);
+        let Some(entry) = latest_entry(app) else {
+            debug!("No transcription to post-process again");
+            return;
+        };
+        let prompt_id = match post_processing_binding_of(&settings, Some(&entry)) {
+            Some(binding_id) => post_process_prompt_for(&settings, binding_id),
+            None => post_process_ready(&settings)
+                .then(|| settings.post_process_selected_prompt_id.clone())
+                .flatten(),
+        };
+        let Some(prompt_id) = prompt_id else {
+            debug!("Post-processing is not set up; nothing to re-run");
+            return;
+        };
+        let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+
+        let ah = app.clone();
+        show_processing_overlay(app);
+        tauri::async_runtime::spawn(async move {
+            let result = crate::post_process::post_process_transcription(
+                &settings,
+                &entry.transcription_text,
+                &prompt_id,
+            )
+            .await;
+            let processed = match result {
+                Ok(Some(processed)) => processed,
+                Ok(None) => {
+                    utils::hide_recording_overlay(&ah);
+                    return;
+                }
+                Err(e) => {
+                    pipeline_error::report(&ah, &PipelineError::post_processing(e));
+                    return;
+                }
+            };
+
+            let prompt = settings
+                .post_process_prompts
+                .iter()
+                .find(|p| p.id == prompt_id)
+                .map(|p| p.prompt.as_str());
+            if let Err(e) = hm
+                .set_post_processed(entry.id, &processed.text, prompt)
+                .await
+            {
+                error!("Failed to update history entry {}: {}", entry.id, e);
+            }
+            paste_on_main_thread(&ah, processed.text, Some(entry.id));
+        });
+    }
+
+    fn stop(&self, _app: &AppHandle, _binding_id: &str, _shortcut_str: &str) {
+        // Post-processing is re-run on press only
+    }
+}
+
+/// Binding whose prompt is kept in step with the globally selected prompt.
+const POST_PROCESS_BINDING: &str = "transcribe_with_post_process";
+
+/// The item after `current`, wrapping around; the first item when `current`
+/// is not in the list.
+fn next_in_cycle<'a>(items: &'a [String], current: Option<&str>) -> Option<&'a String> {
+    let next = current
+        .and_then(|current| items.iter().position(|item| item == current))
+        .map_or(0, |idx| (idx + 1) % items.len());
+    items.get(next)
+}
+
+/// Return the overlay and tray to idle after processing, unless the next
+/// hands-free recording has started in the meantime.
+fn show_idle(app: &AppHandle, hide_overlay: bool) {
+    if app.state::<Arc<AudioRecordingManager>>().is_recording() {
+        return;
+    }
+    if hide_overlay {
+        utils::hide_recording_overlay(app);
+    }
+    change_tray_icon(app, TrayIconState::Idle);
+}
+
+fn paste_on_main_thread(app: &AppHandle, text: String, history_id: Option<i64>) {
+    let ah = app.clone();
+    app.run_on_main_thread(move || match utils::paste(text, ah.clone(), history_id) {
+        Ok(()) => utils::hide_recording_overlay(&ah),
+        Err(e) => pipeline_error::report(&ah, &PipelineError::paste(e)),
+    })
+    .unwrap_or_else(|e| error!("Failed to run paste on main thread: {:?}", e));
+}
+
+fn emit_setting_changed(app: &AppHandle, setting: &str, value: &impl serde::Serialize) {
+    let _ = app.emit(
+        "settings-changed",
+        serde_json::json!({
+            "setting": setting,
+            "value": value
+        }),
+    );
+}
+
+/// Notification texts in the app language.
+fn notification_strings(app: &AppHandle) -> NotificationStrings {
+    get_notification_translations(&get_settings(app).app_language)
+}
+
+/// Confirm a change made from a shortcut, since no window may be visible.
+/// `title` picks the localized name of what changed.
+fn notify_switched(
+    app: &AppHandle,
+    title: impl FnOnce(&NotificationStrings) -> &String,
+    value: &str,
+) {
+    let strings = notification_strings(app);
+    let title = title(&strings);
+    info!("{} switched to '{}'", title, value);
+    notify(app, title, value);
+}
+
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        warn!("Failed to show notification: {}", e);
+    }
+}
+
 // Test Action
 struct TestAction;
 
@@ -539,9 +1303,65 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "toggle_hands_free".to_string(),
+        Arc::new(ToggleHandsFreeAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "meeting".to_string(),
+        Arc::new(MeetingAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "repaste_last".to_string(),
+        Arc::new(RepasteLastAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "copy_last_transcript".to_string(),
+        Arc::new(CopyLastAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "undo_last_insertion".to_string(),
+        Arc::new(UndoInsertionAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "cycle_prompt".to_string(),
+        Arc::new(CyclePromptAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "cycle_microphone".to_string(),
+        Arc::new(CycleMicrophoneAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "switch_model".to_string(),
+        Arc::new(SwitchModelAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "toggle_always_on".to_string(),
+        Arc::new(ToggleAlwaysOnAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "rerun_post_process".to_string(),
+        Arc::new(RerunPostProcessAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
     );
     map
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_to_the_next_item_and_wraps() {
+        let items: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(next_in_cycle(&items, Some("a")).unwrap(), "b");
+        assert_eq!(next_in_cycle(&items, Some("c")).unwrap(), "a");
+        assert_eq!(next_in_cycle(&items, Some("gone")).unwrap(), "a");
+        assert_eq!(next_in_cycle(&items, None).unwrap(), "a");
+        assert!(next_in_cycle(&[], Some("a")).is_none());
+    }
+}
@@ -12,24 +12,41 @@ use tauri::{AppHandle, Manager};
 pub enum SoundType {
     Start,
     Stop,
+    /// Played when a dictation fails somewhere in the pipeline.
+    Error,
 }
 
+/// Shared by the built-in themes; only the custom theme overrides it.
+const ERROR_SOUND_PATH: &str = "resources/error.wav";
+
 fn resolve_sound_path(
     app: &AppHandle,
     settings: &AppSettings,
     sound_type: SoundType,
 ) -> Option<PathBuf> {
+    let is_error = matches!(sound_type, SoundType::Error);
     let sound_file = get_sound_path(settings, sound_type);
     let base_dir = get_sound_base_dir(settings);
-    app.path().resolve(&sound_file, base_dir).ok()
+    let path = app.path().resolve(&sound_file, base_dir).ok()?;
+
+    // A custom theme without its own error sound uses the built-in one
+    if is_error && !path.exists() {
+        return app
+            .path()
+            .resolve(ERROR_SOUND_PATH, tauri::path::BaseDirectory::Resource)
+            .ok();
+    }
+    Some(path)
 }
 
 fn get_sound_path(settings: &AppSettings, sound_type: SoundType) -> String {
     match (settings.sound_theme, sound_type) {
         (SoundTheme::Custom, SoundType::Start) => "custom_start.wav".to_string(),
         (SoundTheme::Custom, SoundType::Stop) => "custom_stop.wav".to_string(),
+        (SoundTheme::Custom, SoundType::Error) => "custom_error.wav".to_string(),
         (_, SoundType::Start) => settings.sound_theme.to_start_path(),
         (_, SoundType::Stop) => settings.sound_theme.to_stop_path(),
+        (_, SoundType::Error) => ERROR_SOUND_PATH.to_string(),
     }
 }
 
@@ -83,11 +100,42 @@ fn play_sound_at_path(app: &AppHandle, path: &Path) -> Result<(), Box<dyn std::e
     play_audio_file(path, selected_device, volume)
 }
 
+/// Play mono samples through the selected output device, blocking until
+/// playback ends. Used to let users hear a microphone test capture.
+pub fn play_samples(
+    app: &AppHandle,
+    samples: Vec<f32>,
+    sample_rate: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let settings = settings::get_settings(app);
+    let stream_handle = open_output_stream(settings.selected_output_device)?;
+    let sink = rodio::Sink::connect_new(stream_handle.mixer());
+    sink.append(rodio::buffer::SamplesBuffer::new(1, sample_rate, samples));
+    sink.sleep_until_end();
+    Ok(())
+}
+
 fn play_audio_file(
     path: &std::path::Path,
     selected_device: Option<String>,
     volume: f32,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let stream_handle = open_output_stream(selected_device)?;
+    let mixer = stream_handle.mixer();
+
+    let file = File::open(path)?;
+    let buf_reader = BufReader::new(file);
+
+    let sink = rodio::play(mixer, buf_reader)?;
+    sink.set_volume(volume);
+    sink.sleep_until_end();
+
+    Ok(())
+}
+
+fn open_output_stream(
+    selected_device: Option<String>,
+) -> Result<rodio::OutputStream, Box<dyn std::error::Error>> {
     let stream_builder = if let Some(device_name) = selected_device {
         if device_name == "Default" {
             debug!("Using default device");
@@ -117,15 +165,5 @@ fn play_audio_file(
         OutputStreamBuilder::from_default_device()?
     };
 
-    let stream_handle = stream_builder.open_stream()?;
-    let mixer = stream_handle.mixer();
-
-    let file = File::open(path)?;
-    let buf_reader = BufReader::new(file);
-
-    let sink = rodio::play(mixer, buf_reader)?;
-    sink.set_volume(volume);
-    sink.sleep_until_end();
-
-    Ok(())
+    Ok(stream_builder.open_stream()?)
 }
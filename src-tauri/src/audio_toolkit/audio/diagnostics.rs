@@ -0,0 +1,107 @@
+/// Lowest level reported, so silence does not turn into `-inf`.
+const FLOOR_DBFS: f32 = -100.0;
+
+/// Samples at or above this magnitude count as clipped. Slightly below full
+/// scale because resampling smooths the flat tops of clipped input.
+const CLIP_LEVEL: f32 = 0.99;
+
+/// Share of frames taken as the noise floor (quietest) and as the speech
+/// level (loudest) when estimating the signal-to-noise ratio.
+const NOISE_PERCENTILE: f32 = 0.1;
+const SIGNAL_PERCENTILE: f32 = 0.9;
+
+/// Level statistics of a test capture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelStats {
+    pub peak_dbfs: f32,
+    pub rms_dbfs: f32,
+    /// Fraction of samples (0..=1) at full scale.
+    pub clipped_ratio: f32,
+    pub noise_floor_dbfs: f32,
+    pub snr_db: f32,
+}
+
+pub fn to_dbfs(level: f32) -> f32 {
+    if level <= 0.0 {
+        FLOOR_DBFS
+    } else {
+        (20.0 * level.log10()).max(FLOOR_DBFS)
+    }
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Measure a mono capture. The noise floor and SNR come from the spread of
+/// per-frame RMS levels, so the capture should contain both speech and
+/// pauses.
+pub fn analyze_levels(samples: &[f32], frame_len: usize) -> LevelStats {
+    let peak = samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+    let clipped = samples.iter().filter(|s| s.abs() >= CLIP_LEVEL).count();
+
+    let mut frame_levels: Vec<f32> = samples.chunks(frame_len.max(1)).map(rms).collect();
+    frame_levels.sort_by(f32::total_cmp);
+    let percentile = |p: f32| -> f32 {
+        if frame_levels.is_empty() {
+            return 0.0;
+        }
+        let idx = ((frame_levels.len() - 1) as f32 * p).round() as usize;
+        frame_levels[idx]
+    };
+
+    let noise_floor_dbfs = to_dbfs(percentile(NOISE_PERCENTILE));
+    let signal_dbfs = to_dbfs(percentile(SIGNAL_PERCENTILE));
+
+    LevelStats {
+        peak_dbfs: to_dbfs(peak),
+        rms_dbfs: to_dbfs(rms(samples)),
+        clipped_ratio: if samples.is_empty() {
+            0.0
+        } else {
+            clipped as f32 / samples.len() as f32
+        },
+        noise_floor_dbfs,
+        snr_db: signal_dbfs - noise_floor_dbfs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silence_reports_the_floor() {
+        let stats = analyze_levels(&[0.0; 4800], 480);
+        assert_eq!(stats.peak_dbfs, FLOOR_DBFS);
+        assert_eq!(stats.noise_floor_dbfs, FLOOR_DBFS);
+        assert_eq!(stats.snr_db, 0.0);
+        assert_eq!(analyze_levels(&[], 480).clipped_ratio, 0.0);
+    }
+
+    #[test]
+    fn speech_over_quiet_noise_has_positive_snr() {
+        // 7 frames of "noise" at 0.001, 3 frames of "speech" at 0.1
+        let mut samples = vec![0.001f32; 480 * 7];
+        samples.extend(std::iter::repeat_n(0.1f32, 480 * 3));
+        let stats = analyze_levels(&samples, 480);
+
+        assert!((stats.noise_floor_dbfs + 60.0).abs() < 0.1);
+        assert!((stats.snr_db - 40.0).abs() < 0.1);
+        assert!((stats.peak_dbfs + 20.0).abs() < 0.1);
+        assert_eq!(stats.clipped_ratio, 0.0);
+    }
+
+    #[test]
+    fn counts_clipped_samples() {
+        let mut samples = vec![0.5f32; 1000];
+        samples[..10].fill(1.0);
+        samples[10..20].fill(-1.0);
+        let stats = analyze_levels(&samples, 480);
+        assert!((stats.clipped_ratio - 0.02).abs() < 1e-6);
+        assert_eq!(stats.peak_dbfs, 0.0);
+    }
+}
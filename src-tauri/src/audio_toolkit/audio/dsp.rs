@@ -0,0 +1,422 @@
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+
+/// Which processing stages run on captured audio before the VAD.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DspConfig {
+    /// Remove rumble and DC offset below ~80 Hz.
+    pub high_pass: bool,
+    /// Attenuate stationary background noise (fans, HVAC, office hum).
+    pub noise_suppression: bool,
+    /// Bring quiet and loud microphones to a consistent speech level.
+    pub auto_gain: bool,
+}
+
+/// Samples at or above this magnitude count as clipped.
+const CLIP_LEVEL: f32 = 0.999;
+
+const HIGH_PASS_HZ: f32 = 80.0;
+
+/// Processing chain applied to 16 kHz mono frames. Clipping is always
+/// measured on the unprocessed input, even with every stage disabled.
+pub struct DspChain {
+    high_pass: Option<Biquad>,
+    denoiser: Option<NoiseSuppressor>,
+    agc: Option<AutoGain>,
+    out: Vec<f32>,
+    clipped: usize,
+    total: usize,
+}
+
+impl DspChain {
+    pub fn new(config: DspConfig, sample_rate: u32) -> Self {
+        Self {
+            high_pass: config
+                .high_pass
+                .then(|| Biquad::high_pass(HIGH_PASS_HZ, sample_rate as f32)),
+            denoiser: config.noise_suppression.then(NoiseSuppressor::new),
+            agc: config.auto_gain.then(AutoGain::new),
+            out: Vec::new(),
+            clipped: 0,
+            total: 0,
+        }
+    }
+
+    fn is_passthrough(&self) -> bool {
+        self.high_pass.is_none() && self.denoiser.is_none() && self.agc.is_none()
+    }
+
+    /// Process one frame. The result has the same length as `input`; the
+    /// noise suppressor delays the signal by a fixed few milliseconds.
+    pub fn process<'a>(&'a mut self, input: &'a [f32]) -> &'a [f32] {
+        self.total += input.len();
+        self.clipped += input.iter().filter(|s| s.abs() >= CLIP_LEVEL).count();
+
+        if self.is_passthrough() {
+            return input;
+        }
+
+        self.out.clear();
+        self.out.extend_from_slice(input);
+        if let Some(hp) = &mut self.high_pass {
+            hp.process(&mut self.out);
+        }
+        if let Some(ns) = &mut self.denoiser {
+            ns.process(&mut self.out);
+        }
+        if let Some(agc) = &mut self.agc {
+            agc.process(&mut self.out);
+        }
+        &self.out
+    }
+
+    /// Fraction of input samples that were clipped since the last reset.
+    pub fn clipped_ratio(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.clipped as f32 / self.total as f32
+        }
+    }
+
+    pub fn reset_clip_stats(&mut self) {
+        self.clipped = 0;
+        self.total = 0;
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Second-order IIR section (RBJ cookbook), direct form I.
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Biquad {
+    /// Butterworth high-pass.
+    fn high_pass(cutoff_hz: f32, sample_rate: f32) -> Self {
+        let w0 = 2.0 * PI * cutoff_hz / sample_rate;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos_w0) / 2.0 / a0,
+            b1: -(1.0 + cos_w0) / a0,
+            b2: (1.0 + cos_w0) / 2.0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for s in samples {
+            let x = *s;
+            let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2
+                - self.a1 * self.y1
+                - self.a2 * self.y2;
+            self.x2 = self.x1;
+            self.x1 = x;
+            self.y2 = self.y1;
+            self.y1 = y;
+            *s = y;
+        }
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+const NS_FFT_SIZE: usize = 512;
+const NS_HOP: usize = NS_FFT_SIZE / 2;
+/// Lowest gain applied to a bin, so noise is reduced rather than gated,
+/// which avoids "musical noise" artefacts.
+const NS_GAIN_FLOOR: f32 = 0.1;
+const NS_OVER_SUBTRACTION: f32 = 3.0;
+/// Noise estimate smoothing when the bin power falls below / rises above it.
+/// The estimate follows drops quickly and climbs slowly, so speech does not
+/// get learned as noise, while steady tones and hum eventually are.
+const NS_NOISE_FALL: f32 = 0.9;
+const NS_NOISE_RISE: f32 = 0.998;
+const NS_GAIN_SMOOTHING: f32 = 0.5;
+const NS_POWER_SMOOTHING: f32 = 0.7;
+
+/// Spectral-subtraction noise suppressor: per-bin gains from a running
+/// noise-floor estimate, applied with 50% overlap-add.
+struct NoiseSuppressor {
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    input: Vec<f32>,
+    pending: Vec<f32>,
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+    noise: Vec<f32>,
+    smoothed: Vec<f32>,
+    gains: Vec<f32>,
+    spectrum: Vec<Complex32>,
+    primed: bool,
+}
+
+impl NoiseSuppressor {
+    fn new() -> Self {
+        let mut planner = FftPlanner::<f32>::new();
+        // Periodic sqrt-Hann: analysis × synthesis sums to one at 50% overlap
+        let window = (0..NS_FFT_SIZE)
+            .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f32 / NS_FFT_SIZE as f32).cos()).sqrt())
+            .collect();
+        let bins = NS_FFT_SIZE / 2 + 1;
+
+        Self {
+            fft: planner.plan_fft_forward(NS_FFT_SIZE),
+            ifft: planner.plan_fft_inverse(NS_FFT_SIZE),
+            window,
+            input: vec![0.0; NS_FFT_SIZE],
+            pending: Vec::with_capacity(NS_HOP),
+            overlap: vec![0.0; NS_FFT_SIZE],
+            // Latency of one hop keeps output aligned with input length
+            output: std::iter::repeat_n(0.0, NS_HOP).collect(),
+            noise: vec![0.0; bins],
+            smoothed: vec![0.0; bins],
+            gains: vec![1.0; bins],
+            spectrum: vec![Complex32::new(0.0, 0.0); NS_FFT_SIZE],
+            primed: false,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for &s in samples.iter() {
+            self.pending.push(s);
+            if self.pending.len() == NS_HOP {
+                self.process_hop();
+            }
+        }
+        for s in samples.iter_mut() {
+            *s = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+
+    fn process_hop(&mut self) {
+        self.input.copy_within(NS_HOP.., 0);
+        self.input[NS_FFT_SIZE - NS_HOP..].copy_from_slice(&self.pending);
+        self.pending.clear();
+
+        for (i, c) in self.spectrum.iter_mut().enumerate() {
+            *c = Complex32::new(self.input[i] * self.window[i], 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let bins = self.noise.len();
+        for k in 0..bins {
+            let raw = self.spectrum[k].norm_sqr();
+            self.smoothed[k] = if self.primed {
+                NS_POWER_SMOOTHING * self.smoothed[k] + (1.0 - NS_POWER_SMOOTHING) * raw
+            } else {
+                raw
+            };
+            let power = self.smoothed[k];
+            if !self.primed {
+                self.noise[k] = power;
+            } else {
+                let a = if power < self.noise[k] {
+                    NS_NOISE_FALL
+                } else {
+                    NS_NOISE_RISE
+                };
+                self.noise[k] = a * self.noise[k] + (1.0 - a) * power;
+            }
+
+            let target = if power > 0.0 {
+                (1.0 - NS_OVER_SUBTRACTION * self.noise[k] / power)
+                    .max(NS_GAIN_FLOOR * NS_GAIN_FLOOR)
+                    .sqrt()
+            } else {
+                NS_GAIN_FLOOR
+            };
+            self.gains[k] = NS_GAIN_SMOOTHING * self.gains[k] + (1.0 - NS_GAIN_SMOOTHING) * target;
+        }
+        self.primed = true;
+
+        // Apply symmetric gains so the inverse transform stays real
+        for k in 0..NS_FFT_SIZE {
+            let bin = if k < bins { k } else { NS_FFT_SIZE - k };
+            self.spectrum[k] *= self.gains[bin];
+        }
+        self.ifft.process(&mut self.spectrum);
+
+        let scale = 1.0 / NS_FFT_SIZE as f32;
+        for i in 0..NS_FFT_SIZE {
+            self.overlap[i] += self.spectrum[i].re * scale * self.window[i];
+        }
+        self.output.extend(&self.overlap[..NS_HOP]);
+        self.overlap.copy_within(NS_HOP.., 0);
+        self.overlap[NS_FFT_SIZE - NS_HOP..].fill(0.0);
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Speech level the AGC aims for (about -20 dBFS RMS).
+const AGC_TARGET_RMS: f32 = 0.1;
+/// Frames quieter than this (about -50 dBFS) are treated as silence and do
+/// not move the gain, so background noise is not pumped up between words.
+const AGC_GATE_RMS: f32 = 0.003;
+const AGC_MIN_GAIN: f32 = 0.25;
+const AGC_MAX_GAIN: f32 = 10.0;
+const AGC_ATTACK: f32 = 0.3;
+const AGC_RELEASE: f32 = 0.05;
+const AGC_LIMIT: f32 = 0.98;
+
+struct AutoGain {
+    gain: f32,
+}
+
+impl AutoGain {
+    fn new() -> Self {
+        Self { gain: 1.0 }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        if rms > AGC_GATE_RMS {
+            let desired = (AGC_TARGET_RMS / rms).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN);
+            // Turn down quickly when too loud, turn up slowly
+            let rate = if desired < self.gain {
+                AGC_ATTACK
+            } else {
+                AGC_RELEASE
+            };
+            self.gain += (desired - self.gain) * rate;
+        }
+
+        for s in samples {
+            *s = (*s * self.gain).clamp(-AGC_LIMIT, AGC_LIMIT);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16_000;
+    const FRAME: usize = 480;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn run(chain: &mut DspChain, input: &[f32]) -> Vec<f32> {
+        let mut out = Vec::with_capacity(input.len());
+        for frame in input.chunks(FRAME) {
+            out.extend_from_slice(chain.process(frame));
+        }
+        out
+    }
+
+    /// Deterministic white-ish noise in [-amplitude, amplitude].
+    fn noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn tone(len: usize, hz: f32, amplitude: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * PI * hz * i as f32 / RATE as f32).sin() * amplitude)
+            .collect()
+    }
+
+    #[test]
+    fn passthrough_still_counts_clipping() {
+        let mut chain = DspChain::new(DspConfig::default(), RATE);
+        let mut input = vec![0.1; FRAME];
+        input[..48].fill(1.0);
+        assert_eq!(run(&mut chain, &input), input);
+        assert!((chain.clipped_ratio() - 0.1).abs() < 1e-6);
+
+        chain.reset_clip_stats();
+        assert_eq!(chain.clipped_ratio(), 0.0);
+    }
+
+    #[test]
+    fn high_pass_removes_dc_offset() {
+        let config = DspConfig {
+            high_pass: true,
+            ..Default::default()
+        };
+        let mut chain = DspChain::new(config, RATE);
+        let out = run(&mut chain, &vec![0.5; RATE as usize]);
+        assert!(rms(&out[out.len() - FRAME..]) < 0.01);
+    }
+
+    #[test]
+    fn noise_suppression_attenuates_steady_noise_but_keeps_bursts() {
+        let config = DspConfig {
+            noise_suppression: true,
+            ..Default::default()
+        };
+        let len = RATE as usize * 2;
+
+        let mut chain = DspChain::new(config, RATE);
+        let hiss = noise(len, 0.05);
+        let out = run(&mut chain, &hiss);
+        let tail = len / 2..;
+        assert!(rms(&out[tail.clone()]) < rms(&hiss[tail]) * 0.5);
+
+        // Tone bursts, standing in for speech, survive mostly intact
+        let mut chain = DspChain::new(config, RATE);
+        let bursts: Vec<f32> = tone(len, 440.0, 0.3)
+            .iter()
+            .enumerate()
+            .map(|(i, t)| if (i / 4800) % 2 == 0 { *t } else { 0.0 })
+            .collect();
+        let mixed: Vec<f32> = bursts.iter().zip(&hiss).map(|(t, n)| t + n).collect();
+        let out = run(&mut chain, &mixed);
+        // Middle of an "on" burst, after the noise estimate has settled
+        let burst = 4800 * 4 + 1000..4800 * 5 - 1000;
+        assert!(rms(&out[burst]) > 0.3 / 2f32.sqrt() * 0.75);
+    }
+
+    #[test]
+    fn auto_gain_boosts_quiet_input_towards_target() {
+        let config = DspConfig {
+            auto_gain: true,
+            ..Default::default()
+        };
+        let mut chain = DspChain::new(config, RATE);
+        let quiet = tone(RATE as usize * 3, 300.0, 0.02);
+        let out = run(&mut chain, &quiet);
+        let level = rms(&out[out.len() - FRAME..]);
+        assert!(level > 0.07 && level < 0.13, "level {level}");
+
+        // Silence is left alone
+        let mut chain = DspChain::new(config, RATE);
+        let out = run(&mut chain, &vec![0.001; FRAME * 10]);
+        assert!(out.iter().all(|s| (s - 0.001).abs() < 1e-6));
+    }
+}
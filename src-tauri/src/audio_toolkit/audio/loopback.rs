@@ -0,0 +1,162 @@
+//! System audio ("loopback") capture through PulseAudio/PipeWire monitor
+//! sources. cpal only sees ALSA capture devices on Linux, so monitor sources
+//! are listed with `pactl` and recorded with `parec`, which PipeWire serves
+//! through `pipewire-pulse` as well.
+
+use std::io::{Error, ErrorKind, Read};
+use std::process::{Child, Command, Stdio};
+use std::thread::JoinHandle;
+
+use crate::audio_toolkit::constants;
+
+/// Source name PulseAudio and PipeWire resolve to the monitor of the current
+/// default output.
+pub const DEFAULT_MONITOR: &str = "@DEFAULT_MONITOR@";
+
+/// Samples per frame delivered by [`LoopbackCapture`] (30 ms at 16 kHz).
+const FRAME_SAMPLES: usize = 480;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorSource {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Pick the monitor sources out of `pactl list short sources` output
+/// (`index<TAB>name<TAB>driver<TAB>spec<TAB>state` per line).
+fn parse_monitor_sources(output: &str, default_sink: Option<&str>) -> Vec<MonitorSource> {
+    let default_monitor = default_sink.map(|sink| format!("{}.monitor", sink.trim()));
+    output
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .filter(|name| name.ends_with(".monitor"))
+        .map(|name| MonitorSource {
+            name: name.to_string(),
+            is_default: default_monitor.as_deref() == Some(name),
+        })
+        .collect()
+}
+
+fn pactl(args: &[&str]) -> Option<String> {
+    let output = Command::new("pactl").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Monitor sources of the running sound server. Empty when `pactl` is not
+/// available.
+pub fn list_monitor_sources() -> Vec<MonitorSource> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    let Some(sources) = pactl(&["list", "short", "sources"]) else {
+        return Vec::new();
+    };
+    let default_sink = pactl(&["get-default-sink"]);
+    parse_monitor_sources(&sources, default_sink.as_deref())
+}
+
+/// A running `parec` process delivering 16 kHz mono frames.
+pub struct LoopbackCapture {
+    child: Child,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl LoopbackCapture {
+    /// Start recording `source` (see [`DEFAULT_MONITOR`]). Frames are sent
+    /// on `tx` until [`LoopbackCapture::stop`] is called or the receiver is
+    /// dropped.
+    pub fn start(
+        source: &str,
+        tx: tokio::sync::mpsc::Sender<Vec<f32>>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if !cfg!(target_os = "linux") {
+            return Err(Box::new(Error::new(
+                ErrorKind::Unsupported,
+                "System audio capture requires PulseAudio or PipeWire",
+            )));
+        }
+
+        let mut child = Command::new("parec")
+            .arg(format!("--device={}", source))
+            .arg("--format=float32le")
+            .arg(format!("--rate={}", constants::WHISPER_SAMPLE_RATE))
+            .arg("--channels=1")
+            .arg("--latency-msec=30")
+            .arg("--raw")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| Error::new(e.kind(), format!("Failed to start parec: {}", e)))?;
+
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| Error::new(ErrorKind::BrokenPipe, "parec has no output"))?;
+
+        let reader = std::thread::spawn(move || {
+            let mut bytes = vec![0u8; FRAME_SAMPLES * 4];
+            while stdout.read_exact(&mut bytes).is_ok() {
+                let frame = bytes
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect();
+                if tx.blocking_send(frame).is_err() {
+                    break;
+                }
+            }
+            log::debug!("Loopback reader exited");
+        });
+
+        Ok(Self {
+            child,
+            reader: Some(reader),
+        })
+    }
+
+    pub fn stop(mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+impl Drop for LoopbackCapture {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_only_monitor_sources() {
+        let output = "\
+52\talsa_output.pci-0000_00_1f.3.analog-stereo.monitor\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED
+53\talsa_input.pci-0000_00_1f.3.analog-stereo\tPipeWire\ts32le 2ch 48000Hz\tRUNNING
+61\tbluez_output.00_11_22.1.monitor\tPipeWire\ts16le 2ch 48000Hz\tIDLE
+";
+        let sources = parse_monitor_sources(output, Some("bluez_output.00_11_22.1\n"));
+
+        assert_eq!(
+            sources,
+            vec![
+                MonitorSource {
+                    name: "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor".to_string(),
+                    is_default: false,
+                },
+                MonitorSource {
+                    name: "bluez_output.00_11_22.1.monitor".to_string(),
+                    is_default: true,
+                },
+            ]
+        );
+        assert!(parse_monitor_sources("", None).is_empty());
+    }
+}
@@ -1,12 +1,22 @@
 // Re-export all audio components
 mod device;
+mod diagnostics;
+mod dsp;
+mod loopback;
+mod pre_roll;
 mod recorder;
 mod resampler;
 mod utils;
 mod visualizer;
 
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
+pub use diagnostics::{analyze_levels, to_dbfs, LevelStats};
+pub use dsp::{DspChain, DspConfig};
+pub use loopback::{list_monitor_sources, LoopbackCapture, MonitorSource, DEFAULT_MONITOR};
+pub use pre_roll::{frames_for_duration, PreRollBuffer};
 pub use recorder::AudioRecorder;
 pub use resampler::{resample_i16, FrameResampler};
-pub use utils::{encode_wav_bytes, extract_pcm_from_wav, save_wav_file};
+pub use utils::{
+    encode_wav_bytes, extract_pcm_from_wav, read_wav_range, save_wav_file, WavFileWriter,
+};
 pub use visualizer::AudioVisualiser;
@@ -0,0 +1,140 @@
+/// Fixed-size ring of the most recent audio frames captured while not
+/// recording, so a recording can start slightly before the key press.
+///
+/// Like `SmoothedVad`, storage is a flat buffer allocated lazily from the
+/// first frame's size; afterwards pushing frames does not allocate.
+pub struct PreRollBuffer {
+    ring_buf: Vec<f32>,
+    frame_size: usize,
+    max_frames: usize,
+    frame_count: usize,
+    write_head: usize,
+}
+
+impl PreRollBuffer {
+    pub fn new(max_frames: usize) -> Self {
+        Self {
+            ring_buf: Vec::new(),
+            frame_size: 0,
+            max_frames,
+            frame_count: 0,
+            write_head: 0,
+        }
+    }
+
+    /// Change how many frames are kept. Buffered audio is discarded when the
+    /// size actually changes.
+    pub fn set_max_frames(&mut self, max_frames: usize) {
+        if max_frames != self.max_frames {
+            self.max_frames = max_frames;
+            self.ring_buf = Vec::new();
+            self.frame_size = 0;
+            self.clear();
+        }
+    }
+
+    pub fn push(&mut self, frame: &[f32]) {
+        if self.max_frames == 0 || frame.is_empty() {
+            return;
+        }
+
+        if self.frame_size != frame.len() {
+            // First frame, or the frame size changed: start over
+            self.frame_size = frame.len();
+            self.ring_buf = vec![0.0; self.max_frames * self.frame_size];
+            self.clear();
+        }
+
+        let start = self.write_head * self.frame_size;
+        self.ring_buf[start..start + self.frame_size].copy_from_slice(frame);
+        self.write_head = (self.write_head + 1) % self.max_frames;
+        self.frame_count = (self.frame_count + 1).min(self.max_frames);
+    }
+
+    /// Hand the buffered frames to `f`, oldest first, and empty the buffer.
+    pub fn drain(&mut self, mut f: impl FnMut(&[f32])) {
+        if self.frame_count == 0 {
+            return;
+        }
+
+        let oldest = (self.write_head + self.max_frames - self.frame_count) % self.max_frames;
+        for i in 0..self.frame_count {
+            let idx = (oldest + i) % self.max_frames;
+            let start = idx * self.frame_size;
+            f(&self.ring_buf[start..start + self.frame_size]);
+        }
+        self.clear();
+    }
+
+    /// Drop all but the newest `frames` frames.
+    pub fn retain_newest(&mut self, frames: usize) {
+        self.frame_count = self.frame_count.min(frames);
+    }
+
+    pub fn clear(&mut self) {
+        self.frame_count = 0;
+        self.write_head = 0;
+    }
+}
+
+/// Number of frames of `frame_ms` needed to cover `duration_ms`.
+pub fn frames_for_duration(duration_ms: u32, frame_ms: u32) -> usize {
+    if frame_ms == 0 {
+        return 0;
+    }
+    duration_ms.div_ceil(frame_ms) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(buffer: &mut PreRollBuffer) -> Vec<f32> {
+        let mut out = Vec::new();
+        buffer.drain(|frame| out.extend_from_slice(frame));
+        out
+    }
+
+    #[test]
+    fn keeps_only_the_most_recent_frames_in_order() {
+        let mut buffer = PreRollBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(&[i as f32, i as f32]);
+        }
+
+        assert_eq!(collect(&mut buffer), vec![2.0, 2.0, 3.0, 3.0, 4.0, 4.0]);
+        assert!(collect(&mut buffer).is_empty());
+    }
+
+    #[test]
+    fn retaining_drops_the_oldest_frames() {
+        let mut buffer = PreRollBuffer::new(4);
+        for i in 0..6 {
+            buffer.push(&[i as f32]);
+        }
+        buffer.retain_newest(2);
+        assert_eq!(collect(&mut buffer), vec![4.0, 5.0]);
+
+        buffer.push(&[6.0]);
+        buffer.retain_newest(10);
+        assert_eq!(collect(&mut buffer), vec![6.0]);
+    }
+
+    #[test]
+    fn disabled_buffer_stores_nothing() {
+        let mut buffer = PreRollBuffer::new(0);
+        buffer.push(&[1.0, 2.0]);
+        assert!(collect(&mut buffer).is_empty());
+
+        buffer.set_max_frames(2);
+        buffer.push(&[1.0]);
+        assert_eq!(collect(&mut buffer), vec![1.0]);
+    }
+
+    #[test]
+    fn frame_count_rounds_up() {
+        assert_eq!(frames_for_duration(0, 30), 0);
+        assert_eq!(frames_for_duration(300, 30), 10);
+        assert_eq!(frames_for_duration(500, 30), 17);
+    }
+}
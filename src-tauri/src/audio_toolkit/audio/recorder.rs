@@ -1,6 +1,9 @@
 use std::{
     io::Error,
-    sync::{mpsc, Arc, Mutex},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
     time::Duration,
 };
 
@@ -10,15 +13,30 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{
+        frames_for_duration, AudioVisualiser, DspChain, DspConfig, FrameResampler, PreRollBuffer,
+    },
     constants,
-    vad::{self, VadFrame},
-    VoiceActivityDetector,
+    vad::{self, AutoStopConfig, Endpointer, UtteranceSegmenter, VadFrame},
+    SharedKeywordSpotter, VoiceActivityDetector,
 };
 
+/// Length of the frames handed to the VAD and the pre-roll buffer.
+const FRAME_MS: u32 = 30;
+
+/// Audio kept before the next speech when silence is dropped from a recording.
+const SILENCE_LEAD_IN_MS: usize = 300;
+
+/// Recordings with more clipped samples than this are reported.
+const CLIP_REPORT_RATIO: f32 = 0.001;
+
+/// Wake-word candidates waiting for the spotter; further ones are dropped.
+const SPOTTER_QUEUE: usize = 2;
+
 enum Cmd {
     Start(Option<tokio::sync::mpsc::Sender<Vec<f32>>>),
     Stop(mpsc::Sender<Vec<f32>>),
+    SwitchDevice(Device, mpsc::Sender<Result<(), String>>),
     Shutdown,
 }
 
@@ -27,7 +45,44 @@ pub struct AudioRecorder {
     cmd_tx: Option<mpsc::Sender<Cmd>>,
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    filter_silence: bool,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    endpoint_cb: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
+    wake_word_cb: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
+    clipping_cb: Option<Arc<dyn Fn(f32) + Send + Sync + 'static>>,
+    stream_error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+    pre_roll_ms: Arc<AtomicU32>,
+    auto_stop: Arc<Mutex<Option<AutoStopConfig>>>,
+    keyword_spotter: SharedKeywordSpotter,
+    dsp: DspConfig,
+}
+
+/// Everything the worker thread needs besides the sample and command channels.
+struct ConsumerOptions {
+    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    filter_silence: bool,
+    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    endpoint_cb: Option<Arc<dyn Fn() + Send + Sync + 'static>>,
+    clipping_cb: Option<Arc<dyn Fn(f32) + Send + Sync + 'static>>,
+    pre_roll_ms: Arc<AtomicU32>,
+    auto_stop: Arc<Mutex<Option<AutoStopConfig>>>,
+    keyword_spotter: SharedKeywordSpotter,
+    wake_word: Option<WakeWordLink>,
+    dsp: DspConfig,
+}
+
+/// An utterance of the idle stream for the spotter to check, and the idle
+/// frame it ended with.
+struct Candidate {
+    utterance: Vec<f32>,
+    end_frame: u64,
+}
+
+/// How the worker thread reaches the spotting thread.
+struct WakeWordLink {
+    candidates: mpsc::SyncSender<Candidate>,
+    /// End frame of the last utterance the wake phrase was heard in, or 0
+    heard_at: Arc<AtomicU64>,
 }
 
 impl AudioRecorder {
@@ -37,7 +92,16 @@ impl AudioRecorder {
             cmd_tx: None,
             worker_handle: None,
             vad: None,
+            filter_silence: true,
             level_cb: None,
+            endpoint_cb: None,
+            wake_word_cb: None,
+            clipping_cb: None,
+            stream_error_cb: None,
+            pre_roll_ms: Arc::new(AtomicU32::new(0)),
+            auto_stop: Arc::new(Mutex::new(None)),
+            keyword_spotter: Arc::new(Mutex::new(None)),
+            dsp: DspConfig::default(),
         })
     }
 
@@ -46,6 +110,62 @@ impl AudioRecorder {
         self
     }
 
+    /// Whether frames the VAD classifies as noise are dropped from the
+    /// recording (the default). When false the VAD only drives auto-stop.
+    pub fn with_silence_filter(mut self, enabled: bool) -> Self {
+        self.filter_silence = enabled;
+        self
+    }
+
+    /// Called from the audio thread when auto-stop decides the current
+    /// recording is over. See [`AudioRecorder::set_auto_stop`].
+    pub fn with_endpoint_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.endpoint_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Called from the spotting thread when the keyword spotter set with
+    /// [`AudioRecorder::with_keyword_spotter`] hears the wake phrase while not
+    /// recording. Only runs when a VAD is set.
+    pub fn with_wake_word_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.wake_word_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Called from the audio thread when a recording stops with a noticeable
+    /// share of clipped input samples; the argument is that share (0..=1).
+    pub fn with_clipping_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(f32) + Send + Sync + 'static,
+    {
+        self.clipping_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Check short utterances of the idle stream with the spotter in `slot`
+    /// whenever it holds one. The spotter runs on a thread of its own.
+    pub fn with_keyword_spotter(mut self, slot: SharedKeywordSpotter) -> Self {
+        self.keyword_spotter = slot;
+        self
+    }
+
+    /// Called from the audio thread when the input stream reports an error,
+    /// typically because its device was unplugged. The recorder stays open;
+    /// use [`AudioRecorder::switch_device`] to continue on another device.
+    pub fn with_stream_error_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        self.stream_error_cb = Some(Arc::new(cb));
+        self
+    }
+
     pub fn with_level_callback<F>(mut self, cb: F) -> Self
     where
         F: Fn(Vec<f32>) + Send + Sync + 'static,
@@ -54,6 +174,26 @@ impl AudioRecorder {
         self
     }
 
+    /// Keep the last `pre_roll` of audio captured while idle and prepend it
+    /// to the next recording. Only has an effect while the stream is open
+    /// between recordings. Can be changed while the stream is running.
+    pub fn set_pre_roll(&self, pre_roll: Duration) {
+        self.pre_roll_ms
+            .store(pre_roll.as_millis() as u32, Ordering::Relaxed);
+    }
+
+    /// Auto-stop rules for recordings started from now on, or `None` to only
+    /// stop on request.
+    pub fn set_auto_stop(&self, config: Option<AutoStopConfig>) {
+        *self.auto_stop.lock().unwrap() = config;
+    }
+
+    /// Processing applied to captured audio before the VAD. Takes effect the
+    /// next time the stream is opened.
+    pub fn set_dsp(&mut self, config: DspConfig) {
+        self.dsp = config;
+    }
+
     pub fn open(&mut self, device: Option<Device>) -> Result<(), Box<dyn std::error::Error>> {
         if self.worker_handle.is_some() {
             return Ok(()); // already open
@@ -71,56 +211,50 @@ impl AudioRecorder {
         };
 
         let thread_device = device.clone();
-        let vad = self.vad.clone();
-        // Move the optional level callback into the worker thread
-        let level_cb = self.level_cb.clone();
+        // Move the optional callbacks into the worker thread
+        let options = ConsumerOptions {
+            vad: self.vad.clone(),
+            filter_silence: self.filter_silence,
+            level_cb: self.level_cb.clone(),
+            endpoint_cb: self.endpoint_cb.clone(),
+            clipping_cb: self.clipping_cb.clone(),
+            pre_roll_ms: Arc::clone(&self.pre_roll_ms),
+            auto_stop: Arc::clone(&self.auto_stop),
+            keyword_spotter: Arc::clone(&self.keyword_spotter),
+            wake_word: self.spawn_spotter(),
+            dsp: self.dsp,
+        };
 
+        let error_cb = self.stream_error_cb.clone();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
         let worker = std::thread::spawn(move || {
-            let config = AudioRecorder::get_preferred_config(&thread_device)
-                .expect("failed to fetch preferred config");
-
-            let sample_rate = config.sample_rate().0;
-            let channels = config.channels() as usize;
-
-            log::info!(
-                "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
-                thread_device.name(),
-                sample_rate,
-                channels,
-                config.sample_format()
-            );
-
-            let stream = match config.sample_format() {
-                cpal::SampleFormat::U8 => {
-                    AudioRecorder::build_stream::<u8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I8 => {
-                    AudioRecorder::build_stream::<i8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I16 => {
-                    AudioRecorder::build_stream::<i16>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
+            let stream = match open_input_stream(&thread_device, &sample_tx, &error_cb) {
+                Ok(stream) => {
+                    let _ = ready_tx.send(Ok(()));
+                    stream
                 }
-                cpal::SampleFormat::I32 => {
-                    AudioRecorder::build_stream::<i32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                    return;
                 }
-                cpal::SampleFormat::F32 => {
-                    AudioRecorder::build_stream::<f32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                _ => panic!("unsupported sample format"),
             };
 
-            stream.play().expect("failed to start stream");
-
             // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
-            // stream is dropped here, after run_consumer returns
+            run_consumer(stream, sample_tx, sample_rx, cmd_rx, error_cb, options);
+            // the stream is dropped when run_consumer returns
         });
 
+        let opened = ready_rx
+            .recv()
+            .unwrap_or_else(|_| Err("audio worker exited before opening the stream".to_string()));
+        if let Err(e) = opened {
+            let _ = worker.join();
+            return Err(Box::new(Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to open input stream: {}", e),
+            )));
+        }
+
         self.device = Some(device);
         self.cmd_tx = Some(cmd_tx);
         self.worker_handle = Some(worker);
@@ -128,6 +262,24 @@ impl AudioRecorder {
         Ok(())
     }
 
+    /// Start the thread that checks wake-word candidates, so a slow model
+    /// never holds up the audio thread. It ends with the worker thread, which
+    /// owns the sending side.
+    fn spawn_spotter(&self) -> Option<WakeWordLink> {
+        let (Some(_), Some(cb)) = (&self.vad, self.wake_word_cb.clone()) else {
+            return None;
+        };
+        let (candidates, candidate_rx) = mpsc::sync_channel(SPOTTER_QUEUE);
+        let heard_at = Arc::new(AtomicU64::new(0));
+        let slot = Arc::clone(&self.keyword_spotter);
+        let thread_heard_at = Arc::clone(&heard_at);
+        std::thread::spawn(move || run_spotter(candidate_rx, slot, cb, thread_heard_at));
+        Some(WakeWordLink {
+            candidates,
+            heard_at,
+        })
+    }
+
     pub fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = &self.cmd_tx {
             tx.send(Cmd::Start(None))?;
@@ -153,6 +305,31 @@ impl AudioRecorder {
         Ok(resp_rx.recv()?) // wait for the samples
     }
 
+    /// Move the open stream to `device` without interrupting a recording in
+    /// progress: the audio captured so far is kept and capture continues on
+    /// the new device. If the new device can't be opened the recorder stays
+    /// open without input.
+    pub fn switch_device(&mut self, device: Device) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(tx) = &self.cmd_tx else {
+            return Err(Box::new(Error::new(
+                std::io::ErrorKind::NotConnected,
+                "Recorder is not open",
+            )));
+        };
+        let (resp_tx, resp_rx) = mpsc::channel();
+        tx.send(Cmd::SwitchDevice(device.clone(), resp_tx))?;
+        resp_rx
+            .recv()?
+            .map_err(|e| Error::new(std::io::ErrorKind::Other, e))?;
+        self.device = Some(device);
+        Ok(())
+    }
+
+    /// Name of the device the open stream captures from.
+    pub fn device_name(&self) -> Option<String> {
+        self.device.as_ref().and_then(|d| d.name().ok())
+    }
+
     pub fn close(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if let Some(tx) = self.cmd_tx.take() {
             let _ = tx.send(Cmd::Shutdown);
@@ -169,6 +346,7 @@ impl AudioRecorder {
         config: &cpal::SupportedStreamConfig,
         sample_tx: mpsc::Sender<Vec<f32>>,
         channels: usize,
+        error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
     ) -> Result<cpal::Stream, cpal::BuildStreamError>
     where
         T: Sample + SizedSample + Send + 'static,
@@ -205,12 +383,19 @@ impl AudioRecorder {
         device.build_input_stream(
             &config.clone().into(),
             stream_cb,
-            |err| log::error!("Stream error: {}", err),
+            move |err| {
+                log::error!("Stream error: {}", err);
+                if let Some(cb) = &error_cb {
+                    cb(err.to_string());
+                }
+            },
             None,
         )
     }
 
-    fn get_preferred_config(
+    /// The stream configuration `open` uses for `device`: 16 kHz when
+    /// supported, in the best available sample format.
+    pub fn get_preferred_config(
         device: &cpal::Device,
     ) -> Result<cpal::SupportedStreamConfig, Box<dyn std::error::Error>> {
         let supported_configs = device.supported_input_configs()?;
@@ -249,22 +434,115 @@ impl AudioRecorder {
     }
 }
 
+/// A playing input stream and the rate of the samples it delivers.
+struct InputStream {
+    _stream: cpal::Stream,
+    sample_rate: u32,
+}
+
+/// Build and start a stream on `device` that sends mono samples on
+/// `sample_tx`.
+fn open_input_stream(
+    device: &Device,
+    sample_tx: &mpsc::Sender<Vec<f32>>,
+    error_cb: &Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+) -> Result<InputStream, String> {
+    let config = AudioRecorder::get_preferred_config(device).map_err(|e| e.to_string())?;
+
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+
+    log::info!(
+        "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
+        device.name(),
+        sample_rate,
+        channels,
+        config.sample_format()
+    );
+
+    let tx = sample_tx.clone();
+    let cb = error_cb.clone();
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::U8 => {
+            AudioRecorder::build_stream::<u8>(device, &config, tx, channels, cb)
+        }
+        cpal::SampleFormat::I8 => {
+            AudioRecorder::build_stream::<i8>(device, &config, tx, channels, cb)
+        }
+        cpal::SampleFormat::I16 => {
+            AudioRecorder::build_stream::<i16>(device, &config, tx, channels, cb)
+        }
+        cpal::SampleFormat::I32 => {
+            AudioRecorder::build_stream::<i32>(device, &config, tx, channels, cb)
+        }
+        cpal::SampleFormat::F32 => {
+            AudioRecorder::build_stream::<f32>(device, &config, tx, channels, cb)
+        }
+        format => return Err(format!("unsupported sample format {:?}", format)),
+    }
+    .map_err(|e| e.to_string())?;
+
+    stream.play().map_err(|e| e.to_string())?;
+
+    Ok(InputStream {
+        _stream: stream,
+        sample_rate,
+    })
+}
+
+/// Check wake-word candidates until the worker thread goes away. The slot
+/// is only locked to take the current spotter, so it can be swapped while a
+/// candidate is checked.
+fn run_spotter(
+    candidates: mpsc::Receiver<Candidate>,
+    slot: SharedKeywordSpotter,
+    cb: Arc<dyn Fn() + Send + Sync + 'static>,
+    heard_at: Arc<AtomicU64>,
+) {
+    for candidate in candidates {
+        let Some(spotter) = slot.lock().unwrap().clone() else {
+            continue;
+        };
+        let heard = spotter.lock().unwrap().detect(&candidate.utterance);
+        match heard {
+            Ok(true) => {
+                heard_at.store(candidate.end_frame, Ordering::SeqCst);
+                cb();
+            }
+            Ok(false) => {}
+            Err(e) => log::warn!("Keyword spotting failed: {}", e),
+        }
+    }
+}
+
 fn run_consumer(
-    in_sample_rate: u32,
-    vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+    stream: InputStream,
+    sample_tx: mpsc::Sender<Vec<f32>>,
     sample_rx: mpsc::Receiver<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
-    level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    error_cb: Option<Arc<dyn Fn(String) + Send + Sync + 'static>>,
+    options: ConsumerOptions,
 ) {
+    let in_sample_rate = stream.sample_rate;
+    let mut input = Some(stream);
     let mut frame_resampler = FrameResampler::new(
         in_sample_rate as usize,
         constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
+        Duration::from_millis(FRAME_MS as u64),
     );
+    let mut pre_roll = PreRollBuffer::new(frames_for_duration(
+        options.pre_roll_ms.load(Ordering::Relaxed),
+        FRAME_MS,
+    ));
 
     let mut processed_samples = Vec::<f32>::new();
     let mut recording = false;
     let mut stream_tap: Option<tokio::sync::mpsc::Sender<Vec<f32>>> = None;
+    let mut endpointer: Option<Endpointer> = None;
+    let mut segmenter = UtteranceSegmenter::new();
+    // Frames of the idle stream so far, to locate a heard wake phrase
+    let mut idle_frames: u64 = 0;
+    let mut dsp = DspChain::new(options.dsp, constants::WHISPER_SAMPLE_RATE);
 
     // ---------- spectrum visualisation setup ---------------------------- //
     const BUCKETS: usize = 16;
@@ -277,53 +555,149 @@ fn run_consumer(
         4000.0, // vocal_max_hz
     );
 
-    fn handle_frame(
+    fn keep_samples(
         samples: &[f32],
-        recording: bool,
-        vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
         out_buf: &mut Vec<f32>,
         stream_tap: &Option<tokio::sync::mpsc::Sender<Vec<f32>>>,
     ) {
-        if !recording {
-            return;
+        out_buf.extend_from_slice(samples);
+        if let Some(tap) = stream_tap {
+            let _ = tap.try_send(samples.to_vec());
         }
+    }
 
-        if let Some(vad_arc) = vad {
-            let mut det = vad_arc.lock().unwrap();
-            match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
-                VadFrame::Speech(buf) => {
-                    out_buf.extend_from_slice(buf);
-                    if let Some(tap) = stream_tap {
-                        let _ = tap.try_send(buf.to_vec());
+    fn handle_frame(
+        samples: &[f32],
+        options: &ConsumerOptions,
+        out_buf: &mut Vec<f32>,
+        stream_tap: &Option<tokio::sync::mpsc::Sender<Vec<f32>>>,
+        endpointer: &mut Option<Endpointer>,
+    ) {
+        let filtering = options.filter_silence && options.vad.is_some();
+
+        let is_speech = match &options.vad {
+            Some(vad_arc) => {
+                let mut det = vad_arc.lock().unwrap();
+                match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
+                    VadFrame::Speech(buf) => {
+                        if filtering {
+                            keep_samples(buf, out_buf, stream_tap);
+                        }
+                        true
                     }
+                    VadFrame::Noise => false,
                 }
-                VadFrame::Noise => {}
             }
-        } else {
-            out_buf.extend_from_slice(samples);
-            if let Some(tap) = stream_tap {
-                let _ = tap.try_send(samples.to_vec());
+            None => true,
+        };
+
+        if !filtering {
+            keep_samples(samples, out_buf, stream_tap);
+        }
+
+        if let Some(ep) = endpointer {
+            if ep.push(is_speech, Duration::from_millis(FRAME_MS as u64)) {
+                if let Some(cb) = &options.endpoint_cb {
+                    cb();
+                }
+            } else if ep.restart_if_silent() {
+                // Nothing was said; keep only the lead-in of whatever comes next
+                let keep = constants::WHISPER_SAMPLE_RATE as usize * SILENCE_LEAD_IN_MS / 1000;
+                out_buf.drain(..out_buf.len().saturating_sub(keep));
             }
         }
     }
 
+    /// Cut utterances out of the idle stream and hand them to the spotting
+    /// thread. Candidates are dropped while it is still busy.
+    fn listen_for_wake_word(
+        samples: &[f32],
+        options: &ConsumerOptions,
+        segmenter: &mut UtteranceSegmenter,
+        end_frame: u64,
+    ) {
+        let (Some(vad_arc), Some(link)) = (&options.vad, &options.wake_word) else {
+            return;
+        };
+
+        let mut det = vad_arc.lock().unwrap();
+        let frame = Duration::from_millis(FRAME_MS as u64);
+        let utterance = match det.push_frame(samples).unwrap_or(VadFrame::Noise) {
+            VadFrame::Speech(buf) => segmenter.push(buf, true, frame),
+            VadFrame::Noise => segmenter.push(samples, false, frame),
+        };
+        drop(det);
+
+        if let Some(utterance) = utterance {
+            let candidate = Candidate {
+                utterance,
+                end_frame,
+            };
+            if let Err(mpsc::TrySendError::Full(_)) = link.candidates.try_send(candidate) {
+                log::debug!("Keyword spotter busy, skipping a candidate");
+            }
+        }
+    }
+
+    /// Keep the wake phrase itself out of the recording it starts: drop the
+    /// pre-roll up to the end of the utterance it was heard in.
+    fn forget_wake_phrase(
+        options: &ConsumerOptions,
+        pre_roll: &mut PreRollBuffer,
+        idle_frames: u64,
+    ) {
+        let Some(link) = &options.wake_word else {
+            return;
+        };
+        let end_frame = link.heard_at.swap(0, Ordering::SeqCst);
+        if end_frame > 0 {
+            pre_roll.retain_newest(idle_frames.saturating_sub(end_frame) as usize);
+        }
+    }
+
     loop {
-        let raw = match sample_rx.recv() {
+        let raw = match sample_rx.recv_timeout(Duration::from_millis(FRAME_MS as u64)) {
             Ok(s) => s,
-            Err(_) => break, // stream closed
+            // No input, e.g. the device is gone; keep serving commands
+            Err(mpsc::RecvTimeoutError::Timeout) => Vec::new(),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
         };
 
         // ---------- spectrum processing ---------------------------------- //
         if let Some(buckets) = visualizer.feed(&raw) {
-            if let Some(cb) = &level_cb {
+            if let Some(cb) = &options.level_cb {
                 cb(buckets);
             }
         }
 
         // ---------- existing pipeline ------------------------------------ //
-        frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples, &stream_tap)
-        });
+        if recording {
+            frame_resampler.push(&raw, &mut |frame: &[f32]| {
+                handle_frame(
+                    dsp.process(frame),
+                    &options,
+                    &mut processed_samples,
+                    &stream_tap,
+                    &mut endpointer,
+                )
+            });
+        } else {
+            pre_roll.set_max_frames(frames_for_duration(
+                options.pre_roll_ms.load(Ordering::Relaxed),
+                FRAME_MS,
+            ));
+            let listening =
+                options.wake_word.is_some() && options.keyword_spotter.lock().unwrap().is_some();
+            frame_resampler.push(&raw, &mut |frame: &[f32]| {
+                let frame = dsp.process(frame);
+                pre_roll.push(frame);
+                idle_frames += 1;
+                if listening {
+                    listen_for_wake_word(frame, &options, &mut segmenter, idle_frames);
+                }
+            });
+            forget_wake_phrase(&options, &mut pre_roll, idle_frames);
+        }
 
         // non-blocking check for a command
         while let Ok(cmd) = cmd_rx.try_recv() {
@@ -332,30 +706,117 @@ fn run_consumer(
                     processed_samples.clear();
                     stream_tap = tap_tx;
                     recording = true;
+                    endpointer = options.auto_stop.lock().unwrap().map(Endpointer::new);
+                    segmenter.reset();
+                    dsp.reset_clip_stats();
                     visualizer.reset();
-                    if let Some(v) = &vad {
+                    if let Some(v) = &options.vad {
                         v.lock().unwrap().reset();
                     }
+                    forget_wake_phrase(&options, &mut pre_roll, idle_frames);
+                    // Start the recording with the audio captured just before it
+                    pre_roll.drain(|frame| {
+                        handle_frame(
+                            frame,
+                            &options,
+                            &mut processed_samples,
+                            &stream_tap,
+                            &mut endpointer,
+                        )
+                    });
                 }
                 Cmd::Stop(reply_tx) => {
                     recording = false;
+                    // The recording is ending anyway; don't auto-stop it again
+                    endpointer = None;
 
                     // Drain any audio chunks that were captured but not yet consumed
                     while let Ok(remaining) = sample_rx.try_recv() {
                         frame_resampler.push(&remaining, &mut |frame: &[f32]| {
-                            handle_frame(frame, true, &vad, &mut processed_samples, &stream_tap)
+                            handle_frame(
+                                dsp.process(frame),
+                                &options,
+                                &mut processed_samples,
+                                &stream_tap,
+                                &mut endpointer,
+                            )
                         });
                     }
 
                     frame_resampler.finish(&mut |frame: &[f32]| {
-                        handle_frame(frame, true, &vad, &mut processed_samples, &stream_tap)
+                        handle_frame(
+                            dsp.process(frame),
+                            &options,
+                            &mut processed_samples,
+                            &stream_tap,
+                            &mut endpointer,
+                        )
                     });
 
+                    let clipped = dsp.clipped_ratio();
+                    if clipped > CLIP_REPORT_RATIO {
+                        if let Some(cb) = &options.clipping_cb {
+                            cb(clipped);
+                        }
+                    }
+
                     // Drop the stream tap so the receiver side knows audio is done
                     stream_tap = None;
+                    // Don't let the tail of this recording leak into the next one
+                    pre_roll.clear();
 
                     let _ = reply_tx.send(std::mem::take(&mut processed_samples));
                 }
+                Cmd::SwitchDevice(device, reply_tx) => {
+                    // Stop the old device so nothing more arrives at its rate,
+                    // then finish what it already delivered
+                    drop(input.take());
+                    while let Ok(remaining) = sample_rx.try_recv() {
+                        if recording {
+                            frame_resampler.push(&remaining, &mut |frame: &[f32]| {
+                                handle_frame(
+                                    dsp.process(frame),
+                                    &options,
+                                    &mut processed_samples,
+                                    &stream_tap,
+                                    &mut endpointer,
+                                )
+                            });
+                        }
+                    }
+                    if recording {
+                        frame_resampler.finish(&mut |frame: &[f32]| {
+                            handle_frame(
+                                dsp.process(frame),
+                                &options,
+                                &mut processed_samples,
+                                &stream_tap,
+                                &mut endpointer,
+                            )
+                        });
+                    }
+
+                    let result = match open_input_stream(&device, &sample_tx, &error_cb) {
+                        Ok(stream) => {
+                            frame_resampler = FrameResampler::new(
+                                stream.sample_rate as usize,
+                                constants::WHISPER_SAMPLE_RATE as usize,
+                                Duration::from_millis(FRAME_MS as u64),
+                            );
+                            visualizer = AudioVisualiser::new(
+                                stream.sample_rate,
+                                WINDOW_SIZE,
+                                BUCKETS,
+                                400.0,
+                                4000.0,
+                            );
+                            input = Some(stream);
+                            Ok(())
+                        }
+                        Err(e) => Err(e),
+                    };
+                    let _ = reply_tx.send(result);
+                }
                 Cmd::Shutdown => return,
             }
         }
@@ -1,6 +1,8 @@
 use anyhow::{ensure, Result};
 use hound::{WavReader, WavSpec, WavWriter};
 use log::debug;
+use std::fs::File;
+use std::io::BufWriter;
 use std::path::Path;
 
 const WAV_SPEC: WavSpec = WavSpec {
@@ -25,17 +27,49 @@ pub async fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Res
     Ok(())
 }
 
-/// Extract raw i16 PCM samples from WAV bytes (mono, 16-bit expected).
-/// Returns `(samples, sample_rate)`.
+/// Writes a recording to disk while it is captured, so long recordings are
+/// not held in memory. Samples of several channels are interleaved.
+pub struct WavFileWriter {
+    writer: WavWriter<BufWriter<File>>,
+}
+
+impl WavFileWriter {
+    pub fn create<P: AsRef<Path>>(file_path: P, channels: u16) -> Result<Self> {
+        let spec = WavSpec {
+            channels,
+            ..WAV_SPEC
+        };
+        let writer = WavWriter::create(file_path.as_ref(), spec)?;
+        debug!("Streaming WAV file: {:?}", file_path.as_ref());
+        Ok(Self { writer })
+    }
+
+    pub fn write(&mut self, samples: &[f32]) -> Result<()> {
+        for sample in samples {
+            self.writer
+                .write_sample((sample * i16::MAX as f32) as i16)?;
+        }
+        Ok(())
+    }
+
+    /// Update the header so the file is readable even if the app quits
+    /// before [`WavFileWriter::finalize`].
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+
+    pub fn finalize(self) -> Result<()> {
+        Ok(self.writer.finalize()?)
+    }
+}
+
+/// Extract raw i16 PCM samples from 16-bit WAV bytes, mixing several
+/// channels down to mono. Returns `(samples, sample_rate)`.
 pub fn extract_pcm_from_wav(wav_bytes: &[u8]) -> Result<(Vec<i16>, u32)> {
     let cursor = std::io::Cursor::new(wav_bytes);
     let reader = WavReader::new(cursor)?;
     let spec = reader.spec();
-    ensure!(
-        spec.channels == 1,
-        "extract_pcm_from_wav expects mono audio, got {} channels",
-        spec.channels
-    );
+    ensure!(spec.channels > 0, "WAV audio has no channels");
     ensure!(
         spec.bits_per_sample == 16,
         "extract_pcm_from_wav expects 16-bit audio, got {}-bit",
@@ -45,7 +79,40 @@ pub fn extract_pcm_from_wav(wav_bytes: &[u8]) -> Result<(Vec<i16>, u32)> {
     let samples: Vec<i16> = reader
         .into_samples::<i16>()
         .collect::<std::result::Result<_, _>>()?;
-    Ok((samples, sample_rate))
+    if spec.channels == 1 {
+        return Ok((samples, sample_rate));
+    }
+    let mono = samples
+        .chunks(spec.channels as usize)
+        .map(|frame| {
+            let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+            (sum / frame.len() as i32) as i16
+        })
+        .collect();
+    Ok((mono, sample_rate))
+}
+
+/// Read `frames` frames starting at `start` from a 16-bit WAV file as mono
+/// samples, without loading the rest of the file.
+pub fn read_wav_range<P: AsRef<Path>>(
+    file_path: P,
+    start: usize,
+    frames: usize,
+) -> Result<Vec<f32>> {
+    let mut reader = WavReader::open(file_path.as_ref())?;
+    let channels = reader.spec().channels as usize;
+    ensure!(channels > 0, "WAV audio has no channels");
+    reader.seek(start as u32)?;
+    let samples: Vec<i16> = reader
+        .samples::<i16>()
+        .take(frames * channels)
+        .collect::<std::result::Result<_, _>>()?;
+    Ok(samples
+        .chunks(channels)
+        .map(|frame| {
+            frame.iter().map(|&s| s as f32).sum::<f32>() / (channels as f32 * i16::MAX as f32)
+        })
+        .collect())
 }
 
 /// Encode audio samples to WAV bytes in memory (for cloud API upload)
@@ -0,0 +1,360 @@
+//! Experimental speaker diarization without a dedicated model.
+//!
+//! This is a heuristic, not a speaker-embedding model: it can tell apart
+//! clearly different voices in clean audio but merges similar voices and is
+//! easily thrown off by noise, music or changing microphones. Prefer a
+//! provider that reports speakers itself when accuracy matters.
+//!
+//! Speech found by the VAD is cut into windows of a few seconds. Each window
+//! is described by its average log mel spectrum with the overall level
+//! removed, which mostly reflects the speaker's voice rather than what is
+//! said. Windows are clustered online by distance to the running speaker
+//! centroids, reassigned once to the final centroids, and adjacent windows
+//! of the same speaker are joined into turns.
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+
+const FFT_SIZE: usize = 512;
+const HOP: usize = 160;
+const MEL_BANDS: usize = 24;
+const MEL_LOW_HZ: f32 = 100.0;
+const MEL_HIGH_HZ: f32 = 7000.0;
+
+/// Longest stretch of speech described by one embedding.
+const WINDOW_SECS: f32 = 3.0;
+
+/// Pauses shorter than this do not end a speech region.
+const MAX_GAP_SECS: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiarizationConfig {
+    /// Upper bound on distinct speakers; further voices join the closest.
+    pub max_speakers: usize,
+    /// RMS distance (natural-log units per mel band) beyond which a window
+    /// starts a new speaker. Tuned by hand on a few recordings.
+    pub threshold: f32,
+}
+
+impl Default for DiarizationConfig {
+    fn default() -> Self {
+        Self {
+            max_speakers: 6,
+            threshold: 1.0,
+        }
+    }
+}
+
+/// A stretch of audio attributed to one speaker, as sample offsets into the
+/// analysed recording. Speakers are numbered from 0 in order of appearance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeakerTurn {
+    pub speaker: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Split `samples` into speaker turns. `speech` holds one VAD decision per
+/// `frame_len` samples.
+pub fn diarize(
+    samples: &[f32],
+    speech: &[bool],
+    frame_len: usize,
+    config: DiarizationConfig,
+) -> Vec<SpeakerTurn> {
+    let max_gap = (MAX_GAP_SECS * WHISPER_SAMPLE_RATE as f32) as usize;
+    let window = (WINDOW_SECS * WHISPER_SAMPLE_RATE as f32) as usize;
+
+    let windows: Vec<(usize, usize)> = speech_regions(speech, frame_len, max_gap)
+        .into_iter()
+        .map(|(start, end)| (start.min(samples.len()), end.min(samples.len())))
+        .flat_map(|(start, end)| split_region(start, end, window))
+        .filter(|(start, end)| end - start >= FFT_SIZE)
+        .collect();
+    if windows.is_empty() {
+        return Vec::new();
+    }
+
+    let embedder = Embedder::new();
+    let embeddings: Vec<Vec<f32>> = windows
+        .iter()
+        .map(|&(start, end)| embedder.embed(&samples[start..end]))
+        .collect();
+    let labels = cluster(&embeddings, config);
+
+    let mut turns: Vec<SpeakerTurn> = Vec::new();
+    for (&(start, end), &speaker) in windows.iter().zip(&labels) {
+        match turns.last_mut() {
+            Some(last) if last.speaker == speaker => last.end = end,
+            _ => turns.push(SpeakerTurn {
+                speaker,
+                start,
+                end,
+            }),
+        }
+    }
+    turns
+}
+
+/// Sample ranges of speech, bridging pauses up to `max_gap` samples.
+fn speech_regions(speech: &[bool], frame_len: usize, max_gap: usize) -> Vec<(usize, usize)> {
+    let mut regions: Vec<(usize, usize)> = Vec::new();
+    for (i, _) in speech.iter().enumerate().filter(|(_, s)| **s) {
+        let (start, end) = (i * frame_len, (i + 1) * frame_len);
+        match regions.last_mut() {
+            Some(last) if start - last.1 <= max_gap => last.1 = end,
+            _ => regions.push((start, end)),
+        }
+    }
+    regions
+}
+
+/// Cut a region into near-equal windows no longer than `window`.
+fn split_region(start: usize, end: usize, window: usize) -> Vec<(usize, usize)> {
+    let len = end - start;
+    let count = len.div_ceil(window).max(1);
+    (0..count)
+        .map(|i| (start + len * i / count, start + len * (i + 1) / count))
+        .collect()
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    let sum: f32 = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+    (sum / a.len() as f32).sqrt()
+}
+
+fn nearest(centroids: &[Vec<f32>], embedding: &[f32]) -> Option<(usize, f32)> {
+    centroids
+        .iter()
+        .map(|c| distance(c, embedding))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Label each embedding with a speaker index, numbered by first appearance.
+fn cluster(embeddings: &[Vec<f32>], config: DiarizationConfig) -> Vec<usize> {
+    let dims = embeddings.first().map_or(0, Vec::len);
+    let mut centroids: Vec<Vec<f32>> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+
+    // Online pass: join the nearest speaker or open a new one
+    for embedding in embeddings {
+        match nearest(&centroids, embedding) {
+            Some((idx, dist))
+                if dist <= config.threshold || centroids.len() >= config.max_speakers.max(1) =>
+            {
+                counts[idx] += 1;
+                let n = counts[idx] as f32;
+                for (c, e) in centroids[idx].iter_mut().zip(embedding) {
+                    *c += (e - *c) / n;
+                }
+            }
+            _ => {
+                centroids.push(embedding.clone());
+                counts.push(1);
+            }
+        }
+    }
+
+    // Refinement: early windows were placed against immature centroids
+    let labels: Vec<usize> = embeddings
+        .iter()
+        .map(|e| nearest(&centroids, e).map_or(0, |(idx, _)| idx))
+        .collect();
+    let mut sums = vec![vec![0.0f32; dims]; centroids.len()];
+    let mut sizes = vec![0usize; centroids.len()];
+    for (e, &label) in embeddings.iter().zip(&labels) {
+        sizes[label] += 1;
+        for (s, v) in sums[label].iter_mut().zip(e) {
+            *s += v;
+        }
+    }
+    let refined: Vec<Vec<f32>> = sums
+        .into_iter()
+        .zip(&sizes)
+        .filter(|(_, &size)| size > 0)
+        .map(|(sum, &size)| sum.into_iter().map(|s| s / size as f32).collect())
+        .collect();
+
+    // Renumber in order of appearance
+    let mut order: Vec<usize> = Vec::new();
+    embeddings
+        .iter()
+        .map(|e| {
+            let idx = nearest(&refined, e).map_or(0, |(idx, _)| idx);
+            match order.iter().position(|&o| o == idx) {
+                Some(pos) => pos,
+                None => {
+                    order.push(idx);
+                    order.len() - 1
+                }
+            }
+        })
+        .collect()
+}
+
+/// Computes the level-normalised mean log mel spectrum of a window.
+struct Embedder {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    /// Per band: (first bin, triangle weights from that bin)
+    bands: Vec<(usize, Vec<f32>)>,
+}
+
+impl Embedder {
+    fn new() -> Self {
+        let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+        let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+        let bin_hz = WHISPER_SAMPLE_RATE as f32 / FFT_SIZE as f32;
+
+        let (low, high) = (hz_to_mel(MEL_LOW_HZ), hz_to_mel(MEL_HIGH_HZ));
+        let edges: Vec<f32> = (0..MEL_BANDS + 2)
+            .map(|i| mel_to_hz(low + (high - low) * i as f32 / (MEL_BANDS + 1) as f32) / bin_hz)
+            .collect();
+        let bands = edges
+            .windows(3)
+            .map(|e| {
+                let first = e[0].ceil() as usize;
+                let last = e[2].floor() as usize;
+                let weights = (first..=last)
+                    .map(|bin| {
+                        let bin = bin as f32;
+                        if bin <= e[1] {
+                            (bin - e[0]) / (e[1] - e[0])
+                        } else {
+                            (e[2] - bin) / (e[2] - e[1])
+                        }
+                    })
+                    .collect();
+                (first, weights)
+            })
+            .collect();
+
+        Self {
+            fft: FftPlanner::new().plan_fft_forward(FFT_SIZE),
+            window: (0..FFT_SIZE)
+                .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / FFT_SIZE as f32).cos())
+                .collect(),
+            bands,
+        }
+    }
+
+    fn embed(&self, samples: &[f32]) -> Vec<f32> {
+        let mut mean = vec![0.0f32; MEL_BANDS];
+        let mut frames = 0usize;
+        let mut spectrum = vec![Complex32::default(); FFT_SIZE];
+
+        for start in (0..=samples.len().saturating_sub(FFT_SIZE)).step_by(HOP) {
+            let frame = &samples[start..start + FFT_SIZE];
+            for ((s, x), w) in spectrum.iter_mut().zip(frame).zip(&self.window) {
+                *s = Complex32::new(x * w, 0.0);
+            }
+            self.fft.process(&mut spectrum);
+
+            for (m, (first, weights)) in mean.iter_mut().zip(&self.bands) {
+                let energy: f32 = weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| w * spectrum[first + i].norm_sqr())
+                    .sum();
+                *m += (energy + 1e-10).ln();
+            }
+            frames += 1;
+        }
+
+        let frames = frames.max(1) as f32;
+        mean.iter_mut().for_each(|m| *m /= frames);
+        // Remove the overall level so loudness does not separate speakers
+        let level = mean.iter().sum::<f32>() / MEL_BANDS as f32;
+        mean.iter_mut().for_each(|m| *m -= level);
+        mean
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: usize = 480;
+
+    /// A crude voice: harmonics of `f0` shaped by one formant at `formant`.
+    fn voice(f0: f32, formant: f32, secs: f32, seed: u32) -> Vec<f32> {
+        let n = (secs * WHISPER_SAMPLE_RATE as f32) as usize;
+        let mut noise = seed.wrapping_mul(2654435761);
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / WHISPER_SAMPLE_RATE as f32;
+                let tone: f32 = (1..40)
+                    .map(|h| {
+                        let f = f0 * h as f32;
+                        let gain = (-((f - formant) / 400.0).powi(2)).exp();
+                        gain * (2.0 * PI * f * t).sin()
+                    })
+                    .sum();
+                noise = noise.wrapping_mul(1664525).wrapping_add(1013904223);
+                0.1 * tone + 0.001 * ((noise >> 8) as f32 / (1u32 << 24) as f32 - 0.5)
+            })
+            .collect()
+    }
+
+    fn silence(secs: f32) -> Vec<f32> {
+        vec![0.0; (secs * WHISPER_SAMPLE_RATE as f32) as usize]
+    }
+
+    fn speech_flags(samples: &[f32]) -> Vec<bool> {
+        samples
+            .chunks(FRAME)
+            .map(|f| f.iter().any(|s| s.abs() > 0.01))
+            .collect()
+    }
+
+    #[test]
+    fn separates_two_alternating_voices() {
+        let mut samples = Vec::new();
+        for turn in 0..4 {
+            if turn % 2 == 0 {
+                samples.extend(voice(110.0, 600.0, 2.0, turn));
+            } else {
+                samples.extend(voice(210.0, 2200.0, 2.0, turn));
+            }
+            samples.extend(silence(0.5));
+        }
+
+        let speech = speech_flags(&samples);
+        let turns = diarize(&samples, &speech, FRAME, DiarizationConfig::default());
+
+        let speakers: Vec<usize> = turns.iter().map(|t| t.speaker).collect();
+        assert_eq!(speakers, vec![0, 1, 0, 1]);
+        assert!(turns.windows(2).all(|w| w[0].end <= w[1].start));
+    }
+
+    #[test]
+    fn one_voice_stays_one_speaker() {
+        let mut samples = Vec::new();
+        for turn in 0..3 {
+            samples.extend(voice(140.0, 900.0, 2.0, turn));
+            samples.extend(silence(0.6));
+        }
+
+        let speech = speech_flags(&samples);
+        let turns = diarize(&samples, &speech, FRAME, DiarizationConfig::default());
+
+        assert!(!turns.is_empty());
+        assert!(turns.iter().all(|t| t.speaker == 0));
+
+        let quiet = silence(1.0);
+        let no_speech = vec![false; quiet.len() / FRAME];
+        assert!(diarize(&quiet, &no_speech, FRAME, DiarizationConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn speech_regions_bridge_short_pauses() {
+        let speech = [true, true, false, true, false, false, false, true];
+        assert_eq!(speech_regions(&speech, 10, 10), vec![(0, 40), (70, 80)]);
+        assert_eq!(split_region(0, 100, 40), vec![(0, 33), (33, 66), (66, 100)]);
+    }
+}
@@ -0,0 +1,18 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+
+/// Decides whether a short, isolated utterance is the wake phrase.
+///
+/// The recorder calls this on a spotting thread of its own for each candidate
+/// cut out of the idle stream. Candidates arriving while one is checked are
+/// dropped, so implementations should use a small model that keeps up with
+/// speech and must not share an engine with dictation.
+pub trait KeywordSpotter: Send {
+    fn detect(&mut self, utterance: &[f32]) -> Result<bool>;
+}
+
+/// A spotter slot shared between a recorder and whoever loads the model, so
+/// it can be swapped or cleared while the stream runs. The spotter itself is
+/// shared too, so swapping it never waits for a detection in progress.
+pub type SharedKeywordSpotter = Arc<Mutex<Option<Arc<Mutex<dyn KeywordSpotter>>>>>;
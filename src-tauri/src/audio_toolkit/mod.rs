@@ -1,5 +1,7 @@
 pub mod audio;
 pub mod constants;
+pub mod diarization;
+pub mod keyword;
 pub mod text;
 pub mod utils;
 pub mod vad;
@@ -7,6 +9,7 @@ pub mod vad;
 pub use audio::{
     list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
 };
-pub use text::{apply_custom_words, filter_transcription_output};
+pub use keyword::{KeywordSpotter, SharedKeywordSpotter};
+pub use text::{apply_custom_words, contains_wake_phrase, filter_transcription_output};
 pub use utils::get_cpal_host;
 pub use vad::{SileroVad, VoiceActivityDetector};
@@ -300,6 +300,35 @@ pub fn filter_transcription_output(text: &str) -> String {
     }
 }
 
+/// Checks whether a short transcription contains a wake phrase
+///
+/// Words are compared the same way custom words are: punctuation stripped,
+/// lowercased and joined without spaces, so "Hey, Handless!" matches
+/// "hey handless". Windows of up to one word more than the phrase are
+/// compared, so words a model splits or merges still match, and a small
+/// edit distance tolerates near-miss spellings from small models.
+///
+/// # Arguments
+/// * `text` - The transcription of the candidate utterance
+/// * `phrase` - The configured wake phrase
+pub fn contains_wake_phrase(text: &str, phrase: &str) -> bool {
+    let phrase_words: Vec<&str> = phrase.split_whitespace().collect();
+    let target = build_ngram(&phrase_words);
+    if target.is_empty() {
+        return false;
+    }
+
+    let max_distance = (target.chars().count() / 5).max(1);
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    (1..=phrase_words.len() + 1).any(|size| {
+        words.windows(size).any(|window| {
+            let candidate = build_ngram(window);
+            !candidate.is_empty() && levenshtein(&candidate, &target) <= max_distance
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -475,4 +504,14 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_contains_wake_phrase() {
+        assert!(contains_wake_phrase("Hey, Handless!", "hey handless"));
+        assert!(contains_wake_phrase("Okay. Hey handles.", "hey handless"));
+        assert!(contains_wake_phrase("Heyhandless.", "hey handless"));
+        assert!(!contains_wake_phrase("hello there", "hey handless"));
+        assert!(!contains_wake_phrase("hey", "hey handless"));
+        assert!(!contains_wake_phrase("hey handless", "  "));
+    }
 }
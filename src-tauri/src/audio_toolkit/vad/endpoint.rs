@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+/// When a recording should end on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoStopConfig {
+    /// Trailing silence after speech that ends the recording.
+    pub silence: Duration,
+    /// Speech required before trailing silence can end the recording.
+    pub min_speech: Duration,
+    /// Hard limit on the recording length. `None` means unlimited.
+    pub max_duration: Option<Duration>,
+    /// Silence without any speech for this long is dropped and the
+    /// recording starts over, so listening continuously never runs into
+    /// `max_duration`. `None` keeps the silence.
+    pub no_speech: Option<Duration>,
+}
+
+/// Decides from per-frame VAD results when a recording has ended.
+pub struct Endpointer {
+    config: AutoStopConfig,
+    speech: Duration,
+    trailing_silence: Duration,
+    total: Duration,
+    fired: bool,
+}
+
+impl Endpointer {
+    pub fn new(config: AutoStopConfig) -> Self {
+        Self {
+            config,
+            speech: Duration::ZERO,
+            trailing_silence: Duration::ZERO,
+            total: Duration::ZERO,
+            fired: false,
+        }
+    }
+
+    /// Feed the VAD decision for one frame. Returns true exactly once, on the
+    /// frame where the recording should stop.
+    pub fn push(&mut self, is_speech: bool, frame: Duration) -> bool {
+        if self.fired {
+            return false;
+        }
+
+        self.total += frame;
+        if is_speech {
+            self.speech += frame;
+            self.trailing_silence = Duration::ZERO;
+        } else if !self.speech.is_zero() {
+            self.trailing_silence += frame;
+        }
+
+        let over_limit = self
+            .config
+            .max_duration
+            .is_some_and(|max| self.total >= max);
+        let finished_speaking = self.speech >= self.config.min_speech
+            && !self.speech.is_zero()
+            && self.trailing_silence >= self.config.silence;
+
+        self.fired = over_limit || finished_speaking;
+        self.fired
+    }
+
+    /// Start counting from scratch if nothing has been said for the
+    /// `no_speech` timeout. Returns true when the caller should drop the
+    /// silence recorded so far.
+    pub fn restart_if_silent(&mut self) -> bool {
+        let silent = self.speech.is_zero()
+            && self
+                .config
+                .no_speech
+                .is_some_and(|timeout| self.total >= timeout);
+        if silent {
+            *self = Self::new(self.config);
+        }
+        silent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(30);
+
+    fn config() -> AutoStopConfig {
+        AutoStopConfig {
+            silence: Duration::from_millis(300),
+            min_speech: Duration::from_millis(150),
+            max_duration: Some(Duration::from_secs(2)),
+            no_speech: None,
+        }
+    }
+
+    fn feed(endpointer: &mut Endpointer, is_speech: bool, frames: usize) -> Option<usize> {
+        (0..frames).position(|_| endpointer.push(is_speech, FRAME))
+    }
+
+    #[test]
+    fn stops_after_trailing_silence_following_speech() {
+        let mut endpointer = Endpointer::new(config());
+        // Leading silence never ends the recording on its own
+        assert_eq!(feed(&mut endpointer, false, 20), None);
+        assert_eq!(feed(&mut endpointer, true, 10), None);
+        assert_eq!(feed(&mut endpointer, false, 20), Some(9));
+        // Fires only once
+        assert_eq!(feed(&mut endpointer, false, 20), None);
+    }
+
+    #[test]
+    fn short_blips_do_not_count_as_speech() {
+        let mut endpointer = Endpointer::new(config());
+        assert_eq!(feed(&mut endpointer, true, 2), None);
+        assert_eq!(feed(&mut endpointer, false, 20), None);
+    }
+
+    #[test]
+    fn stops_at_max_duration() {
+        let mut endpointer = Endpointer::new(config());
+        assert_eq!(feed(&mut endpointer, true, 100), Some(66));
+
+        let mut unlimited = Endpointer::new(AutoStopConfig {
+            max_duration: None,
+            ..config()
+        });
+        assert_eq!(feed(&mut unlimited, true, 1000), None);
+    }
+
+    #[test]
+    fn restarts_after_silence_without_speech() {
+        let mut endpointer = Endpointer::new(AutoStopConfig {
+            no_speech: Some(Duration::from_millis(900)),
+            ..config()
+        });
+        assert_eq!(feed(&mut endpointer, false, 29), None);
+        assert!(!endpointer.restart_if_silent());
+        assert_eq!(feed(&mut endpointer, false, 1), None);
+        assert!(endpointer.restart_if_silent());
+
+        // The silence no longer counts towards the maximum duration
+        assert_eq!(feed(&mut endpointer, false, 60), None);
+
+        // Once something was said the recording ends as usual
+        let mut endpointer = Endpointer::new(AutoStopConfig {
+            no_speech: Some(Duration::from_millis(900)),
+            max_duration: None,
+            ..config()
+        });
+        assert_eq!(feed(&mut endpointer, true, 10), None);
+        assert_eq!(feed(&mut endpointer, false, 40), Some(9));
+        assert!(!endpointer.restart_if_silent());
+    }
+}
@@ -25,8 +25,12 @@ pub trait VoiceActivityDetector: Send + Sync {
     fn reset(&mut self) {}
 }
 
+mod endpoint;
 mod silero;
 mod smoothed;
+mod utterance;
 
+pub use endpoint::{AutoStopConfig, Endpointer};
 pub use silero::SileroVad;
 pub use smoothed::SmoothedVad;
+pub use utterance::UtteranceSegmenter;
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+/// Shortest speech that can be a wake phrase.
+const MIN_SPEECH: Duration = Duration::from_millis(250);
+/// Longer speech is ordinary talking, not a wake phrase.
+const MAX_SPEECH: Duration = Duration::from_millis(2500);
+/// Silence that ends a candidate utterance.
+const TRAILING_SILENCE: Duration = Duration::from_millis(300);
+
+/// Cuts short, isolated utterances out of the idle stream so they can be
+/// checked for a wake phrase. Speech that runs past `MAX_SPEECH` is skipped
+/// until the next pause, so normal conversation is never transcribed.
+#[derive(Default)]
+pub struct UtteranceSegmenter {
+    samples: Vec<f32>,
+    speech: Duration,
+    trailing_silence: Duration,
+    too_long: bool,
+}
+
+impl UtteranceSegmenter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one frame with its VAD decision. `samples` may span several
+    /// frames when the VAD releases its prefill. Returns the audio of a
+    /// finished candidate utterance.
+    pub fn push(&mut self, samples: &[f32], is_speech: bool, frame: Duration) -> Option<Vec<f32>> {
+        if is_speech {
+            self.speech += frame;
+            self.trailing_silence = Duration::ZERO;
+            if self.speech > MAX_SPEECH {
+                self.too_long = true;
+                self.samples.clear();
+            }
+            if !self.too_long {
+                self.samples.extend_from_slice(samples);
+            }
+            return None;
+        }
+
+        if self.speech.is_zero() {
+            return None;
+        }
+
+        self.trailing_silence += frame;
+        if self.trailing_silence < TRAILING_SILENCE {
+            return None;
+        }
+
+        let candidate = !self.too_long && self.speech >= MIN_SPEECH;
+        let samples = std::mem::take(&mut self.samples);
+        self.reset();
+        candidate.then_some(samples)
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.speech = Duration::ZERO;
+        self.trailing_silence = Duration::ZERO;
+        self.too_long = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(30);
+
+    fn feed(
+        segmenter: &mut UtteranceSegmenter,
+        is_speech: bool,
+        frames: usize,
+    ) -> Option<Vec<f32>> {
+        let mut out = None;
+        for _ in 0..frames {
+            if let Some(samples) = segmenter.push(&[1.0; 4], is_speech, FRAME) {
+                out = Some(samples);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn emits_short_utterance_after_pause() {
+        let mut segmenter = UtteranceSegmenter::new();
+        assert!(feed(&mut segmenter, false, 10).is_none());
+        assert!(feed(&mut segmenter, true, 30).is_none());
+        let utterance = feed(&mut segmenter, false, 10).expect("utterance");
+        assert_eq!(utterance.len(), 30 * 4);
+    }
+
+    #[test]
+    fn ignores_blips_and_long_speech() {
+        let mut segmenter = UtteranceSegmenter::new();
+        feed(&mut segmenter, true, 3);
+        assert!(feed(&mut segmenter, false, 20).is_none());
+
+        feed(&mut segmenter, true, 200);
+        assert!(feed(&mut segmenter, false, 20).is_none());
+
+        // Back to normal after the pause
+        feed(&mut segmenter, true, 20);
+        assert!(feed(&mut segmenter, false, 20).is_some());
+    }
+}
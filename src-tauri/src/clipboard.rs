@@ -1,17 +1,230 @@
+use crate::clipboard_snapshot::{self, ClipboardSnapshot};
 use crate::input::{self, EnigoState};
+use crate::input_method;
 #[cfg(target_os = "linux")]
 use crate::settings::TypingTool;
 use crate::settings::{get_settings, AutoSubmitKey, ClipboardHandling, PasteMethod};
 use enigo::{Direction, Enigo, Key, Keyboard};
 use log::info;
 use std::process::Command;
-use std::time::Duration;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 use tauri_plugin_clipboard_manager::ClipboardExt;
+use unicode_segmentation::UnicodeSegmentation;
 
+#[cfg(target_os = "linux")]
+use crate::uinput_keyboard;
 #[cfg(target_os = "linux")]
 use crate::utils::{is_kde_wayland, is_wayland};
 
+/// What the last paste inserted, so it can be taken back.
+#[derive(Clone, Debug, PartialEq)]
+struct Insertion {
+    /// The inserted text, including an appended trailing space
+    text: String,
+    method: PasteMethod,
+    /// History entry the text came from
+    history_id: Option<i64>,
+    /// Whether auto-submit sent the text on
+    submitted: bool,
+    /// When the text was inserted
+    inserted_at: Instant,
+    /// The window that received the text, if it can be identified
+    window: Option<String>,
+}
+
+static LAST_INSERTION: Mutex<Option<Insertion>> = Mutex::new(None);
+
+/// How long after a paste it can still be undone. Later, the user has
+/// likely moved on and the keys would hit unrelated text.
+const UNDO_WINDOW: Duration = Duration::from_secs(60);
+
+/// Keys that take an insertion back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Keystroke {
+    /// The application's undo shortcut, for text pasted in one step
+    Undo,
+    /// One Backspace per typed character
+    Backspaces(usize),
+}
+
+fn undo_keystroke(insertion: &Insertion) -> Option<Keystroke> {
+    // One Backspace removes one user-perceived character
+    let backspaces = Keystroke::Backspaces(insertion.text.graphemes(true).count());
+    match insertion.method {
+        PasteMethod::Direct | PasteMethod::InputMethod => Some(backspaces),
+        PasteMethod::CtrlV => Some(Keystroke::Undo),
+        // These paste into terminals, which have no undo and where Ctrl+Z
+        // suspends the foreground job. A pasted line break may already have
+        // run a command, so only a single line is erased.
+        PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
+            (!insertion.text.contains('\n')).then_some(backspaces)
+        }
+        PasteMethod::None | PasteMethod::ExternalScript => None,
+    }
+}
+
+/// Identifies the focused window where the platform lets us, so an undo
+/// never sends keys to a different window than the paste went to. On Linux
+/// this only works on X11: Wayland doesn't expose the active window to other
+/// clients, so there the guard relies on the undo time limit alone.
+fn focused_window() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        if is_wayland() {
+            return None;
+        }
+        x11_active_window::get()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+        let window = unsafe { GetForegroundWindow() };
+        (!window.is_invalid()).then(|| format!("{:?}", window.0))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::output_sinks::frontmost_app()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Why the last insertion wasn't taken back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UndoRefusal {
+    NothingToUndo,
+    /// Auto-submit already sent the text on
+    Submitted,
+    /// Older than [`UNDO_WINDOW`]
+    TooOld,
+    /// The window the text went to is no longer focused
+    WindowChanged,
+    /// The paste method has no safe way to take text back
+    NotSupported,
+}
+
+/// Why an undo didn't happen: a refusal to show the user, or a failure.
+#[derive(Debug)]
+pub enum UndoError {
+    Refused(UndoRefusal),
+    Failed(String),
+}
+
+impl std::fmt::Display for UndoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Refused(refusal) => write!(f, "Undo refused: {:?}", refusal),
+            Self::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<String> for UndoError {
+    fn from(e: String) -> Self {
+        Self::Failed(e)
+    }
+}
+
+impl From<&str> for UndoError {
+    fn from(e: &str) -> Self {
+        Self::Failed(e.to_string())
+    }
+}
+
+/// Why `insertion` can no longer be taken back, if it can't.
+fn undo_blocker(insertion: &Insertion, window: Option<&str>) -> Option<UndoRefusal> {
+    if insertion.submitted {
+        return Some(UndoRefusal::Submitted);
+    }
+    if insertion.inserted_at.elapsed() > UNDO_WINDOW {
+        return Some(UndoRefusal::TooOld);
+    }
+    match (insertion.window.as_deref(), window) {
+        (Some(pasted_into), Some(focused)) if pasted_into != focused => {
+            Some(UndoRefusal::WindowChanged)
+        }
+        _ => None,
+    }
+}
+
+/// Reads `_NET_ACTIVE_WINDOW` over one X11 connection that is kept open, so
+/// recording the pasted-into window costs a round trip rather than a process.
+#[cfg(target_os = "linux")]
+mod x11_active_window {
+    use std::sync::Mutex;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt as _, Window};
+    use x11rb::rust_connection::RustConnection;
+
+    struct Display {
+        conn: RustConnection,
+        root: Window,
+        active_window: Atom,
+    }
+
+    static DISPLAY: Mutex<Option<Display>> = Mutex::new(None);
+
+    fn connect() -> Option<Display> {
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots[screen_num].root;
+        let active_window = conn
+            .intern_atom(false, b"_NET_ACTIVE_WINDOW")
+            .ok()?
+            .reply()
+            .ok()?
+            .atom;
+        Some(Display {
+            conn,
+            root,
+            active_window,
+        })
+    }
+
+    fn query(display: &Display) -> Result<Option<String>, ()> {
+        let reply = display
+            .conn
+            .get_property(
+                false,
+                display.root,
+                display.active_window,
+                AtomEnum::WINDOW,
+                0,
+                1,
+            )
+            .map_err(|_| ())?
+            .reply()
+            .map_err(|_| ())?;
+        Ok(reply
+            .value32()
+            .and_then(|mut ids| ids.next())
+            .filter(|id| *id != 0)
+            .map(|id| id.to_string()))
+    }
+
+    pub(super) fn get() -> Option<String> {
+        let mut display = DISPLAY.lock().unwrap();
+        if display.is_none() {
+            *display = connect();
+        }
+        match query(display.as_ref()?) {
+            Ok(window) => window,
+            Err(()) => {
+                // The connection broke; reconnect next time
+                *display = None;
+                None
+            }
+        }
+    }
+}
+
 /// Pastes text using the clipboard: saves current content, writes text, sends paste keystroke, restores clipboard.
 fn paste_via_clipboard(
     enigo: &mut Enigo,
@@ -19,28 +232,12 @@ fn paste_via_clipboard(
     app_handle: &AppHandle,
     paste_method: &PasteMethod,
     paste_delay_ms: u64,
+    restore_delay_ms: Option<u64>,
 ) -> Result<(), String> {
-    let clipboard = app_handle.clipboard();
-    let clipboard_content = clipboard.read_text().unwrap_or_default();
+    let snapshot =
+        restore_delay_ms.map(|delay_ms| (ClipboardSnapshot::take_or_capture(app_handle), delay_ms));
 
-    // Write text to clipboard first
-    // On Wayland, prefer wl-copy for better compatibility (especially with umlauts)
-    #[cfg(target_os = "linux")]
-    let write_result = if is_wayland() && is_wl_copy_available() {
-        info!("Using wl-copy for clipboard write on Wayland");
-        write_clipboard_via_wl_copy(text)
-    } else {
-        clipboard
-            .write_text(text)
-            .map_err(|e| format!("Failed to write to clipboard: {}", e))
-    };
-
-    #[cfg(not(target_os = "linux"))]
-    let write_result = clipboard
-        .write_text(text)
-        .map_err(|e| format!("Failed to write to clipboard: {}", e));
-
-    write_result?;
+    write_paste_text(app_handle, text)?;
 
     std::thread::sleep(Duration::from_millis(paste_delay_ms));
 
@@ -61,21 +258,38 @@ fn paste_via_clipboard(
         }
     }
 
-    std::thread::sleep(std::time::Duration::from_millis(50));
+    // Restore original clipboard content once the target app had time to
+    // read the pasted text
+    if let Some((snapshot, delay_ms)) = snapshot {
+        snapshot.restore_later(
+            app_handle,
+            text.to_string(),
+            Duration::from_millis(delay_ms),
+        );
+    }
+
+    Ok(())
+}
 
-    // Restore original clipboard content
-    // On Wayland, prefer wl-copy for better compatibility
+/// Write the text to paste to the clipboard.
+fn write_paste_text(app_handle: &AppHandle, text: &str) -> Result<(), String> {
     #[cfg(target_os = "linux")]
-    if is_wayland() && is_wl_copy_available() {
-        let _ = write_clipboard_via_wl_copy(&clipboard_content);
-    } else {
-        let _ = clipboard.write_text(&clipboard_content);
+    {
+        match clipboard_snapshot::write_transient_text(text) {
+            Ok(()) => return Ok(()),
+            Err(e) => info!("Falling back to plain clipboard write: {}", e),
+        }
+        // On Wayland, prefer wl-copy for better compatibility (especially with umlauts)
+        if is_wayland() && is_wl_copy_available() {
+            info!("Using wl-copy for clipboard write on Wayland");
+            return write_clipboard_via_wl_copy(text);
+        }
     }
 
-    #[cfg(not(target_os = "linux"))]
-    let _ = clipboard.write_text(&clipboard_content);
-
-    Ok(())
+    app_handle
+        .clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))
 }
 
 /// Attempts to send a key combination using Linux-native tools.
@@ -117,6 +331,48 @@ fn try_send_key_combo_linux(paste_method: &PasteMethod) -> Result<bool, String>
     Ok(false)
 }
 
+/// Attempts to send an undo keystroke using Linux-native tools, preferring
+/// them in the same order as `try_send_key_combo_linux`.
+/// Returns `Ok(true)` if a native tool handled it, `Ok(false)` to fall back to enigo.
+#[cfg(target_os = "linux")]
+fn try_send_keystroke_linux(keystroke: Keystroke) -> Result<bool, String> {
+    if is_wayland() {
+        if !is_kde_wayland() && is_wtype_available() {
+            info!("Using wtype for {:?}", keystroke);
+            send_keystroke_via_wtype(keystroke)?;
+            return Ok(true);
+        }
+        if is_dotool_available() {
+            info!("Using dotool for {:?}", keystroke);
+            send_keystroke_via_dotool(keystroke)?;
+            return Ok(true);
+        }
+        if is_ydotool_available() {
+            info!("Using ydotool for {:?}", keystroke);
+            send_keystroke_via_ydotool(keystroke)?;
+            return Ok(true);
+        }
+        if uinput_keyboard::is_available() {
+            info!("Using uinput keyboard for {:?}", keystroke);
+            send_keystroke_via_uinput(keystroke)?;
+            return Ok(true);
+        }
+    } else {
+        if is_xdotool_available() {
+            info!("Using xdotool for {:?}", keystroke);
+            send_keystroke_via_xdotool(keystroke)?;
+            return Ok(true);
+        }
+        if is_ydotool_available() {
+            info!("Using ydotool for {:?}", keystroke);
+            send_keystroke_via_ydotool(keystroke)?;
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
 /// Attempts to type text directly using Linux-native tools.
 /// Returns `Ok(true)` if a native tool handled it, `Ok(false)` to fall back to enigo.
 #[cfg(target_os = "linux")]
@@ -149,6 +405,11 @@ fn try_direct_typing_linux(text: &str, preferred_tool: TypingTool) -> Result<boo
                 type_text_via_xdotool(text)?;
                 Ok(true)
             }
+            TypingTool::Uinput if uinput_keyboard::is_available() => {
+                info!("Using user-specified uinput keyboard");
+                uinput_keyboard::type_text(text)?;
+                Ok(true)
+            }
             _ => Err(format!(
                 "Typing tool {:?} is not available on this system",
                 preferred_tool
@@ -195,6 +456,13 @@ fn try_direct_typing_linux(text: &str, preferred_tool: TypingTool) -> Result<boo
         }
     }
 
+    // Without any of the tools, type on a built-in virtual keyboard
+    if uinput_keyboard::is_available() {
+        info!("Using uinput keyboard for direct text input");
+        uinput_keyboard::type_text(text)?;
+        return Ok(true);
+    }
+
     Ok(false)
 }
 
@@ -218,6 +486,9 @@ pub fn get_available_typing_tools() -> Vec<String> {
     if is_xdotool_available() {
         tools.push("xdotool".to_string());
     }
+    if uinput_keyboard::is_available() {
+        tools.push("uinput".to_string());
+    }
     tools
 }
 
@@ -474,6 +745,118 @@ fn send_key_combo_via_ydotool(paste_method: &PasteMethod) -> Result<(), String>
     Ok(())
 }
 
+/// Send an undo keystroke via wtype on Wayland.
+#[cfg(target_os = "linux")]
+fn send_keystroke_via_wtype(keystroke: Keystroke) -> Result<(), String> {
+    let args: Vec<&str> = match keystroke {
+        Keystroke::Undo => vec!["-M", "ctrl", "-k", "z"],
+        Keystroke::Backspaces(count) => ["-k", "BackSpace"].repeat(count),
+    };
+
+    let output = Command::new("wtype")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to execute wtype: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("wtype failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+/// Send an undo keystroke via dotool.
+#[cfg(target_os = "linux")]
+fn send_keystroke_via_dotool(keystroke: Keystroke) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let commands = match keystroke {
+        Keystroke::Undo => "key ctrl+z\n".to_string(),
+        Keystroke::Backspaces(count) => "key backspace\n".repeat(count),
+    };
+
+    let mut child = Command::new("dotool")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to spawn dotool: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(commands.as_bytes())
+            .map_err(|e| format!("Failed to write to dotool stdin: {}", e))?;
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for dotool: {}", e))?;
+    if !status.success() {
+        return Err("dotool failed".into());
+    }
+
+    Ok(())
+}
+
+/// Send an undo keystroke via ydotool (requires ydotoold daemon).
+#[cfg(target_os = "linux")]
+fn send_keystroke_via_ydotool(keystroke: Keystroke) -> Result<(), String> {
+    // Keycodes: ctrl=29, z=44, backspace=14
+    let mut args = vec!["key"];
+    match keystroke {
+        Keystroke::Undo => args.extend(["29:1", "44:1", "44:0", "29:0"]),
+        Keystroke::Backspaces(count) => args.extend(["14:1", "14:0"].repeat(count)),
+    }
+
+    let output = Command::new("ydotool")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to execute ydotool: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ydotool failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+/// Send an undo keystroke via xdotool on X11.
+#[cfg(target_os = "linux")]
+fn send_keystroke_via_xdotool(keystroke: Keystroke) -> Result<(), String> {
+    let repeat;
+    let args: Vec<&str> = match keystroke {
+        Keystroke::Undo => vec!["key", "--clearmodifiers", "ctrl+z"],
+        Keystroke::Backspaces(count) => {
+            repeat = count.to_string();
+            vec!["key", "--clearmodifiers", "--repeat", &repeat, "BackSpace"]
+        }
+    };
+
+    let output = Command::new("xdotool")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to execute xdotool: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("xdotool failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+/// Send an undo keystroke on the built-in uinput keyboard.
+#[cfg(target_os = "linux")]
+fn send_keystroke_via_uinput(keystroke: Keystroke) -> Result<(), String> {
+    match keystroke {
+        Keystroke::Undo => uinput_keyboard::press_ctrl_with('z'),
+        Keystroke::Backspaces(count) => uinput_keyboard::press_backspaces(count),
+    }
+}
+
 /// Send a key combination (e.g., Ctrl+V) via xdotool on X11.
 #[cfg(target_os = "linux")]
 fn send_key_combo_via_xdotool(paste_method: &PasteMethod) -> Result<(), String> {
@@ -588,7 +971,9 @@ fn should_send_auto_submit(auto_submit: bool, paste_method: PasteMethod) -> bool
     auto_submit && paste_method != PasteMethod::None
 }
 
-pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
+/// Insert `text` with the configured paste method. `history_id` is the
+/// history entry the text came from, marked as retracted if it's undone.
+pub fn paste(text: String, app_handle: AppHandle, history_id: Option<i64>) -> Result<(), String> {
     let settings = get_settings(&app_handle);
     let paste_method = settings.paste_method;
     let paste_delay_ms = settings.paste_delay_ms;
@@ -628,12 +1013,16 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
             )?;
         }
         PasteMethod::CtrlV | PasteMethod::CtrlShiftV | PasteMethod::ShiftInsert => {
+            // Text copied to the clipboard afterwards replaces it anyway
+            let restore_delay_ms = (settings.clipboard_handling == ClipboardHandling::DontModify)
+                .then_some(settings.clipboard_restore_delay_ms);
             paste_via_clipboard(
                 &mut enigo,
                 &text,
                 &app_handle,
                 &paste_method,
                 paste_delay_ms,
+                restore_delay_ms,
             )?
         }
         PasteMethod::ExternalScript => {
@@ -644,13 +1033,26 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
                 .ok_or("External script path is not configured")?;
             paste_via_external_script(&text, script_path)?;
         }
+        PasteMethod::InputMethod => {
+            input_method::commit(&text)?;
+        }
     }
 
-    if should_send_auto_submit(settings.auto_submit, paste_method) {
+    let submitted = should_send_auto_submit(settings.auto_submit, paste_method);
+    if submitted {
         std::thread::sleep(Duration::from_millis(50));
         send_return_key(&mut enigo, settings.auto_submit_key)?;
     }
 
+    *LAST_INSERTION.lock().unwrap() = (paste_method != PasteMethod::None).then(|| Insertion {
+        text: text.clone(),
+        method: paste_method,
+        history_id,
+        submitted,
+        inserted_at: Instant::now(),
+        window: focused_window(),
+    });
+
     // After pasting, optionally copy to clipboard based on settings
     if settings.clipboard_handling == ClipboardHandling::CopyToClipboard {
         let clipboard = app_handle.clipboard();
@@ -662,6 +1064,54 @@ pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Take back the text inserted by the last paste: Backspaces for typed text,
+/// the undo shortcut for pasted text. Returns the history entry it came from.
+pub fn undo_last_insertion(app_handle: &AppHandle) -> Result<Option<i64>, UndoError> {
+    // Only consumed once the keys were sent, so a refused undo can be
+    // retried after switching back to the right window
+    let insertion = LAST_INSERTION
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(UndoError::Refused(UndoRefusal::NothingToUndo))?;
+    if let Some(refusal) = undo_blocker(&insertion, focused_window().as_deref()) {
+        return Err(UndoError::Refused(refusal));
+    }
+    let keystroke = undo_keystroke(&insertion).ok_or_else(|| {
+        info!("Text inserted with {:?} can't be undone", insertion.method);
+        UndoError::Refused(UndoRefusal::NotSupported)
+    })?;
+    info!("Undoing last insertion with {:?}", keystroke);
+
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    let mut enigo = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+
+    #[cfg(target_os = "linux")]
+    let keystroke_sent = try_send_keystroke_linux(keystroke)?;
+
+    #[cfg(not(target_os = "linux"))]
+    let keystroke_sent = false;
+
+    if !keystroke_sent {
+        match keystroke {
+            Keystroke::Undo => input::send_undo(&mut enigo)?,
+            Keystroke::Backspaces(count) => input::send_backspaces(&mut enigo, count)?,
+        }
+    }
+
+    let mut last = LAST_INSERTION.lock().unwrap();
+    if last.as_ref() == Some(&insertion) {
+        *last = None;
+    }
+
+    Ok(insertion.history_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -684,4 +1134,92 @@ mod tests {
         assert!(should_send_auto_submit(true, PasteMethod::CtrlShiftV));
         assert!(should_send_auto_submit(true, PasteMethod::ShiftInsert));
     }
+
+    fn insertion(text: &str, method: PasteMethod) -> Insertion {
+        Insertion {
+            text: text.to_string(),
+            method,
+            history_id: None,
+            submitted: false,
+            inserted_at: Instant::now(),
+            window: Some("editor".to_string()),
+        }
+    }
+
+    #[test]
+    fn typed_text_is_undone_with_one_backspace_per_character() {
+        assert_eq!(
+            undo_keystroke(&insertion("Grüße ", PasteMethod::Direct)),
+            Some(Keystroke::Backspaces(6))
+        );
+    }
+
+    #[test]
+    fn typed_text_is_undone_one_backspace_per_grapheme() {
+        // "e" with a combining accent and a family emoji are one key each
+        assert_eq!(
+            undo_keystroke(&insertion("cafe\u{301} 👨‍👩‍👧", PasteMethod::Direct)),
+            Some(Keystroke::Backspaces(6))
+        );
+    }
+
+    #[test]
+    fn committed_text_is_undone_like_typed_text() {
+        assert_eq!(
+            undo_keystroke(&insertion("你好", PasteMethod::InputMethod)),
+            Some(Keystroke::Backspaces(2))
+        );
+    }
+
+    #[test]
+    fn pasted_text_is_undone_with_the_undo_shortcut() {
+        assert_eq!(
+            undo_keystroke(&insertion("hello", PasteMethod::CtrlV)),
+            Some(Keystroke::Undo)
+        );
+        assert_eq!(
+            undo_keystroke(&insertion("hello", PasteMethod::ExternalScript)),
+            None
+        );
+    }
+
+    #[test]
+    fn terminal_pastes_are_erased_without_ctrl_z() {
+        for method in [PasteMethod::CtrlShiftV, PasteMethod::ShiftInsert] {
+            assert_eq!(
+                undo_keystroke(&insertion("ls -la", method)),
+                Some(Keystroke::Backspaces(6))
+            );
+            assert_eq!(undo_keystroke(&insertion("ls\nrm", method)), None);
+        }
+    }
+
+    #[test]
+    fn old_or_unfocused_insertions_are_not_undone() {
+        let recent = insertion("hello", PasteMethod::CtrlV);
+        assert_eq!(undo_blocker(&recent, Some("editor")), None);
+        assert_eq!(undo_blocker(&recent, None), None);
+        assert_eq!(
+            undo_blocker(&recent, Some("browser")),
+            Some(UndoRefusal::WindowChanged)
+        );
+
+        let old = Insertion {
+            inserted_at: Instant::now() - UNDO_WINDOW - Duration::from_secs(1),
+            ..recent.clone()
+        };
+        assert_eq!(
+            undo_blocker(&old, Some("editor")),
+            Some(UndoRefusal::TooOld)
+        );
+
+        let submitted = Insertion {
+            submitted: true,
+            ..recent
+        };
+        assert_eq!(
+            undo_blocker(&submitted, Some("editor")),
+            Some(UndoRefusal::Submitted)
+        );
+    }
 }
@@ -0,0 +1,838 @@
+//! Saving and restoring the clipboard around clipboard-based pastes
+//!
+//! Pasting puts the transcription on the clipboard for a moment. To give the
+//! user their clipboard back untouched, every format it offers (text, HTML,
+//! images, file lists, ...) is read beforehand and offered again afterwards.
+//! On Linux this talks to the X11 selection or the Wayland data-control
+//! protocol directly, from a background thread and within a time limit so a
+//! slow clipboard owner can't hold up pasting. Elsewhere only text and images
+//! can be read back, so those are kept. If nothing could be read, the
+//! clipboard is left holding the transcription rather than being replaced by
+//! something the user didn't copy. An empty clipboard is cleared again.
+//!
+//! Restoring waits for the target app to read the pasted text, which happens
+//! on its own thread so pasting and undo don't wait for it.
+//!
+//! Clipboard managers would record the transcription as a new entry, so it is
+//! offered with the `x-kde-passwordManagerHint` type that Klipper, CopyQ and
+//! others honour by not recording it.
+
+use log::{debug, info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Snapshots larger than this keep only their text. Reading stops as soon
+/// as the limit is passed.
+#[cfg(target_os = "linux")]
+const MAX_SNAPSHOT_BYTES: usize = 32 * 1024 * 1024;
+
+/// Formats beyond this many are left out of a snapshot.
+#[cfg(target_os = "linux")]
+const MAX_SNAPSHOT_FORMATS: usize = 32;
+
+/// How long pasting waits for all formats to be read before keeping only
+/// the text.
+#[cfg(target_os = "linux")]
+const CAPTURE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Clipboard managers don't record content offering this type.
+#[cfg(target_os = "linux")]
+const PASSWORD_MANAGER_HINT: &str = "x-kde-passwordManagerHint";
+
+/// X11 targets that describe the selection rather than hold its content.
+#[cfg(target_os = "linux")]
+const META_TARGETS: [&str; 7] = [
+    "TARGETS",
+    "TIMESTAMP",
+    "MULTIPLE",
+    "SAVE_TARGETS",
+    "DELETE",
+    "INSERT_SELECTION",
+    "INSERT_PROPERTY",
+];
+
+/// Types the text is offered under, in order of preference.
+#[cfg(target_os = "linux")]
+const TEXT_TYPES: [&str; 3] = ["text/plain;charset=utf-8", "UTF8_STRING", "text/plain"];
+
+/// What the clipboard held before pasting.
+pub enum ClipboardSnapshot {
+    /// Nothing was on the clipboard.
+    Empty,
+    /// Every format, as (MIME type or X11 target, data) pairs.
+    #[cfg(target_os = "linux")]
+    Formats(Vec<(String, Vec<u8>)>),
+    Text(String),
+    #[cfg(not(target_os = "linux"))]
+    Image(tauri::image::Image<'static>),
+    /// The content couldn't be read, so it isn't restored.
+    Unreadable,
+}
+
+/// A restore waiting for its delay to pass. The next paste takes it over
+/// instead of saving the clipboard again, which by then holds the previous
+/// transcription.
+struct PendingRestore {
+    snapshot: ClipboardSnapshot,
+    generation: u64,
+}
+
+static PENDING_RESTORE: Mutex<Option<PendingRestore>> = Mutex::new(None);
+static RESTORE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+impl ClipboardSnapshot {
+    /// The content to restore after the next paste: that of a restore still
+    /// waiting, or what the clipboard holds now.
+    pub fn take_or_capture(app: &AppHandle) -> Self {
+        match PENDING_RESTORE.lock().unwrap().take() {
+            Some(pending) => pending.snapshot,
+            None => Self::capture(app),
+        }
+    }
+
+    /// Read every format the clipboard currently offers.
+    pub fn capture(app: &AppHandle) -> Self {
+        // Pasting runs on the main thread, which mustn't wait on the
+        // clipboard owner for longer than the time limit
+        #[cfg(target_os = "linux")]
+        {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let deadline = Instant::now() + CAPTURE_TIMEOUT;
+            std::thread::spawn(move || {
+                let _ = tx.send(linux::read_all(deadline));
+            });
+            match rx.recv_timeout(CAPTURE_TIMEOUT) {
+                Ok(Ok(formats)) if formats.is_empty() => return Self::Empty,
+                Ok(Ok(formats)) => {
+                    debug!(
+                        "Saved clipboard formats: {}",
+                        formats
+                            .iter()
+                            .map(|(mime, _)| mime.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                    return Self::Formats(formats);
+                }
+                Ok(Err(e)) => debug!("Saving clipboard text only: {}", e),
+                Err(_) => debug!("Saving clipboard text only: reading it took too long"),
+            }
+        }
+
+        match app.clipboard().read_text() {
+            Ok(text) if !text.is_empty() => return Self::Text(text),
+            _ => {}
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        if let Ok(image) = app.clipboard().read_image() {
+            debug!("Saved clipboard image");
+            return Self::Image(image.to_owned());
+        }
+
+        debug!("Clipboard content can't be saved, it won't be restored");
+        Self::Unreadable
+    }
+
+    /// Put the saved content back on the clipboard after `delay`, on a
+    /// separate thread, unless something other than `pasted` was copied
+    /// meanwhile.
+    pub fn restore_later(self, app: &AppHandle, pasted: String, delay: Duration) {
+        let generation = RESTORE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        *PENDING_RESTORE.lock().unwrap() = Some(PendingRestore {
+            snapshot: self,
+            generation,
+        });
+
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            let mut pending = PENDING_RESTORE.lock().unwrap();
+            // A later paste took the snapshot over
+            if pending.as_ref().map(|p| p.generation) != Some(generation) {
+                return;
+            }
+            let snapshot = pending.take().unwrap().snapshot;
+            if holds_text(&app, &pasted) {
+                if let Err(e) = snapshot.restore(&app) {
+                    warn!("{}", e);
+                }
+            }
+        });
+    }
+
+    /// Put the saved content back on the clipboard.
+    pub fn restore(self, app: &AppHandle) -> Result<(), String> {
+        let text = match self {
+            Self::Unreadable => {
+                debug!("Leaving the clipboard as it is, its content wasn't saved");
+                return Ok(());
+            }
+            Self::Empty => {
+                return app
+                    .clipboard()
+                    .clear()
+                    .map_err(|e| format!("Failed to clear clipboard: {}", e));
+            }
+            #[cfg(target_os = "linux")]
+            Self::Formats(formats) => {
+                let text = linux::text_of(&formats);
+                match linux::write_all(formats) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => warn!("Restoring clipboard text only: {}", e),
+                }
+                text.ok_or("Failed to restore clipboard: it held no text")?
+            }
+            #[cfg(not(target_os = "linux"))]
+            Self::Image(image) => {
+                return app
+                    .clipboard()
+                    .write_image(&image)
+                    .map_err(|e| format!("Failed to restore clipboard: {}", e));
+            }
+            Self::Text(text) => text,
+        };
+
+        // Text alone is written natively too, which on Wayland handles
+        // non-ASCII text more reliably than the clipboard plugin
+        #[cfg(target_os = "linux")]
+        match linux::write_all(text_formats(&text)) {
+            Ok(()) => return Ok(()),
+            Err(e) => warn!("Restoring clipboard text with the plugin: {}", e),
+        }
+
+        app.clipboard()
+            .write_text(&text)
+            .map_err(|e| format!("Failed to restore clipboard: {}", e))
+    }
+}
+
+/// Put `text` on the clipboard for pasting, marked so clipboard managers
+/// don't record it.
+#[cfg(target_os = "linux")]
+pub fn write_transient_text(text: &str) -> Result<(), String> {
+    let mut formats = text_formats(text);
+    formats.push((PASSWORD_MANAGER_HINT.to_string(), b"secret".to_vec()));
+    linux::write_all(formats)
+}
+
+#[cfg(target_os = "linux")]
+fn text_formats(text: &str) -> Vec<(String, Vec<u8>)> {
+    TEXT_TYPES
+        .iter()
+        .map(|mime| (mime.to_string(), text.as_bytes().to_vec()))
+        .collect()
+}
+
+/// Whether the clipboard still holds `text`. If anything else put content on
+/// the clipboard meanwhile, that content is kept rather than overwritten.
+fn holds_text(app: &AppHandle, text: &str) -> bool {
+    match app.clipboard().read_text() {
+        Ok(current) if current != text => {
+            info!("Clipboard changed while pasting, not restoring it");
+            false
+        }
+        _ => true,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{MAX_SNAPSHOT_BYTES, MAX_SNAPSHOT_FORMATS, META_TARGETS, TEXT_TYPES};
+    use crate::utils::is_wayland;
+    use log::debug;
+    use std::time::Instant;
+
+    /// Read the clipboard's formats, giving up at `deadline`.
+    pub(super) fn read_all(deadline: Instant) -> Result<Vec<(String, Vec<u8>)>, String> {
+        if is_wayland() {
+            super::wayland::read_all(deadline)
+        } else {
+            super::x11::read_all(deadline)
+        }
+    }
+
+    /// The content formats among `targets`, at most [`MAX_SNAPSHOT_FORMATS`].
+    pub(super) fn content_targets(targets: Vec<String>) -> Vec<String> {
+        let content: Vec<String> = targets
+            .into_iter()
+            .filter(|name| !is_meta_target(name))
+            .collect();
+        if content.len() > MAX_SNAPSHOT_FORMATS {
+            debug!(
+                "Saving only the first {} of {} clipboard formats",
+                MAX_SNAPSHOT_FORMATS,
+                content.len()
+            );
+        }
+        content.into_iter().take(MAX_SNAPSHOT_FORMATS).collect()
+    }
+
+    /// Fail once the time is up or `size` bytes were read in total.
+    pub(super) fn check_limits(size: usize, deadline: Instant) -> Result<(), String> {
+        if size > MAX_SNAPSHOT_BYTES {
+            return Err(format!(
+                "clipboard holds more than {} bytes",
+                MAX_SNAPSHOT_BYTES
+            ));
+        }
+        if Instant::now() >= deadline {
+            return Err("reading the clipboard took too long".into());
+        }
+        Ok(())
+    }
+
+    pub(super) fn write_all(formats: Vec<(String, Vec<u8>)>) -> Result<(), String> {
+        if is_wayland() {
+            super::wayland::write_all(formats)
+        } else {
+            super::x11::write_all(formats)
+        }
+    }
+
+    /// The text among saved `formats`, if any.
+    pub(super) fn text_of(formats: &[(String, Vec<u8>)]) -> Option<String> {
+        TEXT_TYPES.iter().find_map(|text| {
+            formats
+                .iter()
+                .find(|(mime, _)| mime == text)
+                .map(|(_, data)| String::from_utf8_lossy(data).into_owned())
+        })
+    }
+
+    pub(super) fn is_meta_target(name: &str) -> bool {
+        META_TARGETS.contains(&name)
+    }
+
+    /// The single format to keep when only one can be offered: an image if
+    /// there is one, since its text is usually just a file name.
+    pub(super) fn preferred(formats: &[(String, Vec<u8>)]) -> Option<&(String, Vec<u8>)> {
+        formats
+            .iter()
+            .find(|(mime, _)| mime == "image/png")
+            .or_else(|| {
+                TEXT_TYPES
+                    .iter()
+                    .find_map(|text| formats.iter().find(|(mime, _)| mime == text))
+            })
+            .or_else(|| formats.first())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod wayland {
+    use log::debug;
+    use std::io::{Read, Write};
+    use std::process::{Command, Stdio};
+    use std::time::Instant;
+    use wl_clipboard_rs::copy::{self, MimeSource, Source};
+    use wl_clipboard_rs::paste::{self, ClipboardType, Seat};
+
+    use super::linux::{check_limits, content_targets, preferred};
+    use super::MAX_SNAPSHOT_BYTES;
+
+    /// Read all formats through the data-control protocol.
+    pub(super) fn read_all(deadline: Instant) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let mime_types = match paste::get_mime_types(ClipboardType::Regular, Seat::Unspecified) {
+            Ok(mime_types) => mime_types,
+            Err(paste::Error::ClipboardEmpty) => return Ok(Vec::new()),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let mut formats = Vec::new();
+        let mut size = 0;
+        for mime in content_targets(mime_types.into_iter().collect()) {
+            let (pipe, _) = paste::get_contents(
+                ClipboardType::Regular,
+                Seat::Unspecified,
+                paste::MimeType::Specific(&mime),
+            )
+            .map_err(|e| format!("Failed to read {}: {}", mime, e))?;
+            // Read one byte past the limit so oversized content is noticed
+            // without reading all of it
+            let mut data = Vec::new();
+            pipe.take((MAX_SNAPSHOT_BYTES - size + 1) as u64)
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read {}: {}", mime, e))?;
+            size += data.len();
+            check_limits(size, deadline)?;
+            formats.push((mime, data));
+        }
+        Ok(formats)
+    }
+
+    /// Offer all formats through the data-control protocol, or the preferred
+    /// one through wl-copy on compositors that lack it. The data is served from
+    /// a background thread until something else takes the clipboard.
+    pub(super) fn write_all(formats: Vec<(String, Vec<u8>)>) -> Result<(), String> {
+        let sources: Vec<MimeSource> = formats
+            .iter()
+            .map(|(mime, data)| MimeSource {
+                source: Source::Bytes(data.clone().into_boxed_slice()),
+                mime_type: copy::MimeType::Specific(mime.clone()),
+            })
+            .collect();
+
+        match copy::Options::new().copy_multi(sources) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                debug!("Data-control copy failed, using wl-copy: {}", e);
+                let (mime, data) = preferred(&formats).ok_or("Nothing to copy")?;
+                write_via_wl_copy(mime, data)
+            }
+        }
+    }
+
+    fn write_via_wl_copy(mime: &str, data: &[u8]) -> Result<(), String> {
+        // wl-copy forks a daemon that inherits stdout and stderr, so those
+        // must not be pipes we'd wait on
+        let mut child = Command::new("wl-copy")
+            .arg("--type")
+            .arg(mime)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to execute wl-copy: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(data)
+                .map_err(|e| format!("Failed to write to wl-copy: {}", e))?;
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("Failed to wait for wl-copy: {}", e))?;
+        if !status.success() {
+            return Err("wl-copy failed".into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use log::debug;
+    use std::collections::HashMap;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use x11rb::connection::{Connection, RequestConnection};
+    use x11rb::protocol::xproto::{
+        Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt as _, CreateWindowAux, EventMask,
+        GetPropertyReply, PropMode, Property, SelectionNotifyEvent, SelectionRequestEvent, Window,
+        WindowClass, SELECTION_NOTIFY_EVENT,
+    };
+    use x11rb::protocol::Event;
+    use x11rb::rust_connection::RustConnection;
+    use x11rb::wrapper::ConnectionExt as _;
+    use x11rb::{COPY_DEPTH_FROM_PARENT, CURRENT_TIME, NONE};
+
+    use super::linux::{check_limits, content_targets};
+    use super::MAX_SNAPSHOT_BYTES;
+
+    /// How long the clipboard owner gets to answer each request.
+    const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// A connection with an invisible window to send and receive the
+    /// clipboard through.
+    struct Clipboard {
+        conn: RustConnection,
+        window: Window,
+        selection: Atom,
+        targets: Atom,
+        incr: Atom,
+    }
+
+    impl Clipboard {
+        fn open() -> Result<Self, String> {
+            let (conn, screen_num) = x11rb::connect(None).map_err(|e| e.to_string())?;
+            let screen = &conn.setup().roots[screen_num];
+            let window = conn.generate_id().map_err(|e| e.to_string())?;
+            conn.create_window(
+                COPY_DEPTH_FROM_PARENT,
+                window,
+                screen.root,
+                0,
+                0,
+                1,
+                1,
+                0,
+                WindowClass::INPUT_OUTPUT,
+                screen.root_visual,
+                &CreateWindowAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+            )
+            .map_err(|e| e.to_string())?;
+
+            let mut clipboard = Self {
+                conn,
+                window,
+                selection: NONE,
+                targets: NONE,
+                incr: NONE,
+            };
+            clipboard.selection = clipboard.atom("CLIPBOARD")?;
+            clipboard.targets = clipboard.atom("TARGETS")?;
+            clipboard.incr = clipboard.atom("INCR")?;
+            Ok(clipboard)
+        }
+
+        fn atom(&self, name: &str) -> Result<Atom, String> {
+            Ok(self
+                .conn
+                .intern_atom(false, name.as_bytes())
+                .map_err(|e| e.to_string())?
+                .reply()
+                .map_err(|e| e.to_string())?
+                .atom)
+        }
+
+        fn atom_name(&self, atom: Atom) -> Result<String, String> {
+            let reply = self
+                .conn
+                .get_atom_name(atom)
+                .map_err(|e| e.to_string())?
+                .reply()
+                .map_err(|e| e.to_string())?;
+            Ok(String::from_utf8_lossy(&reply.name).into_owned())
+        }
+
+        fn next_event(&self, deadline: Instant) -> Result<Event, String> {
+            loop {
+                if let Some(event) = self.conn.poll_for_event().map_err(|e| e.to_string())? {
+                    return Ok(event);
+                }
+                if Instant::now() >= deadline {
+                    return Err("the clipboard owner didn't answer".into());
+                }
+                thread::sleep(Duration::from_millis(5));
+            }
+        }
+
+        /// Ask the owner to convert the clipboard to `target` and return the
+        /// property format and data, following INCR transfers. Gives up at
+        /// `limit` or once `max_bytes` were received.
+        fn convert(
+            &self,
+            target: Atom,
+            property: Atom,
+            limit: Instant,
+            max_bytes: usize,
+        ) -> Result<(u8, Vec<u8>), String> {
+            self.conn
+                .convert_selection(self.window, self.selection, target, property, CURRENT_TIME)
+                .map_err(|e| e.to_string())?;
+            self.conn.flush().map_err(|e| e.to_string())?;
+
+            let deadline = limit.min(Instant::now() + REPLY_TIMEOUT);
+            loop {
+                if let Event::SelectionNotify(event) = self.next_event(deadline)? {
+                    if event.property == NONE {
+                        return Err("the clipboard owner refused the target".into());
+                    }
+                    break;
+                }
+            }
+
+            let reply = self.take_property(property)?;
+            if reply.type_ != self.incr {
+                return Ok((reply.format, reply.value));
+            }
+
+            // Deleting the INCR property asked the owner for the first chunk;
+            // an empty chunk ends the transfer
+            let mut data = Vec::new();
+            let mut format = 8;
+            let mut deadline = limit.min(Instant::now() + REPLY_TIMEOUT);
+            loop {
+                match self.next_event(deadline)? {
+                    Event::PropertyNotify(event)
+                        if event.atom == property && event.state == Property::NEW_VALUE =>
+                    {
+                        let chunk = self.take_property(property)?;
+                        if chunk.value.is_empty() {
+                            return Ok((format, data));
+                        }
+                        format = chunk.format;
+                        data.extend_from_slice(&chunk.value);
+                        if data.len() > max_bytes {
+                            return Err("the clipboard content is too large".into());
+                        }
+                        deadline = limit.min(Instant::now() + REPLY_TIMEOUT);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        fn take_property(&self, property: Atom) -> Result<GetPropertyReply, String> {
+            self.conn
+                .get_property(true, self.window, property, AtomEnum::ANY, 0, u32::MAX)
+                .map_err(|e| e.to_string())?
+                .reply()
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    pub(super) fn read_all(deadline: Instant) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let clipboard = Clipboard::open()?;
+        let owner = clipboard
+            .conn
+            .get_selection_owner(clipboard.selection)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?
+            .owner;
+        if owner == NONE {
+            return Ok(Vec::new());
+        }
+
+        let property = clipboard.atom("HANDLESS_CLIPBOARD")?;
+        let (_, targets) =
+            clipboard.convert(clipboard.targets, property, deadline, MAX_SNAPSHOT_BYTES)?;
+        let mut names = Vec::new();
+        for chunk in targets.chunks_exact(4) {
+            let target = u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            names.push(clipboard.atom_name(target)?);
+        }
+
+        let mut formats = Vec::new();
+        let mut size = 0;
+        for name in content_targets(names) {
+            check_limits(size, deadline)?;
+            let target = clipboard.atom(&name)?;
+            match clipboard.convert(target, property, deadline, MAX_SNAPSHOT_BYTES - size) {
+                // Only byte data is restored; other formats are references
+                // like atoms or window IDs
+                Ok((8, data)) => {
+                    size += data.len();
+                    formats.push((name, data));
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Skipping clipboard target {}: {}", name, e),
+            }
+        }
+        check_limits(size, deadline)?;
+        Ok(formats)
+    }
+
+    /// Take ownership of the clipboard and serve `formats` from a background
+    /// thread until another application takes it.
+    pub(super) fn write_all(formats: Vec<(String, Vec<u8>)>) -> Result<(), String> {
+        let clipboard = Clipboard::open()?;
+        let mut served = Vec::with_capacity(formats.len());
+        for (name, data) in formats {
+            served.push((clipboard.atom(&name)?, data));
+        }
+
+        clipboard
+            .conn
+            .set_selection_owner(clipboard.window, clipboard.selection, CURRENT_TIME)
+            .map_err(|e| e.to_string())?;
+        let owner = clipboard
+            .conn
+            .get_selection_owner(clipboard.selection)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?
+            .owner;
+        if owner != clipboard.window {
+            return Err("Failed to take ownership of the clipboard".into());
+        }
+
+        thread::spawn(move || {
+            if let Err(e) = serve(&clipboard, &served) {
+                debug!("Stopped serving the clipboard: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    /// An INCR transfer in progress: the next chunk is sent when the
+    /// requestor deletes the property.
+    struct Transfer {
+        format: usize,
+        offset: usize,
+    }
+
+    fn serve(clipboard: &Clipboard, formats: &[(Atom, Vec<u8>)]) -> Result<(), String> {
+        let conn = &clipboard.conn;
+        let chunk_size = conn.maximum_request_bytes() / 4;
+        let mut transfers: HashMap<(Window, Atom), Transfer> = HashMap::new();
+
+        loop {
+            match conn.wait_for_event().map_err(|e| e.to_string())? {
+                Event::SelectionClear(event) if event.selection == clipboard.selection => {
+                    return Ok(());
+                }
+                Event::SelectionRequest(request) => {
+                    let property =
+                        answer_request(clipboard, formats, &request, chunk_size, &mut transfers)?;
+                    let notify = SelectionNotifyEvent {
+                        response_type: SELECTION_NOTIFY_EVENT,
+                        sequence: 0,
+                        time: request.time,
+                        requestor: request.requestor,
+                        selection: request.selection,
+                        target: request.target,
+                        property,
+                    };
+                    conn.send_event(false, request.requestor, EventMask::NO_EVENT, notify)
+                        .map_err(|e| e.to_string())?;
+                }
+                Event::PropertyNotify(event) if event.state == Property::DELETE => {
+                    let key = (event.window, event.atom);
+                    let Some(transfer) = transfers.get_mut(&key) else {
+                        continue;
+                    };
+                    let data = &formats[transfer.format].1;
+                    let end = (transfer.offset + chunk_size).min(data.len());
+                    conn.change_property8(
+                        PropMode::REPLACE,
+                        event.window,
+                        event.atom,
+                        formats[transfer.format].0,
+                        &data[transfer.offset..end],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    if transfer.offset == end {
+                        transfers.remove(&key);
+                    } else {
+                        transfer.offset = end;
+                    }
+                }
+                _ => continue,
+            }
+            conn.flush().map_err(|e| e.to_string())?;
+        }
+    }
+
+    /// Write the requested target to the requestor's property and return the
+    /// property, or `NONE` if the target isn't offered.
+    fn answer_request(
+        clipboard: &Clipboard,
+        formats: &[(Atom, Vec<u8>)],
+        request: &SelectionRequestEvent,
+        chunk_size: usize,
+        transfers: &mut HashMap<(Window, Atom), Transfer>,
+    ) -> Result<Atom, String> {
+        let conn = &clipboard.conn;
+        // Obsolete clients leave the property unset and expect the target
+        let property = if request.property == NONE {
+            request.target
+        } else {
+            request.property
+        };
+
+        if request.target == clipboard.targets {
+            let mut targets = vec![clipboard.targets];
+            targets.extend(formats.iter().map(|(target, _)| *target));
+            conn.change_property32(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                AtomEnum::ATOM,
+                &targets,
+            )
+            .map_err(|e| e.to_string())?;
+            return Ok(property);
+        }
+
+        let Some(format) = formats
+            .iter()
+            .position(|(target, _)| *target == request.target)
+        else {
+            return Ok(NONE);
+        };
+        let data = &formats[format].1;
+
+        if data.len() <= chunk_size {
+            conn.change_property8(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                request.target,
+                data,
+            )
+            .map_err(|e| e.to_string())?;
+        } else {
+            // Too large for one request: announce the size and send chunks
+            // as the requestor consumes them
+            conn.change_window_attributes(
+                request.requestor,
+                &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+            )
+            .map_err(|e| e.to_string())?;
+            conn.change_property32(
+                PropMode::REPLACE,
+                request.requestor,
+                property,
+                clipboard.incr,
+                &[data.len() as u32],
+            )
+            .map_err(|e| e.to_string())?;
+            transfers.insert(
+                (request.requestor, property),
+                Transfer { format, offset: 0 },
+            );
+        }
+        Ok(property)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::linux::{content_targets, preferred, text_of};
+    use super::MAX_SNAPSHOT_FORMATS;
+
+    fn formats(mimes: &[&str]) -> Vec<(String, Vec<u8>)> {
+        mimes
+            .iter()
+            .map(|mime| (mime.to_string(), Vec::new()))
+            .collect()
+    }
+
+    #[test]
+    fn single_format_restore_prefers_images_then_text() {
+        let copied_image = formats(&["text/uri-list", "text/plain", "image/png"]);
+        assert_eq!(preferred(&copied_image).unwrap().0, "image/png");
+
+        let copied_html = formats(&["text/html", "text/plain", "text/plain;charset=utf-8"]);
+        assert_eq!(
+            preferred(&copied_html).unwrap().0,
+            "text/plain;charset=utf-8"
+        );
+
+        let copied_files = formats(&["x-special/gnome-copied-files"]);
+        assert_eq!(
+            preferred(&copied_files).unwrap().0,
+            "x-special/gnome-copied-files"
+        );
+        assert!(preferred(&[]).is_none());
+    }
+
+    #[test]
+    fn saves_a_bounded_number_of_content_formats() {
+        let targets = ["TARGETS", "TIMESTAMP", "text/html", "UTF8_STRING"];
+        assert_eq!(
+            content_targets(targets.iter().map(|t| t.to_string()).collect()),
+            vec!["text/html", "UTF8_STRING"]
+        );
+
+        let many: Vec<String> = (0..100).map(|i| format!("type/{}", i)).collect();
+        let saved = content_targets(many);
+        assert_eq!(saved.len(), MAX_SNAPSHOT_FORMATS);
+        assert_eq!(saved[0], "type/0");
+    }
+
+    #[test]
+    fn text_fallback_uses_the_preferred_text_type() {
+        let mut saved = formats(&["text/html", "text/plain", "UTF8_STRING"]);
+        saved[2].1 = "dictated".as_bytes().to_vec();
+        assert_eq!(text_of(&saved).as_deref(), Some("dictated"));
+        assert_eq!(text_of(&formats(&["image/png"])), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Provider fallback chain for transcription.
+//!
+//! A failed cloud request is classified so the chain knows whether to retry
+//! the same provider with backoff, move on to the next one, or skip the
+//! remaining cloud providers entirely because the machine is offline.
+
+use std::time::Duration;
+use tokio_tungstenite::tungstenite;
+
+use crate::settings::SttFallbackStep;
+
+use super::ApiError;
+
+/// Upper bound for a single backoff delay.
+const MAX_BACKOFF_MS: u64 = 8_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Could not reach the server at all.
+    Offline,
+    Timeout,
+    /// Rejected credentials; retrying will not help.
+    Auth,
+    RateLimited,
+    Server,
+    Other,
+}
+
+impl FailureKind {
+    /// Whether retrying the same provider after a backoff may succeed.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            FailureKind::Timeout | FailureKind::RateLimited | FailureKind::Server
+        )
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailureKind::Offline => "offline",
+            FailureKind::Timeout => "timeout",
+            FailureKind::Auth => "auth",
+            FailureKind::RateLimited => "rate_limited",
+            FailureKind::Server => "server",
+            FailureKind::Other => "other",
+        }
+    }
+}
+
+fn classify_status(status: u16) -> FailureKind {
+    match status {
+        401 | 403 => FailureKind::Auth,
+        408 => FailureKind::Timeout,
+        429 => FailureKind::RateLimited,
+        500..=599 => FailureKind::Server,
+        _ => FailureKind::Other,
+    }
+}
+
+/// Work out why a transcription attempt failed, for batch requests and
+/// realtime WebSocket sessions alike.
+pub fn classify(err: &anyhow::Error) -> FailureKind {
+    for cause in err.chain() {
+        if let Some(api_error) = cause.downcast_ref::<ApiError>() {
+            return classify_status(api_error.status.as_u16());
+        }
+        if let Some(ws_error) = cause.downcast_ref::<tungstenite::Error>() {
+            return match ws_error {
+                tungstenite::Error::Io(_) => FailureKind::Offline,
+                tungstenite::Error::Http(response) => classify_status(response.status().as_u16()),
+                // The server dropped the session midway
+                tungstenite::Error::ConnectionClosed
+                | tungstenite::Error::AlreadyClosed
+                | tungstenite::Error::Protocol(_) => FailureKind::Server,
+                _ => FailureKind::Other,
+            };
+        }
+        if let Some(http_error) = cause.downcast_ref::<reqwest::Error>() {
+            if http_error.is_timeout() {
+                return FailureKind::Timeout;
+            }
+            if http_error.is_connect() {
+                return FailureKind::Offline;
+            }
+        }
+        if cause.is::<tokio::time::error::Elapsed>() {
+            return FailureKind::Timeout;
+        }
+    }
+    FailureKind::Other
+}
+
+/// Exponential backoff before retry number `attempt` (starting at 0).
+pub fn backoff_delay(attempt: u32, base_ms: u64) -> Duration {
+    let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    Duration::from_millis(base_ms.saturating_mul(factor).min(MAX_BACKOFF_MS))
+}
+
+/// The ordered list of steps to try: the primary target first, then the
+/// configured fallbacks with duplicates of earlier steps removed.
+pub fn build_chain(
+    primary: SttFallbackStep,
+    fallbacks: &[SttFallbackStep],
+) -> Vec<SttFallbackStep> {
+    let mut chain = vec![primary];
+    for step in fallbacks {
+        let duplicate = chain
+            .iter()
+            .any(|s| s.provider_id == step.provider_id && s.model_id == step.model_id);
+        if !duplicate {
+            chain.push(step.clone());
+        }
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(provider_id: &str, model_id: Option<&str>) -> SttFallbackStep {
+        SttFallbackStep {
+            provider_id: provider_id.to_string(),
+            model_id: model_id.map(String::from),
+            timeout_secs: 30,
+            max_retries: 1,
+        }
+    }
+
+    fn api_error(status: u16) -> anyhow::Error {
+        ApiError {
+            context: "test",
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            body: String::new(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn classifies_http_statuses() {
+        assert_eq!(classify(&api_error(401)), FailureKind::Auth);
+        assert_eq!(classify(&api_error(429)), FailureKind::RateLimited);
+        assert_eq!(classify(&api_error(503)), FailureKind::Server);
+        assert_eq!(classify(&api_error(400)), FailureKind::Other);
+        assert_eq!(
+            classify(&anyhow::anyhow!("something else")),
+            FailureKind::Other
+        );
+        assert!(!FailureKind::Auth.is_retryable());
+        assert!(FailureKind::RateLimited.is_retryable());
+    }
+
+    #[test]
+    fn classifies_realtime_errors() {
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        let connect = anyhow::Error::new(tungstenite::Error::Io(io)).context("connection failed");
+        assert_eq!(classify(&connect), FailureKind::Offline);
+
+        let closed = anyhow::Error::new(tungstenite::Error::ConnectionClosed);
+        assert_eq!(classify(&closed), FailureKind::Server);
+        assert!(classify(&closed).is_retryable());
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(backoff_delay(0, 500), Duration::from_millis(500));
+        assert_eq!(backoff_delay(2, 500), Duration::from_millis(2_000));
+        assert_eq!(
+            backoff_delay(10, 500),
+            Duration::from_millis(MAX_BACKOFF_MS)
+        );
+        assert_eq!(
+            backoff_delay(80, 500),
+            Duration::from_millis(MAX_BACKOFF_MS)
+        );
+    }
+
+    #[test]
+    fn chain_starts_with_primary_and_skips_duplicates() {
+        let chain = build_chain(
+            step("soniox", None),
+            &[
+                step("openai_stt", None),
+                step("soniox", None),
+                step("local", Some("parakeet-tdt-0.6b-v3")),
+            ],
+        );
+
+        let ids: Vec<&str> = chain.iter().map(|s| s.provider_id.as_str()).collect();
+        assert_eq!(ids, vec!["soniox", "openai_stt", "local"]);
+    }
+}
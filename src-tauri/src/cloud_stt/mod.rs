@@ -1,7 +1,40 @@
+pub mod fallback;
 pub mod openai;
 pub mod realtime;
 pub mod soniox;
 
+use std::fmt;
+
+/// A non-success HTTP response from a cloud STT API. Kept typed so the
+/// fallback chain can tell auth failures from rate limits and outages.
+#[derive(Debug)]
+pub struct ApiError {
+    pub context: &'static str,
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.context, self.status, self.body)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl ApiError {
+    /// Consume an error response into an `ApiError`.
+    pub async fn from_response(context: &'static str, response: reqwest::Response) -> Self {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Self {
+            context,
+            status,
+            body,
+        }
+    }
+}
+
 pub async fn test_api_key(
     provider_id: &str,
     api_key: &str,
@@ -1,3 +1,4 @@
+use super::ApiError;
 use anyhow::Result;
 use log::debug;
 use reqwest::multipart;
@@ -92,13 +93,9 @@ pub async fn transcribe(
         .await?;
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(anyhow::anyhow!(
-            "OpenAI STT API error ({}): {}",
-            status,
-            body
-        ));
+        return Err(ApiError::from_response("OpenAI STT API error", response)
+            .await
+            .into());
     }
 
     let result: TranscriptionResponse = response.json().await?;
@@ -2,7 +2,9 @@ use anyhow::Result;
 use futures_util::{SinkExt, StreamExt};
 use log::debug;
 use std::time::Duration;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{connect_async, tungstenite, tungstenite::Message};
+
+use crate::cloud_stt::ApiError;
 
 /// Build the Soniox config JSON, shared by both batch and streaming paths.
 /// `extra_fields` allows the caller to add format-specific fields (e.g. `sample_rate`).
@@ -67,6 +69,31 @@ fn build_soniox_config(
     config
 }
 
+/// Keep the WebSocket error in the chain so the fallback chain can tell an
+/// unreachable server from other failures.
+fn connection_error(e: tungstenite::Error) -> anyhow::Error {
+    let message = format!("Soniox RT connection failed: {}", e);
+    anyhow::Error::new(e).context(message)
+}
+
+/// Soniox reports errors with HTTP status codes, which are classified like
+/// the ones of the REST API.
+fn response_error(code: &serde_json::Value, message: &str) -> anyhow::Error {
+    let status = code
+        .as_u64()
+        .and_then(|code| u16::try_from(code).ok())
+        .and_then(|code| reqwest::StatusCode::from_u16(code).ok());
+    match status {
+        Some(status) => ApiError {
+            context: "Soniox RT error",
+            status,
+            body: message.to_string(),
+        }
+        .into(),
+        None => anyhow::anyhow!("Soniox RT error ({}): {}", code, message),
+    }
+}
+
 /// Soniox's realtime WebSocket endpoint lives on a different host
 /// (`stt-rt.soniox.com`) than the REST API (`api.soniox.com`), so we
 /// cannot derive the WS URL from the user-configured `base_url`.
@@ -85,7 +112,7 @@ pub async fn test_api_key(api_key: &str, model: &str) -> Result<()> {
 
     let (ws_stream, _) = connect_async(SONIOX_WS_URL)
         .await
-        .map_err(|e| anyhow::anyhow!("Soniox RT connection failed: {}", e))?;
+        .map_err(connection_error)?;
     let (mut write, mut read) = ws_stream.split();
 
     write.send(Message::Text(config.to_string().into())).await?;
@@ -104,13 +131,15 @@ pub async fn test_api_key(api_key: &str, model: &str) -> Result<()> {
                         .get("error_message")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown");
-                    return Err(anyhow::anyhow!("Soniox RT error ({}): {}", code, err_msg));
+                    return Err(response_error(code, err_msg));
                 }
             }
         }
         Ok(None) => {}
-        Err(_) => {
-            return Err(anyhow::anyhow!("Soniox RT: timed out waiting for response"));
+        Err(elapsed) => {
+            return Err(
+                anyhow::Error::new(elapsed).context("Soniox RT: timed out waiting for response")
+            );
         }
     }
 
@@ -130,7 +159,7 @@ pub async fn transcribe(
 
     let (ws_stream, _) = connect_async(SONIOX_WS_URL)
         .await
-        .map_err(|e| anyhow::anyhow!("Soniox RT connection failed: {}", e))?;
+        .map_err(connection_error)?;
     let (mut write, mut read) = ws_stream.split();
 
     write.send(Message::Text(config.to_string().into())).await?;
@@ -149,11 +178,10 @@ pub async fn transcribe(
         let msg = match msg {
             Ok(Some(msg)) => msg?,
             Ok(None) => break,
-            Err(_) => {
+            Err(elapsed) => {
                 let _ = write.send(Message::Close(None)).await;
-                return Err(anyhow::anyhow!(
-                    "Soniox RT: timed out waiting for transcription"
-                ));
+                return Err(anyhow::Error::new(elapsed)
+                    .context("Soniox RT: timed out waiting for transcription"));
             }
         };
 
@@ -166,7 +194,7 @@ pub async fn transcribe(
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown");
                 let _ = write.send(Message::Close(None)).await;
-                return Err(anyhow::anyhow!("Soniox RT error ({}): {}", code, err_msg));
+                return Err(response_error(code, err_msg));
             }
 
             if let Some(tokens) = resp.get("tokens").and_then(|v| v.as_array()) {
@@ -215,7 +243,7 @@ pub async fn start_streaming(
 
     let (ws_stream, _) = connect_async(SONIOX_WS_URL)
         .await
-        .map_err(|e| anyhow::anyhow!("Soniox RT connection failed: {}", e))?;
+        .map_err(connection_error)?;
     let (mut write, mut read) = ws_stream.split();
 
     write.send(Message::Text(config.to_string().into())).await?;
@@ -252,10 +280,9 @@ pub async fn start_streaming(
             let msg = match msg {
                 Ok(Some(msg)) => msg?,
                 Ok(None) => break,
-                Err(_) => {
-                    return Err(anyhow::anyhow!(
-                        "Soniox RT streaming: timed out waiting for transcription"
-                    ));
+                Err(elapsed) => {
+                    return Err(anyhow::Error::new(elapsed)
+                        .context("Soniox RT streaming: timed out waiting for transcription"));
                 }
             };
 
@@ -267,7 +294,7 @@ pub async fn start_streaming(
                         .get("error_message")
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown");
-                    return Err(anyhow::anyhow!("Soniox RT error ({}): {}", code, err_msg));
+                    return Err(response_error(code, err_msg));
                 }
 
                 if let Some(tokens) = resp.get("tokens").and_then(|v| v.as_array()) {
@@ -1,3 +1,5 @@
+use super::ApiError;
+use crate::managers::history::TranscriptSegment;
 use anyhow::Result;
 use log::debug;
 use reqwest::multipart;
@@ -21,6 +23,20 @@ struct TranscriptionStatusResponse {
 #[derive(Deserialize)]
 struct TranscriptResponse {
     text: String,
+    #[serde(default)]
+    tokens: Vec<TranscriptToken>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptToken {
+    text: String,
+    #[serde(default)]
+    start_ms: i64,
+    #[serde(default)]
+    end_ms: i64,
+    /// Present when speaker diarization was requested.
+    #[serde(default)]
+    speaker: Option<String>,
 }
 
 /// Test API key and model by uploading a minimal file and creating a transcription.
@@ -73,8 +89,6 @@ pub async fn test_api_key(api_key: &str, base_url: &str, model: &str) -> Result<
 }
 
 /// Transcribe audio using the Soniox async file transcription API.
-///
-/// Flow: upload file → create transcription → poll until complete → fetch transcript.
 pub async fn transcribe(
     api_key: &str,
     base_url: &str,
@@ -82,6 +96,61 @@ pub async fn transcribe(
     audio_wav: Vec<u8>,
     options: Option<&serde_json::Value>,
 ) -> Result<String> {
+    let result = fetch_transcript(api_key, base_url, model, audio_wav, options).await?;
+    debug!("Soniox STT result: '{}'", result.text);
+    Ok(result.text)
+}
+
+/// Transcribe with speaker diarization forced on and group the tokens into
+/// speaker turns.
+pub async fn transcribe_with_speakers(
+    api_key: &str,
+    base_url: &str,
+    model: &str,
+    audio_wav: Vec<u8>,
+    options: Option<&serde_json::Value>,
+) -> Result<Vec<TranscriptSegment>> {
+    let mut options = options.cloned().unwrap_or_else(|| serde_json::json!({}));
+    options["enable_speaker_diarization"] = serde_json::json!(true);
+
+    let result = fetch_transcript(api_key, base_url, model, audio_wav, Some(&options)).await?;
+    Ok(group_speaker_tokens(&result.tokens))
+}
+
+/// Join consecutive tokens of the same speaker. Tokens carry their own
+/// leading spaces, so they are concatenated as-is.
+fn group_speaker_tokens(tokens: &[TranscriptToken]) -> Vec<TranscriptSegment> {
+    let mut segments: Vec<TranscriptSegment> = Vec::new();
+    for token in tokens {
+        let speaker = format!("Speaker {}", token.speaker.as_deref().unwrap_or("1"));
+        match segments.last_mut() {
+            Some(last) if last.speaker == speaker => {
+                last.text.push_str(&token.text);
+                last.end_ms = token.end_ms;
+            }
+            _ => segments.push(TranscriptSegment {
+                speaker,
+                start_ms: token.start_ms,
+                end_ms: token.end_ms,
+                text: token.text.clone(),
+            }),
+        }
+    }
+    for segment in &mut segments {
+        segment.text = segment.text.trim().to_string();
+    }
+    segments.retain(|segment| !segment.text.is_empty());
+    segments
+}
+
+/// Flow: upload file → create transcription → poll until complete → fetch transcript.
+async fn fetch_transcript(
+    api_key: &str,
+    base_url: &str,
+    model: &str,
+    audio_wav: Vec<u8>,
+    options: Option<&serde_json::Value>,
+) -> Result<TranscriptResponse> {
     let base = base_url.trim_end_matches('/');
     let client = reqwest::Client::new();
 
@@ -106,13 +175,11 @@ pub async fn transcribe(
         .await?;
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(anyhow::anyhow!(
-            "Soniox file upload error ({}): {}",
-            status,
-            body
-        ));
+        return Err(
+            ApiError::from_response("Soniox file upload error", response)
+                .await
+                .into(),
+        );
     }
 
     let file: FileUploadResponse = response.json().await?;
@@ -181,13 +248,11 @@ pub async fn transcribe(
         .await?;
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(anyhow::anyhow!(
-            "Soniox transcription create error ({}): {}",
-            status,
-            body
-        ));
+        return Err(
+            ApiError::from_response("Soniox transcription create error", response)
+                .await
+                .into(),
+        );
     }
 
     let transcription: TranscriptionCreateResponse = response.json().await?;
@@ -205,13 +270,11 @@ pub async fn transcribe(
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Soniox transcription poll error ({}): {}",
-                status,
-                body
-            ));
+            return Err(
+                ApiError::from_response("Soniox transcription poll error", response)
+                    .await
+                    .into(),
+            );
         }
 
         let status_resp: TranscriptionStatusResponse = response.json().await?;
@@ -236,16 +299,50 @@ pub async fn transcribe(
         .await?;
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(anyhow::anyhow!(
-            "Soniox transcript fetch error ({}): {}",
-            status,
-            body
-        ));
+        return Err(
+            ApiError::from_response("Soniox transcript fetch error", response)
+                .await
+                .into(),
+        );
     }
 
-    let result: TranscriptResponse = response.json().await?;
-    debug!("Soniox STT result: '{}'", result.text);
-    Ok(result.text)
+    Ok(response.json().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, start_ms: i64, speaker: &str) -> TranscriptToken {
+        TranscriptToken {
+            text: text.to_string(),
+            start_ms,
+            end_ms: start_ms + 100,
+            speaker: Some(speaker.to_string()),
+        }
+    }
+
+    #[test]
+    fn groups_consecutive_tokens_by_speaker() {
+        let tokens = [
+            token("Hi", 0, "1"),
+            token(" there", 100, "1"),
+            token(" Hello", 400, "2"),
+            token(" again", 600, "1"),
+        ];
+        let segments = group_speaker_tokens(&tokens);
+
+        let summary: Vec<(&str, &str, i64, i64)> = segments
+            .iter()
+            .map(|s| (s.speaker.as_str(), s.text.as_str(), s.start_ms, s.end_ms))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Speaker 1", "Hi there", 0, 200),
+                ("Speaker 2", "Hello", 400, 500),
+                ("Speaker 1", "again", 600, 700),
+            ]
+        );
+    }
 }
@@ -1,8 +1,10 @@
 use crate::audio_feedback;
-use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
+use crate::audio_toolkit::audio::{list_input_devices, list_monitor_sources, list_output_devices};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
 use crate::helpers::clamshell;
-use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
-use crate::settings::{get_settings, write_settings};
+use crate::managers::audio::{AudioRecordingManager, MicrophoneMode, MicrophoneTest};
+use crate::managers::model::ModelManager;
+use crate::settings::{get_settings, write_settings, InputProcessing};
 use log::warn;
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -61,6 +63,113 @@ pub fn update_microphone_mode(app: AppHandle, always_on: bool) -> Result<(), Str
         .map_err(|e| format!("Failed to update microphone mode: {}", e))
 }
 
+/// Longest pre-roll kept in memory.
+const MAX_PRE_ROLL_MS: u32 = 1000;
+/// Longest time the microphone is kept warm after an on-demand recording.
+const MAX_WARM_STANDBY_SECS: u32 = 600;
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_pre_roll_settings(
+    app: AppHandle,
+    pre_roll_ms: u32,
+    warm_standby_secs: u32,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.pre_roll_ms = pre_roll_ms.min(MAX_PRE_ROLL_MS);
+    settings.warm_standby_secs = warm_standby_secs.min(MAX_WARM_STANDBY_SECS);
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>().update_pre_roll();
+    Ok(())
+}
+
+/// Shortest trailing silence accepted for auto-stop, so a breath between
+/// words doesn't end the recording.
+const MIN_AUTO_STOP_SILENCE_MS: u32 = 300;
+const MAX_AUTO_STOP_SILENCE_MS: u32 = 10_000;
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_auto_stop_settings(
+    app: AppHandle,
+    enabled: bool,
+    silence_ms: u32,
+    min_speech_ms: u32,
+    max_duration_secs: u32,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.auto_stop_enabled = enabled;
+    settings.auto_stop_silence_ms =
+        silence_ms.clamp(MIN_AUTO_STOP_SILENCE_MS, MAX_AUTO_STOP_SILENCE_MS);
+    settings.auto_stop_min_speech_ms = min_speech_ms;
+    settings.auto_stop_max_duration_secs = max_duration_secs;
+    write_settings(&app, settings);
+
+    // Auto-stop may need a VAD the open stream was created without
+    app.state::<Arc<AudioRecordingManager>>()
+        .restart_idle_stream()
+        .map_err(|e| format!("Failed to restart microphone stream: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_wake_word_settings(
+    app: AppHandle,
+    enabled: bool,
+    phrase: String,
+    model_id: Option<String>,
+) -> Result<(), String> {
+    let phrase = phrase.trim().to_string();
+    if enabled && phrase.is_empty() {
+        return Err("Wake phrase cannot be empty".to_string());
+    }
+
+    let model_id = model_id.filter(|id| !id.is_empty());
+    if enabled {
+        let model_manager = app.state::<Arc<ModelManager>>();
+        let spotting_model = model_id
+            .as_deref()
+            .unwrap_or(crate::wake_word::DEFAULT_WAKE_WORD_MODEL);
+        crate::wake_word::check_model(&model_manager, spotting_model).map_err(|e| e.to_string())?;
+    }
+
+    let mut settings = get_settings(&app);
+    settings.wake_word_enabled = enabled;
+    if !phrase.is_empty() {
+        settings.wake_word_phrase = phrase;
+    }
+    settings.wake_word_model = model_id;
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>()
+        .update_wake_word()
+        .map_err(|e| format!("Failed to restart microphone stream: {}", e))
+}
+
+/// Configure meeting recording: the monitor source to capture (`None` for
+/// the monitor of the default output), whether to mix in the microphone,
+/// and how many seconds of audio each transcription chunk holds.
+#[tauri::command]
+#[specta::specta]
+pub fn update_meeting_settings(
+    app: AppHandle,
+    source: Option<String>,
+    include_microphone: bool,
+    chunk_secs: u32,
+) -> Result<(), String> {
+    if !(10..=300).contains(&chunk_secs) {
+        return Err("Chunk length must be between 10 and 300 seconds".to_string());
+    }
+
+    let mut settings = get_settings(&app);
+    settings.meeting_source = source.filter(|s| !s.is_empty());
+    settings.meeting_include_microphone = include_microphone;
+    settings.meeting_chunk_secs = chunk_secs;
+    write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_microphone_mode(app: AppHandle) -> Result<bool, String> {
@@ -89,6 +198,73 @@ pub fn get_available_microphones() -> Result<Vec<AudioDevice>, String> {
     Ok(result)
 }
 
+/// System audio sources (PulseAudio/PipeWire monitors) that meeting
+/// recording can capture. Empty where loopback capture is unsupported.
+#[tauri::command]
+#[specta::specta]
+pub fn get_loopback_sources() -> Vec<AudioDevice> {
+    list_monitor_sources()
+        .into_iter()
+        .enumerate()
+        .map(|(index, source)| AudioDevice {
+            index: index.to_string(),
+            name: source.name,
+            is_default: source.is_default,
+        })
+        .collect()
+}
+
+/// Set the capture processing for one input device (`Default` for the
+/// system default). Applied the next time the microphone opens, or right
+/// away when it is open and idle.
+#[tauri::command]
+#[specta::specta]
+pub fn set_input_processing(
+    app: AppHandle,
+    device_name: String,
+    processing: InputProcessing,
+) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    if processing == InputProcessing::default() {
+        settings.input_processing.remove(&device_name);
+    } else {
+        settings.input_processing.insert(device_name, processing);
+    }
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>()
+        .restart_idle_stream()
+        .map_err(|e| format!("Failed to restart microphone stream: {}", e))
+}
+
+/// Record a few seconds from `device` (the system default when `None`) and
+/// report levels, clipping, noise floor and how much of it the VAD heard as
+/// speech. With `playback`, the capture is played back afterwards.
+#[tauri::command]
+#[specta::specta]
+pub async fn test_microphone(
+    app: AppHandle,
+    device: Option<String>,
+    seconds: u32,
+    playback: bool,
+) -> Result<MicrophoneTest, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        let (report, samples) = rm
+            .test_microphone(device.as_deref(), seconds)
+            .map_err(|e| e.to_string())?;
+
+        if playback {
+            if let Err(e) = audio_feedback::play_samples(&app, samples, WHISPER_SAMPLE_RATE) {
+                warn!("Failed to play back microphone test: {}", e);
+            }
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Microphone test failed: {}", e))?
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn set_microphone_priority(app: AppHandle, priority: Vec<String>) -> Result<(), String> {
@@ -181,6 +357,7 @@ pub async fn play_test_sound(app: AppHandle, sound_type: String) {
     let sound = match sound_type.as_str() {
         "start" => audio_feedback::SoundType::Start,
         "stop" => audio_feedback::SoundType::Stop,
+        "error" => audio_feedback::SoundType::Error,
         _ => {
             warn!("Unknown sound type: {}", sound_type);
             return;
@@ -66,6 +66,66 @@ pub async fn delete_history_entry(
         .map_err(|e| e.to_string())
 }
 
+/// Transcribe the stored audio of a history entry again, e.g. after the
+/// original attempt failed, and replace its transcription.
+#[tauri::command]
+#[specta::specta]
+pub async fn retry_history_entry_transcription(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<HistoryEntry, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+
+    crate::actions::transcribe_history_entry(&app, &entry)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Split the stored audio of a history entry into speaker turns and save
+/// them on the entry.
+#[tauri::command]
+#[specta::specta]
+pub async fn diarize_history_entry(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<HistoryEntry, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+
+    crate::diarization::diarize_history_entry(&app, &entry)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether meeting recordings are diarized once transcribed, and how many
+/// speakers the experimental local diarization may tell apart.
+#[tauri::command]
+#[specta::specta]
+pub fn update_diarization_settings(
+    app: AppHandle,
+    meeting_diarization: bool,
+    max_speakers: u32,
+) -> Result<(), String> {
+    if !(1..=10).contains(&max_speakers) {
+        return Err("Speaker count must be between 1 and 10".to_string());
+    }
+
+    let mut settings = crate::settings::get_settings(&app);
+    settings.meeting_diarization = meeting_diarization;
+    settings.diarization_max_speakers = max_speakers;
+    crate::settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn update_history_limit(
@@ -4,6 +4,7 @@ pub mod history;
 pub mod models;
 pub mod transcription;
 
+use crate::meeting::MeetingRecorder;
 use crate::settings::{self, get_settings, write_settings, AppSettings, LogLevel};
 use crate::utils::cancel_current_operation;
 use crate::TranscriptionCoordinator;
@@ -25,6 +26,40 @@ pub fn confirm_recording(app: AppHandle) {
     }
 }
 
+/// Turn hands-free continuous dictation on or off.
+#[tauri::command]
+#[specta::specta]
+pub fn set_hands_free_mode(app: AppHandle, enabled: bool) {
+    if let Some(coordinator) = app.try_state::<TranscriptionCoordinator>() {
+        coordinator.set_hands_free(enabled);
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_hands_free_mode(app: AppHandle) -> bool {
+    app.try_state::<TranscriptionCoordinator>()
+        .is_some_and(|coordinator| coordinator.is_hands_free())
+}
+
+/// Start a meeting recording, or stop and transcribe the running one.
+/// Returns whether a meeting is running afterwards.
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_meeting(app: AppHandle) -> Result<bool, String> {
+    let meeting = app
+        .try_state::<MeetingRecorder>()
+        .ok_or_else(|| "Meeting recorder not available".to_string())?;
+    meeting.toggle().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn is_meeting_active(app: AppHandle) -> bool {
+    app.try_state::<MeetingRecorder>()
+        .is_some_and(|meeting| meeting.is_active())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_app_dir_path(app: AppHandle) -> Result<String, String> {
@@ -186,6 +221,14 @@ pub fn is_homebrew_install() -> bool {
     }
 }
 
+/// Whether Fcitx is the input method framework, which the input method paste
+/// method doesn't support.
+#[specta::specta]
+#[tauri::command]
+pub fn is_fcitx_active() -> bool {
+    crate::input_method::fcitx_active()
+}
+
 /// Try to initialize Enigo (keyboard/mouse simulation).
 /// On macOS, this will return an error if accessibility permissions are not granted.
 #[specta::specta]
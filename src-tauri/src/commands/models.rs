@@ -42,13 +42,14 @@ pub async fn delete_model(
     transcription_manager: State<'_, Arc<TranscriptionManager>>,
     model_id: String,
 ) -> Result<(), String> {
-    // If deleting the active model, unload it and clear the setting
+    // Unload the model if it is resident in the pool
+    transcription_manager
+        .unload_model_by_id(&model_id)
+        .map_err(|e| format!("Failed to unload model: {}", e))?;
+
+    // If deleting the active model, clear the setting
     let settings = get_settings(&app_handle);
     if settings.selected_model == model_id {
-        transcription_manager
-            .unload_model()
-            .map_err(|e| format!("Failed to unload model: {}", e))?;
-
         let mut settings = get_settings(&app_handle);
         settings.selected_model = String::new();
         write_settings(&app_handle, settings);
@@ -2,12 +2,15 @@ use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, write_settings, ModelUnloadTimeout};
 use serde::Serialize;
 use specta::Type;
+use std::sync::Arc;
 use tauri::{AppHandle, State};
 
 #[derive(Serialize, Type)]
 pub struct ModelLoadStatus {
     is_loaded: bool,
     current_model: Option<String>,
+    /// All resident models, most recently used first.
+    loaded_models: Vec<String>,
 }
 
 #[tauri::command]
@@ -26,9 +29,46 @@ pub fn get_model_load_status(
     Ok(ModelLoadStatus {
         is_loaded: transcription_manager.is_model_loaded(),
         current_model: transcription_manager.get_current_model(),
+        loaded_models: transcription_manager.loaded_models(),
     })
 }
 
+/// Override the unload timeout for a single model. `None` reverts it to the global timeout.
+#[tauri::command]
+#[specta::specta]
+pub fn set_model_unload_timeout_override(
+    app: AppHandle,
+    model_id: String,
+    timeout: Option<ModelUnloadTimeout>,
+) {
+    let mut settings = get_settings(&app);
+    match timeout {
+        Some(timeout) => {
+            settings.model_unload_timeouts.insert(model_id, timeout);
+        }
+        None => {
+            settings.model_unload_timeouts.remove(&model_id);
+        }
+    }
+    write_settings(&app, settings);
+}
+
+/// Set how much memory (in MB) resident models may use before the least
+/// recently used ones are evicted. Zero keeps only the active model loaded.
+#[tauri::command]
+#[specta::specta]
+pub fn set_model_pool_budget(
+    app: AppHandle,
+    transcription_manager: State<Arc<TranscriptionManager>>,
+    budget_mb: u64,
+) {
+    let mut settings = get_settings(&app);
+    settings.model_pool_budget_mb = budget_mb;
+    write_settings(&app, settings);
+
+    transcription_manager.enforce_pool_budget();
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn unload_model_manually(
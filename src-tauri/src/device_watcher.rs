@@ -1,10 +1,175 @@
-use tauri::{AppHandle, Emitter};
+use log::debug;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
 
-/// Start watching for audio device changes and emit "audio-devices-changed"
-/// on the app event bus whenever the set of input devices changes.
+use crate::audio_toolkit::audio::list_input_devices;
+use crate::managers::audio::AudioRecordingManager;
+
+/// Quiet period after the last notification before devices are re-listed,
+/// so a device that registers several nodes causes a single event.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Interval of the polling fallback.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Microphones added and removed since the previous event, sent as
+/// "audio-devices-changed".
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Type)]
+pub struct AudioDevicesChanged {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl AudioDevicesChanged {
+    fn between(previous: &HashSet<String>, current: &HashSet<String>) -> Self {
+        let mut added: Vec<String> = current.difference(previous).cloned().collect();
+        let mut removed: Vec<String> = previous.difference(current).cloned().collect();
+        added.sort();
+        removed.sort();
+        Self { added, removed }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A device being added or removed, as reported by the sound server or udev.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+enum DeviceEvent {
+    Added,
+    Removed,
+}
+
+/// Names of the input devices currently present.
+fn device_names() -> HashSet<String> {
+    list_input_devices()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|info| info.name)
+        .collect()
+}
+
+/// Turns raw "something changed" notifications into debounced
+/// `AudioDevicesChanged` events.
+struct DeviceTracker {
+    app: AppHandle,
+    known: Mutex<HashSet<String>>,
+    /// Whether a removal was reported since the last refresh. A device that
+    /// is unplugged and plugged back within `DEBOUNCE` leaves the names
+    /// unchanged, but streams opened on it are gone.
+    removed: AtomicBool,
+    /// When the pending refresh is due; pushed back by every notification.
+    due: Mutex<Option<Instant>>,
+    due_changed: Condvar,
+}
+
+impl DeviceTracker {
+    fn new(app: &AppHandle) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            app: app.clone(),
+            known: Mutex::new(device_names()),
+            removed: AtomicBool::new(false),
+            due: Mutex::new(None),
+            due_changed: Condvar::new(),
+        });
+        let debouncer = Arc::clone(&tracker);
+        std::thread::spawn(move || debouncer.debounce());
+        tracker
+    }
+
+    /// Re-list devices once notifications have been quiet for `DEBOUNCE`.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "linux")), allow(dead_code))]
+    fn trigger(&self) {
+        *self.due.lock().unwrap() = Some(Instant::now() + DEBOUNCE);
+        self.due_changed.notify_one();
+    }
+
+    /// Timer thread behind `trigger`: waits until the pending refresh is due
+    /// and runs it.
+    fn debounce(&self) -> ! {
+        let mut due = self.due.lock().unwrap();
+        loop {
+            match *due {
+                None => due = self.due_changed.wait(due).unwrap(),
+                Some(at) if at > Instant::now() => {
+                    due = self
+                        .due_changed
+                        .wait_timeout(due, at.saturating_duration_since(Instant::now()))
+                        .unwrap()
+                        .0;
+                }
+                Some(_) => {
+                    *due = None;
+                    drop(due);
+                    self.refresh();
+                    due = self.due.lock().unwrap();
+                }
+            }
+        }
+    }
+
+    /// Note a device event and refresh once events have been quiet.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn on_event(&self, event: DeviceEvent) {
+        if event == DeviceEvent::Removed {
+            self.removed.store(true, Ordering::SeqCst);
+        }
+        self.trigger();
+    }
+
+    /// Compare the current devices with the last known set and notify the
+    /// frontend and the recording manager when they differ or a device was
+    /// plugged back in.
+    fn refresh(&self) {
+        let current = device_names();
+        let replugged = self.removed.swap(false, Ordering::SeqCst);
+        let change = {
+            let mut known = self.known.lock().unwrap();
+            let change = AudioDevicesChanged::between(&known, &current);
+            *known = current;
+            change
+        };
+        if change.is_empty() && !replugged {
+            return;
+        }
+
+        debug!(
+            "Audio devices changed: added {:?}, removed {:?}, replugged: {}",
+            change.added, change.removed, replugged
+        );
+        let _ = self.app.emit("audio-devices-changed", change);
+        if let Some(rm) = self.app.try_state::<Arc<AudioRecordingManager>>() {
+            rm.handle_devices_changed(replugged);
+        }
+    }
+
+    #[cfg_attr(target_os = "macos", allow(dead_code))]
+    fn poll(&self) -> ! {
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            self.refresh();
+        }
+    }
+}
+
+/// Start watching for microphone changes and emit "audio-devices-changed"
+/// with an [`AudioDevicesChanged`] payload whenever input devices are added
+/// or removed. An open microphone stream follows the change (see
+/// [`AudioRecordingManager::handle_devices_changed`]).
 ///
 /// macOS: zero-overhead push via `AudioObjectAddPropertyListener`.
-/// Windows/Linux: lightweight polling thread (checks every 2 s).
+/// Linux: PulseAudio/PipeWire subscription, then udev, then polling.
+/// Windows: lightweight polling thread (checks every 2 s).
+///
+/// Only the Linux subscriptions report removals as such, so only there a
+/// microphone that is unplugged and plugged back in quickly is reopened.
 #[cfg(target_os = "macos")]
 pub fn start(app: &AppHandle) {
     use coreaudio_sys::{
@@ -19,13 +184,13 @@ pub fn start(app: &AppHandle) {
         _addresses: *const coreaudio_sys::AudioObjectPropertyAddress,
         client_data: *mut c_void,
     ) -> coreaudio_sys::OSStatus {
-        let app = &*(client_data as *const AppHandle);
-        let _ = app.emit("audio-devices-changed", ());
+        let tracker = &*(client_data as *const Arc<DeviceTracker>);
+        tracker.trigger();
         0
     }
 
-    // Leak the AppHandle box — the listener lives for the entire app lifetime.
-    let client_data = Box::into_raw(Box::new(app.clone())) as *mut c_void;
+    // Leak the tracker box — the listener lives for the entire app lifetime.
+    let client_data = Box::into_raw(Box::new(DeviceTracker::new(app))) as *mut c_void;
 
     let address = AudioObjectPropertyAddress {
         mSelector: kAudioHardwarePropertyDevices,
@@ -44,32 +209,186 @@ pub fn start(app: &AppHandle) {
     }
 }
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(target_os = "linux")]
 pub fn start(app: &AppHandle) {
-    use crate::audio_toolkit::audio::list_input_devices;
-    use std::collections::HashSet;
+    let tracker = DeviceTracker::new(app);
+    std::thread::spawn(move || linux::watch(&tracker));
+}
 
-    let app = app.clone();
-    std::thread::spawn(move || {
-        let mut prev: HashSet<String> = list_input_devices()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|info| info.name)
-            .collect();
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn start(app: &AppHandle) {
+    let tracker = DeviceTracker::new(app);
+    std::thread::spawn(move || tracker.poll());
+}
 
-        loop {
-            std::thread::sleep(std::time::Duration::from_secs(2));
+/// Parse a `pactl subscribe` line for a source being added or removed, e.g.
+/// `Event 'new' on source #53`. Sinks, cards, volume and state changes are
+/// ignored.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn pulse_device_event(line: &str) -> Option<DeviceEvent> {
+    if !line.contains(" on source #") {
+        return None;
+    }
+    if line.starts_with("Event 'new'") {
+        Some(DeviceEvent::Added)
+    } else if line.starts_with("Event 'remove'") {
+        Some(DeviceEvent::Removed)
+    } else {
+        None
+    }
+}
+
+/// Parse a `udevadm monitor` line for a sound device being added or
+/// removed, e.g. `UDEV  [1234.567890] add      /devices/.../sound/card2 (sound)`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn udev_sound_event(line: &str) -> Option<DeviceEvent> {
+    let mut fields = line.split_whitespace();
+    if fields.next() != Some("UDEV") || !line.ends_with("(sound)") {
+        return None;
+    }
+    match fields.nth(1) {
+        Some("add") => Some(DeviceEvent::Added),
+        Some("remove") => Some(DeviceEvent::Removed),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{pulse_device_event, udev_sound_event, DeviceEvent, DeviceTracker};
+    use log::{debug, info};
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// A subscription that ends sooner than this is considered broken (no
+    /// sound server, missing permissions) rather than restarted.
+    const MIN_SUBSCRIPTION: Duration = Duration::from_secs(10);
+
+    /// Event sources in order of preference: the sound server knows about
+    /// Bluetooth and virtual devices, udev only about hardware cards.
+    const SUBSCRIPTIONS: &[(&str, &[&str], EventParser)] = &[
+        ("pactl", &["subscribe"], pulse_device_event),
+        (
+            "udevadm",
+            &["monitor", "--udev", "--subsystem-match=sound"],
+            udev_sound_event,
+        ),
+    ];
 
-            let current: HashSet<String> = list_input_devices()
-                .unwrap_or_default()
-                .into_iter()
-                .map(|info| info.name)
-                .collect();
+    type EventParser = fn(&str) -> Option<DeviceEvent>;
 
-            if current != prev {
-                prev = current;
-                let _ = app.emit("audio-devices-changed", ());
+    pub(super) fn watch(tracker: &Arc<DeviceTracker>) {
+        for (program, args, parse_event) in SUBSCRIPTIONS {
+            loop {
+                let started = Instant::now();
+                if !follow(program, args, *parse_event, tracker) {
+                    break;
+                }
+                if started.elapsed() < MIN_SUBSCRIPTION {
+                    debug!("{} stopped right away, trying the next source", program);
+                    break;
+                }
+                // e.g. the sound server restarted; devices may have changed meanwhile
+                debug!("{} exited, resubscribing", program);
+                tracker.trigger();
             }
         }
-    });
+
+        info!("No device notification source available, polling for changes");
+        tracker.poll();
+    }
+
+    /// Run `program` and pass each device event it prints to the tracker.
+    /// Returns false when it could not be started.
+    fn follow(
+        program: &str,
+        args: &[&str],
+        parse_event: EventParser,
+        tracker: &Arc<DeviceTracker>,
+    ) -> bool {
+        let Ok(mut child) = Command::new(program)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return false;
+        };
+        debug!("Watching audio devices with {}", program);
+
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(event) = parse_event(&line) {
+                    tracker.on_event(event);
+                }
+            }
+        }
+        let _ = child.wait();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_added_and_removed_devices() {
+        let change = AudioDevicesChanged::between(
+            &set(&["Built-in", "Headset"]),
+            &set(&["Built-in", "USB Mic", "Webcam"]),
+        );
+
+        assert_eq!(change.added, vec!["USB Mic", "Webcam"]);
+        assert_eq!(change.removed, vec!["Headset"]);
+        assert!(AudioDevicesChanged::between(&set(&["A"]), &set(&["A"])).is_empty());
+    }
+
+    #[test]
+    fn filters_subscription_events() {
+        assert_eq!(
+            pulse_device_event("Event 'new' on source #53"),
+            Some(DeviceEvent::Added)
+        );
+        assert_eq!(
+            pulse_device_event("Event 'remove' on source #57"),
+            Some(DeviceEvent::Removed)
+        );
+        assert_eq!(pulse_device_event("Event 'new' on sink #58"), None);
+        assert_eq!(pulse_device_event("Event 'remove' on card #57"), None);
+        assert_eq!(pulse_device_event("Event 'change' on source #53"), None);
+        assert_eq!(pulse_device_event("Event 'new' on sink-input #120"), None);
+
+        assert_eq!(
+            udev_sound_event(
+                "UDEV  [4711.123456] add      /devices/pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0/sound/card2 (sound)"
+            ),
+            Some(DeviceEvent::Added)
+        );
+        assert_eq!(
+            udev_sound_event(
+                "UDEV  [4711.123456] remove   /devices/pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0/sound/card2 (sound)"
+            ),
+            Some(DeviceEvent::Removed)
+        );
+        assert_eq!(
+            udev_sound_event(
+                "UDEV  [4711.123456] change   /devices/pci0000:00/0000:00:1f.3/sound/card0 (sound)"
+            ),
+            None
+        );
+        assert_eq!(
+            udev_sound_event("monitor will print the received events for:"),
+            None
+        );
+    }
 }
@@ -0,0 +1,118 @@
+//! Speaker-labelled transcripts for history entries.
+//!
+//! When the selected cloud provider reports speakers itself (Soniox with
+//! speaker diarization enabled), its tokens are used as they are. Otherwise
+//! the experimental local diarizer in `audio_toolkit` splits the recording
+//! into speaker turns and each turn is transcribed through the regular
+//! pipeline. Turns that fail to transcribe are left out.
+
+use log::{debug, info, warn};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::diarization::{diarize, DiarizationConfig};
+use crate::managers::audio::{AudioRecordingManager, VAD_FRAME_SAMPLES};
+use crate::managers::history::{HistoryEntry, HistoryManager, TranscriptSegment};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::get_settings;
+
+fn samples_to_ms(samples: usize) -> i64 {
+    (samples as i64 * 1000) / WHISPER_SAMPLE_RATE as i64
+}
+
+/// Diarize the stored audio of a history entry and save its speaker turns.
+pub async fn diarize_history_entry(
+    app: &AppHandle,
+    entry: &HistoryEntry,
+) -> anyhow::Result<HistoryEntry> {
+    let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+
+    let samples = hm.load_audio(&entry.file_name)?;
+
+    if let Some(segments) = tm.transcribe_with_speakers(samples.clone()).await? {
+        info!(
+            "Provider diarized history entry {} into {} turns",
+            entry.id,
+            segments.len()
+        );
+        let text = segments
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        return hm.set_segments(entry.id, &segments, Some(&text)).await;
+    }
+
+    let settings = get_settings(app);
+    let config = DiarizationConfig {
+        max_speakers: settings.diarization_max_speakers.max(1) as usize,
+        ..Default::default()
+    };
+    let speech = rm.detect_speech(&samples)?;
+    let turns = diarize(&samples, &speech, VAD_FRAME_SAMPLES, config);
+    info!(
+        "Experimental local diarization found {} turns in history entry {}",
+        turns.len(),
+        entry.id
+    );
+
+    tm.initiate_model_load();
+    let total = turns.len();
+    let mut segments = Vec::new();
+    let mut first_error = None;
+    let mut failed = 0;
+    for turn in turns {
+        let text = match tm.transcribe(samples[turn.start..turn.end].to_vec()).await {
+            Ok(text) => text,
+            Err(e) => {
+                warn!(
+                    "Skipping turn {}..{} of history entry {}: {}",
+                    turn.start, turn.end, entry.id, e
+                );
+                failed += 1;
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        segments.push(TranscriptSegment {
+            speaker: format!("Speaker {}", turn.speaker + 1),
+            start_ms: samples_to_ms(turn.start),
+            end_ms: samples_to_ms(turn.end),
+            text: text.to_string(),
+        });
+    }
+
+    if failed == total {
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+    }
+    if failed > 0 {
+        warn!(
+            "{} of {} turns of history entry {} could not be transcribed",
+            failed, total, entry.id
+        );
+    }
+
+    info!(
+        "Diarized history entry {} into {} turns",
+        entry.id,
+        segments.len()
+    );
+    if segments.is_empty() {
+        return hm.set_segments(entry.id, &segments, None).await;
+    }
+    let text = segments
+        .iter()
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    hm.set_segments(entry.id, &segments, Some(&text)).await
+}
@@ -0,0 +1,477 @@
+//! Committing text through an input method engine
+//!
+//! With the input method paste method, Handless registers an IBus engine and
+//! text is committed to the focused field by IBus itself, the same way an
+//! input method for another script would. This works in any toolkit, also in
+//! Wayland clients that ignore synthetic key events, and lets streaming
+//! transcription show its partial results as preedit text.
+//!
+//! The engine only receives text while it's the active input source, so
+//! "Handless" has to be selected as one in the IBus or desktop settings. Keys
+//! typed meanwhile are passed through unchanged.
+//!
+//! Fcitx 5 doesn't host IBus engines: its engines are addons loaded into the
+//! Fcitx process, with no D-Bus interface for an outside program to commit
+//! text through. While Fcitx is the input method framework this method is
+//! unavailable, and the settings say so.
+
+use tauri::AppHandle;
+
+use crate::settings::{get_settings, PasteMethod};
+
+/// Register the engine if the input method paste method is selected and it
+/// isn't registered yet.
+pub fn init(app: &AppHandle) {
+    if !is_selected(app) {
+        return;
+    }
+    if fcitx_active() {
+        log::warn!("The input method paste method needs IBus, but Fcitx is active");
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    linux::start();
+}
+
+/// Commit `text` to the focused field, replacing any preedit.
+pub fn commit(text: &str) -> Result<(), String> {
+    if fcitx_active() {
+        return Err(
+            "The input method paste method needs IBus, but Fcitx is the active input method framework"
+                .into(),
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::commit(text)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = text;
+        Err("The input method paste method is only available on Linux with IBus".into())
+    }
+}
+
+/// Whether Fcitx is the input method framework of the session, as announced
+/// to toolkits through the usual environment variables.
+pub fn fcitx_active() -> bool {
+    cfg!(target_os = "linux")
+        && ["GTK_IM_MODULE", "QT_IM_MODULE", "XMODIFIERS"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .any(|value| names_fcitx(&value))
+}
+
+/// Whether an input method variable such as `XMODIFIERS=@im=fcitx5` picks Fcitx.
+fn names_fcitx(value: &str) -> bool {
+    value.to_ascii_lowercase().contains("fcitx")
+}
+
+/// Whether the input method paste method is selected, and with it the
+/// preedit shows partial transcriptions.
+pub fn is_selected(app: &AppHandle) -> bool {
+    get_settings(app).paste_method == PasteMethod::InputMethod
+}
+
+/// Show a partial transcription as preedit text, or clear it with an empty
+/// string. Callers check [`is_selected`] once per recording.
+pub fn show_preedit(text: &str) {
+    #[cfg(target_os = "linux")]
+    if let Err(e) = linux::update_preedit(text) {
+        log::debug!("Failed to update preedit: {}", e);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = text;
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use log::{info, warn};
+    use std::collections::HashMap;
+    use std::process::Command;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
+    use zbus::blocking::{connection, Connection};
+    use zbus::object_server::ObjectServer;
+    use zbus::zvariant::{OwnedObjectPath, Structure, StructureBuilder, Value};
+    use zbus::{fdo, interface};
+
+    const COMPONENT_NAME: &str = "org.freedesktop.IBus.Handless";
+    const ENGINE_NAME: &str = "handless";
+    const ENGINE_INTERFACE: &str = "org.freedesktop.IBus.Engine";
+
+    /// `IBUS_ENGINE_PREEDIT_CLEAR`: drop the preedit when focus moves on.
+    const PREEDIT_CLEAR: u32 = 0;
+
+    /// The connection to the IBus daemon, once the engine is registered.
+    static CONNECTION: Mutex<Option<Connection>> = Mutex::new(None);
+
+    /// Whether a registration is in progress or done.
+    static STARTED: AtomicBool = AtomicBool::new(false);
+
+    /// The engine instance of the focused input context.
+    static FOCUSED_ENGINE: Mutex<Option<OwnedObjectPath>> = Mutex::new(None);
+
+    pub(super) fn start() {
+        if STARTED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        thread::spawn(|| match register() {
+            Ok(connection) => {
+                info!("Registered IBus engine '{}'", ENGINE_NAME);
+                *CONNECTION.lock().unwrap() = Some(connection);
+            }
+            Err(e) => {
+                warn!("Failed to register IBus engine: {}", e);
+                // Let a later init try again, e.g. once IBus is running
+                STARTED.store(false, Ordering::SeqCst);
+            }
+        });
+    }
+
+    pub(super) fn commit(text: &str) -> Result<(), String> {
+        let (connection, path) = focused_engine()?;
+        emit_preedit(&connection, &path, "")?;
+        connection
+            .emit_signal(
+                None::<&str>,
+                &path,
+                ENGINE_INTERFACE,
+                "CommitText",
+                &(ibus_text(text),),
+            )
+            .map_err(|e| format!("Failed to commit text: {}", e))
+    }
+
+    pub(super) fn update_preedit(text: &str) -> Result<(), String> {
+        let (connection, path) = focused_engine()?;
+        emit_preedit(&connection, &path, text)
+    }
+
+    fn focused_engine() -> Result<(Connection, OwnedObjectPath), String> {
+        let connection = CONNECTION
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or("The Handless IBus engine isn't registered. Is IBus running?")?;
+        let path = FOCUSED_ENGINE.lock().unwrap().clone().ok_or(
+            "No text field has focus with the Handless input source. Select it as an input source in your IBus settings.",
+        )?;
+        Ok((connection, path))
+    }
+
+    fn emit_preedit(
+        connection: &Connection,
+        path: &OwnedObjectPath,
+        text: &str,
+    ) -> Result<(), String> {
+        let cursor = text.chars().count() as u32;
+        connection
+            .emit_signal(
+                None::<&str>,
+                path,
+                ENGINE_INTERFACE,
+                "UpdatePreeditText",
+                &(ibus_text(text), cursor, !text.is_empty(), PREEDIT_CLEAR),
+            )
+            .map_err(|e| format!("Failed to update preedit: {}", e))
+    }
+
+    /// Connect to the IBus daemon and register a component with our engine;
+    /// IBus then asks the factory for an engine when it's selected.
+    fn register() -> Result<Connection, String> {
+        let address = bus_address()?;
+        let connection = connection::Builder::address(address.as_str())
+            .and_then(|builder| {
+                builder.serve_at("/org/freedesktop/IBus/Factory", Factory::default())
+            })
+            .and_then(|builder| builder.build())
+            .map_err(|e| format!("Failed to connect to IBus: {}", e))?;
+
+        let component = component().map_err(|e| e.to_string())?;
+        connection
+            .call_method(
+                Some("org.freedesktop.IBus"),
+                "/org/freedesktop/IBus",
+                Some("org.freedesktop.IBus"),
+                "RegisterComponent",
+                &(Value::from(component),),
+            )
+            .map_err(|e| format!("Failed to register IBus component: {}", e))?;
+        Ok(connection)
+    }
+
+    /// IBus runs its own bus, announced in `IBUS_ADDRESS` or by `ibus address`.
+    fn bus_address() -> Result<String, String> {
+        if let Ok(address) = std::env::var("IBUS_ADDRESS") {
+            return Ok(address);
+        }
+        let output = Command::new("ibus")
+            .arg("address")
+            .output()
+            .map_err(|e| format!("Failed to execute ibus: {}", e))?;
+        let address = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || address.is_empty() || address == "(null)" {
+            return Err("IBus isn't running".into());
+        }
+        Ok(address)
+    }
+
+    /// Serialized `IBusSerializable`s start with their type name and a dict of
+    /// attachments.
+    fn serializable(type_name: &'static str) -> StructureBuilder<'static> {
+        StructureBuilder::new()
+            .add_field(type_name)
+            .add_field(HashMap::<String, Value<'static>>::new())
+    }
+
+    /// An `IBusText` without attributes.
+    fn ibus_text(text: &str) -> Value<'static> {
+        let attributes = serializable("IBusAttrList")
+            .add_field(Vec::<Value<'static>>::new())
+            .build()
+            .expect("IBusAttrList is a valid structure");
+        let text = serializable("IBusText")
+            .add_field(text.to_string())
+            .append_field(Value::Value(Box::new(Value::from(attributes))))
+            .build()
+            .expect("IBusText is a valid structure");
+        Value::from(text)
+    }
+
+    /// An `IBusComponent` offering one engine.
+    fn component() -> zbus::zvariant::Result<Structure<'static>> {
+        let engine = serializable("IBusEngineDesc")
+            .add_field(ENGINE_NAME) // name
+            .add_field("Handless") // longname
+            .add_field("Dictated text from Handless") // description
+            .add_field("other") // language
+            .add_field("MIT") // license
+            .add_field("Handless") // author
+            .add_field("audio-input-microphone") // icon
+            .add_field("default") // layout
+            .add_field(0u32) // rank
+            .add_field("") // hotkeys
+            .add_field("🎤") // symbol
+            .add_field("") // setup
+            .add_field("") // layout_variant
+            .add_field("") // layout_option
+            .add_field(env!("CARGO_PKG_VERSION")) // version
+            .add_field("") // textdomain
+            .add_field("") // icon_prop_key
+            .build()?;
+
+        serializable("IBusComponent")
+            .add_field(COMPONENT_NAME) // name
+            .add_field("Handless speech to text") // description
+            .add_field(env!("CARGO_PKG_VERSION")) // version
+            .add_field("MIT") // license
+            .add_field("Handless") // author
+            .add_field("https://github.com/ElwinLiu/handless") // homepage
+            .add_field("") // exec, empty since we're already running
+            .add_field("") // textdomain
+            .add_field(Vec::<Value<'static>>::new()) // observed paths
+            .add_field(vec![Value::from(engine)]) // engines
+            .build()
+    }
+
+    /// Creates an engine whenever IBus activates ours for an input context.
+    #[derive(Default)]
+    struct Factory {
+        engines: u32,
+    }
+
+    #[interface(name = "org.freedesktop.IBus.Factory")]
+    impl Factory {
+        async fn create_engine(
+            &mut self,
+            #[zbus(object_server)] server: &ObjectServer,
+            name: &str,
+        ) -> fdo::Result<OwnedObjectPath> {
+            self.engines += 1;
+            let path =
+                OwnedObjectPath::try_from(format!("/org/freedesktop/IBus/Engine/{}", self.engines))
+                    .map_err(|e| fdo::Error::Failed(e.to_string()))?;
+            server
+                .at(path.clone(), Engine { path: path.clone() })
+                .await?;
+            server
+                .at(path.clone(), Service { path: path.clone() })
+                .await?;
+            info!("IBus created engine '{}' at {}", name, path);
+            Ok(path)
+        }
+    }
+
+    /// An engine instance. It handles no keys itself; text arrives through
+    /// `commit` and `update_preedit`.
+    struct Engine {
+        path: OwnedObjectPath,
+    }
+
+    impl Engine {
+        fn lose_focus(&self) {
+            let mut focused = FOCUSED_ENGINE.lock().unwrap();
+            if focused.as_ref() == Some(&self.path) {
+                *focused = None;
+            }
+        }
+    }
+
+    #[interface(name = "org.freedesktop.IBus.Engine")]
+    impl Engine {
+        fn process_key_event(&self, _keyval: u32, _keycode: u32, _state: u32) -> bool {
+            false
+        }
+
+        fn focus_in(&self) {
+            *FOCUSED_ENGINE.lock().unwrap() = Some(self.path.clone());
+        }
+
+        fn focus_in_id(&self, _object_path: &str, _client: &str) {
+            *FOCUSED_ENGINE.lock().unwrap() = Some(self.path.clone());
+        }
+
+        fn focus_out(&self) {
+            self.lose_focus();
+        }
+
+        fn focus_out_id(&self, _object_path: &str) {
+            self.lose_focus();
+        }
+
+        fn disable(&self) {
+            self.lose_focus();
+        }
+
+        fn enable(&self) {}
+
+        fn reset(&self) {}
+
+        fn set_cursor_location(&self, _x: i32, _y: i32, _w: i32, _h: i32) {}
+
+        fn set_capabilities(&self, _caps: u32) {}
+
+        fn set_surrounding_text(&self, _text: Value<'_>, _cursor_pos: u32, _anchor_pos: u32) {}
+
+        fn set_content_type(&self, _purpose: u32, _hints: u32) {}
+
+        fn property_activate(&self, _name: &str, _state: u32) {}
+
+        fn property_show(&self, _name: &str) {}
+
+        fn property_hide(&self, _name: &str) {}
+
+        fn candidate_clicked(&self, _index: u32, _button: u32, _state: u32) {}
+
+        fn page_up(&self) {}
+
+        fn page_down(&self) {}
+
+        fn cursor_up(&self) {}
+
+        fn cursor_down(&self) {}
+    }
+
+    /// Lets IBus destroy an engine instance it no longer needs.
+    struct Service {
+        path: OwnedObjectPath,
+    }
+
+    #[interface(name = "org.freedesktop.IBus.Service")]
+    impl Service {
+        async fn destroy(&self, #[zbus(object_server)] server: &ObjectServer) -> fdo::Result<()> {
+            {
+                let mut focused = FOCUSED_ENGINE.lock().unwrap();
+                if focused.as_ref() == Some(&self.path) {
+                    *focused = None;
+                }
+            }
+            server.remove::<Engine, _>(self.path.clone()).await?;
+            server.remove::<Service, _>(self.path.clone()).await?;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn engine(index: u32) -> Engine {
+            Engine {
+                path: OwnedObjectPath::try_from(format!("/org/freedesktop/IBus/Engine/{}", index))
+                    .unwrap(),
+            }
+        }
+
+        fn focused() -> Option<String> {
+            FOCUSED_ENGINE
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|path| path.to_string())
+        }
+
+        #[test]
+        fn forwards_keys_and_commits_to_the_focused_engine_only() {
+            let first = engine(1);
+            let second = engine(2);
+
+            // Keys typed while the engine is active reach the application
+            assert!(!first.process_key_event(0x61, 30, 0));
+
+            assert!(commit("hello").unwrap_err().contains("isn't registered"));
+
+            first.focus_in();
+            assert_eq!(focused().as_deref(), Some("/org/freedesktop/IBus/Engine/1"));
+
+            // Another context losing focus leaves the focused one alone
+            second.focus_out();
+            assert_eq!(focused().as_deref(), Some("/org/freedesktop/IBus/Engine/1"));
+
+            second.focus_in_id("/org/freedesktop/IBus/InputContext_2", "gtk3");
+            first.disable();
+            assert_eq!(focused().as_deref(), Some("/org/freedesktop/IBus/Engine/2"));
+
+            second.focus_out_id("/org/freedesktop/IBus/InputContext_2");
+            assert_eq!(focused(), None);
+        }
+
+        #[test]
+        fn text_is_serialized_as_ibus_text() {
+            assert_eq!(
+                ibus_text("hello").value_signature().to_string(),
+                "(sa{sv}sv)"
+            );
+        }
+
+        #[test]
+        fn component_lists_the_engine() {
+            let component = Value::from(component().unwrap());
+            assert_eq!(
+                component.value_signature().to_string(),
+                "(sa{sv}ssssssssavav)"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_fcitx_in_input_method_variables() {
+        assert!(names_fcitx("fcitx"));
+        assert!(names_fcitx("@im=fcitx5"));
+        assert!(names_fcitx("Fcitx5"));
+        assert!(!names_fcitx("ibus"));
+        assert!(!names_fcitx("@im=ibus"));
+    }
+}
@@ -0,0 +1,282 @@
+//! Language-based routing of transcriptions to a preferred model or provider.
+//!
+//! The language comes either from the user's `selected_language` or, when that
+//! is `auto`, from a quick language-ID pass: the first seconds of audio are
+//! transcribed with a multilingual model and the script and vocabulary of the
+//! result decide the language.
+
+use crate::settings::{AppSettings, LanguageRoute};
+
+/// Where a transcription should be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteTarget {
+    pub provider_id: String,
+    /// Local model to use; `None` means the currently selected model.
+    pub model_id: Option<String>,
+    /// Language hint passed to the engine (`auto` when unknown).
+    pub language: String,
+}
+
+impl RouteTarget {
+    /// The configured provider and model, without any routing applied.
+    pub fn default_for(settings: &AppSettings) -> Self {
+        Self {
+            provider_id: settings.stt_provider_id.clone(),
+            model_id: None,
+            language: settings.selected_language.clone(),
+        }
+    }
+}
+
+/// Strip script/region suffixes so `zh-Hans` and `zh` match the same rule.
+pub fn base_language(code: &str) -> &str {
+    code.split(['-', '_']).next().unwrap_or(code)
+}
+
+fn route_matches(route: &LanguageRoute, language: &str) -> bool {
+    route.language == language || base_language(&route.language) == base_language(language)
+}
+
+/// Pick the route for `language`.
+///
+/// An explicit rule for the language always wins. Otherwise the default
+/// provider is kept, unless it cannot handle the language
+/// (`default_supports_language` is false) and a catch-all `*` rule exists.
+pub fn resolve_route(
+    settings: &AppSettings,
+    language: &str,
+    default_supports_language: bool,
+) -> RouteTarget {
+    let rule = settings
+        .language_routes
+        .iter()
+        .find(|route| route.language != "*" && route_matches(route, language))
+        .or_else(|| {
+            if default_supports_language {
+                None
+            } else {
+                settings
+                    .language_routes
+                    .iter()
+                    .find(|route| route.language == "*")
+            }
+        });
+
+    match rule {
+        Some(route) => RouteTarget {
+            provider_id: route.provider_id.clone(),
+            model_id: route.model_id.clone().filter(|id| !id.is_empty()),
+            language: language.to_string(),
+        },
+        None => RouteTarget {
+            language: language.to_string(),
+            ..RouteTarget::default_for(settings)
+        },
+    }
+}
+
+fn is_han(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}')
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{309F}' | '\u{30A0}'..='\u{30FF}')
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}')
+}
+
+/// Common function words used to tell Latin-script languages apart. Some are
+/// shared ("que", "de", "e"), so a language only wins with more hits than any
+/// other.
+const LATIN_STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "to", "of", "it", "that", "this", "what", "with",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "ein", "eine", "mit", "zu",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "une", "je", "pas", "que", "des", "vous", "avec",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "es", "una", "que", "por", "para", "con", "está", "pero",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "di", "che", "e", "non", "sono", "una", "per", "con", "questo", "gli",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "e", "não", "uma", "que", "com", "para", "você", "está", "isso",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "ik", "dat", "van", "met", "zijn",
+        ],
+    ),
+];
+
+/// Guess the language of a transcript from its script and, for Latin text,
+/// from common function words. Returns `None` when the text is inconclusive.
+pub fn detect_language_from_text(text: &str) -> Option<String> {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.is_empty() {
+        return None;
+    }
+
+    let count = |pred: fn(char) -> bool| letters.iter().filter(|c| pred(**c)).count();
+    let kana = count(is_kana);
+    let han = count(is_han);
+    let hangul = count(is_hangul);
+    let cyrillic = count(|c| matches!(c, '\u{0400}'..='\u{04FF}'));
+    let arabic = count(|c| matches!(c, '\u{0600}'..='\u{06FF}'));
+    let hebrew = count(|c| matches!(c, '\u{0590}'..='\u{05FF}'));
+    let greek = count(|c| matches!(c, '\u{0370}'..='\u{03FF}'));
+    let thai = count(|c| matches!(c, '\u{0E00}'..='\u{0E7F}'));
+    let devanagari = count(|c| matches!(c, '\u{0900}'..='\u{097F}'));
+
+    // A script has to make up a meaningful share of the letters to count,
+    // so a stray loanword does not flip the result.
+    let dominant = |n: usize| n * 3 >= letters.len();
+
+    if kana > 0 && dominant(kana + han) {
+        return Some("ja".to_string());
+    }
+    let scripts = [
+        (hangul, "ko"),
+        (han, "zh"),
+        (cyrillic, "ru"),
+        (arabic, "ar"),
+        (hebrew, "he"),
+        (greek, "el"),
+        (thai, "th"),
+        (devanagari, "hi"),
+    ];
+    if let Some((_, language)) = scripts
+        .iter()
+        .filter(|(n, _)| dominant(*n))
+        .max_by_key(|(n, _)| *n)
+    {
+        return Some(language.to_string());
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    let mut scores: Vec<(&str, usize)> = LATIN_STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|w| stopwords.contains(&w.as_str()))
+                .count();
+            (*language, hits)
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.cmp(&a.1));
+
+    match scores.as_slice() {
+        [(language, hits), (_, runner_up), ..] if *hits > *runner_up => Some(language.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::get_default_settings;
+
+    fn route(language: &str, provider_id: &str, model_id: Option<&str>) -> LanguageRoute {
+        LanguageRoute {
+            language: language.to_string(),
+            provider_id: provider_id.to_string(),
+            model_id: model_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn detects_scripts() {
+        assert_eq!(
+            detect_language_from_text("你好，今天天气很好").as_deref(),
+            Some("zh")
+        );
+        assert_eq!(
+            detect_language_from_text("こんにちは、元気ですか").as_deref(),
+            Some("ja")
+        );
+        assert_eq!(
+            detect_language_from_text("안녕하세요 반갑습니다").as_deref(),
+            Some("ko")
+        );
+        assert_eq!(
+            detect_language_from_text("Привет, как дела?").as_deref(),
+            Some("ru")
+        );
+    }
+
+    #[test]
+    fn detects_latin_languages_from_function_words() {
+        assert_eq!(
+            detect_language_from_text("What is the status of this ticket?").as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language_from_text("Ich weiß nicht, ob das eine gute Idee ist.").as_deref(),
+            Some("de")
+        );
+        assert_eq!(detect_language_from_text("...").as_deref(), None);
+    }
+
+    #[test]
+    fn shared_function_words_are_inconclusive() {
+        // "con" and "una" are both Spanish and Italian
+        assert_eq!(detect_language_from_text("con una").as_deref(), None);
+        assert_eq!(detect_language_from_text("que").as_deref(), None);
+        assert_eq!(
+            detect_language_from_text("Creo que es una buena idea para el equipo").as_deref(),
+            Some("es")
+        );
+    }
+
+    #[test]
+    fn explicit_rule_wins() {
+        let mut settings = get_default_settings();
+        settings.language_routes = vec![route("zh", "local", Some("small"))];
+
+        let target = resolve_route(&settings, "zh-Hans", true);
+
+        assert_eq!(target.provider_id, "local");
+        assert_eq!(target.model_id.as_deref(), Some("small"));
+        assert_eq!(target.language, "zh-Hans");
+    }
+
+    #[test]
+    fn catch_all_only_applies_when_default_cannot_handle_language() {
+        let mut settings = get_default_settings();
+        settings.language_routes = vec![route("*", "soniox", None)];
+
+        assert_eq!(resolve_route(&settings, "en", true).provider_id, "local");
+        assert_eq!(resolve_route(&settings, "de", false).provider_id, "soniox");
+    }
+}
@@ -5,13 +5,20 @@ mod audio_feedback;
 pub mod audio_toolkit;
 pub mod cli;
 mod clipboard;
+mod clipboard_snapshot;
 mod cloud_stt;
 mod commands;
 mod device_watcher;
+mod diarization;
 mod helpers;
 mod input;
+mod input_method;
+mod language_routing;
 mod managers;
+mod meeting;
 mod overlay;
+mod output_sinks;
+mod pipeline_error;
 pub mod post_process;
 mod settings;
 mod shortcut;
@@ -20,7 +27,10 @@ pub mod stt_provider;
 mod transcription_coordinator;
 mod tray;
 mod tray_i18n;
+#[cfg(target_os = "linux")]
+mod uinput_keyboard;
 mod utils;
+mod wake_word;
 
 pub use cli::CliArgs;
 #[cfg(debug_assertions)]
@@ -143,14 +153,28 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     // for the first transcription without the 2-5s cold-start delay.
     {
         let settings = get_settings(app_handle);
-        if settings.model_unload_timeout != ModelUnloadTimeout::Immediately {
+        if settings.unload_timeout_for(&settings.selected_model) != ModelUnloadTimeout::Immediately
+        {
             transcription_manager.initiate_model_load();
         }
     }
 
+    // Load the wake-word model, which needs the model manager
+    if get_settings(app_handle).wake_word_enabled {
+        if let Err(e) = recording_manager.update_wake_word() {
+            log::warn!("Failed to start listening for the wake phrase: {}", e);
+        }
+    }
+
+    // Retry transcriptions left unfinished by a crash or forced quit
+    actions::recover_pending_transcriptions(app_handle);
+
     // Subscribe to OS audio device change notifications
     device_watcher::start(app_handle);
 
+    // Register the IBus engine when text is committed through it
+    input_method::init(app_handle);
+
     // Note: Shortcuts are NOT initialized here.
     // The frontend is responsible for calling the `initialize_shortcuts` command
     // after permissions are confirmed (on macOS) or after onboarding completes.
@@ -288,7 +312,9 @@ pub fn run(cli_args: CliArgs) {
         shortcut::change_autostart_setting,
         shortcut::change_translate_to_english_setting,
         shortcut::change_selected_language_setting,
+        shortcut::change_language_routing_setting,
         shortcut::change_overlay_position_setting,
+        shortcut::change_error_notifications_setting,
         shortcut::change_debug_mode_setting,
         shortcut::change_word_correction_threshold_setting,
         shortcut::change_paste_method_setting,
@@ -296,11 +322,16 @@ pub fn run(cli_args: CliArgs) {
         shortcut::change_typing_tool_setting,
         shortcut::change_external_script_path_setting,
         shortcut::change_clipboard_handling_setting,
+        shortcut::change_clipboard_restore_delay_setting,
         shortcut::change_auto_submit_setting,
         shortcut::change_auto_submit_key_setting,
         shortcut::add_transcribe_binding,
         shortcut::remove_transcribe_binding,
         shortcut::update_binding_prompt,
+        shortcut::update_binding_activation_mode,
+        shortcut::update_binding_device_trigger,
+        shortcut::update_binding_output_sinks,
+        shortcut::device_triggers::capture_device_trigger,
         post_process::commands::change_post_process_base_url_setting,
         post_process::commands::change_post_process_api_key_setting,
         post_process::commands::change_post_process_model_setting,
@@ -328,11 +359,16 @@ pub fn run(cli_args: CliArgs) {
         shortcut::change_stt_api_key_setting,
         shortcut::change_stt_cloud_model_setting,
         shortcut::change_stt_realtime_enabled_setting,
+        shortcut::change_stt_fallback_setting,
         shortcut::handy_keys::start_handy_keys_recording,
         shortcut::handy_keys::stop_handy_keys_recording,
         trigger_update_check,
         commands::cancel_operation,
         commands::confirm_recording,
+        commands::set_hands_free_mode,
+        commands::get_hands_free_mode,
+        commands::toggle_meeting,
+        commands::is_meeting_active,
         commands::get_app_dir_path,
         commands::get_app_settings,
         commands::get_default_settings,
@@ -345,6 +381,7 @@ pub fn run(cli_args: CliArgs) {
         commands::reload_settings,
         commands::check_apple_intelligence_available,
         commands::is_homebrew_install,
+        commands::is_fcitx_active,
         commands::initialize_enigo,
         commands::initialize_shortcuts,
         commands::models::get_available_models,
@@ -361,8 +398,15 @@ pub fn run(cli_args: CliArgs) {
         commands::models::update_dictionary_terms,
         commands::models::update_dictionary_context,
         commands::audio::update_microphone_mode,
+        commands::audio::update_pre_roll_settings,
+        commands::audio::update_auto_stop_settings,
+        commands::audio::update_wake_word_settings,
+        commands::audio::update_meeting_settings,
+        commands::audio::set_input_processing,
+        commands::audio::test_microphone,
         commands::audio::get_microphone_mode,
         commands::audio::get_available_microphones,
+        commands::audio::get_loopback_sources,
         commands::audio::set_microphone_priority,
         commands::audio::set_selected_microphone,
         commands::audio::get_selected_microphone,
@@ -378,11 +422,16 @@ pub fn run(cli_args: CliArgs) {
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::unload_model_manually,
+        commands::transcription::set_model_unload_timeout_override,
+        commands::transcription::set_model_pool_budget,
         commands::history::get_history_entries,
         commands::history::get_history_entries_page,
         commands::history::toggle_history_entry_saved,
         commands::history::get_audio_file_path,
         commands::history::delete_history_entry,
+        commands::history::retry_history_entry_transcription,
+        commands::history::diarize_history_entry,
+        commands::history::update_diarization_settings,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
         commands::history::get_speaking_stats,
@@ -447,6 +496,7 @@ pub fn run(cli_args: CliArgs) {
         }))
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_clipboard_manager::init())
@@ -495,6 +545,7 @@ pub fn run(cli_args: CliArgs) {
             FILE_LOG_LEVEL.store(file_log_level.to_level_filter() as u8, Ordering::Relaxed);
             let app_handle = app.handle().clone();
             app.manage(TranscriptionCoordinator::new(app_handle.clone()));
+            app.manage(meeting::MeetingRecorder::new(&app_handle));
 
             initialize_core_logic(&app_handle);
 
@@ -578,6 +629,9 @@ pub fn run(cli_args: CliArgs) {
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app, event| {
+            if let tauri::RunEvent::Exit = &event {
+                output_sinks::stop_commands();
+            }
             #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Reopen { .. } = &event {
                 show_main_window(app);
@@ -1,11 +1,23 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::audio_toolkit::{
+    audio::{analyze_levels, DspConfig},
+    get_cpal_host, list_input_devices,
+    vad::{AutoStopConfig, SmoothedVad},
+    AudioRecorder, SharedKeywordSpotter, SileroVad, VoiceActivityDetector,
+};
 use crate::helpers::clamshell;
+use crate::managers::model::ModelManager;
 use crate::settings::{get_settings, AppSettings};
 use crate::utils;
-use log::{debug, error, info};
+use crate::wake_word::{self, PhraseSpotter};
+use crate::TranscriptionCoordinator;
+use cpal::traits::{DeviceTrait, HostTrait};
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use specta::Type;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use tauri::Manager;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 
 fn set_mute(mute: bool) {
     // Expected behavior:
@@ -98,6 +110,12 @@ fn set_mute(mute: bool) {
 
 const WHISPER_SAMPLE_RATE: usize = 16000;
 
+/// Samples per VAD frame (30 ms at 16 kHz).
+pub const VAD_FRAME_SAMPLES: usize = 480;
+
+/// Silence after which a hands-free utterance without speech starts over.
+const HANDS_FREE_NO_SPEECH: Duration = Duration::from_secs(10);
+
 /* ──────────────────────────────────────────────────────────────── */
 
 #[derive(Clone, Debug)]
@@ -112,23 +130,170 @@ pub enum MicrophoneMode {
     OnDemand,
 }
 
+/// Result of a short test capture from one input device.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct MicrophoneTest {
+    pub device_name: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+    pub duration_secs: f32,
+    pub peak_dbfs: f32,
+    pub rms_dbfs: f32,
+    pub clipped_percent: f32,
+    pub noise_floor_dbfs: f32,
+    pub snr_db: f32,
+    /// Share of 30 ms frames (0..=1) the VAD classified as speech.
+    pub speech_ratio: f32,
+}
+
 /* ──────────────────────────────────────────────────────────────── */
 
+/// How the recorder uses voice activity detection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct VadUsage {
+    /// Drop non-speech frames from the recording.
+    filter: bool,
+    /// Run the VAD for auto-stop even when not filtering.
+    endpointing: bool,
+    /// Run the VAD on the idle stream to find wake-word candidates.
+    wake_word: bool,
+}
+
+impl VadUsage {
+    fn new(settings: &AppSettings, hands_free: bool) -> Self {
+        Self {
+            filter: settings.stt_provider_id == "local",
+            endpointing: settings.auto_stop_enabled || hands_free,
+            wake_word: settings.wake_word_enabled,
+        }
+    }
+
+    fn needs_vad(self) -> bool {
+        self.filter || self.endpointing || self.wake_word
+    }
+}
+
+/// Hands-free mode always ends utterances on silence, using the auto-stop
+/// timings even when auto-stop itself is off. It also drops silence that
+/// no speech followed, so an utterance only starts counting towards the
+/// maximum duration once something is said.
+fn auto_stop_config(settings: &AppSettings, hands_free: bool) -> Option<AutoStopConfig> {
+    (settings.auto_stop_enabled || hands_free).then(|| AutoStopConfig {
+        silence: Duration::from_millis(settings.auto_stop_silence_ms as u64),
+        min_speech: Duration::from_millis(settings.auto_stop_min_speech_ms as u64),
+        max_duration: (settings.auto_stop_max_duration_secs > 0)
+            .then(|| Duration::from_secs(settings.auto_stop_max_duration_secs as u64)),
+        no_speech: hands_free.then_some(HANDS_FREE_NO_SPEECH),
+    })
+}
+
+/// Capture processing configured for the device about to be opened.
+fn dsp_config(settings: &AppSettings, device: Option<&cpal::Device>) -> DspConfig {
+    let name = device
+        .and_then(|d| d.name().ok())
+        .unwrap_or_else(|| "Default".to_string());
+    let processing = settings
+        .input_processing
+        .get(&name)
+        .copied()
+        .unwrap_or_default();
+
+    DspConfig {
+        high_pass: processing.high_pass,
+        noise_suppression: processing.noise_suppression,
+        auto_gain: processing.auto_gain,
+    }
+}
+
+/// Devices to try, in order, after the device named `failed` stopped
+/// working: the available entries of the priority list ("Default" standing
+/// for the system default), then the system default itself.
+fn failover_order(
+    priority: &[String],
+    available: &[String],
+    default: Option<&str>,
+    failed: &str,
+) -> Vec<String> {
+    let mut order: Vec<String> = Vec::new();
+    let candidates = priority
+        .iter()
+        .map(|name| {
+            if name == "Default" {
+                default
+            } else {
+                Some(name.as_str())
+            }
+        })
+        .chain(std::iter::once(default));
+    for name in candidates.flatten() {
+        let usable = name != failed && available.iter().any(|a| a == name);
+        if usable && !order.iter().any(|o| o == name) {
+            order.push(name.to_string());
+        }
+    }
+    order
+}
+
 fn create_audio_recorder(
     vad_path: &str,
     app_handle: &tauri::AppHandle,
-    use_vad: bool,
+    vad_usage: VadUsage,
+    keyword_spotter: &SharedKeywordSpotter,
 ) -> Result<AudioRecorder, anyhow::Error> {
     let mut recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?;
 
-    if use_vad {
+    if vad_usage.needs_vad() {
         let silero = SileroVad::new(vad_path, 0.15)
             .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
         let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
-        recorder = recorder.with_vad(Box::new(smoothed_vad));
+        recorder = recorder
+            .with_vad(Box::new(smoothed_vad))
+            .with_silence_filter(vad_usage.filter);
     }
 
+    recorder = recorder.with_endpoint_callback({
+        let app_handle = app_handle.clone();
+        move || {
+            debug!("Auto-stop: end of speech detected");
+            if let Some(coordinator) = app_handle.try_state::<TranscriptionCoordinator>() {
+                coordinator.confirm_recording();
+            }
+        }
+    });
+
+    recorder = recorder
+        .with_keyword_spotter(Arc::clone(keyword_spotter))
+        .with_wake_word_callback({
+            let app_handle = app_handle.clone();
+            move || wake_word::on_wake_phrase(&app_handle)
+        });
+
+    recorder = recorder.with_clipping_callback({
+        let app_handle = app_handle.clone();
+        move |ratio| {
+            warn!(
+                "Microphone input clipped on {:.1}% of samples; lower the input gain",
+                ratio * 100.0
+            );
+            let _ = app_handle.emit("microphone-clipping", ratio);
+        }
+    });
+
+    recorder = recorder.with_stream_error_callback({
+        let app_handle = app_handle.clone();
+        move |_| {
+            // Reopening a device waits on the audio thread this runs on
+            let app_handle = app_handle.clone();
+            std::thread::spawn(move || {
+                if let Some(rm) = app_handle.try_state::<Arc<AudioRecordingManager>>() {
+                    rm.fail_over();
+                }
+            });
+        }
+    });
+
     recorder = recorder.with_level_callback({
         let app_handle = app_handle.clone();
         move |levels| {
@@ -148,10 +313,18 @@ pub struct AudioRecordingManager {
     app_handle: tauri::AppHandle,
 
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
-    last_vad_enabled: Arc<Mutex<Option<bool>>>,
+    last_vad_usage: Arc<Mutex<Option<VadUsage>>>,
     is_open: Arc<Mutex<bool>>,
     is_recording: Arc<Mutex<bool>>,
     did_mute: Arc<Mutex<bool>>,
+    /// Bumped whenever a pending warm-standby close should be abandoned.
+    standby_generation: Arc<AtomicU64>,
+    /// Keeps the microphone open between utterances in hands-free mode.
+    hands_free: Arc<AtomicBool>,
+    /// Set while moving the stream to another device after a failure.
+    failing_over: Arc<AtomicBool>,
+    /// Wake-word model the recorder checks idle utterances with, if loaded.
+    keyword_spotter: SharedKeywordSpotter,
 }
 
 impl AudioRecordingManager {
@@ -171,10 +344,14 @@ impl AudioRecordingManager {
             app_handle: app.clone(),
 
             recorder: Arc::new(Mutex::new(None)),
-            last_vad_enabled: Arc::new(Mutex::new(None)),
+            last_vad_usage: Arc::new(Mutex::new(None)),
             is_open: Arc::new(Mutex::new(false)),
             is_recording: Arc::new(Mutex::new(false)),
             did_mute: Arc::new(Mutex::new(false)),
+            standby_generation: Arc::new(AtomicU64::new(0)),
+            hands_free: Arc::new(AtomicBool::new(false)),
+            failing_over: Arc::new(AtomicBool::new(false)),
+            keyword_spotter: Arc::new(Mutex::new(None)),
         };
 
         // Always-on?  Open immediately.
@@ -200,6 +377,16 @@ impl AudioRecordingManager {
         }
     }
 
+    fn vad_model_path(&self) -> Result<std::path::PathBuf, anyhow::Error> {
+        self.app_handle
+            .path()
+            .resolve(
+                "resources/models/silero_vad_v4.onnx",
+                tauri::path::BaseDirectory::Resource,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))
+    }
+
     fn get_effective_microphone_device(&self, settings: &AppSettings) -> Option<cpal::Device> {
         // Check if we're in clamshell mode and have a clamshell microphone configured
         let use_clamshell_mic = if let Ok(is_clamshell) = clamshell::is_clamshell() {
@@ -289,34 +476,29 @@ impl AudioRecordingManager {
         *did_mute_guard = false;
 
         let settings = get_settings(&self.app_handle);
-        let use_vad = settings.stt_provider_id == "local";
+        let vad_usage = VadUsage::new(&settings, self.hands_free.load(Ordering::SeqCst));
 
         let mut recorder_opt = self.recorder.lock().unwrap();
-        let mut last_vad = self.last_vad_enabled.lock().unwrap();
+        let mut last_vad = self.last_vad_usage.lock().unwrap();
 
         // Only recreate when the VAD requirement changed (or first call).
-        if recorder_opt.is_none() || *last_vad != Some(use_vad) {
-            let vad_path = self
-                .app_handle
-                .path()
-                .resolve(
-                    "resources/models/silero_vad_v4.onnx",
-                    tauri::path::BaseDirectory::Resource,
-                )
-                .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+        if recorder_opt.is_none() || *last_vad != Some(vad_usage) {
+            let vad_path = self.vad_model_path()?;
             *recorder_opt = Some(create_audio_recorder(
                 vad_path.to_str().unwrap(),
                 &self.app_handle,
-                use_vad,
+                vad_usage,
+                &self.keyword_spotter,
             )?);
-            *last_vad = Some(use_vad);
+            *last_vad = Some(vad_usage);
         }
 
         let selected_device = self.get_effective_microphone_device(&settings);
 
-        recorder_opt
-            .as_mut()
-            .unwrap()
+        let recorder = recorder_opt.as_mut().unwrap();
+        recorder.set_dsp(dsp_config(&settings, selected_device.as_ref()));
+        recorder.set_pre_roll(Duration::from_millis(settings.pre_roll_ms as u64));
+        recorder
             .open(selected_device)
             .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
 
@@ -353,6 +535,125 @@ impl AudioRecordingManager {
         debug!("Microphone stream stopped");
     }
 
+    /// In on-demand mode, close the microphone after a recording, or keep it
+    /// warm for `warm_standby_secs` so the next recording gets pre-roll.
+    fn release_microphone(&self) {
+        if !matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) || self.keeps_listening()
+        {
+            return;
+        }
+
+        let standby_secs = get_settings(&self.app_handle).warm_standby_secs;
+        if standby_secs == 0 {
+            self.stop_microphone_stream();
+            return;
+        }
+
+        let generation = self.standby_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let manager = self.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(standby_secs as u64));
+            if manager.standby_generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            let on_demand = matches!(*manager.mode.lock().unwrap(), MicrophoneMode::OnDemand);
+            if on_demand && !manager.keeps_listening() && !manager.is_recording() {
+                debug!("Warm standby elapsed, closing microphone");
+                manager.stop_microphone_stream();
+            }
+        });
+    }
+
+    /// Apply changed pre-roll and warm-standby settings to the open stream.
+    pub fn update_pre_roll(&self) {
+        let settings = get_settings(&self.app_handle);
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_pre_roll(Duration::from_millis(settings.pre_roll_ms as u64));
+        }
+
+        // Standby was switched off while the microphone was being kept warm
+        let on_demand = matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand);
+        if settings.warm_standby_secs == 0
+            && on_demand
+            && !self.keeps_listening()
+            && !self.is_recording()
+        {
+            self.standby_generation.fetch_add(1, Ordering::SeqCst);
+            self.stop_microphone_stream();
+        }
+    }
+
+    /// Enter or leave hands-free capture. While enabled the microphone stays
+    /// open between recordings and the recorder runs the VAD so every
+    /// recording ends on trailing silence.
+    pub fn set_hands_free(&self, enabled: bool) -> Result<(), anyhow::Error> {
+        if self.hands_free.swap(enabled, Ordering::SeqCst) == enabled {
+            return Ok(());
+        }
+
+        if enabled {
+            self.standby_generation.fetch_add(1, Ordering::SeqCst);
+            // Reopen an idle stream so the recorder is rebuilt with a VAD
+            self.restart_idle_stream()?;
+            self.start_microphone_stream()
+        } else {
+            if !self.is_recording() {
+                self.release_microphone();
+            }
+            Ok(())
+        }
+    }
+
+    /// Whether the microphone stays open between recordings even in
+    /// on-demand mode.
+    fn keeps_listening(&self) -> bool {
+        self.hands_free.load(Ordering::SeqCst) || get_settings(&self.app_handle).wake_word_enabled
+    }
+
+    /// Apply changed wake-word settings. While listening the microphone stays
+    /// open, and the wake-word model is loaded in the background into the
+    /// recorder's own spotter, apart from the transcription models. The
+    /// stream is reopened when idle so the recorder gets the VAD that
+    /// listening needs.
+    pub fn update_wake_word(&self) -> Result<(), anyhow::Error> {
+        let settings = get_settings(&self.app_handle);
+        // The phrase or model may have changed
+        *self.keyword_spotter.lock().unwrap() = None;
+        self.restart_idle_stream()?;
+
+        if !settings.wake_word_enabled {
+            if !self.is_recording() {
+                self.release_microphone();
+            }
+            return Ok(());
+        }
+
+        self.standby_generation.fetch_add(1, Ordering::SeqCst);
+        self.start_microphone_stream()?;
+
+        let slot = Arc::clone(&self.keyword_spotter);
+        let app_handle = self.app_handle.clone();
+        std::thread::spawn(move || {
+            let Some(model_manager) = app_handle.try_state::<Arc<ModelManager>>() else {
+                return;
+            };
+            match PhraseSpotter::load(&model_manager, &settings) {
+                Ok(spotter) => {
+                    // Skip a model the settings moved on from while it loaded
+                    let current = get_settings(&app_handle);
+                    if current.wake_word_enabled
+                        && current.wake_word_phrase == settings.wake_word_phrase
+                        && current.wake_word_model == settings.wake_word_model
+                    {
+                        *slot.lock().unwrap() = Some(Arc::new(Mutex::new(spotter)));
+                    }
+                }
+                Err(e) => error!("Not listening for the wake phrase: {}", e),
+            }
+        });
+        Ok(())
+    }
+
     /* ---------- mode switching --------------------------------------------- */
 
     pub fn update_mode(&self, new_mode: MicrophoneMode) -> Result<(), anyhow::Error> {
@@ -361,7 +662,9 @@ impl AudioRecordingManager {
 
         match (cur_mode, &new_mode) {
             (MicrophoneMode::AlwaysOn, MicrophoneMode::OnDemand) => {
-                if matches!(*self.state.lock().unwrap(), RecordingState::Idle) {
+                if matches!(*self.state.lock().unwrap(), RecordingState::Idle)
+                    && !self.keeps_listening()
+                {
                     drop(mode_guard);
                     self.stop_microphone_stream();
                 }
@@ -377,25 +680,151 @@ impl AudioRecordingManager {
         Ok(())
     }
 
+    /* ---------- device failover ---------------------------------------------- */
+
+    /// Move the open stream to the next working device in
+    /// `microphone_priority` after its device failed. A recording in progress
+    /// keeps the audio captured so far and continues on the new device.
+    pub fn fail_over(&self) {
+        // A failing stream tends to report several errors in a row
+        if self.failing_over.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.switch_to_working_device();
+        self.failing_over.store(false, Ordering::SeqCst);
+    }
+
+    fn switch_to_working_device(&self) {
+        let open_flag = self.is_open.lock().unwrap();
+        if !*open_flag {
+            return;
+        }
+        let mut recorder_opt = self.recorder.lock().unwrap();
+        let Some(recorder) = recorder_opt.as_mut() else {
+            return;
+        };
+
+        let failed = recorder.device_name().unwrap_or_default();
+        let mut devices = match list_input_devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                error!("Failed to list devices for failover: {}", e);
+                return;
+            }
+        };
+        let names: Vec<String> = devices.iter().map(|d| d.name.clone()).collect();
+        let default = devices
+            .iter()
+            .find(|d| d.is_default)
+            .map(|d| d.name.clone());
+
+        let settings = get_settings(&self.app_handle);
+        for name in failover_order(
+            &settings.microphone_priority,
+            &names,
+            default.as_deref(),
+            &failed,
+        ) {
+            let Some(idx) = devices.iter().position(|d| d.name == name) else {
+                continue;
+            };
+            match recorder.switch_device(devices.swap_remove(idx).device) {
+                Ok(()) => {
+                    warn!("Microphone '{}' failed, continuing on '{}'", failed, name);
+                    let _ = self.app_handle.emit("microphone-failover", &name);
+                    return;
+                }
+                Err(e) => warn!("Failover to '{}' failed: {}", name, e),
+            }
+        }
+        error!("Microphone '{}' failed and no other device works", failed);
+    }
+
+    /// React to input devices being added or removed: fail over when the
+    /// open stream's device is gone, reopen it when a device was unplugged
+    /// and plugged back in (`replugged`), and move an idle open stream
+    /// (always-on, hands-free or warm standby) to the device the settings
+    /// now prefer.
+    pub fn handle_devices_changed(&self, replugged: bool) {
+        if !*self.is_open.lock().unwrap() {
+            return;
+        }
+        let Some(current) = self
+            .recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|r| r.device_name())
+        else {
+            return;
+        };
+
+        let available = match list_input_devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                debug!("Failed to list devices: {}", e);
+                return;
+            }
+        };
+        let Some(device) = available.into_iter().find(|d| d.name == current) else {
+            info!("Microphone '{}' was removed", current);
+            self.fail_over();
+            return;
+        };
+
+        // The stream may still belong to the device that was unplugged
+        if replugged {
+            info!("A device was plugged back in, reopening '{}'", current);
+            if let Some(recorder) = self.recorder.lock().unwrap().as_mut() {
+                if let Err(e) = recorder.switch_device(device.device) {
+                    error!("Failed to reopen microphone '{}': {}", current, e);
+                }
+            }
+            return;
+        }
+
+        let settings = get_settings(&self.app_handle);
+        let preferred = self
+            .get_effective_microphone_device(&settings)
+            .or_else(|| get_cpal_host().default_input_device())
+            .and_then(|d| d.name().ok());
+        if preferred.is_some_and(|name| name != current) {
+            info!("Preferred microphone changed, reopening the idle stream");
+            if let Err(e) = self.restart_idle_stream() {
+                error!("Failed to switch microphone: {}", e);
+            }
+        }
+    }
+
     /* ---------- recording --------------------------------------------------- */
 
+    /// Starts recording for `binding_id`. Returns `Ok(false)` when another
+    /// recording is already in progress and `Err` when the microphone or
+    /// recorder could not be started.
     pub fn try_start_recording(
         &self,
         binding_id: &str,
         stream_tap: Option<tokio::sync::mpsc::Sender<Vec<f32>>>,
-    ) -> bool {
+    ) -> Result<bool, String> {
         let mut state = self.state.lock().unwrap();
 
         if let RecordingState::Idle = *state {
+            // Keep a warm microphone open for this recording
+            self.standby_generation.fetch_add(1, Ordering::SeqCst);
+
             // Ensure microphone is open in on-demand mode
             if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
                 if let Err(e) = self.start_microphone_stream() {
                     error!("Failed to open microphone stream: {e}");
-                    return false;
+                    return Err(format!("Failed to open microphone stream: {e}"));
                 }
             }
 
             if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+                rec.set_auto_stop(auto_stop_config(
+                    &get_settings(&self.app_handle),
+                    self.hands_free.load(Ordering::SeqCst),
+                ));
                 let ok = match stream_tap {
                     Some(tap_tx) => rec.start_with_stream_tap(tap_tx).is_ok(),
                     None => rec.start().is_ok(),
@@ -406,13 +835,13 @@ impl AudioRecordingManager {
                         binding_id: binding_id.to_string(),
                     };
                     debug!("Recording started for binding {binding_id}");
-                    return true;
+                    return Ok(true);
                 }
             }
             error!("Recorder not available");
-            false
+            Err("Recorder not available".to_string())
         } else {
-            false
+            Ok(false)
         }
     }
 
@@ -425,6 +854,15 @@ impl AudioRecordingManager {
         Ok(())
     }
 
+    /// Reopen the stream if it is open and idle, so recorder-level settings
+    /// such as auto-stop's need for a VAD take effect.
+    pub fn restart_idle_stream(&self) -> Result<(), anyhow::Error> {
+        if self.is_recording() {
+            return Ok(());
+        }
+        self.update_selected_device()
+    }
+
     pub fn stop_recording(&self, binding_id: &str) -> Option<Vec<f32>> {
         let mut state = self.state.lock().unwrap();
 
@@ -451,9 +889,7 @@ impl AudioRecordingManager {
                 *self.is_recording.lock().unwrap() = false;
 
                 // In on-demand mode turn the mic off again
-                if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
-                    self.stop_microphone_stream();
-                }
+                self.release_microphone();
 
                 // Pad if very short
                 let s_len = samples.len();
@@ -468,6 +904,103 @@ impl AudioRecordingManager {
             _ => None,
         }
     }
+    /// Open an independent recorder on the current microphone, with its
+    /// capture processing but no VAD, for sessions that run alongside
+    /// dictation such as meeting recording.
+    pub fn open_secondary_recorder(&self) -> Result<AudioRecorder, anyhow::Error> {
+        let settings = get_settings(&self.app_handle);
+        let device = self.get_effective_microphone_device(&settings);
+
+        let mut recorder = AudioRecorder::new()
+            .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?;
+        recorder.set_dsp(dsp_config(&settings, device.as_ref()));
+        recorder
+            .open(device)
+            .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
+        Ok(recorder)
+    }
+
+    /// Run the VAD over recorded 16 kHz audio: one decision per 30 ms frame
+    /// (see [`VAD_FRAME_SAMPLES`]); a trailing partial frame is ignored.
+    pub fn detect_speech(&self, samples: &[f32]) -> Result<Vec<bool>, anyhow::Error> {
+        let silero = SileroVad::new(self.vad_model_path()?.to_str().unwrap(), 0.15)
+            .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
+        let mut vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
+        Ok(samples
+            .chunks_exact(VAD_FRAME_SAMPLES)
+            .map(|frame| vad.is_voice(frame).unwrap_or(false))
+            .collect())
+    }
+
+    /// Record `seconds` from `device_name` (`None` or "Default" for the system
+    /// default) through a separate, unprocessed recorder and measure the
+    /// result. Returns the report together with the captured 16 kHz audio.
+    pub fn test_microphone(
+        &self,
+        device_name: Option<&str>,
+        seconds: u32,
+    ) -> Result<(MicrophoneTest, Vec<f32>), anyhow::Error> {
+        if self.is_recording() {
+            return Err(anyhow::anyhow!(
+                "Cannot test the microphone while recording"
+            ));
+        }
+
+        let device = match device_name {
+            Some(name) if name != "Default" => Self::find_device_by_name(name)
+                .ok_or_else(|| anyhow::anyhow!("Microphone '{}' not found", name))?,
+            _ => get_cpal_host()
+                .default_input_device()
+                .ok_or_else(|| anyhow::anyhow!("No input device found"))?,
+        };
+        let name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+        let config = AudioRecorder::get_preferred_config(&device)
+            .map_err(|e| anyhow::anyhow!("Failed to query {}: {}", name, e))?;
+
+        // No VAD, DSP or pre-roll: the report describes what the device
+        // itself delivers.
+        let mut recorder = AudioRecorder::new()
+            .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?;
+        recorder
+            .open(Some(device))
+            .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", name, e))?;
+        let samples = recorder
+            .start()
+            .map(|_| std::thread::sleep(Duration::from_secs(seconds.clamp(1, 10) as u64)))
+            .and_then(|_| recorder.stop());
+        let _ = recorder.close();
+        let samples = samples.map_err(|e| anyhow::anyhow!("Test recording failed: {}", e))?;
+
+        let speech = self.detect_speech(&samples)?;
+        let frames = speech.len();
+        let speech_frames = speech.iter().filter(|s| **s).count();
+
+        let stats = analyze_levels(&samples, VAD_FRAME_SAMPLES);
+        let report = MicrophoneTest {
+            device_name: name,
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+            sample_format: config.sample_format().to_string(),
+            duration_secs: samples.len() as f32 / WHISPER_SAMPLE_RATE as f32,
+            peak_dbfs: stats.peak_dbfs,
+            rms_dbfs: stats.rms_dbfs,
+            clipped_percent: stats.clipped_ratio * 100.0,
+            noise_floor_dbfs: stats.noise_floor_dbfs,
+            snr_db: stats.snr_db,
+            speech_ratio: if frames == 0 {
+                0.0
+            } else {
+                speech_frames as f32 / frames as f32
+            },
+        };
+        info!("Microphone test: {:?}", report);
+        Ok((report, samples))
+    }
+
+    pub fn is_hands_free(&self) -> bool {
+        self.hands_free.load(Ordering::SeqCst)
+    }
+
     pub fn is_recording(&self) -> bool {
         matches!(
             *self.state.lock().unwrap(),
@@ -490,9 +1023,44 @@ impl AudioRecordingManager {
             *self.is_recording.lock().unwrap() = false;
 
             // In on-demand mode turn the mic off again
-            if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
-                self.stop_microphone_stream();
-            }
+            self.release_microphone();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn failover_follows_priority_then_default() {
+        let priority = names(&["Headset", "USB Mic", "Webcam"]);
+        let available = names(&["Built-in", "Webcam", "USB Mic"]);
+
+        assert_eq!(
+            failover_order(&priority, &available, Some("Built-in"), "Headset"),
+            names(&["USB Mic", "Webcam", "Built-in"])
+        );
+        // The failed device is never retried, even as the system default
+        assert_eq!(
+            failover_order(&priority, &available, Some("USB Mic"), "USB Mic"),
+            names(&["Webcam"])
+        );
+    }
+
+    #[test]
+    fn failover_resolves_default_entry() {
+        let priority = names(&["Headset", "Default", "Webcam"]);
+        let available = names(&["Built-in", "Webcam"]);
+
+        assert_eq!(
+            failover_order(&priority, &available, Some("Built-in"), "Headset"),
+            names(&["Built-in", "Webcam"])
+        );
+        assert!(failover_order(&[], &[], None, "Headset").is_empty());
+    }
+}
@@ -7,8 +7,11 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicI64, Ordering};
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::audio_toolkit::audio::{extract_pcm_from_wav, resample_i16};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
 use crate::audio_toolkit::save_wav_file;
 
 /// Database migrations for transcription history.
@@ -39,8 +42,51 @@ static MIGRATIONS: &[M] = &[
             transcription_count INTEGER NOT NULL DEFAULT 0
         );",
     ),
+    M::up("ALTER TABLE transcription_history ADD COLUMN status TEXT NOT NULL DEFAULT 'done';"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN error TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN segments TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN retracted BOOLEAN NOT NULL DEFAULT 0;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN binding_id TEXT;"),
 ];
 
+/// Lifecycle of a history entry. The audio is stored as `pending` before
+/// transcription starts, so a dictation survives errors and crashes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionStatus {
+    Pending,
+    Failed,
+    #[default]
+    Done,
+}
+
+impl TranscriptionStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            TranscriptionStatus::Pending => "pending",
+            TranscriptionStatus::Failed => "failed",
+            TranscriptionStatus::Done => "done",
+        }
+    }
+
+    fn from_db(value: &str) -> Self {
+        match value {
+            "pending" => TranscriptionStatus::Pending,
+            "failed" => TranscriptionStatus::Failed,
+            _ => TranscriptionStatus::Done,
+        }
+    }
+}
+
+/// One speaker turn of a diarized transcription.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+pub struct TranscriptSegment {
+    pub speaker: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct HistoryEntry {
     pub id: i64,
@@ -51,6 +97,20 @@ pub struct HistoryEntry {
     pub transcription_text: String,
     pub post_processed_text: Option<String>,
     pub post_process_prompt: Option<String>,
+    #[serde(default)]
+    pub status: TranscriptionStatus,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Speaker turns, once the entry has been diarized.
+    #[serde(default)]
+    pub segments: Option<Vec<TranscriptSegment>>,
+    /// Whether the inserted text was taken back with "undo dictation".
+    #[serde(default)]
+    pub retracted: bool,
+    /// The shortcut binding the recording was made with, whose
+    /// post-processing prompt also applies when it's transcribed again.
+    #[serde(default)]
+    pub binding_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -68,6 +128,20 @@ pub struct DailySpeakingStats {
     pub avg_wpm: f64,
 }
 
+/// Millisecond stamp of the last recording file name handed out, so names
+/// stay unique when several recordings finish within the same millisecond.
+static LAST_RECORDING_MS: AtomicI64 = AtomicI64::new(0);
+
+fn next_recording_ms() -> i64 {
+    let now = Utc::now().timestamp_millis();
+    let prev = LAST_RECORDING_MS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or(now);
+    now.max(prev + 1)
+}
+
 fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
     Ok(HistoryEntry {
         id: row.get("id")?,
@@ -78,6 +152,13 @@ fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
         transcription_text: row.get("transcription_text")?,
         post_processed_text: row.get("post_processed_text")?,
         post_process_prompt: row.get("post_process_prompt")?,
+        status: TranscriptionStatus::from_db(&row.get::<_, String>("status")?),
+        error: row.get("error")?,
+        segments: row
+            .get::<_, Option<String>>("segments")?
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        retracted: row.get("retracted")?,
+        binding_id: row.get("binding_id")?,
     })
 }
 
@@ -212,32 +293,101 @@ impl HistoryManager {
         Ok(Connection::open(&self.db_path)?)
     }
 
-    /// Save a transcription to history (both database and WAV file)
-    pub async fn save_transcription(
+    /// A recording file name such as `handless-1700000000123.wav` that no
+    /// earlier recording uses, so a new recording never replaces one on disk.
+    pub fn new_recording_file_name(&self, prefix: &str) -> String {
+        let stamp = next_recording_ms();
+        let mut file_name = format!("{}-{}.wav", prefix, stamp);
+        let mut suffix = 1;
+        while self.recordings_dir.join(&file_name).exists() {
+            file_name = format!("{}-{}-{}.wav", prefix, stamp, suffix);
+            suffix += 1;
+        }
+        file_name
+    }
+
+    /// Store the recording and a `pending` history row before transcription
+    /// starts, so the dictation survives transcription errors and crashes.
+    pub async fn create_pending_entry(
         &self,
-        audio_samples: Vec<f32>,
+        audio_samples: &[f32],
+        binding_id: Option<&str>,
+    ) -> Result<HistoryEntry> {
+        let file_name = self.new_recording_file_name("handless");
+
+        // Save WAV file
+        let file_path = self.recordings_dir.join(&file_name);
+        save_wav_file(file_path, audio_samples).await?;
+
+        self.create_pending_entry_for_file(file_name, binding_id)
+    }
+
+    /// Add a `pending` history row for a recording that is written to the
+    /// recordings directory separately, e.g. while a meeting is captured.
+    pub fn create_pending_entry_for_file(
+        &self,
+        file_name: String,
+        binding_id: Option<&str>,
+    ) -> Result<HistoryEntry> {
+        let timestamp = Utc::now().timestamp();
+        let title = self.format_timestamp_title(timestamp);
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, status, binding_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![file_name, timestamp, false, title, "", TranscriptionStatus::Pending.as_str(), binding_id],
+        )?;
+        let entry = HistoryEntry {
+            id: conn.last_insert_rowid(),
+            file_name,
+            timestamp,
+            saved: false,
+            title,
+            transcription_text: String::new(),
+            post_processed_text: None,
+            post_process_prompt: None,
+            status: TranscriptionStatus::Pending,
+            error: None,
+            segments: None,
+            retracted: false,
+            binding_id: binding_id.map(String::from),
+        };
+        drop(conn);
+        debug!("Saved pending history entry with id: {}", entry.id);
+
+        // Emit the new entry so the frontend can prepend it without a full reload
+        if let Err(e) = self.app_handle.emit("history-entry-added", &entry) {
+            error!("Failed to emit history-entry-added event: {}", e);
+        }
+
+        Ok(entry)
+    }
+
+    /// Store the transcription of a pending (or failed) entry and mark it done.
+    pub async fn complete_entry(
+        &self,
+        id: i64,
         transcription_text: String,
         post_processed_text: Option<String>,
         post_process_prompt: Option<String>,
         word_count: i32,
         speaking_duration_ms: i64,
-    ) -> Result<()> {
-        let timestamp = Utc::now().timestamp();
-        let file_name = format!("handless-{}.wav", timestamp);
-        let title = self.format_timestamp_title(timestamp);
-
-        // Save WAV file
-        let file_path = self.recordings_dir.join(&file_name);
-        save_wav_file(file_path, &audio_samples).await?;
-
+    ) -> Result<HistoryEntry> {
         // Save to database and update daily stats in a single connection
         let conn = self.get_connection()?;
         conn.execute(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![file_name, timestamp, false, title, transcription_text, post_processed_text, post_process_prompt],
+            "UPDATE transcription_history
+             SET transcription_text = ?1, post_processed_text = ?2, post_process_prompt = ?3, status = ?4, error = NULL
+             WHERE id = ?5",
+            params![
+                transcription_text,
+                post_processed_text,
+                post_process_prompt,
+                TranscriptionStatus::Done.as_str(),
+                id
+            ],
         )?;
-        let new_id = conn.last_insert_rowid();
-        debug!("Saved transcription to database with id: {}", new_id);
+        debug!("Completed history entry with id: {}", id);
 
         let today = Local::now().format("%Y-%m-%d").to_string();
         conn.execute(
@@ -254,24 +404,158 @@ impl HistoryManager {
         // Clean up old entries
         self.cleanup_old_entries()?;
 
-        // Emit the new entry so the frontend can prepend it without a full reload
-        let new_entry = HistoryEntry {
-            id: new_id,
-            file_name,
-            timestamp,
-            saved: false,
-            title,
-            transcription_text,
-            post_processed_text,
-            post_process_prompt,
-        };
-        if let Err(e) = self.app_handle.emit("history-entry-added", &new_entry) {
-            error!("Failed to emit history-entry-added event: {}", e);
+        self.emit_entry_updated(id).await
+    }
+
+    /// Mark an entry as failed, keeping its audio for a later retry.
+    pub async fn mark_failed(&self, id: i64, error_message: &str) -> Result<HistoryEntry> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET status = ?1, error = ?2 WHERE id = ?3",
+            params![TranscriptionStatus::Failed.as_str(), error_message, id],
+        )?;
+        drop(conn);
+        debug!("Marked history entry {} as failed: {}", id, error_message);
+
+        self.emit_entry_updated(id).await
+    }
+
+    /// Store the partial transcription of an entry whose audio could only
+    /// partly be transcribed, keeping it failed so it can be retried.
+    pub async fn save_partial(
+        &self,
+        id: i64,
+        transcription_text: &str,
+        error_message: &str,
+    ) -> Result<HistoryEntry> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET transcription_text = ?1, status = ?2, error = ?3 WHERE id = ?4",
+            params![
+                transcription_text,
+                TranscriptionStatus::Failed.as_str(),
+                error_message,
+                id
+            ],
+        )?;
+        drop(conn);
+        debug!("Saved partial transcription of history entry {}", id);
+
+        self.emit_entry_updated(id).await
+    }
+
+    /// Store the speaker turns of an entry. `transcription_text` replaces the
+    /// entry's text when the diarized transcription was made from scratch.
+    /// Empty `segments` clear the stored turns.
+    pub async fn set_segments(
+        &self,
+        id: i64,
+        segments: &[TranscriptSegment],
+        transcription_text: Option<&str>,
+    ) -> Result<HistoryEntry> {
+        let json = (!segments.is_empty())
+            .then(|| serde_json::to_string(segments))
+            .transpose()?;
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history
+             SET segments = ?1, transcription_text = COALESCE(?2, transcription_text)
+             WHERE id = ?3",
+            params![json, transcription_text, id],
+        )?;
+        drop(conn);
+        debug!(
+            "Stored {} speaker segments for entry {}",
+            segments.len(),
+            id
+        );
+
+        self.emit_entry_updated(id).await
+    }
+
+    /// Replace the post-processed text of an entry, e.g. after running a
+    /// prompt on it again.
+    pub async fn set_post_processed(
+        &self,
+        id: i64,
+        post_processed_text: &str,
+        post_process_prompt: Option<&str>,
+    ) -> Result<HistoryEntry> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history
+             SET post_processed_text = ?1, post_process_prompt = ?2
+             WHERE id = ?3",
+            params![post_processed_text, post_process_prompt, id],
+        )?;
+        drop(conn);
+        debug!("Updated post-processed text of entry {}", id);
+
+        self.emit_entry_updated(id).await
+    }
+
+    /// Mark an entry whose inserted text was taken back.
+    pub async fn set_retracted(&self, id: i64) -> Result<HistoryEntry> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET retracted = 1 WHERE id = ?1",
+            params![id],
+        )?;
+        drop(conn);
+        debug!("Marked history entry {} as retracted", id);
+
+        self.emit_entry_updated(id).await
+    }
+
+    async fn emit_entry_updated(&self, id: i64) -> Result<HistoryEntry> {
+        let entry = self
+            .get_entry_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        if let Err(e) = self.app_handle.emit("history-entry-updated", &entry) {
+            error!("Failed to emit history-entry-updated event: {}", e);
         }
+        Ok(entry)
+    }
 
+    /// Drop a pending entry whose recording turned out to contain no speech.
+    pub async fn discard_pending_entry(&self, id: i64) -> Result<()> {
+        self.delete_entry(id).await?;
+        if let Err(e) = self.app_handle.emit("history-entry-removed", id) {
+            error!("Failed to emit history-entry-removed event: {}", e);
+        }
         Ok(())
     }
 
+    /// Entries whose transcription never finished, oldest first.
+    pub fn get_pending_entries(&self) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, status, error, segments, retracted, binding_id
+             FROM transcription_history WHERE status = 'pending' ORDER BY timestamp ASC",
+        )?;
+        let entries = stmt
+            .query_map([], row_to_entry)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// Read back the audio of a history entry as 16 kHz mono samples. Meeting
+    /// recordings with separate microphone and system channels are mixed down.
+    pub fn load_audio(&self, file_name: &str) -> Result<Vec<f32>> {
+        let wav_bytes = fs::read(self.get_audio_file_path(file_name))?;
+        let (samples, sample_rate) = extract_pcm_from_wav(&wav_bytes)?;
+        let samples = if sample_rate == WHISPER_SAMPLE_RATE {
+            samples
+        } else {
+            resample_i16(&samples, sample_rate, WHISPER_SAMPLE_RATE)?
+        };
+        Ok(samples
+            .into_iter()
+            .map(|sample| sample as f32 / i16::MAX as f32)
+            .collect())
+    }
+
     pub fn cleanup_old_entries(&self) -> Result<()> {
         let retention_period = crate::settings::get_recording_retention_period(&self.app_handle);
 
@@ -327,7 +611,7 @@ impl HistoryManager {
 
         // Only fetch entries beyond the limit using SQL OFFSET
         let mut stmt = conn.prepare(
-            "SELECT id, file_name FROM transcription_history WHERE saved = 0 ORDER BY timestamp DESC LIMIT -1 OFFSET ?1"
+            "SELECT id, file_name FROM transcription_history WHERE saved = 0 AND status != 'pending' ORDER BY timestamp DESC LIMIT -1 OFFSET ?1"
         )?;
 
         let rows = stmt.query_map(params![limit as i64], |row| {
@@ -364,7 +648,7 @@ impl HistoryManager {
 
         // Get all unsaved entries older than the cutoff timestamp
         let mut stmt = conn.prepare(
-            "SELECT id, file_name FROM transcription_history WHERE saved = 0 AND timestamp < ?1",
+            "SELECT id, file_name FROM transcription_history WHERE saved = 0 AND status != 'pending' AND timestamp < ?1",
         )?;
 
         let rows = stmt.query_map(params![cutoff_timestamp], |row| {
@@ -391,7 +675,7 @@ impl HistoryManager {
     pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt FROM transcription_history ORDER BY timestamp DESC"
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, status, error, segments, retracted, binding_id FROM transcription_history ORDER BY timestamp DESC"
         )?;
 
         let rows = stmt.query_map([], row_to_entry)?;
@@ -416,13 +700,13 @@ impl HistoryManager {
             .is_some()
         {
             (
-                "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+                "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, status, error, segments, retracted, binding_id
                  FROM transcription_history WHERE id < ?1 ORDER BY id DESC LIMIT ?2",
                 vec![Box::new(cursor.unwrap()), Box::new(limit)],
             )
         } else {
             (
-                "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+                "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, status, error, segments, retracted, binding_id
                  FROM transcription_history ORDER BY id DESC LIMIT ?1",
                 vec![Box::new(limit)],
             )
@@ -446,8 +730,9 @@ impl HistoryManager {
 
     fn get_latest_entry_with_conn(conn: &Connection) -> Result<Option<HistoryEntry>> {
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, status, error, segments, retracted, binding_id
              FROM transcription_history
+             WHERE status = 'done'
              ORDER BY timestamp DESC
              LIMIT 1",
         )?;
@@ -501,8 +786,8 @@ impl HistoryManager {
             "SELECT COUNT(*) > 0 FROM transcription_history WHERE file_name = ?1 AND timestamp = ?2",
         )?;
         let mut insert_stmt = tx.prepare(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, status, error, segments, retracted, binding_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
         )?;
 
         for entry in entries {
@@ -518,6 +803,15 @@ impl HistoryManager {
                     entry.transcription_text,
                     entry.post_processed_text,
                     entry.post_process_prompt,
+                    entry.status.as_str(),
+                    entry.error,
+                    entry
+                        .segments
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()?,
+                    entry.retracted,
+                    entry.binding_id,
                 ])?;
                 imported += 1;
             }
@@ -566,7 +860,7 @@ impl HistoryManager {
     pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, status, error, segments, retracted, binding_id
              FROM transcription_history WHERE id = ?1",
         )?;
 
@@ -673,7 +967,12 @@ mod tests {
                 title TEXT NOT NULL,
                 transcription_text TEXT NOT NULL,
                 post_processed_text TEXT,
-                post_process_prompt TEXT
+                post_process_prompt TEXT,
+                status TEXT NOT NULL DEFAULT 'done',
+                error TEXT,
+                segments TEXT,
+                retracted BOOLEAN NOT NULL DEFAULT 0,
+                binding_id TEXT
             );",
         )
         .expect("create transcription_history table");
@@ -697,6 +996,12 @@ mod tests {
         .expect("insert history entry");
     }
 
+    #[test]
+    fn recording_stamps_never_repeat() {
+        let stamps: Vec<i64> = (0..1000).map(|_| next_recording_ms()).collect();
+        assert!(stamps.windows(2).all(|w| w[1] > w[0]));
+    }
+
     #[test]
     fn get_latest_entry_returns_none_when_empty() {
         let conn = setup_conn();
@@ -718,4 +1023,54 @@ mod tests {
         assert_eq!(entry.transcription_text, "second");
         assert_eq!(entry.post_processed_text.as_deref(), Some("processed"));
     }
+
+    #[test]
+    fn get_latest_entry_skips_unfinished_transcriptions() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "done", None);
+        insert_entry(&conn, 200, "", None);
+        conn.execute(
+            "UPDATE transcription_history SET status = 'pending' WHERE timestamp = 200",
+            [],
+        )
+        .expect("mark entry pending");
+
+        let entry = HistoryManager::get_latest_entry_with_conn(&conn)
+            .expect("fetch latest entry")
+            .expect("entry exists");
+
+        assert_eq!(entry.timestamp, 100);
+        assert_eq!(entry.status, TranscriptionStatus::Done);
+    }
+
+    #[test]
+    fn entries_read_back_their_speaker_segments() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "hello there", None);
+        let segments = vec![
+            TranscriptSegment {
+                speaker: "Speaker 1".to_string(),
+                start_ms: 0,
+                end_ms: 900,
+                text: "hello".to_string(),
+            },
+            TranscriptSegment {
+                speaker: "Speaker 2".to_string(),
+                start_ms: 1200,
+                end_ms: 2000,
+                text: "there".to_string(),
+            },
+        ];
+        conn.execute(
+            "UPDATE transcription_history SET segments = ?1",
+            params![serde_json::to_string(&segments).unwrap()],
+        )
+        .expect("store segments");
+
+        let entry = HistoryManager::get_latest_entry_with_conn(&conn)
+            .expect("fetch latest entry")
+            .expect("entry exists");
+        assert_eq!(entry.segments, Some(segments));
+        assert!(!entry.retracted);
+    }
 }
@@ -1,4 +1,5 @@
 pub mod audio;
 pub mod history;
 pub mod model;
+pub mod model_pool;
 pub mod transcription;
@@ -0,0 +1,263 @@
+//! Bookkeeping for keeping several local engines resident at once.
+//!
+//! The pool itself knows nothing about transcription engines; it only tracks
+//! which model IDs are loaded, how much memory each is estimated to use and
+//! when each was last used, so the `TranscriptionManager` can decide what to
+//! evict when the configured RAM budget is exceeded.
+
+use std::collections::HashMap;
+
+struct PooledModel<E> {
+    /// `None` while the engine is checked out for a transcription.
+    engine: Option<E>,
+    size_mb: u64,
+    last_used_ms: u64,
+}
+
+pub struct ModelPool<E> {
+    entries: HashMap<String, PooledModel<E>>,
+}
+
+impl<E> Default for ModelPool<E> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<E> ModelPool<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, model_id: &str) -> bool {
+        self.entries.contains_key(model_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Loaded model IDs, most recently used first.
+    pub fn loaded_ids(&self) -> Vec<String> {
+        let mut ids: Vec<(&String, u64)> = self
+            .entries
+            .iter()
+            .map(|(id, entry)| (id, entry.last_used_ms))
+            .collect();
+        ids.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ids.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    pub fn total_size_mb(&self) -> u64 {
+        self.entries.values().map(|entry| entry.size_mb).sum()
+    }
+
+    /// Insert a freshly loaded engine, replacing any previous entry for the same ID.
+    /// The replaced engine is returned so the caller can unload it.
+    #[must_use]
+    pub fn insert(&mut self, model_id: &str, engine: E, size_mb: u64, now_ms: u64) -> Option<E> {
+        self.entries
+            .insert(
+                model_id.to_string(),
+                PooledModel {
+                    engine: Some(engine),
+                    size_mb,
+                    last_used_ms: now_ms,
+                },
+            )
+            .and_then(|previous| previous.engine)
+    }
+
+    pub fn touch(&mut self, model_id: &str, now_ms: u64) {
+        if let Some(entry) = self.entries.get_mut(model_id) {
+            entry.last_used_ms = now_ms;
+        }
+    }
+
+    /// Check an engine out for exclusive use. The entry stays in the pool so
+    /// it is neither evicted nor reloaded while the engine is busy.
+    pub fn take(&mut self, model_id: &str) -> Option<E> {
+        self.entries
+            .get_mut(model_id)
+            .and_then(|entry| entry.engine.take())
+    }
+
+    /// Return an engine previously obtained with [`ModelPool::take`]. If the
+    /// model was removed while checked out, the engine is handed back so the
+    /// caller can unload it.
+    #[must_use]
+    pub fn put_back(&mut self, model_id: &str, engine: E, now_ms: u64) -> Option<E> {
+        match self.entries.get_mut(model_id) {
+            Some(entry) => {
+                entry.engine = Some(engine);
+                entry.last_used_ms = now_ms;
+                None
+            }
+            None => Some(engine),
+        }
+    }
+
+    /// Remove a model from the pool. Returns the engine if it was not checked out.
+    #[must_use]
+    pub fn remove(&mut self, model_id: &str) -> Option<E> {
+        self.entries.remove(model_id).and_then(|entry| entry.engine)
+    }
+
+    /// Remove every model that is not currently checked out.
+    #[must_use]
+    pub fn drain_idle(&mut self) -> Vec<(String, E)> {
+        let ids: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.engine.is_some())
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| self.remove(&id).map(|engine| (id, engine)))
+            .collect()
+    }
+
+    /// Evict least recently used models until the pool fits in `budget_mb`.
+    ///
+    /// Models in `keep` are never evicted, and neither are engines that are
+    /// checked out. A budget of zero keeps only `keep` resident; models larger
+    /// than the budget are still allowed to stay loaded.
+    #[must_use]
+    pub fn evict_over_budget(&mut self, budget_mb: u64, keep: &[&str]) -> Vec<(String, E)> {
+        let mut evicted = Vec::new();
+        let mut candidates: Vec<(String, u64)> = self
+            .entries
+            .iter()
+            .filter(|(id, entry)| !keep.contains(&id.as_str()) && entry.engine.is_some())
+            .map(|(id, entry)| (id.clone(), entry.last_used_ms))
+            .collect();
+        candidates.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+        for (id, _) in candidates {
+            if budget_mb > 0 && self.total_size_mb() <= budget_mb {
+                break;
+            }
+            if let Some(engine) = self.remove(&id) {
+                evicted.push((id, engine));
+            }
+        }
+        evicted
+    }
+
+    /// IDs of models idle for longer than their timeout. `timeout_ms_for`
+    /// returns `None` for models that should never be unloaded by idleness.
+    pub fn idle_ids<F>(&self, now_ms: u64, timeout_ms_for: F) -> Vec<String>
+    where
+        F: Fn(&str) -> Option<u64>,
+    {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.engine.is_some())
+            .filter(|(id, entry)| match timeout_ms_for(id) {
+                Some(limit_ms) => now_ms.saturating_sub(entry.last_used_ms) > limit_ms,
+                None => false,
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_with(models: &[(&str, u64, u64)]) -> ModelPool<String> {
+        let mut pool = ModelPool::new();
+        for (id, size_mb, last_used_ms) in models {
+            let _ = pool.insert(id, id.to_string(), *size_mb, *last_used_ms);
+        }
+        pool
+    }
+
+    #[test]
+    fn evicts_least_recently_used_first() {
+        let mut pool = pool_with(&[("a", 500, 1), ("b", 500, 2), ("c", 500, 3)]);
+
+        let evicted = pool.evict_over_budget(1000, &["c"]);
+
+        assert_eq!(
+            evicted.into_iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec!["a"]
+        );
+        assert_eq!(pool.loaded_ids(), vec!["c", "b"]);
+    }
+
+    #[test]
+    fn zero_budget_keeps_only_the_active_model() {
+        let mut pool = pool_with(&[("a", 100, 3), ("b", 100, 2)]);
+
+        let _ = pool.evict_over_budget(0, &["b"]);
+
+        assert_eq!(pool.loaded_ids(), vec!["b"]);
+    }
+
+    #[test]
+    fn loading_a_probe_model_keeps_the_active_engine() {
+        // The manager pins both the model being loaded and the current one
+        let mut pool = pool_with(&[("active", 1500, 1)]);
+        let _ = pool.insert("probe", "probe".to_string(), 150, 2);
+
+        let evicted = pool.evict_over_budget(0, &["probe", "active"]);
+
+        assert!(evicted.is_empty());
+        assert_eq!(pool.loaded_ids(), vec!["probe", "active"]);
+
+        // The probe itself is evicted by the next load, the active model isn't
+        let _ = pool.insert("other", "other".to_string(), 150, 3);
+        let evicted = pool.evict_over_budget(0, &["other", "active"]);
+        assert_eq!(
+            evicted.into_iter().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec!["probe"]
+        );
+        assert!(pool.contains("active"));
+    }
+
+    #[test]
+    fn checked_out_engines_are_not_evicted() {
+        let mut pool = pool_with(&[("a", 800, 1), ("b", 800, 2)]);
+        let engine = pool.take("a").unwrap();
+
+        let evicted = pool.evict_over_budget(1000, &["b"]);
+
+        assert!(evicted.is_empty());
+        assert!(pool.contains("a"));
+        assert_eq!(pool.put_back("a", engine, 5), None);
+        assert_eq!(pool.loaded_ids(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn engines_removed_while_checked_out_are_handed_back() {
+        let mut pool = pool_with(&[("a", 100, 1)]);
+        let engine = pool.take("a").unwrap();
+        assert_eq!(pool.remove("a"), None);
+
+        assert_eq!(pool.put_back("a", engine, 5), Some("a".to_string()));
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn replaced_engines_are_handed_back() {
+        let mut pool = pool_with(&[("a", 100, 1)]);
+
+        assert_eq!(
+            pool.insert("a", "a2".to_string(), 100, 2),
+            Some("a".to_string())
+        );
+    }
+
+    #[test]
+    fn idle_ids_respects_per_model_timeouts() {
+        let pool = pool_with(&[("fast", 100, 0), ("pinned", 100, 0)]);
+
+        let idle = pool.idle_ids(10_000, |id| if id == "fast" { Some(5_000) } else { None });
+
+        assert_eq!(idle, vec!["fast"]);
+    }
+}
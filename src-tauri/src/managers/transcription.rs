@@ -1,14 +1,20 @@
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
 use crate::audio_toolkit::{apply_custom_words, filter_transcription_output};
+use crate::cloud_stt::fallback::{self, FailureKind};
+use crate::language_routing::{self, RouteTarget};
+use crate::managers::history::TranscriptSegment;
 use crate::managers::model::{EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::managers::model_pool::ModelPool;
+use crate::pipeline_error::ModelLoadError;
+use crate::settings::{get_settings, AppSettings, ModelUnloadTimeout, SttFallbackStep};
 use anyhow::Result;
 use log::{debug, error, info, warn};
 use serde::Serialize;
 use std::panic::{catch_unwind, AssertUnwindSafe};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter};
 use transcribe_rs::{
     engines::{
@@ -36,6 +42,29 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
+/// Emitted when a transcription moves on to the next step of the fallback chain.
+#[derive(Clone, Debug, Serialize)]
+pub struct FallbackEvent {
+    pub failed_provider: String,
+    pub next_provider: String,
+    pub reason: String,
+}
+
+/// How long cloud providers are skipped after a connection failure.
+const OFFLINE_RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a transcription waits for an engine that another transcription,
+/// e.g. a meeting chunk, has checked out.
+const ENGINE_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Who is waiting for a transcription. Background transcriptions such as
+/// meeting chunks leave a shared engine to waiting dictations between chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TranscriptionPriority {
+    Interactive,
+    Background,
+}
+
 enum LoadedEngine {
     Whisper(WhisperEngine),
     Parakeet(ParakeetEngine),
@@ -44,36 +73,61 @@ enum LoadedEngine {
     SenseVoice(SenseVoiceEngine),
 }
 
+impl LoadedEngine {
+    fn unload(&mut self) {
+        match self {
+            LoadedEngine::Whisper(e) => e.unload_model(),
+            LoadedEngine::Parakeet(e) => e.unload_model(),
+            LoadedEngine::Moonshine(e) => e.unload_model(),
+            LoadedEngine::MoonshineStreaming(e) => e.unload_model(),
+            LoadedEngine::SenseVoice(e) => e.unload_model(),
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
 #[derive(Clone)]
 pub struct TranscriptionManager {
-    engine: Arc<Mutex<Option<LoadedEngine>>>,
+    /// Resident engines keyed by model ID. Several models may stay loaded at
+    /// once, bounded by the `model_pool_budget_mb` setting.
+    pool: Arc<Mutex<ModelPool<LoadedEngine>>>,
+    /// Signalled whenever a checked out engine is returned to or dropped from the pool.
+    engine_returned: Arc<Condvar>,
+    /// Interactive transcriptions waiting for a checked out engine.
+    interactive_waiting: Arc<AtomicUsize>,
     model_manager: Arc<ModelManager>,
     app_handle: AppHandle,
+    /// The model used for transcription when no other model is requested.
     current_model_id: Arc<Mutex<Option<String>>>,
-    last_activity: Arc<AtomicU64>,
     shutdown_signal: Arc<AtomicBool>,
     watcher_handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
     is_loading: Arc<Mutex<bool>>,
     loading_condvar: Arc<Condvar>,
+    /// Set after a cloud provider could not be reached; cloud steps of the
+    /// fallback chain are skipped until then.
+    offline_until: Arc<Mutex<Option<Instant>>>,
 }
 
 impl TranscriptionManager {
     pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Result<Self> {
         let manager = Self {
-            engine: Arc::new(Mutex::new(None)),
+            pool: Arc::new(Mutex::new(ModelPool::new())),
+            engine_returned: Arc::new(Condvar::new()),
+            interactive_waiting: Arc::new(AtomicUsize::new(0)),
             model_manager,
             app_handle: app_handle.clone(),
             current_model_id: Arc::new(Mutex::new(None)),
-            last_activity: Arc::new(AtomicU64::new(
-                SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64,
-            )),
             shutdown_signal: Arc::new(AtomicBool::new(false)),
             watcher_handle: Arc::new(Mutex::new(None)),
             is_loading: Arc::new(Mutex::new(false)),
             loading_condvar: Arc::new(Condvar::new()),
+            offline_until: Arc::new(Mutex::new(None)),
         };
 
         // Start the idle watcher
@@ -97,43 +151,28 @@ impl TranscriptionManager {
                         continue;
                     }
 
-                    let timeout_seconds = settings.model_unload_timeout.to_seconds();
-
-                    if let Some(limit_seconds) = timeout_seconds {
+                    // Each resident model is checked against its own timeout,
+                    // falling back to the global one.
+                    let idle_models = manager_cloned.lock_pool().idle_ids(now_ms(), |model_id| {
+                        let timeout = settings.unload_timeout_for(model_id);
                         // Skip polling-based unloading for immediate timeout since it's handled directly in transcribe()
-                        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately {
-                            continue;
+                        if timeout == ModelUnloadTimeout::Immediately {
+                            return None;
                         }
-
-                        let last = manager_cloned.last_activity.load(Ordering::Relaxed);
-                        let now_ms = SystemTime::now()
-                            .duration_since(SystemTime::UNIX_EPOCH)
-                            .unwrap()
-                            .as_millis() as u64;
-
-                        if now_ms.saturating_sub(last) > limit_seconds * 1000 {
-                            // idle -> unload
-                            if manager_cloned.is_model_loaded() {
-                                let unload_start = std::time::Instant::now();
-                                debug!("Starting to unload model due to inactivity");
-
-                                if let Ok(()) = manager_cloned.unload_model() {
-                                    let _ = app_handle_cloned.emit(
-                                        "model-state-changed",
-                                        ModelStateEvent {
-                                            event_type: "unloaded".to_string(),
-                                            model_id: None,
-                                            model_name: None,
-                                            error: None,
-                                        },
-                                    );
-                                    let unload_duration = unload_start.elapsed();
-                                    debug!(
-                                        "Model unloaded due to inactivity (took {}ms)",
-                                        unload_duration.as_millis()
-                                    );
-                                }
-                            }
+                        timeout.to_seconds().map(|seconds| seconds * 1000)
+                    });
+
+                    for model_id in idle_models {
+                        let unload_start = std::time::Instant::now();
+                        debug!("Starting to unload model {} due to inactivity", model_id);
+
+                        if let Ok(()) = manager_cloned.unload_model_by_id(&model_id) {
+                            let unload_duration = unload_start.elapsed();
+                            debug!(
+                                "Model {} unloaded due to inactivity (took {}ms)",
+                                model_id,
+                                unload_duration.as_millis()
+                            );
                         }
                     }
                 }
@@ -145,35 +184,38 @@ impl TranscriptionManager {
         Ok(manager)
     }
 
-    /// Lock the engine mutex, recovering from poison if a previous transcription panicked.
-    fn lock_engine(&self) -> MutexGuard<'_, Option<LoadedEngine>> {
-        self.engine.lock().unwrap_or_else(|poisoned| {
-            warn!("Engine mutex was poisoned by a previous panic, recovering");
+    /// Lock the model pool, recovering from poison if a previous transcription panicked.
+    fn lock_pool(&self) -> MutexGuard<'_, ModelPool<LoadedEngine>> {
+        self.pool.lock().unwrap_or_else(|poisoned| {
+            warn!("Model pool mutex was poisoned by a previous panic, recovering");
             poisoned.into_inner()
         })
     }
 
+    /// Whether any local model is currently resident.
     pub fn is_model_loaded(&self) -> bool {
-        let engine = self.lock_engine();
-        engine.is_some()
+        !self.lock_pool().is_empty()
+    }
+
+    /// Whether the given model is resident in the pool.
+    pub fn is_loaded(&self, model_id: &str) -> bool {
+        self.lock_pool().contains(model_id)
     }
 
+    /// IDs of all resident models, most recently used first.
+    pub fn loaded_models(&self) -> Vec<String> {
+        self.lock_pool().loaded_ids()
+    }
+
+    /// Unloads every resident model.
     pub fn unload_model(&self) -> Result<()> {
         let unload_start = std::time::Instant::now();
-        debug!("Starting to unload model");
+        debug!("Starting to unload all models");
 
-        {
-            let mut engine = self.lock_engine();
-            if let Some(ref mut loaded_engine) = *engine {
-                match loaded_engine {
-                    LoadedEngine::Whisper(ref mut e) => e.unload_model(),
-                    LoadedEngine::Parakeet(ref mut e) => e.unload_model(),
-                    LoadedEngine::Moonshine(ref mut e) => e.unload_model(),
-                    LoadedEngine::MoonshineStreaming(ref mut e) => e.unload_model(),
-                    LoadedEngine::SenseVoice(ref mut e) => e.unload_model(),
-                }
-            }
-            *engine = None; // Drop the engine to free memory
+        let drained = self.lock_pool().drain_idle();
+        let unloaded_count = drained.len();
+        for (_, mut engine) in drained {
+            engine.unload(); // Dropping the engine frees its memory
         }
         {
             let mut current_model = self.current_model_id.lock().unwrap();
@@ -193,29 +235,146 @@ impl TranscriptionManager {
 
         let unload_duration = unload_start.elapsed();
         debug!(
-            "Model unloaded manually (took {}ms)",
+            "{} model(s) unloaded manually (took {}ms)",
+            unloaded_count,
             unload_duration.as_millis()
         );
         Ok(())
     }
 
-    /// Unloads the model immediately if the setting is enabled and the model is loaded
+    /// Unloads a single model, leaving the rest of the pool untouched.
+    pub fn unload_model_by_id(&self, model_id: &str) -> Result<()> {
+        let engine = {
+            let mut pool = self.lock_pool();
+            if !pool.contains(model_id) {
+                return Ok(());
+            }
+            pool.remove(model_id)
+        };
+        self.engine_returned.notify_all();
+        if let Some(mut engine) = engine {
+            engine.unload();
+        }
+
+        let was_current = {
+            let mut current_model = self.current_model_id.lock().unwrap();
+            if current_model.as_deref() == Some(model_id) {
+                *current_model = None;
+                true
+            } else {
+                false
+            }
+        };
+
+        self.emit_model_removed(model_id, was_current);
+        Ok(())
+    }
+
+    /// Emit `unloaded` when the current model goes away, or `evicted` when a
+    /// background model leaves the pool so the UI keeps showing the active one as ready.
+    fn emit_model_removed(&self, model_id: &str, was_current: bool) {
+        let event_type = if was_current { "unloaded" } else { "evicted" };
+        let _ = self.app_handle.emit(
+            "model-state-changed",
+            ModelStateEvent {
+                event_type: event_type.to_string(),
+                model_id: Some(model_id.to_string()),
+                model_name: None,
+                error: None,
+            },
+        );
+    }
+
+    /// Evict least recently used models until the pool fits the configured budget.
+    pub fn enforce_pool_budget(&self) {
+        let budget_mb = get_settings(&self.app_handle).model_pool_budget_mb;
+        let current = self.get_current_model();
+        let keep: Vec<&str> = current.as_deref().into_iter().collect();
+        let evicted = self.lock_pool().evict_over_budget(budget_mb, &keep);
+        self.release_evicted(evicted, budget_mb);
+    }
+
+    fn release_evicted(&self, evicted: Vec<(String, LoadedEngine)>, budget_mb: u64) {
+        for (model_id, mut engine) in evicted {
+            engine.unload();
+            info!(
+                "Evicted model {} to stay within the {} MB model pool budget",
+                model_id, budget_mb
+            );
+            self.emit_model_removed(&model_id, false);
+        }
+    }
+
+    /// Unloads every model whose timeout is set to immediately
     pub fn maybe_unload_immediately(&self, context: &str) {
         let settings = get_settings(&self.app_handle);
-        if settings.model_unload_timeout == ModelUnloadTimeout::Immediately
-            && self.is_model_loaded()
-        {
-            info!("Immediately unloading model after {}", context);
-            if let Err(e) = self.unload_model() {
+        let immediate: Vec<String> = self
+            .loaded_models()
+            .into_iter()
+            .filter(|id| settings.unload_timeout_for(id) == ModelUnloadTimeout::Immediately)
+            .collect();
+
+        for model_id in immediate {
+            info!("Immediately unloading model {} after {}", model_id, context);
+            if let Err(e) = self.unload_model_by_id(&model_id) {
                 warn!("Failed to immediately unload model: {}", e);
             }
         }
     }
 
+    /// Makes an already resident model current. Returns false if it is not loaded.
+    fn activate_resident(&self, model_id: &str) -> bool {
+        if !self.touch_resident(model_id) {
+            return false;
+        }
+        let mut current_model = self.current_model_id.lock().unwrap();
+        *current_model = Some(model_id.to_string());
+        true
+    }
+
+    /// Marks a resident model as used. Returns false if it is not loaded.
+    fn touch_resident(&self, model_id: &str) -> bool {
+        let mut pool = self.lock_pool();
+        if !pool.contains(model_id) {
+            return false;
+        }
+        pool.touch(model_id, now_ms());
+        true
+    }
+
+    /// Loads a model and makes it the current one.
     pub fn load_model(&self, model_id: &str) -> Result<()> {
+        self.ensure_resident(model_id)?;
+        let mut current_model = self.current_model_id.lock().unwrap();
+        *current_model = Some(model_id.to_string());
+        Ok(())
+    }
+
+    /// Loads a model into the pool without changing the current model.
+    fn ensure_resident(&self, model_id: &str) -> Result<()> {
         let load_start = std::time::Instant::now();
         debug!("Starting to load model: {}", model_id);
 
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+
+        // Switching back to a model that is still warm costs nothing
+        if self.touch_resident(model_id) {
+            let _ = self.app_handle.emit(
+                "model-state-changed",
+                ModelStateEvent {
+                    event_type: "loading_completed".to_string(),
+                    model_id: Some(model_id.to_string()),
+                    model_name: Some(model_info.name.clone()),
+                    error: None,
+                },
+            );
+            debug!("Model {} already resident, reusing it", model_id);
+            return Ok(());
+        }
+
         // Emit loading started event
         let _ = self.app_handle.emit(
             "model-state-changed",
@@ -227,11 +386,6 @@ impl TranscriptionManager {
             },
         );
 
-        let model_info = self
-            .model_manager
-            .get_model_info(model_id)
-            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
-
         if !model_info.is_downloaded {
             let error_msg = "Model not downloaded";
             let _ = self.app_handle.emit(
@@ -243,7 +397,7 @@ impl TranscriptionManager {
                     error: Some(error_msg.to_string()),
                 },
             );
-            return Err(anyhow::anyhow!(error_msg));
+            return Err(ModelLoadError(error_msg.to_string()).into());
         }
 
         let model_path = self.model_manager.get_model_path(model_id)?;
@@ -264,7 +418,7 @@ impl TranscriptionManager {
                         error: Some(error_msg.clone()),
                     },
                 );
-                anyhow::anyhow!(error_msg)
+                ModelLoadError(error_msg).into()
             };
 
         // Create appropriate engine based on model type
@@ -309,15 +463,25 @@ impl TranscriptionManager {
             }
         };
 
-        // Update the current engine and model ID
-        {
-            let mut engine = self.lock_engine();
-            *engine = Some(loaded_engine);
-        }
-        {
-            let mut current_model = self.current_model_id.lock().unwrap();
-            *current_model = Some(model_id.to_string());
-        }
+        // Add the engine to the pool, evicting the least recently used
+        // models that no longer fit in the memory budget. The current model
+        // stays too, so loading an auxiliary model such as the language-ID
+        // probe never costs the next dictation a reload.
+        let budget_mb = get_settings(&self.app_handle).model_pool_budget_mb;
+        let current = self.get_current_model();
+        let keep: Vec<&str> = std::iter::once(model_id)
+            .chain(current.as_deref())
+            .collect();
+        let evicted = {
+            let mut pool = self.lock_pool();
+            if let Some(mut previous) =
+                pool.insert(model_id, loaded_engine, model_info.size_mb, now_ms())
+            {
+                previous.unload();
+            }
+            pool.evict_over_budget(budget_mb, &keep)
+        };
+        self.release_evicted(evicted, budget_mb);
 
         // Emit loading completed event
         let _ = self.app_handle.emit(
@@ -347,7 +511,7 @@ impl TranscriptionManager {
         }
 
         let mut is_loading = self.is_loading.lock().unwrap();
-        if *is_loading || self.is_model_loaded() {
+        if *is_loading || self.activate_resident(&settings.selected_model) {
             return;
         }
 
@@ -370,14 +534,25 @@ impl TranscriptionManager {
     }
 
     pub async fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
-        // Update last activity timestamp
-        self.last_activity.store(
-            SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64,
-            Ordering::Relaxed,
-        );
+        self.transcribe_with_priority(audio, TranscriptionPriority::Interactive)
+            .await
+    }
+
+    /// Transcribe audio nobody is waiting on, e.g. a meeting chunk.
+    pub async fn transcribe_background(&self, audio: Vec<f32>) -> Result<String> {
+        self.transcribe_with_priority(audio, TranscriptionPriority::Background)
+            .await
+    }
+
+    async fn transcribe_with_priority(
+        &self,
+        audio: Vec<f32>,
+        priority: TranscriptionPriority,
+    ) -> Result<String> {
+        // Update last activity timestamp so the idle watcher keeps the model warm
+        if let Some(model_id) = self.get_current_model() {
+            self.lock_pool().touch(&model_id, now_ms());
+        }
 
         let st = std::time::Instant::now();
 
@@ -391,61 +566,11 @@ impl TranscriptionManager {
 
         let settings = get_settings(&self.app_handle);
 
-        let raw_text = if settings.stt_provider_id == "local" {
-            self.transcribe_local(audio, &settings)?
-        } else {
-            let wav_bytes = crate::audio_toolkit::audio::encode_wav_bytes(&audio)?;
-            let api_key = settings
-                .stt_api_keys
-                .get(&settings.stt_provider_id)
-                .cloned()
-                .unwrap_or_default();
-            let provider = settings
-                .stt_provider(&settings.stt_provider_id)
-                .ok_or_else(|| anyhow::anyhow!("STT provider not found"))?;
-            let model = settings
-                .stt_cloud_models
-                .get(&settings.stt_provider_id)
-                .cloned()
-                .unwrap_or_default();
-            let cloud_options: Option<serde_json::Value> = settings
-                .stt_cloud_options
-                .get(&settings.stt_provider_id)
-                .and_then(|s| serde_json::from_str(s).ok());
-            let cloud_options = crate::stt_provider::inject_dictionary(
-                &settings.stt_provider_id,
-                cloud_options,
-                &settings.dictionary_terms,
-                &settings.dictionary_context,
-            );
+        let route = self.route_for(&audio, &settings, priority);
 
-            let realtime_enabled = settings
-                .stt_realtime_enabled
-                .get(&settings.stt_provider_id)
-                .copied()
-                .unwrap_or(false);
-
-            if realtime_enabled {
-                crate::cloud_stt::realtime::transcribe(
-                    &settings.stt_provider_id,
-                    &api_key,
-                    &model,
-                    wav_bytes,
-                    cloud_options.as_ref(),
-                )
-                .await?
-            } else {
-                crate::cloud_stt::transcribe(
-                    &settings.stt_provider_id,
-                    &api_key,
-                    &provider.base_url,
-                    &model,
-                    wav_bytes,
-                    cloud_options.as_ref(),
-                )
-                .await?
-            }
-        };
+        let raw_text = self
+            .transcribe_with_fallback(audio, &route, &settings, priority)
+            .await?;
 
         // Apply word correction if custom words are configured
         let corrected_result = if !settings.custom_words.is_empty() {
@@ -484,67 +609,507 @@ impl TranscriptionManager {
         Ok(filtered_result)
     }
 
+    /// Try the routed provider, then each configured fallback step, retrying
+    /// transient cloud failures with backoff. After a connection failure the
+    /// cloud steps are skipped for a while in favour of local ones.
+    async fn transcribe_with_fallback(
+        &self,
+        audio: Vec<f32>,
+        route: &RouteTarget,
+        settings: &AppSettings,
+        priority: TranscriptionPriority,
+    ) -> Result<String> {
+        let mut primary = SttFallbackStep {
+            provider_id: route.provider_id.clone(),
+            model_id: route.model_id.clone(),
+            timeout_secs: settings.stt_request_timeout_secs,
+            max_retries: settings.stt_max_retries,
+        };
+        if primary.provider_id == "local" && primary.model_id.is_none() {
+            primary.model_id = self.get_current_model();
+        }
+        let fallbacks: Vec<SttFallbackStep> = settings
+            .stt_fallback_chain
+            .iter()
+            .cloned()
+            .map(|mut step| {
+                if step.provider_id == "local" && step.model_id.is_none() {
+                    step.model_id = Some(settings.selected_model.clone()).filter(|m| !m.is_empty());
+                }
+                step
+            })
+            .collect();
+
+        let chain = fallback::build_chain(primary, &fallbacks);
+        if chain.len() == 1 && chain[0].max_retries == 0 {
+            // Single attempt: hand the audio over without copying it
+            return self
+                .transcribe_step(audio, &chain[0], route, settings, priority)
+                .await;
+        }
+
+        let has_local_step = chain.iter().any(|step| step.provider_id == "local");
+        let mut last_failure: Option<(String, FailureKind, anyhow::Error)> = None;
+
+        for step in &chain {
+            let is_local = step.provider_id == "local";
+            if !is_local && has_local_step && self.is_offline() {
+                debug!("Offline, skipping cloud provider {}", step.provider_id);
+                continue;
+            }
+
+            if let Some((failed_provider, kind, _)) = &last_failure {
+                let _ = self.app_handle.emit(
+                    "transcription-fallback",
+                    FallbackEvent {
+                        failed_provider: failed_provider.clone(),
+                        next_provider: step.provider_id.clone(),
+                        reason: kind.as_str().to_string(),
+                    },
+                );
+            }
+
+            let mut attempt = 0;
+            loop {
+                match self
+                    .transcribe_step(audio.clone(), step, route, settings, priority)
+                    .await
+                {
+                    Ok(text) => {
+                        if !is_local {
+                            self.set_offline(false);
+                        }
+                        return Ok(text);
+                    }
+                    Err(e) => {
+                        let kind = fallback::classify(&e);
+                        warn!(
+                            "Transcription with {} failed (attempt {}, {}): {}",
+                            step.provider_id,
+                            attempt + 1,
+                            kind.as_str(),
+                            e
+                        );
+                        if kind == FailureKind::Offline {
+                            self.set_offline(true);
+                        }
+                        if !is_local && kind.is_retryable() && attempt < step.max_retries {
+                            tokio::time::sleep(fallback::backoff_delay(
+                                attempt,
+                                settings.stt_retry_backoff_ms,
+                            ))
+                            .await;
+                            attempt += 1;
+                            continue;
+                        }
+                        last_failure = Some((step.provider_id.clone(), kind, e));
+                        break;
+                    }
+                }
+            }
+        }
+
+        Err(last_failure
+            .map(|(_, _, e)| e)
+            .unwrap_or_else(|| anyhow::anyhow!("No transcription provider is available")))
+    }
+
+    /// Run a single attempt against one step of the fallback chain.
+    async fn transcribe_step(
+        &self,
+        audio: Vec<f32>,
+        step: &SttFallbackStep,
+        route: &RouteTarget,
+        settings: &AppSettings,
+        priority: TranscriptionPriority,
+    ) -> Result<String> {
+        if step.provider_id == "local" {
+            return self.transcribe_local(
+                audio,
+                step.model_id.as_deref(),
+                &route.language,
+                settings.translate_to_english,
+                priority,
+            );
+        }
+
+        let request = self.transcribe_cloud(audio, &step.provider_id, settings);
+        if step.timeout_secs == 0 {
+            return request.await;
+        }
+        tokio::time::timeout(Duration::from_secs(step.timeout_secs as u64), request)
+            .await
+            .map_err(|elapsed| {
+                anyhow::Error::new(elapsed).context(format!(
+                    "{} did not respond within {}s",
+                    step.provider_id, step.timeout_secs
+                ))
+            })?
+    }
+
+    async fn transcribe_cloud(
+        &self,
+        audio: Vec<f32>,
+        provider_id: &str,
+        settings: &AppSettings,
+    ) -> Result<String> {
+        let wav_bytes = crate::audio_toolkit::audio::encode_wav_bytes(&audio)?;
+        let api_key = settings
+            .stt_api_keys
+            .get(provider_id)
+            .cloned()
+            .unwrap_or_default();
+        let provider = settings
+            .stt_provider(provider_id)
+            .ok_or_else(|| anyhow::anyhow!("STT provider not found"))?;
+        let model = settings
+            .stt_cloud_models
+            .get(provider_id)
+            .cloned()
+            .unwrap_or_default();
+        let cloud_options: Option<serde_json::Value> = settings
+            .stt_cloud_options
+            .get(provider_id)
+            .and_then(|s| serde_json::from_str(s).ok());
+        let cloud_options = crate::stt_provider::inject_dictionary(
+            provider_id,
+            cloud_options,
+            &settings.dictionary_terms,
+            &settings.dictionary_context,
+        );
+
+        let realtime_enabled = settings
+            .stt_realtime_enabled
+            .get(provider_id)
+            .copied()
+            .unwrap_or(false);
+
+        if realtime_enabled {
+            crate::cloud_stt::realtime::transcribe(
+                provider_id,
+                &api_key,
+                &model,
+                wav_bytes,
+                cloud_options.as_ref(),
+            )
+            .await
+        } else {
+            crate::cloud_stt::transcribe(
+                provider_id,
+                &api_key,
+                &provider.base_url,
+                &model,
+                wav_bytes,
+                cloud_options.as_ref(),
+            )
+            .await
+        }
+    }
+
+    /// Speaker-labelled transcription straight from the cloud provider, when
+    /// the selected provider offers it (Soniox with speaker diarization
+    /// enabled in its options). `None` means diarization has to run locally.
+    pub async fn transcribe_with_speakers(
+        &self,
+        audio: Vec<f32>,
+    ) -> Result<Option<Vec<TranscriptSegment>>> {
+        let settings = get_settings(&self.app_handle);
+        let provider_id = settings.stt_provider_id.as_str();
+        let cloud_options: Option<serde_json::Value> = settings
+            .stt_cloud_options
+            .get(provider_id)
+            .and_then(|s| serde_json::from_str(s).ok());
+        let speaker_tokens = provider_id == "soniox"
+            && cloud_options
+                .as_ref()
+                .and_then(|o| o.get("enable_speaker_diarization"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+        if !speaker_tokens || self.is_offline() {
+            return Ok(None);
+        }
+
+        let provider = settings
+            .stt_provider(provider_id)
+            .ok_or_else(|| anyhow::anyhow!("STT provider not found"))?;
+        let api_key = settings
+            .stt_api_keys
+            .get(provider_id)
+            .cloned()
+            .unwrap_or_default();
+        let model = settings
+            .stt_cloud_models
+            .get(provider_id)
+            .cloned()
+            .unwrap_or_default();
+        let cloud_options = crate::stt_provider::inject_dictionary(
+            provider_id,
+            cloud_options,
+            &settings.dictionary_terms,
+            &settings.dictionary_context,
+        );
+
+        let wav_bytes = crate::audio_toolkit::audio::encode_wav_bytes(&audio)?;
+        crate::cloud_stt::soniox::transcribe_with_speakers(
+            &api_key,
+            &provider.base_url,
+            &model,
+            wav_bytes,
+            cloud_options.as_ref(),
+        )
+        .await
+        .map(Some)
+    }
+
+    fn is_offline(&self) -> bool {
+        self.offline_until
+            .lock()
+            .unwrap()
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    fn set_offline(&self, offline: bool) {
+        let mut offline_until = self.offline_until.lock().unwrap();
+        *offline_until = offline.then(|| Instant::now() + OFFLINE_RECHECK_INTERVAL);
+    }
+
+    /// Decide which provider and model handle this recording based on its language.
+    fn route_for(
+        &self,
+        audio: &[f32],
+        settings: &AppSettings,
+        priority: TranscriptionPriority,
+    ) -> RouteTarget {
+        if !settings.language_routing_enabled || settings.language_routes.is_empty() {
+            return RouteTarget::default_for(settings);
+        }
+
+        let language = if settings.selected_language != "auto" {
+            settings.selected_language.clone()
+        } else {
+            match self.identify_language(audio, settings, priority) {
+                Some(language) => language,
+                None => return RouteTarget::default_for(settings),
+            }
+        };
+
+        let default_supports_language = settings.stt_provider_id != "local"
+            || self.model_supports_language(&settings.selected_model, &language);
+
+        let mut route =
+            language_routing::resolve_route(settings, &language, default_supports_language);
+
+        // A local rule without a model falls back to the selected local model,
+        // even when the default provider is a cloud one.
+        if route.provider_id == "local"
+            && route.model_id.is_none()
+            && settings.stt_provider_id != "local"
+        {
+            route.model_id = Some(settings.selected_model.clone()).filter(|id| !id.is_empty());
+        }
+
+        // Never hand a local model a language it can't transcribe, e.g. an
+        // English-only model routed German speech by a misconfigured rule
+        if route.provider_id == "local" {
+            let model_id = route
+                .model_id
+                .as_deref()
+                .unwrap_or(&settings.selected_model);
+            if !self.model_supports_language(model_id, &language) {
+                warn!(
+                    "Model {} doesn't support '{}', keeping the default route",
+                    model_id, language
+                );
+                return RouteTarget::default_for(settings);
+            }
+        }
+
+        info!(
+            "Routing '{}' speech to provider '{}' (model: {})",
+            language,
+            route.provider_id,
+            route.model_id.as_deref().unwrap_or("default")
+        );
+        route
+    }
+
+    /// Whether a local model can transcribe `language`. Models without a
+    /// language list (custom models) are assumed to handle anything.
+    fn model_supports_language(&self, model_id: &str, language: &str) -> bool {
+        self.model_manager
+            .get_model_info(model_id)
+            .map(|info| {
+                info.supported_languages.is_empty()
+                    || info.supported_languages.iter().any(|supported| {
+                        language_routing::base_language(supported)
+                            == language_routing::base_language(language)
+                    })
+            })
+            .unwrap_or(true)
+    }
+
+    /// Quick language-ID pass: transcribe the first seconds of audio with the
+    /// configured multilingual model and guess the language from the text.
+    fn identify_language(
+        &self,
+        audio: &[f32],
+        settings: &AppSettings,
+        priority: TranscriptionPriority,
+    ) -> Option<String> {
+        let probe_model = settings
+            .language_id_model
+            .as_deref()
+            .filter(|id| !id.is_empty())?;
+
+        // A single-language model transcribes everything in its language, so
+        // its output says nothing about what was spoken
+        let is_multilingual = self
+            .model_manager
+            .get_model_info(probe_model)
+            .is_some_and(|info| info.supported_languages.len() != 1);
+        if !is_multilingual {
+            warn!(
+                "Language ID model {} is not multilingual, skipping language routing",
+                probe_model
+            );
+            return None;
+        }
+        let probe_len =
+            (settings.language_id_seconds as usize * WHISPER_SAMPLE_RATE as usize).min(audio.len());
+
+        let probe_start = std::time::Instant::now();
+        match self.transcribe_local(
+            audio[..probe_len].to_vec(),
+            Some(probe_model),
+            "auto",
+            false,
+            priority,
+        ) {
+            Ok(text) => {
+                let language = language_routing::detect_language_from_text(&text);
+                debug!(
+                    "Language ID pass with {} detected {:?} in {}ms",
+                    probe_model,
+                    language,
+                    probe_start.elapsed().as_millis()
+                );
+                language
+            }
+            Err(e) => {
+                warn!("Language ID pass failed: {}", e);
+                None
+            }
+        }
+    }
+
     /// Perform transcription using the local on-device engine (sync).
+    /// `model_id` selects a specific pooled model, loading it on demand;
+    /// `None` uses the current model.
     fn transcribe_local(
         &self,
         audio: Vec<f32>,
-        settings: &crate::settings::AppSettings,
+        model_id: Option<&str>,
+        language: &str,
+        translate: bool,
+        priority: TranscriptionPriority,
     ) -> Result<String> {
         // Check if model is loaded, if not try to load it
-        {
+        let model_id = {
             // If the model is loading, wait for it to complete.
             let mut is_loading = self.is_loading.lock().unwrap();
             while *is_loading {
                 is_loading = self.loading_condvar.wait(is_loading).unwrap();
             }
+            drop(is_loading);
 
-            let engine_guard = self.lock_engine();
-            if engine_guard.is_none() {
-                return Err(anyhow::anyhow!("Model is not loaded for transcription."));
+            match model_id {
+                Some(model_id) => {
+                    self.ensure_resident(model_id)?;
+                    model_id.to_string()
+                }
+                None => match self.get_current_model() {
+                    Some(model_id) if self.is_loaded(&model_id) => model_id,
+                    _ => {
+                        return Err(ModelLoadError(
+                            "Model is not loaded for transcription.".to_string(),
+                        )
+                        .into())
+                    }
+                },
             }
-        }
+        };
 
         // Perform transcription with the appropriate engine.
         // We use catch_unwind to prevent engine panics from poisoning the mutex,
         // which would make the app hang indefinitely on subsequent operations.
         let result = {
-            let mut engine_guard = self.lock_engine();
+            let mut pool_guard = self.lock_pool();
 
             // Take the engine out so we own it during transcription.
             // If the engine panics, we simply don't put it back (effectively unloading it)
             // instead of poisoning the mutex.
-            let mut engine = match engine_guard.take() {
-                Some(e) => e,
-                None => {
-                    return Err(anyhow::anyhow!(
-                        "Model failed to load after auto-load attempt. Please check your model settings."
+            // Dictation and meeting chunks share the engine, so wait for the
+            // other transcription to hand it back instead of failing. While a
+            // dictation waits, background transcriptions don't take it.
+            let deadline = Instant::now() + ENGINE_WAIT_TIMEOUT;
+            let mut waiting = false;
+            let checked_out = loop {
+                let yields = priority == TranscriptionPriority::Background
+                    && self.interactive_waiting.load(Ordering::SeqCst) > 0;
+                if !yields {
+                    if let Some(e) = pool_guard.take(&model_id) {
+                        break Ok(e);
+                    }
+                }
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if !pool_guard.contains(&model_id) || remaining.is_zero() {
+                    break Err(anyhow::anyhow!(
+                        "Model {} is busy with another transcription or failed to load. Please check your model settings.",
+                        model_id
                     ));
                 }
+                if priority == TranscriptionPriority::Interactive && !waiting {
+                    waiting = true;
+                    self.interactive_waiting.fetch_add(1, Ordering::SeqCst);
+                }
+                debug!(
+                    "Waiting for model {} to finish another transcription",
+                    model_id
+                );
+                pool_guard = self
+                    .engine_returned
+                    .wait_timeout(pool_guard, remaining)
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0;
             };
+            if waiting {
+                self.interactive_waiting.fetch_sub(1, Ordering::SeqCst);
+                // Background transcriptions held back by this one may go on
+                self.engine_returned.notify_all();
+            }
+            let mut engine = checked_out?;
 
             // Release the lock before transcribing — no mutex held during the engine call
-            drop(engine_guard);
+            drop(pool_guard);
 
             let transcribe_result = catch_unwind(AssertUnwindSafe(
                 || -> Result<transcribe_rs::TranscriptionResult> {
                     match &mut engine {
                         LoadedEngine::Whisper(whisper_engine) => {
-                            let whisper_language = if settings.selected_language == "auto" {
+                            let whisper_language = if language == "auto" {
                                 None
                             } else {
-                                let normalized = if settings.selected_language == "zh-Hans"
-                                    || settings.selected_language == "zh-Hant"
-                                {
+                                let normalized = if language == "zh-Hans" || language == "zh-Hant" {
                                     "zh".to_string()
                                 } else {
-                                    settings.selected_language.clone()
+                                    language.to_string()
                                 };
                                 Some(normalized)
                             };
 
                             let params = WhisperInferenceParams {
                                 language: whisper_language,
-                                translate: settings.translate_to_english,
+                                translate,
                                 ..Default::default()
                             };
 
@@ -572,7 +1137,7 @@ impl TranscriptionManager {
                                 anyhow::anyhow!("Moonshine streaming transcription failed: {}", e)
                             }),
                         LoadedEngine::SenseVoice(sense_voice_engine) => {
-                            let language = match settings.selected_language.as_str() {
+                            let language = match language {
                                 "zh" | "zh-Hans" | "zh-Hant" => SenseVoiceLanguage::Chinese,
                                 "en" => SenseVoiceLanguage::English,
                                 "ja" => SenseVoiceLanguage::Japanese,
@@ -596,9 +1161,14 @@ impl TranscriptionManager {
 
             match transcribe_result {
                 Ok(inner_result) => {
-                    // Success or normal error — put the engine back
-                    let mut engine_guard = self.lock_engine();
-                    *engine_guard = Some(engine);
+                    // Success or normal error — put the engine back, or unload
+                    // it if the model was unloaded while it was busy
+                    let orphaned = self.lock_pool().put_back(&model_id, engine, now_ms());
+                    self.engine_returned.notify_all();
+                    if let Some(mut engine) = orphaned {
+                        debug!("Model {} was unloaded during transcription", model_id);
+                        engine.unload();
+                    }
                     inner_result?
                 }
                 Err(panic_payload) => {
@@ -616,20 +1186,24 @@ impl TranscriptionManager {
                         panic_msg
                     );
 
-                    // Clear the model ID so it will be reloaded on next attempt
+                    // Drop the pool entry and clear the model ID so it will be reloaded on next attempt
+                    let _ = self.lock_pool().remove(&model_id);
+                    self.engine_returned.notify_all();
                     {
                         let mut current_model = self
                             .current_model_id
                             .lock()
                             .unwrap_or_else(|e| e.into_inner());
-                        *current_model = None;
+                        if current_model.as_deref() == Some(model_id.as_str()) {
+                            *current_model = None;
+                        }
                     }
 
                     let _ = self.app_handle.emit(
                         "model-state-changed",
                         ModelStateEvent {
                             event_type: "unloaded".to_string(),
-                            model_id: None,
+                            model_id: Some(model_id.clone()),
                             model_name: None,
                             error: Some(format!("Engine panicked: {}", panic_msg)),
                         },
@@ -33,10 +33,24 @@ impl TranscriptionManager {
         false
     }
 
+    pub fn is_loaded(&self, _model_id: &str) -> bool {
+        false
+    }
+
+    pub fn loaded_models(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     pub fn unload_model(&self) -> Result<()> {
         Ok(())
     }
 
+    pub fn unload_model_by_id(&self, _model_id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn enforce_pool_budget(&self) {}
+
     pub fn maybe_unload_immediately(&self, _context: &str) {}
 
     pub fn load_model(&self, _model_id: &str) -> Result<()> {
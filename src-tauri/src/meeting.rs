@@ -0,0 +1,593 @@
+//! Meeting recording.
+//!
+//! A meeting session records system audio from a PulseAudio/PipeWire monitor
+//! source, optionally together with the microphone, for as long as the
+//! session runs. The recording is written to disk as it is captured, with
+//! the microphone in a channel of its own. A mono mixdown is cut into chunks
+//! that are transcribed while the meeting goes on, so stopping only waits
+//! for the last one. The whole recording is saved to history as a single
+//! entry.
+
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use specta::Type;
+use std::ops::Range;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc::{Receiver, UnboundedReceiver, UnboundedSender};
+
+use crate::actions::count_words;
+use crate::audio_toolkit::audio::{
+    read_wav_range, LoopbackCapture, WavFileWriter, DEFAULT_MONITOR,
+};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::AudioRecorder;
+use crate::diarization::diarize_history_entry;
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::history::{HistoryEntry, HistoryManager, TranscriptionStatus};
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::get_settings;
+use crate::tray::{change_tray_icon, TrayIconState};
+
+/// How often the capture thread collects frames from both tracks.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Capacity, in 30 ms frames, of each track's channel between polls.
+const TRACK_CHANNEL_FRAMES: usize = 256;
+
+/// Frame length used to look for a pause to cut a chunk at.
+const SPLIT_FRAME_SAMPLES: usize = 480;
+
+/// A chunk is cut at the quietest frame within this much of its end, so
+/// words are not split between chunks.
+const SPLIT_SEARCH_SECS: usize = 5;
+
+/// When one track falls this far behind the other, its stream is taken to
+/// have stalled and the gap is filled with silence so chunking goes on.
+const TRACK_MAX_LAG_SECS: usize = 3;
+
+enum MeetingAudio {
+    /// Mono mixdown of the frames at `range` of the recording.
+    Chunk {
+        range: Range<usize>,
+        samples: Vec<f32>,
+    },
+    /// The meeting ended after `frames` frames; carries the error that cut
+    /// the recording short, if any.
+    Finished {
+        frames: usize,
+        error: Option<String>,
+    },
+}
+
+/// Transcript so far, sent as `meeting-transcript` after each chunk.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct MeetingTranscript {
+    pub text: String,
+    pub chunks: u32,
+}
+
+struct MeetingSession {
+    stop_tx: mpsc::Sender<()>,
+    worker: JoinHandle<()>,
+}
+
+pub struct MeetingRecorder {
+    app: AppHandle,
+    session: Mutex<Option<MeetingSession>>,
+}
+
+impl MeetingRecorder {
+    pub fn new(app: &AppHandle) -> Self {
+        Self {
+            app: app.clone(),
+            session: Mutex::new(None),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.session.lock().unwrap().is_some()
+    }
+
+    /// Start a meeting, or stop the running one. Returns whether a meeting
+    /// is running afterwards.
+    pub fn toggle(&self) -> Result<bool, anyhow::Error> {
+        if self.is_active() {
+            self.stop();
+            Ok(false)
+        } else {
+            self.start()?;
+            Ok(true)
+        }
+    }
+
+    pub fn start(&self) -> Result<(), anyhow::Error> {
+        let mut session = self.session.lock().unwrap();
+        if session.is_some() {
+            return Ok(());
+        }
+
+        let settings = get_settings(&self.app);
+        let source = settings
+            .meeting_source
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MONITOR.to_string());
+
+        let hm = Arc::clone(&self.app.state::<Arc<HistoryManager>>());
+        let file_name = hm.new_recording_file_name("handless-meeting");
+        let file_path = hm.get_audio_file_path(&file_name);
+        let channels = if settings.meeting_include_microphone {
+            2
+        } else {
+            1
+        };
+        let wav = WavFileWriter::create(&file_path, channels)
+            .map_err(|e| anyhow::anyhow!("Failed to create meeting recording: {}", e))?;
+
+        // Nothing is left behind if the captures or the history row fail
+        let started = self
+            .start_tracks(&source, settings.meeting_include_microphone)
+            .and_then(
+                |tracks| match hm.create_pending_entry_for_file(file_name, None) {
+                    Ok(entry) => Ok((tracks, entry)),
+                    Err(e) => {
+                        tracks.stop();
+                        Err(e)
+                    }
+                },
+            );
+        let (tracks, entry) = match started {
+            Ok(started) => started,
+            Err(e) => {
+                drop(wav);
+                let _ = std::fs::remove_file(&file_path);
+                return Err(e);
+            }
+        };
+
+        let chunk_samples =
+            settings.meeting_chunk_secs.max(10) as usize * WHISPER_SAMPLE_RATE as usize;
+        let (audio_tx, audio_rx) = tokio::sync::mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(transcribe_meeting(self.app.clone(), entry, audio_rx));
+
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            let MeetingTracks {
+                loopback,
+                system_rx,
+                microphone,
+            } = tracks;
+            let (mut recorder, mic_rx) = match microphone {
+                Some((recorder, rx)) => (Some(recorder), Some(rx)),
+                None => (None, None),
+            };
+            capture_meeting(system_rx, mic_rx, stop_rx, chunk_samples, wav, audio_tx);
+
+            loopback.stop();
+            if let Some(recorder) = recorder.as_mut() {
+                let _ = recorder.stop();
+                let _ = recorder.close();
+            }
+        });
+
+        *session = Some(MeetingSession { stop_tx, worker });
+        info!("Meeting recording started from {}", source);
+        change_tray_icon(&self.app, TrayIconState::Recording);
+        let _ = self.app.emit("meeting-state-changed", true);
+        Ok(())
+    }
+
+    fn start_tracks(
+        &self,
+        source: &str,
+        include_microphone: bool,
+    ) -> Result<MeetingTracks, anyhow::Error> {
+        let (system_tx, system_rx) = tokio::sync::mpsc::channel(TRACK_CHANNEL_FRAMES);
+        let loopback = LoopbackCapture::start(source, system_tx)
+            .map_err(|e| anyhow::anyhow!("Failed to capture system audio: {}", e))?;
+
+        let microphone = if include_microphone {
+            let rm = self.app.state::<Arc<AudioRecordingManager>>();
+            let (mic_tx, mic_rx) = tokio::sync::mpsc::channel(TRACK_CHANNEL_FRAMES);
+            let recorder = rm.open_secondary_recorder().and_then(|recorder| {
+                recorder
+                    .start_with_stream_tap(mic_tx)
+                    .map_err(|e| anyhow::anyhow!("Failed to start microphone: {}", e))?;
+                Ok(recorder)
+            });
+            match recorder {
+                Ok(recorder) => Some((recorder, mic_rx)),
+                Err(e) => {
+                    loopback.stop();
+                    return Err(e);
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(MeetingTracks {
+            loopback,
+            system_rx,
+            microphone,
+        })
+    }
+
+    pub fn stop(&self) {
+        let Some(session) = self.session.lock().unwrap().take() else {
+            return;
+        };
+
+        let _ = session.stop_tx.send(());
+        if session.worker.join().is_err() {
+            error!("Meeting capture thread panicked");
+        }
+        info!("Meeting recording stopped");
+        change_tray_icon(&self.app, TrayIconState::Transcribing);
+        let _ = self.app.emit("meeting-state-changed", false);
+    }
+}
+
+/// The running captures of a meeting.
+struct MeetingTracks {
+    loopback: LoopbackCapture,
+    system_rx: Receiver<Vec<f32>>,
+    microphone: Option<(AudioRecorder, Receiver<Vec<f32>>)>,
+}
+
+impl MeetingTracks {
+    fn stop(self) {
+        self.loopback.stop();
+        if let Some((mut recorder, _)) = self.microphone {
+            let _ = recorder.stop();
+            let _ = recorder.close();
+        }
+    }
+}
+
+/// Collect both tracks until told to stop, writing them to `wav` and
+/// handing mixed chunks of about `chunk_samples` to the transcription task.
+fn capture_meeting(
+    mut system_rx: Receiver<Vec<f32>>,
+    mut mic_rx: Option<Receiver<Vec<f32>>>,
+    stop_rx: mpsc::Receiver<()>,
+    chunk_samples: usize,
+    mut wav: WavFileWriter,
+    audio_tx: UnboundedSender<MeetingAudio>,
+) {
+    let max_lag = TRACK_MAX_LAG_SECS * WHISPER_SAMPLE_RATE as usize;
+    let mut system = Vec::new();
+    let mut mic = Vec::new();
+    let mut frames = 0;
+    let mut stalled = false;
+
+    let error = loop {
+        let stopping = match stop_rx.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => false,
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => true,
+        };
+
+        while let Ok(frame) = system_rx.try_recv() {
+            system.extend_from_slice(&frame);
+        }
+        if let Some(rx) = mic_rx.as_mut() {
+            while let Ok(frame) = rx.try_recv() {
+                mic.extend_from_slice(&frame);
+            }
+
+            // Only the part both tracks have covered can be cut, so a track
+            // whose stream died would otherwise stop chunking altogether
+            if pad_lagging_track(&mut system, &mut mic, max_lag) {
+                if !stalled {
+                    warn!(
+                        "A meeting audio track stopped delivering audio, filling it with silence"
+                    );
+                }
+                stalled = true;
+            } else if system.len().abs_diff(mic.len()) < max_lag / 2 {
+                stalled = false;
+            }
+        }
+
+        let mixable = if mic_rx.is_some() {
+            system.len().min(mic.len())
+        } else {
+            system.len()
+        };
+
+        let cut = if stopping {
+            // The shorter track ends in silence
+            let end = system.len().max(mic.len());
+            if mic_rx.is_some() {
+                mic.resize(end, 0.0);
+            }
+            system.resize(end, 0.0);
+            end
+        } else if mixable >= chunk_samples {
+            let search_from =
+                chunk_samples.saturating_sub(SPLIT_SEARCH_SECS * WHISPER_SAMPLE_RATE as usize);
+            let mixed = mix_tracks(
+                &system[..chunk_samples],
+                mic_rx.as_ref().map(|_| &mic[..chunk_samples]),
+            );
+            quietest_split(&mixed, search_from)
+        } else {
+            0
+        };
+
+        if cut > 0 {
+            debug!("Meeting chunk of {} samples", cut);
+            let mic_part = mic_rx.as_ref().map(|_| &mic[..cut]);
+            let written = wav
+                .write(&interleave(&system[..cut], mic_part))
+                .and_then(|_| wav.flush());
+            if let Err(e) = written {
+                error!("Failed to write meeting recording: {}", e);
+                break Some(e.to_string());
+            }
+
+            let mixed = mix_tracks(&system[..cut], mic_part);
+            system.drain(..cut);
+            if mic_rx.is_some() {
+                mic.drain(..cut);
+            }
+            let _ = audio_tx.send(MeetingAudio::Chunk {
+                range: frames..frames + cut,
+                samples: mixed,
+            });
+            frames += cut;
+        }
+
+        if stopping {
+            break None;
+        }
+    };
+
+    let error = match wav.finalize() {
+        Ok(()) => error,
+        Err(e) => error.or(Some(e.to_string())),
+    };
+    let _ = audio_tx.send(MeetingAudio::Finished { frames, error });
+}
+
+/// Fill the track that has fallen more than `max_lag` samples behind the
+/// other with silence. Returns whether a track was padded.
+fn pad_lagging_track(system: &mut Vec<f32>, mic: &mut Vec<f32>, max_lag: usize) -> bool {
+    if system.len() > mic.len() + max_lag {
+        mic.resize(system.len(), 0.0);
+        true
+    } else if mic.len() > system.len() + max_lag {
+        system.resize(mic.len(), 0.0);
+        true
+    } else {
+        false
+    }
+}
+
+/// Transcribe chunks as they arrive and save the meeting to history once
+/// the capture thread reports the end of the recording. Chunks that fail
+/// are tried again from the recording on disk at the end.
+async fn transcribe_meeting(
+    app: AppHandle,
+    entry: HistoryEntry,
+    mut audio_rx: UnboundedReceiver<MeetingAudio>,
+) {
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+    tm.initiate_model_load();
+
+    let mut parts: Vec<Option<String>> = Vec::new();
+    let mut failed: Vec<(usize, Range<usize>)> = Vec::new();
+    let mut failure: Option<String> = None;
+
+    while let Some(audio) = audio_rx.recv().await {
+        match audio {
+            MeetingAudio::Chunk { range, samples } => {
+                let index = parts.len();
+                match tm.transcribe_background(samples).await {
+                    Ok(text) => parts.push(Some(text.trim().to_string())),
+                    Err(e) => {
+                        warn!("Meeting chunk {} failed to transcribe: {}", index + 1, e);
+                        parts.push(None);
+                        failed.push((index, range));
+                        failure = Some(e.to_string());
+                    }
+                }
+                emit_transcript(&app, &parts);
+            }
+            MeetingAudio::Finished { frames, error } => {
+                let path = hm.get_audio_file_path(&entry.file_name);
+                for (index, range) in std::mem::take(&mut failed) {
+                    let retried = match read_wav_range(&path, range.start, range.len()) {
+                        Ok(samples) => tm.transcribe_background(samples).await,
+                        Err(e) => Err(e),
+                    };
+                    match retried {
+                        Ok(text) => parts[index] = Some(text.trim().to_string()),
+                        Err(e) => {
+                            warn!("Meeting chunk {} failed again: {}", index + 1, e);
+                            failed.push((index, range));
+                            failure = Some(e.to_string());
+                        }
+                    }
+                }
+                if !failed.is_empty() {
+                    emit_transcript(&app, &parts);
+                }
+
+                let failure = match (error, failure.filter(|_| !failed.is_empty())) {
+                    (Some(error), _) => Some(format!("Recording stopped early: {}", error)),
+                    (None, Some(reason)) => Some(format!(
+                        "{} of {} chunks failed to transcribe: {}",
+                        failed.len(),
+                        parts.len(),
+                        reason
+                    )),
+                    (None, None) => None,
+                };
+                save_meeting(&app, &hm, entry, frames, &parts, failure).await;
+                break;
+            }
+        }
+    }
+
+    change_tray_icon(&app, TrayIconState::Idle);
+}
+
+fn emit_transcript(app: &AppHandle, parts: &[Option<String>]) {
+    let _ = app.emit(
+        "meeting-transcript",
+        MeetingTranscript {
+            text: join_parts(parts),
+            chunks: parts.len() as u32,
+        },
+    );
+}
+
+/// The transcript of the chunks that could be transcribed.
+fn join_parts(parts: &[Option<String>]) -> String {
+    parts
+        .iter()
+        .flatten()
+        .filter(|text| !text.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+async fn save_meeting(
+    app: &AppHandle,
+    hm: &HistoryManager,
+    entry: HistoryEntry,
+    frames: usize,
+    parts: &[Option<String>],
+    failure: Option<String>,
+) {
+    if frames == 0 {
+        if let Err(e) = hm.discard_pending_entry(entry.id).await {
+            warn!("Failed to discard empty meeting {}: {}", entry.id, e);
+        }
+        return;
+    }
+    let duration_ms = (frames as i64 * 1000) / WHISPER_SAMPLE_RATE as i64;
+    let transcript = join_parts(parts);
+
+    // Keep what was transcribed, but leave the entry retryable if a chunk
+    // is missing
+    let result = match failure {
+        Some(reason) => hm.save_partial(entry.id, &transcript, &reason).await,
+        None => {
+            let word_count = count_words(&transcript);
+            hm.complete_entry(entry.id, transcript, None, None, word_count, duration_ms)
+                .await
+        }
+    };
+    let entry = match result {
+        Ok(entry) => entry,
+        Err(e) => {
+            error!("Failed to store meeting transcript: {}", e);
+            return;
+        }
+    };
+
+    if entry.status == TranscriptionStatus::Done && get_settings(app).meeting_diarization {
+        if let Err(e) = diarize_history_entry(app, &entry).await {
+            warn!("Failed to diarize meeting {}: {}", entry.id, e);
+        }
+    }
+}
+
+/// Mix the microphone into the system track, sample by sample, for
+/// transcription. The result is as long as the system track; missing
+/// microphone samples count as silence.
+fn mix_tracks(system: &[f32], mic: Option<&[f32]>) -> Vec<f32> {
+    let Some(mic) = mic else {
+        return system.to_vec();
+    };
+    system
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s + mic.get(i).copied().unwrap_or(0.0)).clamp(-1.0, 1.0))
+        .collect()
+}
+
+/// Interleave the tracks for the recording on disk: system audio on the
+/// first channel and the microphone, if any, on the second.
+fn interleave(system: &[f32], mic: Option<&[f32]>) -> Vec<f32> {
+    let Some(mic) = mic else {
+        return system.to_vec();
+    };
+    system.iter().zip(mic).flat_map(|(&s, &m)| [s, m]).collect()
+}
+
+/// Where to end a chunk: after the quietest frame at or beyond
+/// `search_from`, or at the end when there is no complete frame to choose.
+fn quietest_split(samples: &[f32], search_from: usize) -> usize {
+    let start = search_from.min(samples.len()) / SPLIT_FRAME_SAMPLES * SPLIT_FRAME_SAMPLES;
+    samples[start..]
+        .chunks_exact(SPLIT_FRAME_SAMPLES)
+        .enumerate()
+        .map(|(i, frame)| (i, frame.iter().map(|s| s * s).sum::<f32>()))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| start + (i + 1) * SPLIT_FRAME_SAMPLES)
+        .unwrap_or(samples.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixes_microphone_into_system_audio() {
+        let mixed = mix_tracks(&[0.2, 0.5, 0.9], Some(&[0.1, 0.6]));
+        assert_eq!(mixed, vec![0.3, 1.0, 0.9]);
+        assert_eq!(mix_tracks(&[0.2], None), vec![0.2]);
+    }
+
+    #[test]
+    fn keeps_the_microphone_on_its_own_channel() {
+        assert_eq!(
+            interleave(&[0.2, 0.5], Some(&[0.1, 0.6])),
+            vec![0.2, 0.1, 0.5, 0.6]
+        );
+        assert_eq!(interleave(&[0.2], None), vec![0.2]);
+    }
+
+    #[test]
+    fn fills_a_stalled_track_with_silence() {
+        let mut system = vec![0.5; 10];
+        let mut mic = vec![0.5; 4];
+        assert!(!pad_lagging_track(&mut system, &mut mic, 6));
+        assert!(pad_lagging_track(&mut system, &mut mic, 5));
+        assert_eq!(mic.len(), 10);
+        assert_eq!(mic[4..], [0.0; 6]);
+    }
+
+    #[test]
+    fn joins_the_chunks_that_were_transcribed() {
+        let parts = vec![
+            Some("Hello".to_string()),
+            None,
+            Some(String::new()),
+            Some("again".to_string()),
+        ];
+        assert_eq!(join_parts(&parts), "Hello again");
+    }
+
+    #[test]
+    fn splits_after_the_quietest_frame() {
+        let mut samples = vec![0.5f32; SPLIT_FRAME_SAMPLES * 10];
+        samples[SPLIT_FRAME_SAMPLES * 7..SPLIT_FRAME_SAMPLES * 8].fill(0.01);
+        // A quieter frame before the search window is ignored
+        samples[..SPLIT_FRAME_SAMPLES].fill(0.0);
+
+        assert_eq!(
+            quietest_split(&samples, SPLIT_FRAME_SAMPLES * 5),
+            SPLIT_FRAME_SAMPLES * 8
+        );
+        assert_eq!(quietest_split(&samples[..100], 0), 100);
+    }
+}
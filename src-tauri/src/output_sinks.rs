@@ -0,0 +1,444 @@
+//! Delivering transcriptions to files, webhooks and commands
+//!
+//! Each binding can list output sinks that receive its transcriptions after
+//! they're finished, with or without pasting them. File sinks append a
+//! rendered template, e.g. to a daily note when the path contains `{date}`.
+//! Webhook sinks POST the transcription as JSON. Command sinks write it as a
+//! line to the stdin of a command that is started on first use and kept
+//! running, so a single process can consume a whole session of dictations.
+//! Commands no binding lists any more are stopped, and so are all of them
+//! when the app exits.
+
+use chrono::{DateTime, Local};
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::settings::{AppSettings, OutputSink};
+
+/// How long a webhook may take before its delivery counts as failed.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Transcriptions a command sink may fall behind by before deliveries to it
+/// fail.
+const QUEUED_LINES: usize = 32;
+
+/// Running command sinks, by command line.
+static COMMANDS: Lazy<Mutex<HashMap<String, CommandSink>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A finished transcription, as sent to webhooks.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptOutput {
+    /// The text that was (or would have been) pasted
+    pub text: String,
+    /// The transcription before post-processing and conversions
+    pub raw_text: String,
+    /// The post-processing prompt, if one was applied
+    pub prompt: Option<String>,
+    pub duration_ms: i64,
+    /// The application that was in front when recording stopped
+    pub app: Option<String>,
+    pub binding_id: String,
+    pub timestamp: DateTime<Local>,
+}
+
+/// Send `output` to every sink. All sinks are tried even if one fails, and
+/// their errors are returned together.
+pub async fn deliver(sinks: &[OutputSink], output: &TranscriptOutput) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for sink in sinks {
+        let result = match sink {
+            OutputSink::File { path, template } => {
+                let path = expand_home(&render(path, output));
+                let entry = render(template, output);
+                tauri::async_runtime::spawn_blocking(move || append_to_file(&path, entry))
+                    .await
+                    .map_err(|e| format!("Failed to append transcription: {}", e))
+                    .and_then(|result| result)
+            }
+            OutputSink::Webhook { url } => post_webhook(url, output).await,
+            OutputSink::Command { command } => pipe_to_command(command, output),
+        };
+        if let Err(e) = result {
+            errors.push(e);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Replace the placeholders of a file sink's path or template.
+fn render(template: &str, output: &TranscriptOutput) -> String {
+    let duration = format!("{:.1}", output.duration_ms as f64 / 1000.0);
+    let date = output.timestamp.format("%Y-%m-%d").to_string();
+    let time = output.timestamp.format("%H:%M").to_string();
+    let datetime = output.timestamp.format("%Y-%m-%d %H:%M").to_string();
+
+    let placeholders: [(&str, &str); 8] = [
+        ("{text}", &output.text),
+        ("{raw_text}", &output.raw_text),
+        ("{prompt}", output.prompt.as_deref().unwrap_or("")),
+        ("{duration}", &duration),
+        ("{app}", output.app.as_deref().unwrap_or("")),
+        ("{date}", &date),
+        ("{time}", &time),
+        ("{datetime}", &datetime),
+    ];
+
+    // Scan once so placeholders inside the transcription stay as spoken
+    let mut rendered = String::with_capacity(template.len() + output.text.len());
+    let mut rest = template;
+    'outer: while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+        for (placeholder, value) in placeholders {
+            if let Some(after) = rest.strip_prefix(placeholder) {
+                rendered.push_str(value);
+                rest = after;
+                continue 'outer;
+            }
+        }
+        rendered.push('{');
+        rest = &rest[1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Expand a leading `~` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    match (path.strip_prefix("~"), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            PathBuf::from(home).join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+fn append_to_file(path: &Path, mut entry: String) -> Result<(), String> {
+    if !entry.ends_with('\n') {
+        entry.push('\n');
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(entry.as_bytes())
+        .map_err(|e| format!("Failed to write to {}: {}", path.display(), e))?;
+
+    debug!("Appended transcription to {}", path.display());
+    Ok(())
+}
+
+async fn post_webhook(url: &str, output: &TranscriptOutput) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(output)
+        .send()
+        .await
+        .map_err(|e| format!("Webhook {} failed: {}", url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Webhook {} returned {}", url, status));
+    }
+    debug!("Posted transcription to {}", url);
+    Ok(())
+}
+
+fn spawn_command(command: &str) -> Result<Child, String> {
+    #[cfg(unix)]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    };
+
+    let child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+    info!("Started output command '{}'", command);
+    Ok(child)
+}
+
+/// A running command sink. Its own thread writes the lines to the command's
+/// stdin, so a command that stops reading never blocks a delivery.
+struct CommandSink {
+    child: Child,
+    lines: SyncSender<String>,
+}
+
+impl CommandSink {
+    fn start(command: &str) -> Result<Self, String> {
+        let mut child = spawn_command(command)?;
+        let Some(mut stdin) = child.stdin.take() else {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("'{}' has no stdin", command));
+        };
+
+        let (lines, queued) = mpsc::sync_channel::<String>(QUEUED_LINES);
+        let command = command.to_string();
+        thread::spawn(move || {
+            for line in queued {
+                if let Err(e) = stdin.write_all(line.as_bytes()).and_then(|_| stdin.flush()) {
+                    warn!("Failed to write to '{}': {}", command, e);
+                    break;
+                }
+            }
+        });
+        Ok(Self { child, lines })
+    }
+
+    fn has_exited(&mut self) -> bool {
+        !matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// Close the command's stdin and kill it.
+    fn stop(self) {
+        let Self { mut child, lines } = self;
+        drop(lines);
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+fn pipe_to_command(command: &str, output: &TranscriptOutput) -> Result<(), String> {
+    let line = format!("{}\n", output.text);
+    let mut stopped = None;
+
+    let result = {
+        let mut commands = COMMANDS.lock().unwrap();
+
+        // Start the command again if it has exited since the last transcription
+        if commands
+            .get_mut(command)
+            .is_some_and(CommandSink::has_exited)
+        {
+            info!("Output command '{}' exited, restarting it", command);
+            stopped = commands.remove(command);
+        }
+        if !commands.contains_key(command) {
+            commands.insert(command.to_string(), CommandSink::start(command)?);
+        }
+
+        match commands[command].lines.try_send(line) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(format!("'{}' is not reading its input", command)),
+            Err(TrySendError::Disconnected(_)) => {
+                stopped = commands.remove(command);
+                Err(format!("Failed to write to '{}'", command))
+            }
+        }
+    };
+
+    // Killing waits for the process, so it happens outside the lock
+    if let Some(sink) = stopped {
+        sink.stop();
+    }
+    result
+}
+
+/// Stop the command sinks that no binding lists any more.
+pub fn stop_unused_commands(settings: &AppSettings) {
+    let used: HashSet<&str> = settings
+        .bindings
+        .values()
+        .flat_map(|binding| &binding.output_sinks)
+        .filter_map(|sink| match sink {
+            OutputSink::Command { command } => Some(command.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let unused: Vec<CommandSink> = {
+        let mut commands = COMMANDS.lock().unwrap();
+        let names: Vec<String> = commands
+            .keys()
+            .filter(|command| !used.contains(command.as_str()))
+            .cloned()
+            .collect();
+        names
+            .iter()
+            .filter_map(|command| commands.remove(command))
+            .collect()
+    };
+    for sink in unused {
+        sink.stop();
+    }
+}
+
+/// Stop every command sink, when the app exits.
+pub fn stop_commands() {
+    let running: Vec<CommandSink> = COMMANDS
+        .lock()
+        .unwrap()
+        .drain()
+        .map(|(_, sink)| sink)
+        .collect();
+    for sink in running {
+        sink.stop();
+    }
+}
+
+/// Name of the application in front, if the platform lets us find out. On
+/// Windows this is the title of the foreground window. Wayland keeps the
+/// active window from other clients, so there only Hyprland and Sway, which
+/// tell through their own IPC, report one.
+pub fn frontmost_app() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    {
+        command_output(
+            "osascript",
+            &[
+                "-e",
+                "tell application \"System Events\" to get name of first application process whose frontmost is true",
+            ],
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if !crate::utils::is_wayland() {
+            return command_output("xdotool", &["getactivewindow", "getwindowclassname"]);
+        }
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            let window: serde_json::Value =
+                serde_json::from_str(&command_output("hyprctl", &["activewindow", "-j"])?).ok()?;
+            return window["class"]
+                .as_str()
+                .filter(|class| !class.is_empty())
+                .map(str::to_string);
+        }
+        if std::env::var_os("SWAYSOCK").is_some() {
+            let tree: serde_json::Value =
+                serde_json::from_str(&command_output("swaymsg", &["-t", "get_tree"])?).ok()?;
+            return sway_focused_app(&tree);
+        }
+        None
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowTextW};
+
+        let mut title = [0u16; 256];
+        let len = unsafe { GetWindowTextW(GetForegroundWindow(), &mut title) };
+        (len > 0).then(|| String::from_utf16_lossy(&title[..len as usize]))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !name.is_empty()).then_some(name)
+}
+
+/// The app ID (or X11 class, for XWayland windows) of the focused window in
+/// a Sway layout tree.
+#[cfg(target_os = "linux")]
+fn sway_focused_app(node: &serde_json::Value) -> Option<String> {
+    if node["focused"].as_bool() == Some(true) {
+        return node["app_id"]
+            .as_str()
+            .or_else(|| node["window_properties"]["class"].as_str())
+            .map(str::to_string);
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[*key].as_array())
+        .flatten()
+        .find_map(sway_focused_app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn output(text: &str) -> TranscriptOutput {
+        TranscriptOutput {
+            text: text.to_string(),
+            raw_text: "raw".to_string(),
+            prompt: None,
+            duration_ms: 4250,
+            app: Some("Obsidian".to_string()),
+            binding_id: "transcribe".to_string(),
+            timestamp: Local.with_ymd_and_hms(2026, 3, 7, 9, 5, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let rendered = render(
+            "- {time} ({duration}s, {app}) {text} [{raw_text}]{prompt}",
+            &output("Call Sam"),
+        );
+        assert_eq!(rendered, "- 09:05 (4.2s, Obsidian) Call Sam [raw]");
+        assert_eq!(
+            render("~/vault/Daily/{date}.md", &output("")),
+            "~/vault/Daily/2026-03-07.md"
+        );
+    }
+
+    #[test]
+    fn leaves_braces_in_text_and_unknown_placeholders_alone() {
+        assert_eq!(
+            render("{text} {unknown}", &output("say {date} {")),
+            "say {date} { {unknown}"
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn finds_the_focused_sway_window() {
+        let tree = serde_json::json!({
+            "focused": false,
+            "nodes": [{
+                "focused": false,
+                "nodes": [{ "focused": false, "app_id": "foot" }],
+                "floating_nodes": [
+                    { "focused": true, "app_id": null, "window_properties": { "class": "Slack" } }
+                ]
+            }]
+        });
+        assert_eq!(sway_focused_app(&tree), Some("Slack".to_string()));
+    }
+}
@@ -2,12 +2,17 @@ use crate::input;
 use crate::settings;
 use crate::settings::{ActivationMode, OverlayPosition};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
 
 /// Monotonic counter incremented each time the overlay is shown.
 /// The hide thread checks this to avoid hiding a freshly-shown overlay.
 static OVERLAY_SHOW_GENERATION: AtomicU64 = AtomicU64::new(0);
 
+/// Activation mode of the binding that started the current recording, set
+/// through `update_overlay_activation_mode`.
+static OVERLAY_ACTIVATION_MODE: Mutex<ActivationMode> = Mutex::new(ActivationMode::Toggle);
+
 #[cfg(not(target_os = "macos"))]
 use log::debug;
 
@@ -340,9 +345,14 @@ struct OverlayPayload<'a> {
     state: &'a str,
     position: &'a str,
     activation_mode: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'a str>,
 }
 
-fn show_overlay_state(app_handle: &AppHandle, state: &str) {
+/// How long the error state stays visible before the overlay hides itself.
+const ERROR_OVERLAY_DURATION: std::time::Duration = std::time::Duration::from_millis(2500);
+
+fn show_overlay_state(app_handle: &AppHandle, state: &str, reason: Option<&str>) {
     // Check if overlay should be shown based on position setting
     let settings = settings::get_settings(app_handle);
     if settings.overlay_position == OverlayPosition::None {
@@ -359,8 +369,8 @@ fn show_overlay_state(app_handle: &AppHandle, state: &str) {
         // In toggle mode during recording, the overlay shows clickable
         // cancel/confirm buttons, so it must accept cursor events.
         // Otherwise, make it fully click-through (see #122).
-        let needs_interaction =
-            state == "recording" && settings.activation_mode == ActivationMode::Toggle;
+        let activation_mode = *OVERLAY_ACTIVATION_MODE.lock().unwrap();
+        let needs_interaction = state == "recording" && activation_mode == ActivationMode::Toggle;
         let _ = overlay_window.set_ignore_cursor_events(!needs_interaction);
 
         // On macOS, also use the NSPanel's order_front_regardless to ensure
@@ -384,7 +394,7 @@ fn show_overlay_state(app_handle: &AppHandle, state: &str) {
             OverlayPosition::Top => "top",
             _ => "bottom",
         };
-        let activation_mode_str = match settings.activation_mode {
+        let activation_mode_str = match activation_mode {
             ActivationMode::Toggle => "toggle",
             ActivationMode::Hold | ActivationMode::HoldOrToggle => "hold",
         };
@@ -394,6 +404,7 @@ fn show_overlay_state(app_handle: &AppHandle, state: &str) {
                 state,
                 position: position_str,
                 activation_mode: activation_mode_str,
+                reason,
             },
         );
     }
@@ -401,17 +412,32 @@ fn show_overlay_state(app_handle: &AppHandle, state: &str) {
 
 /// Shows the recording overlay window with fade-in animation
 pub fn show_recording_overlay(app_handle: &AppHandle) {
-    show_overlay_state(app_handle, "recording");
+    show_overlay_state(app_handle, "recording", None);
 }
 
 /// Shows the transcribing overlay window
 pub fn show_transcribing_overlay(app_handle: &AppHandle) {
-    show_overlay_state(app_handle, "transcribing");
+    show_overlay_state(app_handle, "transcribing", None);
 }
 
 /// Shows the processing overlay window
 pub fn show_processing_overlay(app_handle: &AppHandle) {
-    show_overlay_state(app_handle, "processing");
+    show_overlay_state(app_handle, "processing", None);
+}
+
+/// Shows the error state with a short reason code, then hides the overlay
+/// unless something else was shown in the meantime.
+pub fn show_error_overlay(app_handle: &AppHandle, reason: &str) {
+    show_overlay_state(app_handle, "error", Some(reason));
+
+    let gen = OVERLAY_SHOW_GENERATION.load(Ordering::SeqCst);
+    let app = app_handle.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(ERROR_OVERLAY_DURATION);
+        if OVERLAY_SHOW_GENERATION.load(Ordering::SeqCst) == gen {
+            hide_recording_overlay(&app);
+        }
+    });
 }
 
 /// Updates the overlay window position based on current settings
@@ -461,8 +487,10 @@ pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
 }
 
 /// Notify the overlay that the effective activation mode has changed
-/// (e.g. hold_or_toggle transitioned into toggle after a quick press).
+/// (e.g. a binding with its own mode started recording, or hold_or_toggle
+/// transitioned into toggle after a quick press).
 pub fn update_overlay_activation_mode(app_handle: &AppHandle, mode: ActivationMode) {
+    *OVERLAY_ACTIVATION_MODE.lock().unwrap() = mode;
     if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
         // Toggle mode needs clickable buttons; others are click-through
         let needs_interaction = mode == ActivationMode::Toggle;
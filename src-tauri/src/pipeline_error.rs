@@ -0,0 +1,251 @@
+//! Errors raised while turning a dictation into pasted text.
+//!
+//! Every stage of the pipeline reports failures through [`report`], which
+//! logs them, emits a `pipeline-error` event for the frontend, switches the
+//! overlay into its error state and optionally plays a sound and sends a
+//! desktop notification.
+
+use log::{error, warn};
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::audio_feedback::{play_feedback_sound, SoundType};
+use crate::cloud_stt::fallback::{self, FailureKind};
+use crate::overlay;
+use crate::settings::get_settings;
+use crate::tray_i18n::{get_notification_translations, NotificationStrings};
+
+/// Longest error detail included in a desktop notification.
+const MAX_NOTIFICATION_DETAIL_CHARS: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Recording,
+    ModelLoad,
+    Transcription,
+    PostProcessing,
+    Paste,
+    Output,
+}
+
+/// Short, user-facing cause shown in the overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorReason {
+    Microphone,
+    ModelLoad,
+    Offline,
+    Timeout,
+    Auth,
+    RateLimited,
+    Transcription,
+    PostProcessing,
+    Paste,
+    Output,
+}
+
+impl ErrorReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorReason::Microphone => "microphone",
+            ErrorReason::ModelLoad => "model_load",
+            ErrorReason::Offline => "offline",
+            ErrorReason::Timeout => "timeout",
+            ErrorReason::Auth => "auth",
+            ErrorReason::RateLimited => "rate_limited",
+            ErrorReason::Transcription => "transcription",
+            ErrorReason::PostProcessing => "post_processing",
+            ErrorReason::Paste => "paste",
+            ErrorReason::Output => "output",
+        }
+    }
+}
+
+/// A local model could not be loaded. Carried through `anyhow` so the
+/// transcription error can be attributed to the model-load stage.
+#[derive(Debug)]
+pub struct ModelLoadError(pub String);
+
+impl std::fmt::Display for ModelLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ModelLoadError {}
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct PipelineError {
+    pub stage: PipelineStage,
+    pub reason: ErrorReason,
+    pub message: String,
+}
+
+impl PipelineError {
+    pub fn recording(message: impl Into<String>) -> Self {
+        Self {
+            stage: PipelineStage::Recording,
+            reason: ErrorReason::Microphone,
+            message: message.into(),
+        }
+    }
+
+    /// Attribute a failed transcription to model loading or to the
+    /// provider, using the same classification as the fallback chain.
+    pub fn transcription(err: &anyhow::Error) -> Self {
+        if err.chain().any(|cause| cause.is::<ModelLoadError>()) {
+            return Self {
+                stage: PipelineStage::ModelLoad,
+                reason: ErrorReason::ModelLoad,
+                message: err.to_string(),
+            };
+        }
+
+        let reason = match fallback::classify(err) {
+            FailureKind::Offline => ErrorReason::Offline,
+            FailureKind::Timeout => ErrorReason::Timeout,
+            FailureKind::Auth => ErrorReason::Auth,
+            FailureKind::RateLimited => ErrorReason::RateLimited,
+            FailureKind::Server | FailureKind::Other => ErrorReason::Transcription,
+        };
+        Self {
+            stage: PipelineStage::Transcription,
+            reason,
+            message: err.to_string(),
+        }
+    }
+
+    pub fn post_processing(message: impl Into<String>) -> Self {
+        Self {
+            stage: PipelineStage::PostProcessing,
+            reason: ErrorReason::PostProcessing,
+            message: message.into(),
+        }
+    }
+
+    pub fn paste(message: impl Into<String>) -> Self {
+        Self {
+            stage: PipelineStage::Paste,
+            reason: ErrorReason::Paste,
+            message: message.into(),
+        }
+    }
+
+    pub fn output(message: impl Into<String>) -> Self {
+        Self {
+            stage: PipelineStage::Output,
+            reason: ErrorReason::Output,
+            message: message.into(),
+        }
+    }
+
+    /// English summary for the log.
+    fn summary(&self) -> &'static str {
+        match self.stage {
+            PipelineStage::Recording => "Recording failed",
+            PipelineStage::ModelLoad => "Model failed to load",
+            PipelineStage::Transcription => "Transcription failed",
+            PipelineStage::PostProcessing => "Post-processing failed",
+            PipelineStage::Paste => "Paste failed",
+            PipelineStage::Output => "Output failed",
+        }
+    }
+
+    /// Localized summary for the desktop notification.
+    fn localized_summary(&self, strings: NotificationStrings) -> String {
+        match self.stage {
+            PipelineStage::Recording => strings.recording_failed,
+            PipelineStage::ModelLoad => strings.model_load_failed,
+            PipelineStage::Transcription => strings.transcription_failed,
+            PipelineStage::PostProcessing => strings.post_processing_failed,
+            PipelineStage::Paste => strings.paste_failed,
+            PipelineStage::Output => strings.output_failed,
+        }
+    }
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.summary(), self.message)
+    }
+}
+
+/// Surface a pipeline failure to the user. The overlay hides itself once
+/// the error has been shown, so callers should not hide it afterwards.
+pub fn report(app: &AppHandle, err: &PipelineError) {
+    error!("{}", err);
+
+    let _ = app.emit("pipeline-error", err);
+    overlay::show_error_overlay(app, err.reason.as_str());
+    play_feedback_sound(app, SoundType::Error);
+
+    let settings = get_settings(app);
+    if settings.error_notifications_enabled {
+        send_notification(app, err, &settings.app_language);
+    }
+}
+
+fn send_notification(app: &AppHandle, err: &PipelineError, locale: &str) {
+    let mut detail: String = err
+        .message
+        .chars()
+        .take(MAX_NOTIFICATION_DETAIL_CHARS)
+        .collect();
+    if detail.len() < err.message.len() {
+        detail.push('…');
+    }
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(err.localized_summary(get_notification_translations(locale)))
+        .body(detail)
+        .show()
+    {
+        warn!("Failed to show error notification: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud_stt::ApiError;
+
+    #[test]
+    fn transcription_errors_are_attributed_to_their_cause() {
+        let load: anyhow::Error = ModelLoadError("Model not downloaded".into()).into();
+        let err = PipelineError::transcription(&load.context("local transcription"));
+        assert_eq!(err.stage, PipelineStage::ModelLoad);
+        assert_eq!(err.reason, ErrorReason::ModelLoad);
+
+        let auth: anyhow::Error = ApiError {
+            context: "test",
+            status: reqwest::StatusCode::UNAUTHORIZED,
+            body: String::new(),
+        }
+        .into();
+        let err = PipelineError::transcription(&auth);
+        assert_eq!(err.stage, PipelineStage::Transcription);
+        assert_eq!(err.reason, ErrorReason::Auth);
+
+        let other = PipelineError::transcription(&anyhow::anyhow!("engine crashed"));
+        assert_eq!(other.reason, ErrorReason::Transcription);
+        assert_eq!(other.to_string(), "Transcription failed: engine crashed");
+    }
+
+    #[test]
+    fn notification_titles_follow_the_app_language() {
+        let err = PipelineError::paste("no focused window");
+        assert_eq!(
+            err.localized_summary(get_notification_translations("de-DE")),
+            "Einfügen fehlgeschlagen"
+        );
+        assert_eq!(
+            err.localized_summary(get_notification_translations("xx")),
+            "Paste failed"
+        );
+    }
+}
@@ -60,21 +60,23 @@ fn extract_output_tag(s: &str) -> Option<String> {
     }
 }
 
+/// Run the prompt against the configured LLM provider. Returns `Ok(None)`
+/// when post-processing is skipped and `Err` when the provider fails.
 pub async fn post_process_transcription(
     settings: &AppSettings,
     transcription: &str,
     prompt_id: &str,
-) -> Option<PostProcessResult> {
+) -> Result<Option<PostProcessResult>, String> {
     if transcription.trim().is_empty() {
         debug!("Post-processing skipped because transcription is empty");
-        return None;
+        return Ok(None);
     }
 
     let provider = match settings.active_post_process_provider().cloned() {
         Some(provider) => provider,
         None => {
             debug!("Post-processing enabled but no provider is selected");
-            return None;
+            return Ok(None);
         }
     };
 
@@ -89,7 +91,7 @@ pub async fn post_process_transcription(
             "Post-processing skipped because provider '{}' has no model configured",
             provider.id
         );
-        return None;
+        return Ok(None);
     }
 
     // Skip post-processing if the provider is not verified (except Apple Intelligence)
@@ -102,7 +104,7 @@ pub async fn post_process_transcription(
             "Post-processing skipped because provider '{}' is not verified",
             provider.id
         );
-        return None;
+        return Ok(None);
     }
 
     let prompt = match settings
@@ -116,13 +118,13 @@ pub async fn post_process_transcription(
                 "Post-processing skipped because prompt '{}' was not found",
                 prompt_id
             );
-            return None;
+            return Ok(None);
         }
     };
 
     if prompt.trim().is_empty() {
         debug!("Post-processing skipped because the selected prompt is empty");
-        return None;
+        return Ok(None);
     }
 
     debug!(
@@ -144,8 +146,9 @@ pub async fn post_process_transcription(
         #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
         {
             if !apple_intelligence::check_apple_intelligence_availability() {
-                debug!("Apple Intelligence selected but not currently available on this device");
-                return None;
+                return Err(
+                    "Apple Intelligence is not currently available on this device".to_string(),
+                );
             }
 
             let token_limit = model.trim().parse::<i32>().unwrap_or(0);
@@ -157,28 +160,27 @@ pub async fn post_process_transcription(
             ) {
                 Ok(result) => {
                     if result.trim().is_empty() {
-                        debug!("Apple Intelligence returned an empty response");
-                        None
+                        Err("Apple Intelligence returned an empty response".to_string())
                     } else {
                         let result = strip_invisible_chars(&result);
                         debug!(
                             "Apple Intelligence post-processing succeeded. Output length: {} chars",
                             result.len()
                         );
-                        Some(PostProcessResult::new(result, start, None, model))
+                        Ok(Some(PostProcessResult::new(result, start, None, model)))
                     }
                 }
-                Err(err) => {
-                    error!("Apple Intelligence post-processing failed: {}", err);
-                    None
-                }
+                Err(err) => Err(format!(
+                    "Apple Intelligence post-processing failed: {}",
+                    err
+                )),
             };
         }
 
         #[cfg(not(all(target_os = "macos", target_arch = "aarch64")))]
         {
             debug!("Apple Intelligence provider selected on unsupported platform");
-            return None;
+            return Ok(None);
         }
     }
 
@@ -244,18 +246,17 @@ pub async fn post_process_transcription(
                 provider.id,
                 text.len()
             );
-            Some(PostProcessResult::new(text, start, usage.as_ref(), model))
-        }
-        Ok((None, _)) => {
-            error!("LLM API response has no content");
-            None
-        }
-        Err(e) => {
-            error!(
-                "LLM post-processing failed for provider '{}': {}",
-                provider.id, e
-            );
-            None
+            Ok(Some(PostProcessResult::new(
+                text,
+                start,
+                usage.as_ref(),
+                model,
+            )))
         }
+        Ok((None, _)) => Err("LLM API response has no content".to_string()),
+        Err(e) => Err(format!(
+            "LLM post-processing failed for provider '{}': {}",
+            provider.id, e
+        )),
     }
 }
@@ -146,6 +146,91 @@ pub struct ShortcutBinding {
     pub current_binding: String,
     #[serde(default)]
     pub post_process_prompt_id: Option<String>,
+    /// Overrides the global `activation_mode` for this binding.
+    #[serde(default)]
+    pub activation_mode: Option<ActivationMode>,
+    /// Mouse button or pedal that triggers this binding besides its shortcut.
+    #[serde(default)]
+    pub device_trigger: Option<DeviceTrigger>,
+    /// Where this binding's transcriptions are sent besides being pasted.
+    #[serde(default)]
+    pub output_sinks: Vec<OutputSink>,
+    /// Only send transcriptions to the output sinks, without pasting them.
+    #[serde(default)]
+    pub skip_paste: bool,
+}
+
+impl ShortcutBinding {
+    /// Whether no shortcut is assigned, as for actions that ship without one.
+    pub fn is_unbound(&self) -> bool {
+        self.current_binding.trim().is_empty()
+    }
+}
+
+/// A key or button on one input device, such as an extra mouse button or a
+/// foot pedal.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct DeviceTrigger {
+    /// Device name as reported by the kernel
+    pub device: String,
+    /// evdev key code
+    pub code: u16,
+    /// Name of the key code, e.g. `BTN_SIDE`
+    pub label: String,
+    /// Take the device exclusively so its events don't reach other apps
+    #[serde(default)]
+    pub grab: bool,
+}
+
+/// A destination transcriptions are delivered to after they're finished.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputSink {
+    /// Append to a file, e.g. a daily note. Both the path and the template
+    /// may contain placeholders such as `{text}` and `{date}`.
+    File { path: String, template: String },
+    /// POST the transcription as JSON.
+    Webhook { url: String },
+    /// Write each transcription as a line to the stdin of a command that is
+    /// kept running.
+    Command { command: String },
+}
+
+/// Sends transcriptions in a given language to a preferred provider/model.
+/// A `language` of `*` matches any language the default model cannot handle.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct LanguageRoute {
+    pub language: String,
+    pub provider_id: String,
+    #[serde(default)]
+    pub model_id: Option<String>,
+}
+
+/// One step of the transcription fallback chain, tried after the primary
+/// provider fails. `provider_id` is `local` or a cloud provider ID.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct SttFallbackStep {
+    pub provider_id: String,
+    /// Local model to use; `None` means the selected model.
+    #[serde(default)]
+    pub model_id: Option<String>,
+    /// Per-attempt timeout for cloud providers; 0 disables it.
+    #[serde(default = "default_fallback_timeout_secs")]
+    pub timeout_secs: u32,
+    #[serde(default)]
+    pub max_retries: u32,
+}
+
+/// Capture processing for one input device. Stored per device name, with
+/// `Default` standing for the system default device.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Type)]
+pub struct InputProcessing {
+    #[serde(default)]
+    pub high_pass: bool,
+    #[serde(default)]
+    pub noise_suppression: bool,
+    #[serde(default)]
+    pub auto_gain: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
@@ -194,6 +279,7 @@ pub enum PasteMethod {
     ShiftInsert,
     CtrlShiftV,
     ExternalScript,
+    InputMethod,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
@@ -226,6 +312,7 @@ pub enum RecordingRetentionPeriod {
 pub enum KeyboardImplementation {
     Tauri,
     HandyKeys,
+    Wayland,
 }
 
 impl Default for KeyboardImplementation {
@@ -340,6 +427,7 @@ pub enum TypingTool {
     Dotool,
     Ydotool,
     Xdotool,
+    Uinput,
 }
 
 impl Default for TypingTool {
@@ -368,6 +456,36 @@ pub struct AppSettings {
     pub selected_model: String,
     #[serde(default = "default_always_on_microphone")]
     pub always_on_microphone: bool,
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+    #[serde(default)]
+    pub warm_standby_secs: u32,
+    #[serde(default)]
+    pub auto_stop_enabled: bool,
+    #[serde(default = "default_auto_stop_silence_ms")]
+    pub auto_stop_silence_ms: u32,
+    #[serde(default = "default_auto_stop_min_speech_ms")]
+    pub auto_stop_min_speech_ms: u32,
+    #[serde(default = "default_auto_stop_max_duration_secs")]
+    pub auto_stop_max_duration_secs: u32,
+    #[serde(default)]
+    pub wake_word_enabled: bool,
+    #[serde(default = "default_wake_word_phrase")]
+    pub wake_word_phrase: String,
+    /// Moonshine model that listens for the wake phrase, apart from the
+    /// transcription models; the tiny English model when unset.
+    #[serde(default)]
+    pub wake_word_model: Option<String>,
+    #[serde(default)]
+    pub meeting_source: Option<String>,
+    #[serde(default = "default_meeting_include_microphone")]
+    pub meeting_include_microphone: bool,
+    #[serde(default = "default_meeting_chunk_secs")]
+    pub meeting_chunk_secs: u32,
+    #[serde(default)]
+    pub meeting_diarization: bool,
+    #[serde(default = "default_diarization_max_speakers")]
+    pub diarization_max_speakers: u32,
     #[serde(default)]
     pub selected_microphone: Option<String>,
     #[serde(default)]
@@ -375,13 +493,25 @@ pub struct AppSettings {
     #[serde(default)]
     pub clamshell_microphone: Option<String>,
     #[serde(default)]
+    pub input_processing: HashMap<String, InputProcessing>,
+    #[serde(default)]
     pub selected_output_device: Option<String>,
     #[serde(default = "default_translate_to_english")]
     pub translate_to_english: bool,
     #[serde(default = "default_selected_language")]
     pub selected_language: String,
+    #[serde(default)]
+    pub language_routing_enabled: bool,
+    #[serde(default)]
+    pub language_routes: Vec<LanguageRoute>,
+    #[serde(default)]
+    pub language_id_model: Option<String>,
+    #[serde(default = "default_language_id_seconds")]
+    pub language_id_seconds: u32,
     #[serde(default = "default_overlay_position")]
     pub overlay_position: OverlayPosition,
+    #[serde(default = "default_error_notifications_enabled")]
+    pub error_notifications_enabled: bool,
     #[serde(default = "default_debug_mode")]
     pub debug_mode: bool,
     #[serde(default = "default_log_level")]
@@ -390,6 +520,10 @@ pub struct AppSettings {
     pub custom_words: Vec<String>,
     #[serde(default)]
     pub model_unload_timeout: ModelUnloadTimeout,
+    #[serde(default)]
+    pub model_unload_timeouts: HashMap<String, ModelUnloadTimeout>,
+    #[serde(default)]
+    pub model_pool_budget_mb: u64,
     #[serde(default = "default_word_correction_threshold")]
     pub word_correction_threshold: f64,
     #[serde(default = "default_history_limit")]
@@ -438,6 +572,8 @@ pub struct AppSettings {
     pub show_tray_icon: bool,
     #[serde(default = "default_paste_delay_ms")]
     pub paste_delay_ms: u64,
+    #[serde(default = "default_clipboard_restore_delay_ms")]
+    pub clipboard_restore_delay_ms: u64,
     #[serde(default = "default_typing_tool")]
     pub typing_tool: TypingTool,
     pub external_script_path: Option<String>,
@@ -456,6 +592,14 @@ pub struct AppSettings {
     #[serde(default)]
     pub stt_realtime_enabled: HashMap<String, bool>,
     #[serde(default)]
+    pub stt_fallback_chain: Vec<SttFallbackStep>,
+    #[serde(default)]
+    pub stt_request_timeout_secs: u32,
+    #[serde(default)]
+    pub stt_max_retries: u32,
+    #[serde(default = "default_stt_retry_backoff_ms")]
+    pub stt_retry_backoff_ms: u64,
+    #[serde(default)]
     pub stats_date_range: StatsDateRange,
     #[serde(default)]
     pub dictionary_terms: Vec<String>,
@@ -471,6 +615,38 @@ fn default_always_on_microphone() -> bool {
     false
 }
 
+fn default_pre_roll_ms() -> u32 {
+    500
+}
+
+fn default_auto_stop_silence_ms() -> u32 {
+    1200
+}
+
+fn default_auto_stop_min_speech_ms() -> u32 {
+    300
+}
+
+fn default_auto_stop_max_duration_secs() -> u32 {
+    120
+}
+
+fn default_wake_word_phrase() -> String {
+    "hey handless".to_string()
+}
+
+fn default_meeting_include_microphone() -> bool {
+    true
+}
+
+fn default_meeting_chunk_secs() -> u32 {
+    30
+}
+
+fn default_diarization_max_speakers() -> u32 {
+    6
+}
+
 fn default_translate_to_english() -> bool {
     false
 }
@@ -491,6 +667,10 @@ fn default_selected_language() -> String {
     "auto".to_string()
 }
 
+fn default_language_id_seconds() -> u32 {
+    3
+}
+
 fn default_overlay_position() -> OverlayPosition {
     #[cfg(target_os = "linux")]
     return OverlayPosition::None;
@@ -498,6 +678,10 @@ fn default_overlay_position() -> OverlayPosition {
     return OverlayPosition::Bottom;
 }
 
+fn default_error_notifications_enabled() -> bool {
+    true
+}
+
 fn default_debug_mode() -> bool {
     false
 }
@@ -514,6 +698,10 @@ fn default_paste_delay_ms() -> u64 {
     60
 }
 
+fn default_clipboard_restore_delay_ms() -> u64 {
+    100
+}
+
 fn default_auto_submit() -> bool {
     false
 }
@@ -666,6 +854,14 @@ fn default_stt_cloud_models() -> HashMap<String, String> {
     map
 }
 
+fn default_fallback_timeout_secs() -> u32 {
+    30
+}
+
+fn default_stt_retry_backoff_ms() -> u64 {
+    500
+}
+
 fn ensure_stt_defaults(settings: &mut AppSettings) -> bool {
     let mut changed = false;
     for provider in default_stt_providers() {
@@ -750,6 +946,10 @@ pub fn get_default_settings() -> AppSettings {
             default_binding: default_shortcut.to_string(),
             current_binding: default_shortcut.to_string(),
             post_process_prompt_id: None,
+            activation_mode: None,
+            device_trigger: None,
+            output_sinks: Vec::new(),
+            skip_paste: false,
         },
     );
     #[cfg(target_os = "windows")]
@@ -771,6 +971,10 @@ pub fn get_default_settings() -> AppSettings {
             default_binding: default_post_process_shortcut.to_string(),
             current_binding: default_post_process_shortcut.to_string(),
             post_process_prompt_id: None,
+            activation_mode: None,
+            device_trigger: None,
+            output_sinks: Vec::new(),
+            skip_paste: false,
         },
     );
     bindings.insert(
@@ -782,9 +986,105 @@ pub fn get_default_settings() -> AppSettings {
             default_binding: "escape".to_string(),
             current_binding: "escape".to_string(),
             post_process_prompt_id: None,
+            activation_mode: None,
+            device_trigger: None,
+            output_sinks: Vec::new(),
+            skip_paste: false,
+        },
+    );
+    bindings.insert(
+        "toggle_hands_free".to_string(),
+        ShortcutBinding {
+            id: "toggle_hands_free".to_string(),
+            name: "Toggle Hands-Free".to_string(),
+            description: "Starts or stops continuous dictation without holding a key.".to_string(),
+            // Bindings added after the first release start unbound, so an
+            // update never takes a shortcut another app relies on
+            default_binding: String::new(),
+            current_binding: String::new(),
+            post_process_prompt_id: None,
+            activation_mode: None,
+            device_trigger: None,
+            output_sinks: Vec::new(),
+            skip_paste: false,
         },
     );
 
+    bindings.insert(
+        "meeting".to_string(),
+        ShortcutBinding {
+            id: "meeting".to_string(),
+            name: "Record Meeting".to_string(),
+            description: "Starts or stops recording and transcribing system audio.".to_string(),
+            default_binding: String::new(),
+            current_binding: String::new(),
+            post_process_prompt_id: None,
+            activation_mode: None,
+            device_trigger: None,
+            output_sinks: Vec::new(),
+            skip_paste: false,
+        },
+    );
+
+    for (id, name, description) in [
+        (
+            "repaste_last",
+            "Paste Last Transcript",
+            "Pastes the last transcript again.",
+        ),
+        (
+            "copy_last_transcript",
+            "Copy Last Transcript",
+            "Copies the last transcript to the clipboard.",
+        ),
+        (
+            "undo_last_insertion",
+            "Undo Last Dictation",
+            "Removes the text the last dictation inserted.",
+        ),
+        (
+            "cycle_prompt",
+            "Next Post-Processing Prompt",
+            "Switches post-processing to the next prompt.",
+        ),
+        (
+            "cycle_microphone",
+            "Next Microphone",
+            "Switches to the next available microphone.",
+        ),
+        (
+            "switch_model",
+            "Next Model",
+            "Switches to the next downloaded transcription model.",
+        ),
+        (
+            "toggle_always_on",
+            "Toggle Always-On Microphone",
+            "Turns the always-on microphone on or off.",
+        ),
+        (
+            "rerun_post_process",
+            "Post-Process Last Transcript",
+            "Runs post-processing on the last transcript again and pastes the result.",
+        ),
+    ] {
+        bindings.insert(
+            id.to_string(),
+            ShortcutBinding {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: description.to_string(),
+                default_binding: String::new(),
+                current_binding: String::new(),
+                post_process_prompt_id: None,
+                activation_mode: None,
+                device_trigger: None,
+                output_sinks: Vec::new(),
+                skip_paste: false,
+            },
+        );
+    }
+
     AppSettings {
         bindings,
         activation_mode: ActivationMode::HoldOrToggle,
@@ -795,17 +1095,39 @@ pub fn get_default_settings() -> AppSettings {
         update_checks_enabled: default_update_checks_enabled(),
         selected_model: "".to_string(),
         always_on_microphone: false,
+        pre_roll_ms: default_pre_roll_ms(),
+        warm_standby_secs: 0,
+        auto_stop_enabled: false,
+        auto_stop_silence_ms: default_auto_stop_silence_ms(),
+        auto_stop_min_speech_ms: default_auto_stop_min_speech_ms(),
+        auto_stop_max_duration_secs: default_auto_stop_max_duration_secs(),
+        wake_word_enabled: false,
+        wake_word_phrase: default_wake_word_phrase(),
+        wake_word_model: None,
+        meeting_source: None,
+        meeting_include_microphone: default_meeting_include_microphone(),
+        meeting_chunk_secs: default_meeting_chunk_secs(),
+        meeting_diarization: false,
+        diarization_max_speakers: default_diarization_max_speakers(),
         selected_microphone: None,
         microphone_priority: Vec::new(),
         clamshell_microphone: None,
+        input_processing: HashMap::new(),
         selected_output_device: None,
         translate_to_english: false,
         selected_language: "auto".to_string(),
+        language_routing_enabled: false,
+        language_routes: Vec::new(),
+        language_id_model: None,
+        language_id_seconds: default_language_id_seconds(),
         overlay_position: default_overlay_position(),
+        error_notifications_enabled: default_error_notifications_enabled(),
         debug_mode: false,
         log_level: default_log_level(),
         custom_words: Vec::new(),
         model_unload_timeout: ModelUnloadTimeout::Never,
+        model_unload_timeouts: HashMap::new(),
+        model_pool_budget_mb: 0,
         word_correction_threshold: default_word_correction_threshold(),
         history_limit: default_history_limit(),
         recording_retention_period: default_recording_retention_period(),
@@ -830,6 +1152,7 @@ pub fn get_default_settings() -> AppSettings {
         keyboard_implementation: KeyboardImplementation::default(),
         show_tray_icon: default_show_tray_icon(),
         paste_delay_ms: default_paste_delay_ms(),
+        clipboard_restore_delay_ms: default_clipboard_restore_delay_ms(),
         typing_tool: default_typing_tool(),
         external_script_path: None,
         app_theme: AppTheme::default(),
@@ -839,6 +1162,10 @@ pub fn get_default_settings() -> AppSettings {
         post_process_output_prices: HashMap::new(),
         stt_cloud_options: default_stt_cloud_options(),
         stt_realtime_enabled: HashMap::new(),
+        stt_fallback_chain: Vec::new(),
+        stt_request_timeout_secs: 0,
+        stt_max_retries: 0,
+        stt_retry_backoff_ms: default_stt_retry_backoff_ms(),
         stats_date_range: StatsDateRange::default(),
         dictionary_terms: Vec::new(),
         dictionary_context: String::new(),
@@ -846,6 +1173,24 @@ pub fn get_default_settings() -> AppSettings {
 }
 
 impl AppSettings {
+    /// Activation mode for a binding, falling back to the global
+    /// `activation_mode` when the binding has no override.
+    pub fn activation_mode_for(&self, binding_id: &str) -> ActivationMode {
+        self.bindings
+            .get(binding_id)
+            .and_then(|binding| binding.activation_mode)
+            .unwrap_or(self.activation_mode)
+    }
+
+    /// Unload timeout for a specific local model, falling back to the global
+    /// `model_unload_timeout` when the model has no override.
+    pub fn unload_timeout_for(&self, model_id: &str) -> ModelUnloadTimeout {
+        self.model_unload_timeouts
+            .get(model_id)
+            .copied()
+            .unwrap_or(self.model_unload_timeout)
+    }
+
     pub fn stt_provider(&self, provider_id: &str) -> Option<&SttProvider> {
         self.stt_providers
             .iter()
@@ -1036,4 +1381,25 @@ mod tests {
         assert!(!settings.auto_submit);
         assert_eq!(settings.auto_submit_key, AutoSubmitKey::Enter);
     }
+
+    #[test]
+    fn binding_activation_mode_overrides_global() {
+        let mut settings = get_default_settings();
+        settings.activation_mode = ActivationMode::Toggle;
+        let binding = settings.bindings.get_mut("transcribe").unwrap();
+        binding.activation_mode = Some(ActivationMode::Hold);
+
+        assert_eq!(
+            settings.activation_mode_for("transcribe"),
+            ActivationMode::Hold
+        );
+        assert_eq!(
+            settings.activation_mode_for("transcribe_with_post_process"),
+            ActivationMode::Toggle
+        );
+        assert_eq!(
+            settings.activation_mode_for("missing"),
+            ActivationMode::Toggle
+        );
+    }
 }
@@ -0,0 +1,399 @@
+//! Mouse button and foot pedal triggers
+//!
+//! A binding can also be triggered by a key or button on one input device,
+//! independent of the keyboard implementation. On Linux the devices are read
+//! from `/dev/input`, which reports real press and release events so hold
+//! mode works with a pedal. Pedals usually show up as keyboards that type a
+//! letter, so a trigger can grab its device to keep those events from
+//! reaching other applications.
+
+use tauri::AppHandle;
+
+use crate::settings::DeviceTrigger;
+
+/// How long `capture_device_trigger` waits for a button press.
+#[cfg(target_os = "linux")]
+const CAPTURE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// (Re)start reading the devices of all bindings' triggers.
+pub fn reload(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    linux::reload(app);
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
+
+/// Wait for the next button press on any input device and return it as a
+/// trigger. Triggers are paused meanwhile so grabbed devices can be captured.
+#[tauri::command]
+#[specta::specta]
+pub async fn capture_device_trigger(app: AppHandle) -> Result<DeviceTrigger, String> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(move || {
+            linux::stop();
+            let trigger = linux::capture(CAPTURE_TIMEOUT);
+            linux::reload(&app);
+            trigger
+        })
+        .await
+        .map_err(|e| format!("Device capture failed: {}", e))?
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = app;
+        Err("Mouse button and pedal triggers are only available on Linux".into())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use evdev::{Device, EventSummary, KeyCode};
+    use log::{debug, info, warn};
+    use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+    use tauri::AppHandle;
+
+    use super::super::evdev::{is_keyboard, wait_readable};
+    use super::super::handler::handle_binding_event;
+    use crate::settings::{get_settings, DeviceTrigger, ShortcutBinding};
+
+    /// How long a reader waits for events before checking whether it is
+    /// still current. Also bounds how long an old reader keeps its grab.
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// How often devices plugged in later are looked for.
+    const RESCAN_INTERVAL: Duration = Duration::from_secs(3);
+
+    /// Buttons never offered as triggers since they're needed for pointing.
+    const POINTER_BUTTONS: [KeyCode; 3] =
+        [KeyCode::BTN_LEFT, KeyCode::BTN_RIGHT, KeyCode::BTN_TOUCH];
+
+    /// Bumped on every reload; readers of older generations stop.
+    static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+    /// The triggers on one device
+    #[derive(Default)]
+    struct DeviceBindings {
+        /// Key code to the IDs of the bindings it triggers
+        codes: HashMap<u16, Vec<String>>,
+        /// Code labels, used as the hotkey string of events
+        labels: HashMap<u16, String>,
+        grab: bool,
+    }
+
+    impl DeviceBindings {
+        /// The bindings a key event triggers, with the hotkey string to report
+        /// and whether the key went down. Key repeat (value 2) triggers none.
+        fn key_event(&self, code: u16, value: i32) -> Vec<(&str, &str, bool)> {
+            let is_pressed = match value {
+                0 => false,
+                1 => true,
+                _ => return Vec::new(),
+            };
+            let Some(binding_ids) = self.codes.get(&code) else {
+                return Vec::new();
+            };
+            binding_ids
+                .iter()
+                .map(|id| (id.as_str(), self.labels[&code].as_str(), is_pressed))
+                .collect()
+        }
+    }
+
+    /// Group the triggers of `bindings` by device name.
+    fn device_bindings(
+        bindings: impl IntoIterator<Item = ShortcutBinding>,
+    ) -> HashMap<String, DeviceBindings> {
+        let mut devices: HashMap<String, DeviceBindings> = HashMap::new();
+        for binding in bindings {
+            let Some(trigger) = binding.device_trigger else {
+                continue;
+            };
+            let device = devices.entry(trigger.device).or_default();
+            device
+                .codes
+                .entry(trigger.code)
+                .or_default()
+                .push(binding.id);
+            device.labels.insert(trigger.code, trigger.label);
+            device.grab |= trigger.grab;
+        }
+        devices
+    }
+
+    pub(super) fn stop() {
+        GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(super) fn reload(app: &AppHandle) {
+        let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let devices = device_bindings(get_settings(app).bindings.into_values());
+        if devices.is_empty() {
+            return;
+        }
+
+        let devices = Arc::new(devices);
+        let opened = Arc::new(Mutex::new(HashSet::new()));
+        let app = app.clone();
+        thread::spawn(move || {
+            // Let the readers of the previous generation release their grabs
+            thread::sleep(POLL_INTERVAL * 2);
+            while GENERATION.load(Ordering::SeqCst) == generation {
+                open_new_devices(&app, &devices, &opened, generation);
+                thread::sleep(RESCAN_INTERVAL);
+            }
+        });
+    }
+
+    /// Start a reader for each device with triggers that isn't read yet.
+    fn open_new_devices(
+        app: &AppHandle,
+        devices: &Arc<HashMap<String, DeviceBindings>>,
+        opened: &Arc<Mutex<HashSet<PathBuf>>>,
+        generation: u64,
+    ) {
+        for (path, device) in evdev::enumerate() {
+            let name = device.name().unwrap_or_default().to_string();
+            if !devices.contains_key(&name) || !opened.lock().unwrap().insert(path.clone()) {
+                continue;
+            }
+            info!("Reading triggers from {} ({})", name, path.display());
+            let app = app.clone();
+            let devices = Arc::clone(devices);
+            let opened = Arc::clone(opened);
+            thread::spawn(move || {
+                read_triggers(&app, device, &devices[&name], generation);
+                debug!("Stopped reading triggers from {}", path.display());
+                opened.lock().unwrap().remove(&path);
+            });
+        }
+    }
+
+    fn read_triggers(
+        app: &AppHandle,
+        mut device: Device,
+        bindings: &DeviceBindings,
+        generation: u64,
+    ) {
+        if bindings.grab {
+            if let Err(e) = device.grab() {
+                warn!("Failed to grab {:?}: {}", device.name(), e);
+            }
+        }
+
+        while GENERATION.load(Ordering::SeqCst) == generation {
+            match wait_readable(&device, POLL_INTERVAL) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(_) => return,
+            }
+            let Ok(events) = device.fetch_events() else {
+                return;
+            };
+            let keys: Vec<(u16, i32)> = events
+                .filter_map(|event| match event.destructure() {
+                    EventSummary::Key(_, code, value) => Some((code.code(), value)),
+                    _ => None,
+                })
+                .collect();
+            for (code, value) in keys {
+                for (binding_id, label, is_pressed) in bindings.key_event(code, value) {
+                    handle_binding_event(app, binding_id, label, is_pressed);
+                }
+            }
+        }
+
+        if bindings.grab {
+            let _ = device.ungrab();
+        }
+    }
+
+    /// Wait up to `timeout` for a key or button press on any readable device.
+    pub(super) fn capture(timeout: Duration) -> Result<DeviceTrigger, String> {
+        let (sender, receiver) = mpsc::channel();
+        let done = Arc::new(AtomicBool::new(false));
+        let mut readable = 0;
+
+        for (_, device) in evdev::enumerate() {
+            if device.supported_keys().is_none() {
+                continue;
+            }
+            readable += 1;
+            let sender = sender.clone();
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                if let Some(trigger) = next_press(device, &done) {
+                    let _ = sender.send(trigger);
+                }
+            });
+        }
+        if readable == 0 {
+            return Err(
+                "No input device in /dev/input can be read. Add your user to the 'input' group."
+                    .into(),
+            );
+        }
+
+        let trigger = receiver.recv_timeout(timeout);
+        done.store(true, Ordering::SeqCst);
+        trigger.map_err(|_| "No button was pressed".to_string())
+    }
+
+    fn next_press(mut device: Device, done: &AtomicBool) -> Option<DeviceTrigger> {
+        let name = device.name().unwrap_or_default().to_string();
+        // Grabbing a keyboard or mouse would take it away from the user
+        let grab = !is_keyboard(&device) && device.supported_relative_axes().is_none();
+
+        while !done.load(Ordering::SeqCst) {
+            if !wait_readable(&device, POLL_INTERVAL).ok()? {
+                continue;
+            }
+            for event in device.fetch_events().ok()? {
+                if let EventSummary::Key(_, code, 1) = event.destructure() {
+                    if let Some(trigger) = trigger_for(&name, code, grab) {
+                        return Some(trigger);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The trigger for a press of `code`, unless it's needed for pointing.
+    fn trigger_for(device: &str, code: KeyCode, grab: bool) -> Option<DeviceTrigger> {
+        if POINTER_BUTTONS.contains(&code) {
+            return None;
+        }
+        Some(DeviceTrigger {
+            device: device.to_string(),
+            code: code.code(),
+            label: format!("{:?}", code),
+            grab,
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::settings::get_default_settings;
+
+        const PEDAL: &str = "PCsensor FootSwitch";
+
+        fn binding(id: &str, trigger: Option<(&str, KeyCode, bool)>) -> ShortcutBinding {
+            let mut binding = get_default_settings()
+                .bindings
+                .into_values()
+                .next()
+                .unwrap();
+            binding.id = id.to_string();
+            binding.device_trigger = trigger.map(|(device, code, grab)| DeviceTrigger {
+                device: device.to_string(),
+                code: code.code(),
+                label: format!("{:?}", code),
+                grab,
+            });
+            binding
+        }
+
+        #[test]
+        fn groups_triggers_by_device() {
+            let devices = device_bindings([
+                binding("transcribe", Some((PEDAL, KeyCode::KEY_B, true))),
+                binding("cancel", Some((PEDAL, KeyCode::KEY_C, false))),
+                binding(
+                    "paste_last",
+                    Some(("Logitech Mouse", KeyCode::BTN_SIDE, false)),
+                ),
+                binding("translate", None),
+            ]);
+
+            assert_eq!(devices.len(), 2);
+            // One grabbing trigger grabs the whole device
+            assert!(devices[PEDAL].grab);
+            assert!(!devices["Logitech Mouse"].grab);
+            assert_eq!(
+                devices[PEDAL].codes[&KeyCode::KEY_C.code()],
+                vec!["cancel".to_string()]
+            );
+        }
+
+        #[test]
+        fn matches_press_and_release_but_not_repeat() {
+            let devices = device_bindings([
+                binding("transcribe", Some((PEDAL, KeyCode::KEY_B, true))),
+                binding("meeting", Some((PEDAL, KeyCode::KEY_B, true))),
+            ]);
+            let pedal = &devices[PEDAL];
+            let code = KeyCode::KEY_B.code();
+
+            let mut pressed = pedal.key_event(code, 1);
+            pressed.sort();
+            assert_eq!(
+                pressed,
+                vec![("meeting", "KEY_B", true), ("transcribe", "KEY_B", true)]
+            );
+            assert!(pedal.key_event(code, 2).is_empty());
+            assert_eq!(pedal.key_event(code, 0).len(), 2);
+            assert!(pedal.key_event(code, 0).iter().all(|(_, _, p)| !p));
+            assert!(pedal.key_event(KeyCode::KEY_A.code(), 1).is_empty());
+        }
+
+        #[test]
+        fn pointer_buttons_are_no_triggers() {
+            assert_eq!(trigger_for("Mouse", KeyCode::BTN_LEFT, false), None);
+            assert_eq!(
+                trigger_for("Mouse", KeyCode::BTN_EXTRA, false),
+                Some(DeviceTrigger {
+                    device: "Mouse".to_string(),
+                    code: KeyCode::BTN_EXTRA.code(),
+                    label: "BTN_EXTRA".to_string(),
+                    grab: false,
+                })
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers_round_trip_through_settings_json() {
+        let trigger = DeviceTrigger {
+            device: "PCsensor FootSwitch".to_string(),
+            code: 48,
+            label: "KEY_B".to_string(),
+            grab: true,
+        };
+        let json = serde_json::to_value(&trigger).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "device": "PCsensor FootSwitch",
+                "code": 48,
+                "label": "KEY_B",
+                "grab": true,
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<DeviceTrigger>(json).unwrap(),
+            trigger
+        );
+
+        // Triggers that leave out `grab` don't grab
+        let old: DeviceTrigger = serde_json::from_str(
+            r#"{"device": "PCsensor FootSwitch", "code": 48, "label": "KEY_B"}"#,
+        )
+        .unwrap();
+        assert!(!old.grab);
+    }
+}
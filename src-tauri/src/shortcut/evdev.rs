@@ -0,0 +1,443 @@
+//! Key events read directly from `/dev/input`
+//!
+//! Used by the Wayland implementation when no GlobalShortcuts portal is
+//! available. Reading input devices works under any compositor and reports
+//! real press and release events, but needs read access to the event
+//! devices (usually membership in the `input` group). Devices are only
+//! read, never grabbed, so keys still reach the focused application.
+//!
+//! Device triggers (see `device_triggers`) read buttons from single devices
+//! through the helpers here.
+
+use evdev::{Device, EventSummary, KeyCode};
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::wayland::{Key, Side};
+
+/// How often new devices are looked for.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long a reader waits for events before checking whether to stop.
+const READ_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Keys of the letters in the user's layout, so "ctrl+z" is the key labelled
+/// Z on a German keyboard too. Letters the layout lacks, as Cyrillic ones
+/// do, stay at their US positions.
+static LAYOUT_LETTERS: Lazy<HashMap<char, KeyCode>> =
+    Lazy::new(crate::uinput_keyboard::unmodified_keys);
+
+const LETTER_CODES: [KeyCode; 26] = [
+    KeyCode::KEY_A,
+    KeyCode::KEY_B,
+    KeyCode::KEY_C,
+    KeyCode::KEY_D,
+    KeyCode::KEY_E,
+    KeyCode::KEY_F,
+    KeyCode::KEY_G,
+    KeyCode::KEY_H,
+    KeyCode::KEY_I,
+    KeyCode::KEY_J,
+    KeyCode::KEY_K,
+    KeyCode::KEY_L,
+    KeyCode::KEY_M,
+    KeyCode::KEY_N,
+    KeyCode::KEY_O,
+    KeyCode::KEY_P,
+    KeyCode::KEY_Q,
+    KeyCode::KEY_R,
+    KeyCode::KEY_S,
+    KeyCode::KEY_T,
+    KeyCode::KEY_U,
+    KeyCode::KEY_V,
+    KeyCode::KEY_W,
+    KeyCode::KEY_X,
+    KeyCode::KEY_Y,
+    KeyCode::KEY_Z,
+];
+
+const DIGIT_CODES: [KeyCode; 10] = [
+    KeyCode::KEY_0,
+    KeyCode::KEY_1,
+    KeyCode::KEY_2,
+    KeyCode::KEY_3,
+    KeyCode::KEY_4,
+    KeyCode::KEY_5,
+    KeyCode::KEY_6,
+    KeyCode::KEY_7,
+    KeyCode::KEY_8,
+    KeyCode::KEY_9,
+];
+
+const KEYPAD_DIGIT_CODES: [KeyCode; 10] = [
+    KeyCode::KEY_KP0,
+    KeyCode::KEY_KP1,
+    KeyCode::KEY_KP2,
+    KeyCode::KEY_KP3,
+    KeyCode::KEY_KP4,
+    KeyCode::KEY_KP5,
+    KeyCode::KEY_KP6,
+    KeyCode::KEY_KP7,
+    KeyCode::KEY_KP8,
+    KeyCode::KEY_KP9,
+];
+
+const FUNCTION_KEY_CODES: [KeyCode; 24] = [
+    KeyCode::KEY_F1,
+    KeyCode::KEY_F2,
+    KeyCode::KEY_F3,
+    KeyCode::KEY_F4,
+    KeyCode::KEY_F5,
+    KeyCode::KEY_F6,
+    KeyCode::KEY_F7,
+    KeyCode::KEY_F8,
+    KeyCode::KEY_F9,
+    KeyCode::KEY_F10,
+    KeyCode::KEY_F11,
+    KeyCode::KEY_F12,
+    KeyCode::KEY_F13,
+    KeyCode::KEY_F14,
+    KeyCode::KEY_F15,
+    KeyCode::KEY_F16,
+    KeyCode::KEY_F17,
+    KeyCode::KEY_F18,
+    KeyCode::KEY_F19,
+    KeyCode::KEY_F20,
+    KeyCode::KEY_F21,
+    KeyCode::KEY_F22,
+    KeyCode::KEY_F23,
+    KeyCode::KEY_F24,
+];
+
+/// Position of `name` in a table of keysym names.
+fn index_in(table: &[&str], name: &str) -> Option<usize> {
+    table.iter().position(|entry| *entry == name)
+}
+
+fn named_code(name: &str) -> Option<KeyCode> {
+    use super::wayland::{DIGITS, FUNCTION_KEYS, KEYPAD_DIGITS, LETTERS};
+
+    if let Some(i) = index_in(&LETTERS, name) {
+        let letter = name.chars().next().unwrap_or_default();
+        return Some(
+            LAYOUT_LETTERS
+                .get(&letter)
+                .copied()
+                .unwrap_or(LETTER_CODES[i]),
+        );
+    }
+    if let Some(i) = index_in(&DIGITS, name) {
+        return Some(DIGIT_CODES[i]);
+    }
+    if let Some(i) = index_in(&KEYPAD_DIGITS, name) {
+        return Some(KEYPAD_DIGIT_CODES[i]);
+    }
+    if let Some(i) = index_in(&FUNCTION_KEYS, name) {
+        return Some(FUNCTION_KEY_CODES[i]);
+    }
+
+    let code = match name {
+        "space" => KeyCode::KEY_SPACE,
+        "Return" => KeyCode::KEY_ENTER,
+        "Tab" => KeyCode::KEY_TAB,
+        "Escape" => KeyCode::KEY_ESC,
+        "BackSpace" => KeyCode::KEY_BACKSPACE,
+        "Delete" => KeyCode::KEY_DELETE,
+        "Insert" => KeyCode::KEY_INSERT,
+        "Home" => KeyCode::KEY_HOME,
+        "End" => KeyCode::KEY_END,
+        "Prior" => KeyCode::KEY_PAGEUP,
+        "Next" => KeyCode::KEY_PAGEDOWN,
+        "Up" => KeyCode::KEY_UP,
+        "Down" => KeyCode::KEY_DOWN,
+        "Left" => KeyCode::KEY_LEFT,
+        "Right" => KeyCode::KEY_RIGHT,
+        "Caps_Lock" => KeyCode::KEY_CAPSLOCK,
+        "Print" => KeyCode::KEY_SYSRQ,
+        "Scroll_Lock" => KeyCode::KEY_SCROLLLOCK,
+        "Pause" => KeyCode::KEY_PAUSE,
+        "Menu" => KeyCode::KEY_COMPOSE,
+        "Num_Lock" => KeyCode::KEY_NUMLOCK,
+        "KP_Multiply" => KeyCode::KEY_KPASTERISK,
+        "KP_Add" => KeyCode::KEY_KPPLUS,
+        "KP_Subtract" => KeyCode::KEY_KPMINUS,
+        "KP_Decimal" => KeyCode::KEY_KPDOT,
+        "KP_Divide" => KeyCode::KEY_KPSLASH,
+        "minus" => KeyCode::KEY_MINUS,
+        "equal" => KeyCode::KEY_EQUAL,
+        "bracketleft" => KeyCode::KEY_LEFTBRACE,
+        "bracketright" => KeyCode::KEY_RIGHTBRACE,
+        "backslash" => KeyCode::KEY_BACKSLASH,
+        "semicolon" => KeyCode::KEY_SEMICOLON,
+        "apostrophe" => KeyCode::KEY_APOSTROPHE,
+        "grave" => KeyCode::KEY_GRAVE,
+        "period" => KeyCode::KEY_DOT,
+        "slash" => KeyCode::KEY_SLASH,
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn sided(side: Side, left: KeyCode, right: KeyCode) -> Vec<KeyCode> {
+    match side {
+        Side::Any => vec![left, right],
+        Side::Left => vec![left],
+        Side::Right => vec![right],
+    }
+}
+
+/// Key codes that satisfy one key of a shortcut; "ctrl" accepts either
+/// Ctrl key.
+pub(super) fn key_codes(key: Key) -> Vec<KeyCode> {
+    match key {
+        Key::Ctrl(side) => sided(side, KeyCode::KEY_LEFTCTRL, KeyCode::KEY_RIGHTCTRL),
+        Key::Shift(side) => sided(side, KeyCode::KEY_LEFTSHIFT, KeyCode::KEY_RIGHTSHIFT),
+        Key::Alt(side) => sided(side, KeyCode::KEY_LEFTALT, KeyCode::KEY_RIGHTALT),
+        Key::Super(side) => sided(side, KeyCode::KEY_LEFTMETA, KeyCode::KEY_RIGHTMETA),
+        Key::Fn => vec![KeyCode::KEY_FN],
+        Key::Named(name) => named_code(name).into_iter().collect(),
+    }
+}
+
+/// Whether a key code is a modifier, which is pressed on the way to a
+/// combination rather than on its own.
+pub(super) fn is_modifier(code: KeyCode) -> bool {
+    [
+        Key::Ctrl(Side::Any),
+        Key::Shift(Side::Any),
+        Key::Alt(Side::Any),
+        Key::Super(Side::Any),
+        Key::Fn,
+    ]
+    .into_iter()
+    .any(|key| key_codes(key).contains(&code))
+}
+
+/// Tracks which keys are down and reports shortcuts whose keys are exactly
+/// the keys held.
+#[derive(Default)]
+pub(super) struct KeyMatcher {
+    /// Binding ID to the accepted codes for each key of its shortcut
+    hotkeys: HashMap<String, Vec<Vec<KeyCode>>>,
+    pressed: HashSet<KeyCode>,
+    active: HashSet<String>,
+}
+
+impl KeyMatcher {
+    pub(super) fn insert(&mut self, binding_id: &str, keys: &[Key]) {
+        let codes = keys.iter().map(|key| key_codes(*key)).collect();
+        self.hotkeys.insert(binding_id.to_string(), codes);
+    }
+
+    pub(super) fn remove(&mut self, binding_id: &str) {
+        self.hotkeys.remove(binding_id);
+        self.active.remove(binding_id);
+    }
+
+    /// Update the held keys and return the bindings pressed (`true`) or
+    /// released (`false`) by this event.
+    pub(super) fn key_event(&mut self, code: KeyCode, is_down: bool) -> Vec<(String, bool)> {
+        let mut changes = Vec::new();
+        if is_down {
+            if !self.pressed.insert(code) {
+                return changes;
+            }
+            for (id, keys) in &self.hotkeys {
+                if !self.active.contains(id) && Self::matches(keys, &self.pressed) {
+                    self.active.insert(id.clone());
+                    changes.push((id.clone(), true));
+                }
+            }
+        } else {
+            self.pressed.remove(&code);
+            for (id, keys) in &self.hotkeys {
+                if self.active.contains(id) && keys.iter().any(|codes| codes.contains(&code)) {
+                    self.active.remove(id);
+                    changes.push((id.clone(), false));
+                }
+            }
+        }
+        changes
+    }
+
+    fn matches(keys: &[Vec<KeyCode>], pressed: &HashSet<KeyCode>) -> bool {
+        pressed.len() == keys.len()
+            && keys
+                .iter()
+                .all(|codes| codes.iter().any(|code| pressed.contains(code)))
+    }
+}
+
+pub(super) fn is_keyboard(device: &Device) -> bool {
+    device
+        .supported_keys()
+        .is_some_and(|keys| keys.contains(KeyCode::KEY_SPACE) && keys.contains(KeyCode::KEY_A))
+}
+
+/// Wait up to `timeout` for events on `device`. Fails once the device is gone.
+pub(super) fn wait_readable(device: &Device, timeout: Duration) -> io::Result<bool> {
+    let mut fd = libc::pollfd {
+        fd: device.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+    // SAFETY: `fd` is a valid pollfd for the duration of the call.
+    let ready = unsafe { libc::poll(&mut fd, 1, timeout) };
+    if ready < 0 {
+        let e = io::Error::last_os_error();
+        return match e.kind() {
+            io::ErrorKind::Interrupted => Ok(false),
+            _ => Err(e),
+        };
+    }
+    if fd.revents & (libc::POLLERR | libc::POLLHUP | libc::POLLNVAL) != 0 {
+        return Err(io::Error::other("device removed"));
+    }
+    Ok(ready > 0)
+}
+
+type KeyCallback = Arc<dyn Fn(KeyCode, bool) + Send + Sync>;
+
+/// Keeps the keyboards of [`watch_keyboards`] read. Dropping it stops the
+/// rescan thread and every reader.
+pub(super) struct KeyboardWatch {
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for KeyboardWatch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Read key events from every keyboard, including ones plugged in later, and
+/// pass them to `on_key` (`true` for press, `false` for release) until the
+/// returned watch is dropped. Fails when no keyboard can be read.
+pub(super) fn watch_keyboards(
+    on_key: impl Fn(KeyCode, bool) + Send + Sync + 'static,
+) -> Result<KeyboardWatch, String> {
+    let on_key: KeyCallback = Arc::new(on_key);
+    let opened = Arc::new(Mutex::new(HashSet::new()));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    if open_new_keyboards(&opened, &on_key, &stop) == 0 {
+        return Err(
+            "No keyboard in /dev/input can be read. Add your user to the 'input' group.".into(),
+        );
+    }
+
+    let rescan_stop = Arc::clone(&stop);
+    thread::spawn(move || loop {
+        thread::sleep(RESCAN_INTERVAL);
+        if rescan_stop.load(Ordering::SeqCst) {
+            debug!("Stopped looking for keyboards");
+            return;
+        }
+        open_new_keyboards(&opened, &on_key, &rescan_stop);
+    });
+    Ok(KeyboardWatch { stop })
+}
+
+/// Start a reader for each keyboard not read yet. Returns how many are read.
+fn open_new_keyboards(
+    opened: &Arc<Mutex<HashSet<PathBuf>>>,
+    on_key: &KeyCallback,
+    stop: &Arc<AtomicBool>,
+) -> usize {
+    for (path, device) in evdev::enumerate() {
+        // Text typed by our own virtual keyboard must not trigger shortcuts
+        if !is_keyboard(&device)
+            || device.name() == Some(crate::uinput_keyboard::DEVICE_NAME)
+            || !opened.lock().unwrap().insert(path.clone())
+        {
+            continue;
+        }
+        info!(
+            "Reading shortcuts from {} ({})",
+            device.name().unwrap_or("unnamed device"),
+            path.display()
+        );
+        let opened = Arc::clone(opened);
+        let on_key = Arc::clone(on_key);
+        let stop = Arc::clone(stop);
+        thread::spawn(move || {
+            read_keys(device, &on_key, &stop);
+            debug!("Stopped reading {}", path.display());
+            opened.lock().unwrap().remove(&path);
+        });
+    }
+    opened.lock().unwrap().len()
+}
+
+fn read_keys(mut device: Device, on_key: &KeyCallback, stop: &AtomicBool) {
+    while !stop.load(Ordering::SeqCst) {
+        match wait_readable(&device, READ_POLL_INTERVAL) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(_) => return,
+        }
+        let Ok(events) = device.fetch_events() else {
+            return;
+        };
+        for event in events {
+            // Value 2 is key repeat
+            if let EventSummary::Key(_, code, value @ (0 | 1)) = event.destructure() {
+                on_key(code, value == 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::wayland::parse_combo;
+    use super::*;
+
+    fn matcher(bindings: &[(&str, &str)]) -> KeyMatcher {
+        let mut matcher = KeyMatcher::default();
+        for (id, raw) in bindings {
+            matcher.insert(id, &parse_combo(raw).unwrap());
+        }
+        matcher
+    }
+
+    #[test]
+    fn every_named_key_has_a_code() {
+        for (_, name) in super::super::wayland::NAMED_KEYS {
+            assert!(named_code(name).is_some(), "{name} has no key code");
+        }
+    }
+
+    #[test]
+    fn matches_exact_combination_and_releases_on_any_key() {
+        let mut keys = matcher(&[("transcribe", "ctrl+space"), ("cancel", "ctrl_right")]);
+
+        assert!(keys.key_event(KeyCode::KEY_LEFTCTRL, true).is_empty());
+        assert_eq!(
+            keys.key_event(KeyCode::KEY_SPACE, true),
+            vec![("transcribe".to_string(), true)]
+        );
+        assert_eq!(
+            keys.key_event(KeyCode::KEY_LEFTCTRL, false),
+            vec![("transcribe".to_string(), false)]
+        );
+        assert!(keys.key_event(KeyCode::KEY_SPACE, false).is_empty());
+
+        // Only the right Ctrl key alone triggers "ctrl_right"
+        assert!(keys.key_event(KeyCode::KEY_LEFTCTRL, true).is_empty());
+        keys.key_event(KeyCode::KEY_LEFTCTRL, false);
+        assert_eq!(
+            keys.key_event(KeyCode::KEY_RIGHTCTRL, true),
+            vec![("cancel".to_string(), true)]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! Shared shortcut event handling logic
 //!
 //! This module contains the common logic for handling shortcut events,
-//! used by both the Tauri and handy-keys implementations.
+//! used by every implementation and by device triggers.
 
 use log::warn;
 use std::sync::Arc;
@@ -13,6 +13,8 @@ use crate::settings::get_settings;
 use crate::transcription_coordinator::is_transcribe_binding;
 use crate::TranscriptionCoordinator;
 
+use super::sequence;
+
 /// Handle a shortcut event from either implementation.
 ///
 /// This function contains the shared logic for:
@@ -20,6 +22,7 @@ use crate::TranscriptionCoordinator;
 /// - Handling the cancel binding (only fires when recording)
 /// - Handling push-to-talk mode (start on press, stop on release)
 /// - Handling toggle mode (toggle state on press only)
+/// - Turning the steps of a multi-step binding into a single press/release
 ///
 /// # Arguments
 /// * `app` - The Tauri app handle
@@ -34,10 +37,40 @@ pub fn handle_shortcut_event(
 ) {
     let settings = get_settings(app);
 
+    // Later steps of a sequence are registered as "<binding_id>#<step>".
+    let (binding_id, step) = sequence::parse_step_id(binding_id);
+    let is_pressed = match settings.bindings.get(binding_id) {
+        Some(binding) if sequence::is_sequence(&binding.current_binding) => {
+            match sequence::on_step_event(app, binding, step, is_pressed) {
+                Some(pressed) => pressed,
+                None => return,
+            }
+        }
+        // A step left over from a binding that is no longer a sequence
+        _ if step > 0 => return,
+        _ => is_pressed,
+    };
+
+    handle_binding_event(app, binding_id, hotkey_string, is_pressed);
+}
+
+/// Run the action of `binding_id` for a press or release that is already
+/// resolved, e.g. a completed sequence or a device trigger.
+pub(super) fn handle_binding_event(
+    app: &AppHandle,
+    binding_id: &str,
+    hotkey_string: &str,
+    is_pressed: bool,
+) {
     // Transcribe bindings are handled by the coordinator.
     if is_transcribe_binding(binding_id) {
         if let Some(coordinator) = app.try_state::<TranscriptionCoordinator>() {
-            coordinator.send_input(binding_id, hotkey_string, is_pressed, settings.activation_mode);
+            coordinator.send_input(
+                binding_id,
+                hotkey_string,
+                is_pressed,
+                get_settings(app).activation_mode_for(binding_id),
+            );
         } else {
             warn!("TranscriptionCoordinator is not initialized");
         }
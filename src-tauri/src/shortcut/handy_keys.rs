@@ -41,6 +41,7 @@ use tauri::{AppHandle, Emitter, Manager};
 use crate::settings::{self, get_settings, ShortcutBinding};
 
 use super::handler::handle_shortcut_event;
+use super::sequence;
 
 /// Commands that can be sent to the hotkey manager thread
 enum ManagerCommand {
@@ -123,6 +124,9 @@ impl HandyKeysState {
         let mut binding_to_hotkey: HashMap<String, HotkeyId> = HashMap::new();
         let mut hotkey_to_binding: HashMap<HotkeyId, (String, String)> = HashMap::new(); // (binding_id, hotkey_string)
 
+        // Listens to every key only while a sequence is in progress
+        let mut sequence_listener: Option<KeyboardListener> = None;
+
         loop {
             // Check for hotkey events (non-blocking)
             while let Some(event) = manager.try_recv() {
@@ -136,6 +140,8 @@ impl HandyKeysState {
                 }
             }
 
+            Self::watch_sequence_keys(&app, &mut sequence_listener);
+
             // Check for commands (non-blocking with timeout)
             match cmd_rx.recv_timeout(std::time::Duration::from_millis(10)) {
                 Ok(cmd) => match cmd {
@@ -183,6 +189,43 @@ impl HandyKeysState {
         info!("handy-keys manager thread stopped");
     }
 
+    /// Break off the sequences in progress when a key that is none of their
+    /// steps is pressed, e.g. `c` of `ctrl+c` between the taps of `"ctrl, ctrl"`.
+    fn watch_sequence_keys(app: &AppHandle, listener: &mut Option<KeyboardListener>) {
+        if !sequence::in_progress() {
+            *listener = None;
+            return;
+        }
+
+        if listener.is_none() {
+            match KeyboardListener::new() {
+                Ok(l) => *listener = Some(l),
+                Err(e) => {
+                    debug!("Failed to create keyboard listener for sequences: {}", e);
+                    return;
+                }
+            }
+        }
+        let Some(listener) = listener else {
+            return;
+        };
+
+        while let Some(event) = listener.try_recv() {
+            // Modifiers alone are part of the next step's combination
+            if !event.is_key_down || event.key.is_none() {
+                continue;
+            }
+            let pressed = event
+                .as_hotkey()
+                .map(|h| h.to_handy_string())
+                .unwrap_or_default();
+            sequence::on_key_down(app, |step| {
+                step.parse::<Hotkey>()
+                    .is_ok_and(|hotkey| hotkey.to_handy_string() == pressed)
+            });
+        }
+    }
+
     /// Register a hotkey
     fn do_register(
         manager: &HotkeyManager,
@@ -228,13 +271,19 @@ impl HandyKeysState {
 
     /// Register a shortcut binding
     pub fn register(&self, binding: &ShortcutBinding) -> Result<(), String> {
+        if binding.is_unbound() {
+            return Ok(());
+        }
+
         let (tx, rx) = mpsc::channel();
         self.command_sender
             .lock()
             .map_err(|_| "Failed to lock command_sender")?
             .send(ManagerCommand::Register {
                 binding_id: binding.id.clone(),
-                hotkey_string: binding.current_binding.clone(),
+                // Sequences register their first step; the others are
+                // registered while one is in progress.
+                hotkey_string: sequence::leader(&binding.current_binding).to_string(),
                 response: tx,
             })
             .map_err(|_| "Failed to send register command")?;
@@ -409,11 +458,16 @@ fn modifiers_to_strings(modifiers: handy_keys::Modifiers) -> Vec<String> {
 }
 
 /// Validate a shortcut string for the HandyKeys implementation.
-/// HandyKeys is more permissive: allows modifier-only combos and the fn key.
+/// HandyKeys is more permissive: allows modifier-only combos and the fn key,
+/// which makes double taps such as "fn, fn" possible.
 pub fn validate_shortcut(raw: &str) -> Result<(), String> {
     if raw.trim().is_empty() {
         return Err("Shortcut cannot be empty".into());
     }
+    sequence::validate(raw, validate_step)
+}
+
+fn validate_step(raw: &str) -> Result<(), String> {
     // HandyKeys accepts modifier-only, key-only, and modifier+key combos
     // Just verify the string is parseable
     raw.parse::<Hotkey>()
@@ -5,13 +5,23 @@
 //!
 //! - `tauri`: Uses Tauri's built-in global-shortcut plugin
 //! - `handy_keys`: Uses the handy-keys library for more control
+//! - `wayland`: Uses the GlobalShortcuts desktop portal, or `/dev/input`
+//!   when no portal provides it
+//!
+//! All of them support multi-step bindings such as double taps (see `sequence`).
 //!
 //! The active implementation is determined by the `keyboard_implementation`
-//! setting and can be changed at runtime.
+//! setting and can be changed at runtime. Mouse buttons and pedals bound as
+//! device triggers work alongside any of them (see `device_triggers`).
 
+pub mod device_triggers;
+#[cfg(target_os = "linux")]
+mod evdev;
 mod handler;
 pub mod handy_keys;
+mod sequence;
 mod tauri_impl;
+pub mod wayland;
 
 use log::{error, info, warn};
 use serde::Serialize;
@@ -20,8 +30,9 @@ use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_autostart::ManagerExt;
 
 use crate::settings::{
-    self, get_settings, ActivationMode, AppTheme, AutoSubmitKey, ClipboardHandling,
-    KeyboardImplementation, OverlayPosition, PasteMethod, ShortcutBinding, SoundTheme, TypingTool,
+    self, get_settings, ActivationMode, AppTheme, AutoSubmitKey, ClipboardHandling, DeviceTrigger,
+    KeyboardImplementation, LanguageRoute, OutputSink, OverlayPosition, PasteMethod,
+    ShortcutBinding, SoundTheme, SttFallbackStep, TypingTool,
 };
 use crate::tray;
 
@@ -47,10 +58,24 @@ pub fn init_shortcuts(app: &AppHandle) {
                 settings.keyboard_implementation = KeyboardImplementation::Tauri;
                 settings::write_settings(app, settings);
 
+                tauri_impl::init_shortcuts(app);
+            }
+        }
+        KeyboardImplementation::Wayland => {
+            if let Err(e) = wayland::init_shortcuts(app) {
+                error!("Failed to initialize Wayland shortcuts: {}", e);
+                warn!("Falling back to Tauri global shortcut implementation and saving fallback to settings");
+
+                let mut settings = settings::get_settings(app);
+                settings.keyboard_implementation = KeyboardImplementation::Tauri;
+                settings::write_settings(app, settings);
+
                 tauri_impl::init_shortcuts(app);
             }
         }
     }
+
+    device_triggers::reload(app);
 }
 
 /// Register the cancel shortcut (called when recording starts)
@@ -59,6 +84,7 @@ pub fn register_cancel_shortcut(app: &AppHandle) {
     match settings.keyboard_implementation {
         KeyboardImplementation::Tauri => tauri_impl::register_cancel_shortcut(app),
         KeyboardImplementation::HandyKeys => handy_keys::register_cancel_shortcut(app),
+        KeyboardImplementation::Wayland => wayland::register_cancel_shortcut(app),
     }
 }
 
@@ -68,6 +94,7 @@ pub fn unregister_cancel_shortcut(app: &AppHandle) {
     match settings.keyboard_implementation {
         KeyboardImplementation::Tauri => tauri_impl::unregister_cancel_shortcut(app),
         KeyboardImplementation::HandyKeys => handy_keys::unregister_cancel_shortcut(app),
+        KeyboardImplementation::Wayland => wayland::unregister_cancel_shortcut(app),
     }
 }
 
@@ -77,6 +104,7 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
     match settings.keyboard_implementation {
         KeyboardImplementation::Tauri => tauri_impl::register_shortcut(app, binding),
         KeyboardImplementation::HandyKeys => handy_keys::register_shortcut(app, binding),
+        KeyboardImplementation::Wayland => wayland::register_shortcut(app, binding),
     }
 }
 
@@ -86,6 +114,7 @@ pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<
     match settings.keyboard_implementation {
         KeyboardImplementation::Tauri => tauri_impl::unregister_shortcut(app, binding),
         KeyboardImplementation::HandyKeys => handy_keys::unregister_shortcut(app, binding),
+        KeyboardImplementation::Wayland => wayland::unregister_shortcut(app, binding),
     }
 }
 
@@ -107,8 +136,12 @@ pub fn change_binding(
     id: String,
     binding: String,
 ) -> Result<BindingResponse, String> {
-    // Reject empty bindings — every shortcut should have a value
-    if binding.trim().is_empty() {
+    // Reject empty bindings, except for actions that ship unbound
+    let may_be_unbound = settings::get_default_settings()
+        .bindings
+        .get(&id)
+        .is_some_and(|b| b.default_binding.is_empty());
+    if binding.trim().is_empty() && !may_be_unbound {
         return Err("Binding cannot be empty".to_string());
     }
 
@@ -162,10 +195,13 @@ pub fn change_binding(
     }
 
     // Validate the new shortcut for the current keyboard implementation
-    if let Err(e) = validate_shortcut_for_implementation(&binding, settings.keyboard_implementation)
-    {
-        warn!("change_binding validation error: {}", e);
-        return Err(e);
+    if !binding.trim().is_empty() {
+        if let Err(e) =
+            validate_shortcut_for_implementation(&app, &binding, settings.keyboard_implementation)
+        {
+            warn!("change_binding validation error: {}", e);
+            return Err(e);
+        }
     }
 
     // Create an updated binding
@@ -257,12 +293,16 @@ pub fn add_transcribe_binding(
         default_binding: binding_key.clone(),
         current_binding: binding_key.clone(),
         post_process_prompt_id: prompt_id,
+        activation_mode: None,
+        device_trigger: None,
+        output_sinks: Vec::new(),
+        skip_paste: false,
     };
 
     // Only validate and register if a non-empty key was provided.
     // An empty key means the binding is pending user recording.
     if !binding_key.trim().is_empty() {
-        validate_shortcut_for_implementation(&binding_key, settings.keyboard_implementation)?;
+        validate_shortcut_for_implementation(&app, &binding_key, settings.keyboard_implementation)?;
 
         if let Err(e) = register_shortcut(&app, new_binding.clone()) {
             let error_msg = format!("Failed to register shortcut: {}", e);
@@ -299,8 +339,15 @@ pub fn remove_transcribe_binding(app: AppHandle, id: String) -> Result<(), Strin
         let _ = unregister_shortcut(&app, binding);
     }
 
-    settings.bindings.remove(&id);
+    let had_trigger = settings
+        .bindings
+        .remove(&id)
+        .is_some_and(|binding| binding.device_trigger.is_some());
+    crate::output_sinks::stop_unused_commands(&settings);
     settings::write_settings(&app, settings);
+    if had_trigger {
+        device_triggers::reload(&app);
+    }
 
     Ok(())
 }
@@ -324,6 +371,72 @@ pub fn update_binding_prompt(
     }
 }
 
+/// Set or clear the activation mode override of a binding. `None` makes the
+/// binding follow the global activation mode again.
+#[tauri::command]
+#[specta::specta]
+pub fn update_binding_activation_mode(
+    app: AppHandle,
+    id: String,
+    mode: Option<ActivationMode>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    if let Some(binding) = settings.bindings.get_mut(&id) {
+        binding.activation_mode = mode;
+
+        settings::write_settings(&app, settings);
+        Ok(())
+    } else {
+        Err(format!("Binding '{}' not found", id))
+    }
+}
+
+/// Set or clear the mouse button or pedal that triggers a binding.
+#[tauri::command]
+#[specta::specta]
+pub fn update_binding_device_trigger(
+    app: AppHandle,
+    id: String,
+    trigger: Option<DeviceTrigger>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    if let Some(binding) = settings.bindings.get_mut(&id) {
+        binding.device_trigger = trigger;
+
+        settings::write_settings(&app, settings);
+        device_triggers::reload(&app);
+        Ok(())
+    } else {
+        Err(format!("Binding '{}' not found", id))
+    }
+}
+
+/// Set where a binding's transcriptions are sent and whether they're still
+/// pasted.
+#[tauri::command]
+#[specta::specta]
+pub fn update_binding_output_sinks(
+    app: AppHandle,
+    id: String,
+    sinks: Vec<OutputSink>,
+    skip_paste: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    if let Some(binding) = settings.bindings.get_mut(&id) {
+        binding.output_sinks = sinks;
+        binding.skip_paste = skip_paste;
+
+        crate::output_sinks::stop_unused_commands(&settings);
+        settings::write_settings(&app, settings);
+        Ok(())
+    } else {
+        Err(format!("Binding '{}' not found", id))
+    }
+}
+
 // ============================================================================
 // Keyboard Implementation Switching
 // ============================================================================
@@ -365,21 +478,27 @@ pub fn change_keyboard_implementation_setting(
 
     // Unregister all shortcuts from the current implementation
     unregister_all_shortcuts(&app, current_impl);
+    if current_impl == KeyboardImplementation::Wayland {
+        wayland::suspend(&app);
+    }
 
     // Update the setting
     let mut settings = settings::get_settings(&app);
     settings.keyboard_implementation = new_impl;
     settings::write_settings(&app, settings);
 
-    // Initialize new implementation if needed (HandyKeys needs state)
-    if new_impl == KeyboardImplementation::HandyKeys {
-        if initialize_handy_keys_with_rollback(&app)? {
-            // Shortcuts already registered during init
-            return Ok(ImplementationChangeResult {
-                success: true,
-                reset_bindings: vec![],
-            });
-        }
+    // Initialize new implementation if needed (HandyKeys and Wayland need state)
+    let initialized = match new_impl {
+        KeyboardImplementation::HandyKeys => initialize_handy_keys_with_rollback(&app)?,
+        KeyboardImplementation::Wayland => initialize_wayland_with_rollback(&app)?,
+        KeyboardImplementation::Tauri => false,
+    };
+    if initialized {
+        // Shortcuts already registered during init
+        return Ok(ImplementationChangeResult {
+            success: true,
+            reset_bindings: vec![],
+        });
     }
 
     // Register all shortcuts with new implementation, resetting invalid ones
@@ -411,6 +530,7 @@ pub fn get_keyboard_implementation(app: AppHandle) -> String {
     match settings.keyboard_implementation {
         KeyboardImplementation::Tauri => "tauri".to_string(),
         KeyboardImplementation::HandyKeys => "handy_keys".to_string(),
+        KeyboardImplementation::Wayland => "wayland".to_string(),
     }
 }
 
@@ -420,12 +540,14 @@ pub fn get_keyboard_implementation(app: AppHandle) -> String {
 
 /// Validate a shortcut for a specific implementation
 fn validate_shortcut_for_implementation(
+    app: &AppHandle,
     raw: &str,
     implementation: KeyboardImplementation,
 ) -> Result<(), String> {
     match implementation {
         KeyboardImplementation::Tauri => tauri_impl::validate_shortcut(raw),
         KeyboardImplementation::HandyKeys => handy_keys::validate_shortcut(raw),
+        KeyboardImplementation::Wayland => wayland::validate_shortcut_for(app, raw),
     }
 }
 
@@ -434,6 +556,7 @@ fn parse_keyboard_implementation(s: &str) -> KeyboardImplementation {
     match s {
         "tauri" => KeyboardImplementation::Tauri,
         "handy_keys" => KeyboardImplementation::HandyKeys,
+        "wayland" => KeyboardImplementation::Wayland,
         other => {
             warn!(
                 "Invalid keyboard implementation '{}', defaulting to tauri",
@@ -457,6 +580,7 @@ fn unregister_all_shortcuts(app: &AppHandle, implementation: KeyboardImplementat
         let result = match implementation {
             KeyboardImplementation::Tauri => tauri_impl::unregister_shortcut(app, binding),
             KeyboardImplementation::HandyKeys => handy_keys::unregister_shortcut(app, binding),
+            KeyboardImplementation::Wayland => wayland::unregister_shortcut(app, binding),
         };
 
         if let Err(e) = result {
@@ -486,10 +610,13 @@ fn register_all_shortcuts_for_implementation(
         }
 
         let mut binding = current_settings.bindings.get(id).cloned().unwrap();
+        if binding.is_unbound() {
+            continue;
+        }
 
         // Validate the shortcut for the target implementation
         if let Err(e) =
-            validate_shortcut_for_implementation(&binding.current_binding, implementation)
+            validate_shortcut_for_implementation(app, &binding.current_binding, implementation)
         {
             // Only reset default bindings to their defaults; skip invalid custom bindings
             if let Some(default_binding) = default_bindings.get(id) {
@@ -515,6 +642,7 @@ fn register_all_shortcuts_for_implementation(
         let result = match implementation {
             KeyboardImplementation::Tauri => tauri_impl::register_shortcut(app, binding),
             KeyboardImplementation::HandyKeys => handy_keys::register_shortcut(app, binding),
+            KeyboardImplementation::Wayland => wayland::register_shortcut(app, binding),
         };
 
         if let Err(e) = result {
@@ -556,6 +684,37 @@ fn initialize_handy_keys_with_rollback(app: &AppHandle) -> Result<bool, String>
     Ok(true)
 }
 
+/// Initialize Wayland shortcuts if not already initialized, with rollback on failure
+fn initialize_wayland_with_rollback(app: &AppHandle) -> Result<bool, String> {
+    #[cfg(target_os = "linux")]
+    let initialized = app.try_state::<wayland::WaylandState>().is_some();
+    #[cfg(not(target_os = "linux"))]
+    let initialized = false;
+
+    // Already initialized: read the input devices again, then the caller
+    // registers the shortcuts
+    let result = if initialized {
+        wayland::resume(app)
+    } else {
+        wayland::init_shortcuts(app)
+    };
+    if let Err(e) = result {
+        error!("Failed to initialize Wayland shortcuts: {}", e);
+        // Rollback to Tauri
+        let mut settings = settings::get_settings(app);
+        settings.keyboard_implementation = KeyboardImplementation::Tauri;
+        settings::write_settings(app, settings);
+        tauri_impl::init_shortcuts(app);
+        return Err(format!(
+            "Failed to initialize Wayland shortcuts: {}. Reverted to Tauri.",
+            e
+        ));
+    }
+
+    // init_shortcuts already registered shortcuts
+    Ok(!initialized)
+}
+
 // ============================================================================
 // General Settings Commands
 // ============================================================================
@@ -617,6 +776,30 @@ pub fn change_selected_language_setting(app: AppHandle, language: String) -> Res
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_language_routing_setting(
+    app: AppHandle,
+    enabled: bool,
+    routes: Vec<LanguageRoute>,
+    language_id_model: Option<String>,
+    language_id_seconds: u32,
+) -> Result<(), String> {
+    if routes
+        .iter()
+        .any(|route| route.language.trim().is_empty() || route.provider_id.trim().is_empty())
+    {
+        return Err("Each language route needs a language and a provider".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.language_routing_enabled = enabled;
+    settings.language_routes = routes;
+    settings.language_id_model = language_id_model.filter(|id| !id.is_empty());
+    settings.language_id_seconds = language_id_seconds.clamp(1, 10);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_overlay_position_setting(app: AppHandle, position: String) -> Result<(), String> {
@@ -639,6 +822,15 @@ pub fn change_overlay_position_setting(app: AppHandle, position: String) -> Resu
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_error_notifications_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.error_notifications_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_debug_mode_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -754,6 +946,7 @@ pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(),
         "shift_insert" => PasteMethod::ShiftInsert,
         "ctrl_shift_v" => PasteMethod::CtrlShiftV,
         "external_script" => PasteMethod::ExternalScript,
+        "input_method" => PasteMethod::InputMethod,
         other => {
             warn!("Invalid paste method '{}', defaulting to ctrl_v", other);
             PasteMethod::CtrlV
@@ -761,6 +954,7 @@ pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(),
     };
     settings.paste_method = parsed;
     settings::write_settings(&app, settings);
+    crate::input_method::init(&app);
     Ok(())
 }
 
@@ -788,6 +982,7 @@ pub fn change_typing_tool_setting(app: AppHandle, tool: String) -> Result<(), St
         "dotool" => TypingTool::Dotool,
         "ydotool" => TypingTool::Ydotool,
         "xdotool" => TypingTool::Xdotool,
+        "uinput" => TypingTool::Uinput,
         other => {
             warn!("Invalid typing tool '{}', defaulting to auto", other);
             TypingTool::Auto
@@ -830,6 +1025,15 @@ pub fn change_clipboard_handling_setting(app: AppHandle, handling: String) -> Re
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_clipboard_restore_delay_setting(app: AppHandle, delay_ms: u64) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.clipboard_restore_delay_ms = delay_ms.clamp(50, 1000);
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_auto_submit_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -1005,3 +1209,24 @@ pub fn change_stt_realtime_enabled_setting(
     settings::write_settings(&app, settings);
     Ok(())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_stt_fallback_setting(
+    app: AppHandle,
+    chain: Vec<SttFallbackStep>,
+    request_timeout_secs: u32,
+    max_retries: u32,
+    retry_backoff_ms: u64,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    for step in &chain {
+        validate_stt_provider_exists(&settings, &step.provider_id)?;
+    }
+    settings.stt_fallback_chain = chain;
+    settings.stt_request_timeout_secs = request_timeout_secs;
+    settings.stt_max_retries = max_retries;
+    settings.stt_retry_backoff_ms = retry_backoff_ms;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
@@ -0,0 +1,615 @@
+//! Multi-step shortcuts
+//!
+//! A binding can hold several key combinations separated by commas, pressed
+//! one after the other: `"ctrl+space, p"` is a leader sequence and
+//! `"ctrl_right, ctrl_right"` or `"fn, fn"` a double tap. Both backends only
+//! register the first step permanently; the remaining steps are registered
+//! while a sequence is in progress, so keys such as `p` are not taken away
+//! from other applications.
+//!
+//! The binding fires when its last step is pressed and is released together
+//! with that step, so hold and toggle activation work as for plain shortcuts.
+
+use log::{debug, warn};
+use once_cell::sync::{Lazy, OnceCell};
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::settings::ShortcutBinding;
+
+/// Separates the steps of a sequence in a binding string.
+pub const STEP_SEPARATOR: char = ',';
+
+/// Separates a binding ID from the step index in the ID under which a later
+/// step is registered, e.g. `transcribe#1`.
+const STEP_ID_SEPARATOR: char = '#';
+
+/// Longest supported sequence.
+const MAX_STEPS: usize = 3;
+
+/// Time allowed between the steps of a leader sequence.
+const STEP_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Time allowed between the two presses of a double tap.
+const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(400);
+
+/// Presses of a step that was not released within this time of the previous
+/// press are key repeat.
+const REPEAT_WINDOW: Duration = Duration::from_millis(1000);
+
+static TRACKER: Lazy<Mutex<SequenceTracker>> = Lazy::new(Default::default);
+
+static WORKER: OnceCell<Sender<Job>> = OnceCell::new();
+
+/// Steps of a binding string, trimmed.
+pub fn steps(raw: &str) -> Vec<String> {
+    raw.split(STEP_SEPARATOR)
+        .map(|step| step.trim().to_string())
+        .collect()
+}
+
+/// Whether a binding string has more than one step.
+pub fn is_sequence(raw: &str) -> bool {
+    raw.contains(STEP_SEPARATOR)
+}
+
+/// The step the backends register permanently.
+pub fn leader(raw: &str) -> &str {
+    raw.split(STEP_SEPARATOR).next().unwrap_or(raw).trim()
+}
+
+/// Validate every step of a binding string with the backend's own rules.
+pub fn validate(raw: &str, validate_step: fn(&str) -> Result<(), String>) -> Result<(), String> {
+    if !is_sequence(raw) {
+        return validate_step(raw);
+    }
+
+    let steps = steps(raw);
+    if steps.len() > MAX_STEPS {
+        return Err(format!("Sequences can have at most {} steps", MAX_STEPS));
+    }
+    for (index, step) in steps.iter().enumerate() {
+        if step.is_empty() {
+            return Err(format!("Step {} of the sequence is empty", index + 1));
+        }
+        validate_step(step).map_err(|e| format!("Step {}: {}", index + 1, e))?;
+    }
+    Ok(())
+}
+
+fn step_id(binding_id: &str, index: usize) -> String {
+    format!("{}{}{}", binding_id, STEP_ID_SEPARATOR, index)
+}
+
+/// Split a registered ID into the binding ID and the step index (0 for the
+/// leader, which is registered under the plain binding ID).
+pub(super) fn parse_step_id(id: &str) -> (&str, usize) {
+    id.rsplit_once(STEP_ID_SEPARATOR)
+        .and_then(|(binding_id, index)| Some((binding_id, index.parse().ok()?)))
+        .unwrap_or((id, 0))
+}
+
+/// Feed a key event of a sequence binding into the tracker. Returns the
+/// press state to forward for the binding, or `None` while the sequence is
+/// incomplete or the event does not belong to it.
+pub(super) fn on_step_event(
+    app: &AppHandle,
+    binding: &ShortcutBinding,
+    step: usize,
+    is_pressed: bool,
+) -> Option<bool> {
+    let steps = steps(&binding.current_binding);
+    if step >= steps.len() {
+        return None;
+    }
+
+    let outcome =
+        TRACKER
+            .lock()
+            .unwrap()
+            .on_step(&binding.id, &steps, step, is_pressed, Instant::now());
+    match outcome {
+        StepOutcome::Started { generation, next } => {
+            debug!("Sequence '{}' started", binding.id);
+            arm(app, binding, &steps, generation);
+            expire_after(app, &binding.id, generation, timeout_before(&steps, next));
+            None
+        }
+        StepOutcome::Advanced { generation, next } => {
+            expire_after(app, &binding.id, generation, timeout_before(&steps, next));
+            None
+        }
+        StepOutcome::Fired => Some(true),
+        StepOutcome::Released => {
+            disarm(app, &binding.id);
+            Some(false)
+        }
+        StepOutcome::Broken => {
+            debug!("Sequence '{}' broken off", binding.id);
+            disarm(app, &binding.id);
+            None
+        }
+        StepOutcome::Ignored => None,
+    }
+}
+
+/// Whether any sequence is in progress.
+pub(super) fn in_progress() -> bool {
+    !TRACKER.lock().unwrap().progress.is_empty()
+}
+
+/// Break off the sequences in progress that the pressed key is no step of,
+/// so typing between two taps of `ctrl` does not complete `"ctrl, ctrl"`.
+/// `is_step` tells whether a step string describes the pressed key. Only
+/// backends that see every key press call this; the global-shortcut plugin
+/// reports registered shortcuts alone.
+pub(super) fn on_key_down(app: &AppHandle, is_step: impl Fn(&str) -> bool) {
+    let broken = TRACKER.lock().unwrap().break_unless(is_step);
+    for binding_id in broken {
+        debug!("Sequence '{}' broken off by another key", binding_id);
+        disarm(app, &binding_id);
+    }
+}
+
+fn timeout_before(steps: &[String], next: usize) -> Duration {
+    if steps[next] == steps[next - 1] {
+        DOUBLE_TAP_WINDOW
+    } else {
+        STEP_TIMEOUT
+    }
+}
+
+fn expire_after(app: &AppHandle, binding_id: &str, generation: u64, timeout: Duration) {
+    send_job(
+        app,
+        Job::Expire {
+            binding_id: binding_id.to_string(),
+            generation,
+            at: Instant::now() + timeout,
+        },
+    );
+}
+
+/// Register the later steps of `binding` that differ from its leader.
+fn arm(app: &AppHandle, binding: &ShortcutBinding, steps: &[String], generation: u64) {
+    send_job(
+        app,
+        Job::Arm {
+            binding: binding.clone(),
+            steps: steps.to_vec(),
+            generation,
+        },
+    );
+}
+
+fn disarm(app: &AppHandle, binding_id: &str) {
+    send_job(app, Job::Disarm(binding_id.to_string()));
+}
+
+/// Work the sequences hand to the worker thread.
+enum Job {
+    Arm {
+        binding: ShortcutBinding,
+        steps: Vec<String>,
+        generation: u64,
+    },
+    Disarm(String),
+    Expire {
+        binding_id: String,
+        generation: u64,
+        at: Instant,
+    },
+}
+
+/// Registrations and timeouts run on one worker thread: the backends deliver
+/// key events on the thread that also serves registrations, and one thread
+/// keeps arming and disarming of a binding in order.
+fn send_job(app: &AppHandle, job: Job) {
+    let worker = WORKER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        let app = app.clone();
+        thread::spawn(move || run_worker(&app, rx));
+        tx
+    });
+    if worker.send(job).is_err() {
+        warn!("Sequence worker is gone");
+    }
+}
+
+fn run_worker(app: &AppHandle, jobs: Receiver<Job>) {
+    // Later steps registered for the sequences in progress, by binding ID
+    let mut armed: HashMap<String, Vec<ShortcutBinding>> = HashMap::new();
+    // Pending timeouts: binding ID, generation and deadline
+    let mut deadlines: Vec<(String, u64, Instant)> = Vec::new();
+
+    loop {
+        let next_deadline = deadlines.iter().map(|(_, _, at)| *at).min();
+        let job = match next_deadline {
+            Some(at) => match jobs.recv_timeout(at.saturating_duration_since(Instant::now())) {
+                Ok(job) => Some(job),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            },
+            None => match jobs.recv() {
+                Ok(job) => Some(job),
+                Err(_) => return,
+            },
+        };
+
+        match job {
+            Some(Job::Arm {
+                binding,
+                steps,
+                generation,
+            }) => {
+                let step_bindings = armed.entry(binding.id.clone()).or_default();
+                unregister_all(app, step_bindings);
+                if TRACKER.lock().unwrap().is_current(&binding.id, generation) {
+                    register_steps(app, &binding, &steps, step_bindings);
+                }
+            }
+            Some(Job::Disarm(binding_id)) => unregister_armed(app, &mut armed, &binding_id),
+            Some(Job::Expire {
+                binding_id,
+                generation,
+                at,
+            }) => deadlines.push((binding_id, generation, at)),
+            None => {}
+        }
+
+        let now = Instant::now();
+        let (due, pending) = deadlines.into_iter().partition(|(_, _, at)| *at <= now);
+        deadlines = pending;
+        for (binding_id, generation, _) in due {
+            if TRACKER.lock().unwrap().expire(&binding_id, generation) {
+                debug!("Sequence '{}' timed out", binding_id);
+                unregister_armed(app, &mut armed, &binding_id);
+            }
+        }
+    }
+}
+
+fn register_steps(
+    app: &AppHandle,
+    binding: &ShortcutBinding,
+    steps: &[String],
+    step_bindings: &mut Vec<ShortcutBinding>,
+) {
+    for (index, step) in steps.iter().enumerate().skip(1) {
+        if *step == steps[0] || steps[1..index].contains(step) {
+            continue;
+        }
+        let step_binding = ShortcutBinding {
+            id: step_id(&binding.id, index),
+            current_binding: step.clone(),
+            ..binding.clone()
+        };
+        match super::register_shortcut(app, step_binding.clone()) {
+            Ok(()) => step_bindings.push(step_binding),
+            Err(e) => warn!(
+                "Failed to register step '{}' of '{}': {}",
+                step, binding.id, e
+            ),
+        }
+    }
+}
+
+fn unregister_armed(
+    app: &AppHandle,
+    armed: &mut HashMap<String, Vec<ShortcutBinding>>,
+    binding_id: &str,
+) {
+    if let Some(mut step_bindings) = armed.remove(binding_id) {
+        unregister_all(app, &mut step_bindings);
+    }
+}
+
+fn unregister_all(app: &AppHandle, armed: &mut Vec<ShortcutBinding>) {
+    for step_binding in armed.drain(..) {
+        let _ = super::unregister_shortcut(app, step_binding);
+    }
+}
+
+/// What a key event means for the sequence of a binding.
+#[derive(Debug, PartialEq, Eq)]
+enum StepOutcome {
+    /// The first step was pressed; `next` is the step now expected.
+    Started {
+        generation: u64,
+        next: usize,
+    },
+    /// A middle step was pressed.
+    Advanced {
+        generation: u64,
+        next: usize,
+    },
+    /// The last step was pressed.
+    Fired,
+    /// The last step was released after the binding fired.
+    Released,
+    /// A wrong step ended the sequence in progress.
+    Broken,
+    Ignored,
+}
+
+struct Progress {
+    steps: Vec<String>,
+    next: usize,
+    /// The next step only counts once the previous one was released.
+    released: bool,
+    generation: u64,
+}
+
+/// Sequences of all bindings, tracked separately so bindings that share a
+/// leader (`"ctrl+space, p"` and `"ctrl+space, q"`) progress side by side.
+#[derive(Default)]
+struct SequenceTracker {
+    /// Sequences in progress by binding ID
+    progress: HashMap<String, Progress>,
+    /// Bindings that fired and wait for their last step to be released
+    fired: HashSet<String>,
+    /// Steps held down by binding ID and step, with the last time they were
+    /// reported pressed
+    held: HashMap<(String, String), Instant>,
+    generation: u64,
+}
+
+impl SequenceTracker {
+    fn on_step(
+        &mut self,
+        binding_id: &str,
+        steps: &[String],
+        step: usize,
+        is_pressed: bool,
+        now: Instant,
+    ) -> StepOutcome {
+        let key = &steps[step];
+        let held_key = (binding_id.to_string(), key.clone());
+
+        if !is_pressed {
+            self.held.remove(&held_key);
+            if steps.last() == Some(key) && self.fired.remove(binding_id) {
+                return StepOutcome::Released;
+            }
+            if let Some(p) = self.progress.get_mut(binding_id) {
+                if steps.get(p.next - 1) == Some(key) {
+                    p.released = true;
+                }
+            }
+            return StepOutcome::Ignored;
+        }
+
+        // Key repeat neither advances nor restarts a sequence. A press long
+        // after the previous one counts again, in case its release was lost.
+        if let Some(last) = self.held.insert(held_key, now) {
+            if now.duration_since(last) < REPEAT_WINDOW {
+                return StepOutcome::Ignored;
+            }
+        }
+
+        if let Some(p) = self
+            .progress
+            .get_mut(binding_id)
+            .filter(|p| p.released && steps.get(p.next) == Some(key))
+        {
+            p.next += 1;
+            if p.next < steps.len() {
+                self.generation += 1;
+                p.generation = self.generation;
+                p.released = false;
+                return StepOutcome::Advanced {
+                    generation: self.generation,
+                    next: p.next,
+                };
+            }
+            self.progress.remove(binding_id);
+            self.fired.insert(binding_id.to_string());
+            return StepOutcome::Fired;
+        }
+
+        if *key == steps[0] {
+            self.generation += 1;
+            self.progress.insert(
+                binding_id.to_string(),
+                Progress {
+                    steps: steps.to_vec(),
+                    next: 1,
+                    released: false,
+                    generation: self.generation,
+                },
+            );
+            return StepOutcome::Started {
+                generation: self.generation,
+                next: 1,
+            };
+        }
+
+        if self.progress.remove(binding_id).is_some() {
+            return StepOutcome::Broken;
+        }
+        StepOutcome::Ignored
+    }
+
+    /// Drop the sequences in progress that have no step matching
+    /// `is_step`. Returns their binding IDs.
+    fn break_unless(&mut self, is_step: impl Fn(&str) -> bool) -> Vec<String> {
+        let broken: Vec<String> = self
+            .progress
+            .iter()
+            .filter(|(_, p)| !p.steps.iter().any(|step| is_step(step)))
+            .map(|(binding_id, _)| binding_id.clone())
+            .collect();
+        for binding_id in &broken {
+            self.progress.remove(binding_id);
+        }
+        broken
+    }
+
+    fn is_current(&self, binding_id: &str, generation: u64) -> bool {
+        self.progress
+            .get(binding_id)
+            .is_some_and(|p| p.generation == generation)
+    }
+
+    /// Drop the sequence of `binding_id` if it has not advanced since
+    /// `generation`. Returns whether it was dropped.
+    fn expire(&mut self, binding_id: &str, generation: u64) -> bool {
+        if self.is_current(binding_id, generation) {
+            self.progress.remove(binding_id);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(
+        tracker: &mut SequenceTracker,
+        steps: &[String],
+        step: usize,
+        is_pressed: bool,
+    ) -> StepOutcome {
+        tracker.on_step("b", steps, step, is_pressed, Instant::now())
+    }
+
+    fn tap(tracker: &mut SequenceTracker, steps: &[String], step: usize) -> StepOutcome {
+        let outcome = press(tracker, steps, step, true);
+        press(tracker, steps, step, false);
+        outcome
+    }
+
+    #[test]
+    fn validates_each_step() {
+        fn no_fn(step: &str) -> Result<(), String> {
+            if step == "fn" {
+                Err("unsupported".into())
+            } else {
+                Ok(())
+            }
+        }
+
+        assert!(validate("ctrl+space, p", no_fn).is_ok());
+        assert_eq!(
+            validate("ctrl, fn", no_fn),
+            Err("Step 2: unsupported".into())
+        );
+        assert!(validate("ctrl+space, ", no_fn).is_err());
+        assert!(validate("a, b, c, d", no_fn).is_err());
+        assert_eq!(leader("ctrl+space, p"), "ctrl+space");
+        assert_eq!(
+            parse_step_id("transcribe_custom_1#2"),
+            ("transcribe_custom_1", 2)
+        );
+        assert_eq!(parse_step_id("transcribe"), ("transcribe", 0));
+    }
+
+    #[test]
+    fn double_tap_fires_on_second_press_and_releases_with_it() {
+        let steps = super::steps("ctrl, ctrl");
+        let mut tracker = SequenceTracker::default();
+
+        assert!(matches!(
+            press(&mut tracker, &steps, 0, true),
+            StepOutcome::Started { next: 1, .. }
+        ));
+        // Key repeat before the release does not count as a second tap
+        assert_eq!(press(&mut tracker, &steps, 0, true), StepOutcome::Ignored);
+        assert_eq!(press(&mut tracker, &steps, 0, false), StepOutcome::Ignored);
+        assert_eq!(press(&mut tracker, &steps, 0, true), StepOutcome::Fired);
+        // Nor does key repeat while the binding is held start a new one
+        assert_eq!(press(&mut tracker, &steps, 0, true), StepOutcome::Ignored);
+        assert_eq!(press(&mut tracker, &steps, 0, false), StepOutcome::Released);
+    }
+
+    #[test]
+    fn a_press_after_a_lost_release_counts() {
+        let steps = super::steps("ctrl, ctrl");
+        let mut tracker = SequenceTracker::default();
+        let start = Instant::now();
+
+        tracker.on_step("b", &steps, 0, true, start);
+        assert!(matches!(
+            tracker.on_step("b", &steps, 0, true, start + REPEAT_WINDOW),
+            StepOutcome::Started { .. }
+        ));
+    }
+
+    #[test]
+    fn leader_sequence_and_wrong_step() {
+        let steps = super::steps("ctrl+space, p");
+        let mut tracker = SequenceTracker::default();
+
+        assert!(matches!(
+            tap(&mut tracker, &steps, 0),
+            StepOutcome::Started { .. }
+        ));
+        assert_eq!(tap(&mut tracker, &steps, 1), StepOutcome::Fired);
+
+        let steps = super::steps("ctrl+space, p, q");
+        tap(&mut tracker, &steps, 0);
+        assert!(matches!(
+            tap(&mut tracker, &steps, 1),
+            StepOutcome::Advanced { next: 2, .. }
+        ));
+        assert_eq!(tap(&mut tracker, &steps, 1), StepOutcome::Broken);
+        assert_eq!(tap(&mut tracker, &steps, 2), StepOutcome::Ignored);
+    }
+
+    #[test]
+    fn expired_sequence_starts_over() {
+        let steps = super::steps("ctrl, ctrl");
+        let mut tracker = SequenceTracker::default();
+
+        let StepOutcome::Started { generation, .. } = tap(&mut tracker, &steps, 0) else {
+            panic!("sequence did not start");
+        };
+        assert!(tracker.expire("b", generation));
+        assert!(!tracker.expire("b", generation));
+        assert!(matches!(
+            tap(&mut tracker, &steps, 0),
+            StepOutcome::Started { .. }
+        ));
+    }
+
+    #[test]
+    fn bindings_sharing_a_leader_progress_side_by_side() {
+        let p = super::steps("ctrl+space, p");
+        let q = super::steps("ctrl+space, q");
+        let mut tracker = SequenceTracker::default();
+        let now = Instant::now();
+
+        for (binding_id, steps) in [("p", &p), ("q", &q)] {
+            assert!(matches!(
+                tracker.on_step(binding_id, steps, 0, true, now),
+                StepOutcome::Started { .. }
+            ));
+        }
+        for (binding_id, steps) in [("p", &p), ("q", &q)] {
+            tracker.on_step(binding_id, steps, 0, false, now);
+        }
+        assert_eq!(tracker.on_step("q", &q, 1, true, now), StepOutcome::Fired);
+        assert!(tracker.progress.contains_key("p"));
+    }
+
+    #[test]
+    fn other_keys_break_off_sequences() {
+        let steps = super::steps("ctrl, ctrl");
+        let mut tracker = SequenceTracker::default();
+
+        tap(&mut tracker, &steps, 0);
+        assert!(tracker.break_unless(|step| step == "ctrl").is_empty());
+        assert_eq!(tracker.break_unless(|step| step == "ctrl+c"), ["b"]);
+        assert!(matches!(
+            tap(&mut tracker, &steps, 0),
+            StepOutcome::Started { .. }
+        ));
+    }
+}
@@ -4,12 +4,20 @@
 //! global-shortcut plugin.
 
 use log::{error, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 use crate::settings::{self, get_settings, ShortcutBinding};
 
 use super::handler::handle_shortcut_event;
+use super::sequence;
+
+/// Bindings registered on each shortcut, with whether they may share it.
+/// A shortcut stays registered until its last binding is unregistered.
+static REGISTERED: Lazy<Mutex<HashMap<String, Vec<(String, bool)>>>> = Lazy::new(Default::default);
 
 /// Initialize shortcuts using Tauri's global-shortcut plugin
 pub fn init_shortcuts(app: &AppHandle) {
@@ -28,12 +36,16 @@ pub fn init_shortcuts(app: &AppHandle) {
 }
 
 /// Validate a shortcut string for the Tauri global-shortcut implementation.
-/// Tauri requires at least one non-modifier key and doesn't support the fn key.
+/// Tauri requires at least one non-modifier key and doesn't support the fn key,
+/// in every step of a sequence.
 pub fn validate_shortcut(raw: &str) -> Result<(), String> {
     if raw.trim().is_empty() {
         return Err("Shortcut cannot be empty".into());
     }
+    sequence::validate(raw, validate_step)
+}
 
+fn validate_step(raw: &str) -> Result<(), String> {
     let modifiers = [
         "ctrl", "control", "shift", "alt", "option", "meta", "command", "cmd", "super", "win",
         "windows",
@@ -59,6 +71,10 @@ pub fn validate_shortcut(raw: &str) -> Result<(), String> {
 
 /// Register a shortcut using Tauri's global-shortcut plugin
 pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    if binding.is_unbound() {
+        return Ok(());
+    }
+
     // Validate for Tauri requirements
     if let Err(e) = validate_shortcut(&binding.current_binding) {
         warn!(
@@ -68,8 +84,9 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
         return Err(e);
     }
 
-    // Parse shortcut and return error if it fails
-    let shortcut = match binding.current_binding.parse::<Shortcut>() {
+    // Parse shortcut and return error if it fails. Sequences register
+    // their first step; the others are registered while one is in progress.
+    let shortcut = match sequence::leader(&binding.current_binding).parse::<Shortcut>() {
         Ok(s) => s,
         Err(e) => {
             let error_msg = format!(
@@ -81,30 +98,45 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
         }
     };
 
-    // Prevent duplicate registrations that would silently shadow one another
-    if app.global_shortcut().is_registered(shortcut) {
-        let error_msg = format!("Shortcut '{}' is already in use", binding.current_binding);
-        warn!("register_tauri_shortcut duplicate error: {}", error_msg);
-        return Err(error_msg);
-    }
+    // Sequences share their first step with other sequences, and a later
+    // step can be another binding's shortcut. Only two plain shortcuts on the
+    // same keys would shadow one another.
+    let shortcut_string = shortcut.into_string();
+    {
+        let mut registered = REGISTERED.lock().unwrap();
+        if let Some(bindings) = registered.get_mut(&shortcut_string) {
+            if !can_share(&binding) && bindings.iter().any(|(_, shared)| !shared) {
+                let error_msg = format!("Shortcut '{}' is already in use", binding.current_binding);
+                warn!("register_tauri_shortcut duplicate error: {}", error_msg);
+                return Err(error_msg);
+            }
+            bindings.push((binding.id.clone(), can_share(&binding)));
+            return Ok(());
+        }
 
-    // Clone binding.id for use in the closure
-    let binding_id_for_closure = binding.id.clone();
+        // Prevent duplicate registrations that would silently shadow one another
+        if app.global_shortcut().is_registered(shortcut) {
+            let error_msg = format!("Shortcut '{}' is already in use", binding.current_binding);
+            warn!("register_tauri_shortcut duplicate error: {}", error_msg);
+            return Err(error_msg);
+        }
+        registered.insert(
+            shortcut_string.clone(),
+            vec![(binding.id.clone(), can_share(&binding))],
+        );
+    }
 
     app.global_shortcut()
         .on_shortcut(shortcut, move |app_handle, scut, event| {
             if scut == &shortcut {
-                let shortcut_string = scut.into_string();
                 let is_pressed = event.state == ShortcutState::Pressed;
-                handle_shortcut_event(
-                    app_handle,
-                    &binding_id_for_closure,
-                    &shortcut_string,
-                    is_pressed,
-                );
+                for binding_id in bindings_on(&shortcut_string) {
+                    handle_shortcut_event(app_handle, &binding_id, &shortcut_string, is_pressed);
+                }
             }
         })
         .map_err(|e| {
+            REGISTERED.lock().unwrap().remove(&shortcut.into_string());
             let error_msg = format!(
                 "Couldn't register shortcut '{}': {}",
                 binding.current_binding, e
@@ -116,9 +148,28 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
     Ok(())
 }
 
+/// Whether a binding may share its shortcut with others: the sequence
+/// tracker tells sequences and their later steps apart.
+fn can_share(binding: &ShortcutBinding) -> bool {
+    sequence::is_sequence(&binding.current_binding) || sequence::parse_step_id(&binding.id).1 > 0
+}
+
+fn bindings_on(shortcut_string: &str) -> Vec<String> {
+    REGISTERED
+        .lock()
+        .unwrap()
+        .get(shortcut_string)
+        .map(|bindings| bindings.iter().map(|(id, _)| id.clone()).collect())
+        .unwrap_or_default()
+}
+
 /// Unregister a shortcut from Tauri's global-shortcut plugin
 pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
-    let shortcut = match binding.current_binding.parse::<Shortcut>() {
+    if binding.is_unbound() {
+        return Ok(());
+    }
+
+    let shortcut = match sequence::leader(&binding.current_binding).parse::<Shortcut>() {
         Ok(s) => s,
         Err(e) => {
             let error_msg = format!(
@@ -130,6 +181,18 @@ pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<
         }
     };
 
+    {
+        let mut registered = REGISTERED.lock().unwrap();
+        let shortcut_string = shortcut.into_string();
+        if let Some(bindings) = registered.get_mut(&shortcut_string) {
+            bindings.retain(|(id, _)| *id != binding.id);
+            if !bindings.is_empty() {
+                return Ok(());
+            }
+            registered.remove(&shortcut_string);
+        }
+    }
+
     app.global_shortcut().unregister(shortcut).map_err(|e| {
         let error_msg = format!(
             "Failed to unregister shortcut '{}': {}",
@@ -0,0 +1,755 @@
+//! Wayland shortcut implementation
+//!
+//! Wayland compositors don't let clients grab keys globally, so neither
+//! Tauri's global-shortcut plugin nor handy-keys see shortcuts there. This
+//! implementation binds them through the `org.freedesktop.portal.GlobalShortcuts`
+//! desktop portal, where the compositor asks the user to confirm them once
+//! and reports activation and deactivation. When no portal implements that
+//! interface, it falls back to reading key events from `/dev/input` (see
+//! `evdev`), which also supports modifier-only and multi-step shortcuts.
+
+use tauri::AppHandle;
+
+use crate::settings::ShortcutBinding;
+
+use super::sequence;
+
+/// Which side of the keyboard a modifier must be on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Any,
+    Left,
+    Right,
+}
+
+/// One key of a shortcut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Ctrl(Side),
+    Shift(Side),
+    Alt(Side),
+    Super(Side),
+    Fn,
+    /// Any other key, by its XKB keysym name ("a", "space", "F1").
+    Named(&'static str),
+}
+
+pub const LETTERS: [&str; 26] = [
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s",
+    "t", "u", "v", "w", "x", "y", "z",
+];
+
+pub const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+pub const KEYPAD_DIGITS: [&str; 10] = [
+    "KP_0", "KP_1", "KP_2", "KP_3", "KP_4", "KP_5", "KP_6", "KP_7", "KP_8", "KP_9",
+];
+
+pub const FUNCTION_KEYS: [&str; 24] = [
+    "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12", "F13", "F14", "F15",
+    "F16", "F17", "F18", "F19", "F20", "F21", "F22", "F23", "F24",
+];
+
+/// Spellings the shortcut recorders produce (lowercase, without spaces) for
+/// other keys, with their keysym names.
+pub const NAMED_KEYS: &[(&[&str], &str)] = &[
+    (&["space"], "space"),
+    (&["enter", "return"], "Return"),
+    (&["tab"], "Tab"),
+    (&["esc", "escape"], "Escape"),
+    (&["backspace"], "BackSpace"),
+    (&["delete", "del"], "Delete"),
+    (&["insert"], "Insert"),
+    (&["home"], "Home"),
+    (&["end"], "End"),
+    (&["pageup"], "Prior"),
+    (&["pagedown"], "Next"),
+    (&["up", "arrowup"], "Up"),
+    (&["down", "arrowdown"], "Down"),
+    (&["left", "arrowleft"], "Left"),
+    (&["right", "arrowright"], "Right"),
+    (&["capslock"], "Caps_Lock"),
+    (&["printscreen", "print"], "Print"),
+    (&["scrolllock"], "Scroll_Lock"),
+    (&["pause"], "Pause"),
+    (&["menu", "contextmenu"], "Menu"),
+    (&["numlock"], "Num_Lock"),
+    (&["numpad*"], "KP_Multiply"),
+    (&["numpad-"], "KP_Subtract"),
+    (&["numpad."], "KP_Decimal"),
+    (&["numpad/"], "KP_Divide"),
+    (&["-", "minus"], "minus"),
+    (&["=", "equal"], "equal"),
+    (&["[", "bracketleft"], "bracketleft"),
+    (&["]", "bracketright"], "bracketright"),
+    (&["\\", "backslash"], "backslash"),
+    (&[";", "semicolon"], "semicolon"),
+    (&["'", "quote"], "apostrophe"),
+    (&["`", "backquote"], "grave"),
+    (&[".", "period"], "period"),
+    (&["/", "slash"], "slash"),
+];
+
+/// Parse one key of a shortcut string, e.g. "ctrl", "option_left" or "f5".
+pub fn parse_key(token: &str) -> Option<Key> {
+    let name = token.trim().to_lowercase().replace(' ', "");
+
+    let (base, side) = if let Some(base) = name.strip_suffix("_left") {
+        (base, Side::Left)
+    } else if let Some(base) = name.strip_suffix("_right") {
+        (base, Side::Right)
+    } else {
+        (name.as_str(), Side::Any)
+    };
+    match base {
+        "ctrl" | "control" => return Some(Key::Ctrl(side)),
+        "shift" => return Some(Key::Shift(side)),
+        "alt" | "option" => return Some(Key::Alt(side)),
+        "super" | "meta" | "command" | "cmd" | "win" | "windows" => return Some(Key::Super(side)),
+        "fn" | "function" if side == Side::Any => return Some(Key::Fn),
+        _ if side != Side::Any => return None,
+        _ => {}
+    }
+
+    let tables: [&[&'static str]; 2] = [&LETTERS, &DIGITS];
+    for table in tables {
+        if let Some(key) = table.iter().find(|key| **key == name) {
+            return Some(Key::Named(*key));
+        }
+    }
+    if let Some(digit) = name.strip_prefix("numpad") {
+        if let Some(i) = DIGITS.iter().position(|d| *d == digit) {
+            return Some(Key::Named(KEYPAD_DIGITS[i]));
+        }
+    }
+    if let Some(number) = name.strip_prefix('f').and_then(|n| n.parse::<usize>().ok()) {
+        return FUNCTION_KEYS
+            .get(number.wrapping_sub(1))
+            .map(|key| Key::Named(*key));
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(spellings, _)| spellings.contains(&name.as_str()))
+        .map(|(_, keysym)| Key::Named(*keysym))
+}
+
+/// Parse a single-step shortcut string such as "ctrl+shift+space".
+pub fn parse_combo(raw: &str) -> Result<Vec<Key>, String> {
+    raw.split('+')
+        .map(|token| parse_key(token).ok_or_else(|| format!("Unknown key '{}'", token.trim())))
+        .collect()
+}
+
+/// Trigger in the XDG shortcuts format the portal expects, e.g.
+/// "CTRL+SHIFT+space". The portal cannot express a side or the fn key.
+fn portal_trigger(keys: &[Key]) -> String {
+    keys.iter()
+        .filter_map(|key| match key {
+            Key::Ctrl(_) => Some("CTRL"),
+            Key::Shift(_) => Some("SHIFT"),
+            Key::Alt(_) => Some("ALT"),
+            Key::Super(_) => Some("LOGO"),
+            Key::Fn => None,
+            Key::Named(keysym) => Some(*keysym),
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Validate a shortcut string for the Wayland implementation: every step
+/// must consist of known keys.
+pub fn validate_shortcut(raw: &str) -> Result<(), String> {
+    if raw.trim().is_empty() {
+        return Err("Shortcut cannot be empty".into());
+    }
+    sequence::validate(raw, |step| parse_combo(step).map(|_| ()))
+}
+
+/// Validate a shortcut string for the portal, which binds one combination
+/// of plain modifiers and a main key.
+fn validate_portal_shortcut(raw: &str) -> Result<(), String> {
+    validate_shortcut(raw)?;
+    if sequence::is_sequence(raw) {
+        return Err("Multi-step shortcuts need /dev/input access, not the portal".into());
+    }
+
+    let keys = parse_combo(raw)?;
+    if keys.contains(&Key::Fn) {
+        return Err("The 'fn' key is not supported by the GlobalShortcuts portal".into());
+    }
+    let sided = keys.iter().any(|key| {
+        matches!(
+            key,
+            Key::Ctrl(side) | Key::Shift(side) | Key::Alt(side) | Key::Super(side)
+                if *side != Side::Any
+        )
+    });
+    if sided {
+        return Err("The GlobalShortcuts portal cannot tell left and right modifiers apart".into());
+    }
+    if !keys.iter().any(|key| matches!(key, Key::Named(_))) {
+        return Err(
+            "Portal shortcuts must include a main key (letter, number, F-key, etc.) in addition to modifiers"
+                .into(),
+        );
+    }
+    Ok(())
+}
+
+/// Validate a shortcut string for where shortcuts are read from: the portal
+/// when one is in use, otherwise `/dev/input`.
+pub fn validate_shortcut_for(app: &AppHandle, raw: &str) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if linux::state(app).is_ok_and(|state| !state.reads_input_devices()) {
+        return validate_portal_shortcut(raw);
+    }
+
+    let _ = app;
+    validate_shortcut(raw)
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::WaylandState;
+
+/// Stop reading `/dev/input` when switching to another implementation, so
+/// its threads don't keep running next to the new one.
+pub fn suspend(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    if let Ok(state) = linux::state(app) {
+        state.suspend();
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
+
+/// Read `/dev/input` again when switching back to this implementation.
+pub fn resume(app: &AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if let Ok(state) = linux::state(app) {
+        return state.resume(app);
+    }
+
+    let _ = app;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+const UNSUPPORTED: &str = "Wayland shortcuts are only available on Linux";
+
+/// Initialize Wayland shortcuts, preferring the portal over `/dev/input`
+pub fn init_shortcuts(app: &AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::init_shortcuts(app)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = app;
+        Err(UNSUPPORTED.into())
+    }
+}
+
+/// Register a shortcut
+pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::state(app)?.register(&binding)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (app, binding);
+        Err(UNSUPPORTED.into())
+    }
+}
+
+/// Unregister a shortcut
+pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::state(app)?.unregister(&binding);
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (app, binding);
+        Err(UNSUPPORTED.into())
+    }
+}
+
+/// Register the cancel shortcut (called when recording starts)
+pub fn register_cancel_shortcut(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    linux::set_cancel_registered(app, true);
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
+
+/// Unregister the cancel shortcut (called when recording stops)
+pub fn unregister_cancel_shortcut(app: &AppHandle) {
+    #[cfg(target_os = "linux")]
+    linux::set_cancel_registered(app, false);
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use log::{debug, error, info};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+    use tauri::{AppHandle, Manager, State};
+
+    use super::super::evdev::{self, KeyMatcher};
+    use super::super::handler::handle_shortcut_event;
+    use super::super::sequence;
+    use super::{parse_combo, portal_trigger, validate_portal_shortcut, validate_shortcut};
+    use crate::settings::{self, get_settings, ShortcutBinding};
+    use portal::PortalSession;
+
+    /// Quiet period after the last registration change before the portal is
+    /// asked to bind the new set, so startup binds everything at once.
+    const REBIND_DELAY: Duration = Duration::from_millis(300);
+
+    pub(super) fn init_shortcuts(app: &AppHandle) -> Result<(), String> {
+        let state = WaylandState::new(app)?;
+        let user_settings = settings::load_or_create_app_settings(app);
+
+        // Register all bindings from user settings (includes default + custom)
+        for (id, binding) in &user_settings.bindings {
+            if id == "cancel" {
+                continue;
+            }
+
+            if let Err(e) = state.register(binding) {
+                error!(
+                    "Failed to register Wayland shortcut {} during init: {}",
+                    id, e
+                );
+            }
+        }
+
+        app.manage(state);
+        info!("Wayland shortcuts initialized");
+        Ok(())
+    }
+
+    pub(super) fn state(app: &AppHandle) -> Result<State<'_, WaylandState>, String> {
+        app.try_state::<WaylandState>()
+            .ok_or_else(|| "WaylandState not initialized".to_string())
+    }
+
+    pub(super) fn set_cancel_registered(app: &AppHandle, registered: bool) {
+        let Ok(state) = state(app) else {
+            return;
+        };
+        if let Some(cancel_binding) = get_settings(app).bindings.get("cancel") {
+            if !registered {
+                state.unregister(cancel_binding);
+            } else if let Err(e) = state.register(cancel_binding) {
+                error!("Failed to register cancel shortcut: {}", e);
+            }
+        }
+    }
+
+    enum Source {
+        Portal(Arc<PortalShortcuts>),
+        InputDevices(InputDevices),
+    }
+
+    /// Shortcuts matched against keys read from `/dev/input`. The readers run
+    /// while `watch` is set.
+    struct InputDevices {
+        matcher: Arc<Mutex<KeyMatcher>>,
+        watch: Mutex<Option<evdev::KeyboardWatch>>,
+    }
+
+    /// Read the keyboards and pass their keys through `matcher`.
+    fn watch_keys(
+        app: &AppHandle,
+        matcher: &Arc<Mutex<KeyMatcher>>,
+    ) -> Result<evdev::KeyboardWatch, String> {
+        let matcher = Arc::clone(matcher);
+        let app = app.clone();
+        evdev::watch_keyboards(move |code, is_down| {
+            let changes = matcher.lock().unwrap().key_event(code, is_down);
+            // Any other key breaks off the sequences in progress
+            if is_down && changes.is_empty() && !evdev::is_modifier(code) {
+                sequence::on_key_down(&app, |_| false);
+            }
+            for (binding_id, is_pressed) in changes {
+                handle_shortcut_event(&app, &binding_id, "evdev", is_pressed);
+            }
+        })
+    }
+
+    /// Shortcuts bound through the portal. The portal takes the whole set at
+    /// once, so changes bind the new set again in the same session.
+    struct PortalShortcuts {
+        session: Mutex<PortalSession>,
+        /// Binding ID to (description, trigger)
+        shortcuts: Mutex<BTreeMap<String, (String, String)>>,
+        generation: AtomicU64,
+    }
+
+    impl PortalShortcuts {
+        fn schedule_rebind(self: &Arc<Self>) {
+            let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+            let shortcuts = Arc::clone(self);
+            thread::spawn(move || {
+                thread::sleep(REBIND_DELAY);
+                if shortcuts.generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                let list: Vec<_> = shortcuts
+                    .shortcuts
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(id, (description, trigger))| {
+                        (id.clone(), description.clone(), trigger.clone())
+                    })
+                    .collect();
+                if let Err(e) = shortcuts.session.lock().unwrap().bind(&list) {
+                    error!("Failed to bind shortcuts through the portal: {}", e);
+                }
+            });
+        }
+    }
+
+    /// State for the Wayland shortcut implementation
+    pub struct WaylandState {
+        source: Source,
+    }
+
+    impl WaylandState {
+        pub(super) fn new(app: &AppHandle) -> Result<Self, String> {
+            match PortalSession::connect() {
+                Ok(session) => {
+                    session.listen(app)?;
+                    info!("Binding shortcuts through the GlobalShortcuts portal");
+                    return Ok(Self {
+                        source: Source::Portal(Arc::new(PortalShortcuts {
+                            session: Mutex::new(session),
+                            shortcuts: Mutex::new(BTreeMap::new()),
+                            generation: AtomicU64::new(0),
+                        })),
+                    });
+                }
+                Err(e) => info!(
+                    "GlobalShortcuts portal unavailable ({}), using /dev/input",
+                    e
+                ),
+            }
+
+            let matcher = Arc::new(Mutex::new(KeyMatcher::default()));
+            let watch = watch_keys(app, &matcher)?;
+            Ok(Self {
+                source: Source::InputDevices(InputDevices {
+                    matcher,
+                    watch: Mutex::new(Some(watch)),
+                }),
+            })
+        }
+
+        /// Stop reading input devices while another implementation is in use.
+        pub(super) fn suspend(&self) {
+            if let Source::InputDevices(devices) = &self.source {
+                if devices.watch.lock().unwrap().take().is_some() {
+                    info!("Stopped reading shortcuts from /dev/input");
+                }
+            }
+        }
+
+        /// Read input devices again after [`WaylandState::suspend`].
+        pub(super) fn resume(&self, app: &AppHandle) -> Result<(), String> {
+            if let Source::InputDevices(devices) = &self.source {
+                let mut watch = devices.watch.lock().unwrap();
+                if watch.is_none() {
+                    *watch = Some(watch_keys(app, &devices.matcher)?);
+                }
+            }
+            Ok(())
+        }
+
+        pub(super) fn reads_input_devices(&self) -> bool {
+            matches!(self.source, Source::InputDevices(_))
+        }
+
+        pub(super) fn register(&self, binding: &ShortcutBinding) -> Result<(), String> {
+            if binding.is_unbound() {
+                return Ok(());
+            }
+            match &self.source {
+                Source::Portal(_) => validate_portal_shortcut(&binding.current_binding)?,
+                Source::InputDevices(_) => validate_shortcut(&binding.current_binding)?,
+            }
+            let keys = parse_combo(sequence::leader(&binding.current_binding))?;
+
+            match &self.source {
+                Source::Portal(portal) => {
+                    portal.shortcuts.lock().unwrap().insert(
+                        binding.id.clone(),
+                        (binding.name.clone(), portal_trigger(&keys)),
+                    );
+                    portal.schedule_rebind();
+                }
+                Source::InputDevices(devices) => {
+                    devices.matcher.lock().unwrap().insert(&binding.id, &keys);
+                }
+            }
+            debug!(
+                "Registered Wayland shortcut: {} -> {}",
+                binding.id, binding.current_binding
+            );
+            Ok(())
+        }
+
+        pub(super) fn unregister(&self, binding: &ShortcutBinding) {
+            match &self.source {
+                Source::Portal(portal) => {
+                    if portal
+                        .shortcuts
+                        .lock()
+                        .unwrap()
+                        .remove(&binding.id)
+                        .is_some()
+                    {
+                        portal.schedule_rebind();
+                    }
+                }
+                Source::InputDevices(devices) => {
+                    devices.matcher.lock().unwrap().remove(&binding.id)
+                }
+            }
+        }
+    }
+
+    mod portal {
+        //! Minimal client for `org.freedesktop.portal.GlobalShortcuts`.
+
+        use std::collections::HashMap;
+        use std::thread;
+
+        use log::{debug, warn};
+        use tauri::AppHandle;
+        use zbus::blocking::{Connection, Proxy};
+        use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+        use super::handle_shortcut_event;
+
+        const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+        const PATH: &str = "/org/freedesktop/portal/desktop";
+        const INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+        pub struct PortalSession {
+            connection: Connection,
+            handle: Option<String>,
+            tokens: u32,
+        }
+
+        impl PortalSession {
+            /// Connect to the session bus; fails when no portal backend
+            /// implements GlobalShortcuts.
+            pub fn connect() -> Result<Self, String> {
+                let connection = Connection::session().map_err(|e| e.to_string())?;
+                let version: u32 = Proxy::new(&connection, DESTINATION, PATH, INTERFACE)
+                    .and_then(|proxy| proxy.get_property("version"))
+                    .map_err(|e| e.to_string())?;
+                debug!("GlobalShortcuts portal version {}", version);
+                Ok(Self {
+                    connection,
+                    handle: None,
+                    tokens: 0,
+                })
+            }
+
+            /// Forward activation and deactivation of our shortcuts, whose
+            /// portal IDs are the binding IDs.
+            pub fn listen(&self, app: &AppHandle) -> Result<(), String> {
+                for (signal, is_pressed) in [("Activated", true), ("Deactivated", false)] {
+                    let proxy = Proxy::new(&self.connection, DESTINATION, PATH, INTERFACE)
+                        .map_err(|e| e.to_string())?;
+                    let signals = proxy.receive_signal(signal).map_err(|e| e.to_string())?;
+                    let app = app.clone();
+                    thread::spawn(move || {
+                        for message in signals {
+                            type Args = (OwnedObjectPath, String, u64, HashMap<String, OwnedValue>);
+                            match message.body().deserialize::<Args>() {
+                                Ok((_, shortcut_id, _, _)) => {
+                                    handle_shortcut_event(&app, &shortcut_id, "portal", is_pressed)
+                                }
+                                Err(e) => warn!("Unexpected {} signal: {}", signal, e),
+                            }
+                        }
+                    });
+                }
+                Ok(())
+            }
+
+            /// Replace the bound shortcuts with `shortcuts`, given as
+            /// (id, description, trigger). The session is created on first
+            /// use and kept, so the compositor only asks about shortcuts it
+            /// has not seen in it.
+            pub fn bind(&mut self, shortcuts: &[(String, String, String)]) -> Result<(), String> {
+                let handle = match &self.handle {
+                    Some(handle) => handle.clone(),
+                    None if shortcuts.is_empty() => return Ok(()),
+                    None => self.create_session()?,
+                };
+
+                let result = self.bind_shortcuts(&handle, shortcuts);
+                if result.is_err() {
+                    // The portal may have closed the session; the next
+                    // change starts a new one
+                    self.handle = None;
+                }
+                result
+            }
+
+            fn create_session(&mut self) -> Result<String, String> {
+                let session_token = self.next_token();
+                let token = self.next_token();
+                let options = HashMap::from([
+                    ("handle_token", Value::from(token.as_str())),
+                    ("session_handle_token", Value::from(session_token.as_str())),
+                ]);
+                self.request("CreateSession", &(options,), &token)?;
+                let handle = format!("{}/session/{}/{}", PATH, self.sender()?, session_token);
+                self.handle = Some(handle.clone());
+                Ok(handle)
+            }
+
+            fn bind_shortcuts(
+                &mut self,
+                handle: &str,
+                shortcuts: &[(String, String, String)],
+            ) -> Result<(), String> {
+                let list: Vec<(&str, HashMap<&str, Value>)> = shortcuts
+                    .iter()
+                    .map(|(id, description, trigger)| {
+                        (
+                            id.as_str(),
+                            HashMap::from([
+                                ("description", Value::from(description.as_str())),
+                                ("preferred_trigger", Value::from(trigger.as_str())),
+                            ]),
+                        )
+                    })
+                    .collect();
+                let token = self.next_token();
+                let options = HashMap::from([("handle_token", Value::from(token.as_str()))]);
+                let session = ObjectPath::try_from(handle).map_err(|e| e.to_string())?;
+                self.request("BindShortcuts", &(session, list, "", options), &token)?;
+                debug!("Bound {} shortcuts through the portal", shortcuts.len());
+                Ok(())
+            }
+
+            fn next_token(&mut self) -> String {
+                self.tokens += 1;
+                format!("handy{}_{}", std::process::id(), self.tokens)
+            }
+
+            /// Our unique bus name in the form used by portal object paths
+            fn sender(&self) -> Result<String, String> {
+                self.connection
+                    .unique_name()
+                    .map(|name| name.as_str().trim_start_matches(':').replace('.', "_"))
+                    .ok_or_else(|| "Not connected to the session bus".to_string())
+            }
+
+            /// Call a portal method that answers through a Request object and
+            /// wait for the user's response.
+            fn request<B>(&self, method: &str, body: &B, token: &str) -> Result<(), String>
+            where
+                B: serde::Serialize + zbus::zvariant::DynamicType,
+            {
+                let path = format!("{}/request/{}/{}", PATH, self.sender()?, token);
+                let request = Proxy::new(
+                    &self.connection,
+                    DESTINATION,
+                    path.as_str(),
+                    "org.freedesktop.portal.Request",
+                )
+                .map_err(|e| e.to_string())?;
+                // Subscribe before calling so the response cannot be missed
+                let mut responses = request
+                    .receive_signal("Response")
+                    .map_err(|e| e.to_string())?;
+
+                Proxy::new(&self.connection, DESTINATION, PATH, INTERFACE)
+                    .and_then(|portal| portal.call_method(method, body))
+                    .map_err(|e| format!("{} failed: {}", method, e))?;
+
+                let message = responses
+                    .next()
+                    .ok_or_else(|| format!("{} got no response", method))?;
+                let (response, _results): (u32, HashMap<String, OwnedValue>) =
+                    message.body().deserialize().map_err(|e| e.to_string())?;
+                match response {
+                    0 => Ok(()),
+                    1 => Err(format!("{} was cancelled", method)),
+                    _ => Err(format!("{} failed", method)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_recorder_key_names() {
+        assert_eq!(
+            parse_combo("ctrl+shift+space"),
+            Ok(vec![
+                Key::Ctrl(Side::Any),
+                Key::Shift(Side::Any),
+                Key::Named("space")
+            ])
+        );
+        assert_eq!(parse_combo("option_left"), Ok(vec![Key::Alt(Side::Left)]));
+        assert_eq!(
+            parse_combo("super+f12"),
+            Ok(vec![Key::Super(Side::Any), Key::Named("F12")])
+        );
+        assert_eq!(parse_combo("page up"), Ok(vec![Key::Named("Prior")]));
+        assert_eq!(parse_combo("numpad 7"), Ok(vec![Key::Named("KP_7")]));
+        assert!(parse_combo("ctrl+f25").is_err());
+        assert!(parse_combo("space_left").is_err());
+    }
+
+    #[test]
+    fn builds_portal_triggers() {
+        let keys = parse_combo("ctrl+alt+command+k").unwrap();
+        assert_eq!(portal_trigger(&keys), "CTRL+ALT+LOGO+k");
+        assert_eq!(portal_trigger(&parse_combo("enter").unwrap()), "Return");
+    }
+
+    #[test]
+    fn validates_sequences() {
+        assert!(validate_shortcut("ctrl_right, ctrl_right").is_ok());
+        assert!(validate_shortcut("ctrl+space, p").is_ok());
+        assert!(validate_shortcut("ctrl+space, nope").is_err());
+    }
+
+    #[test]
+    fn portal_takes_one_combination_with_a_main_key() {
+        assert!(validate_portal_shortcut("ctrl+shift+space").is_ok());
+        assert!(validate_portal_shortcut("escape").is_ok());
+        assert!(validate_portal_shortcut("ctrl+space, p").is_err());
+        assert!(validate_portal_shortcut("fn+space").is_err());
+        assert!(validate_portal_shortcut("ctrl_right+space").is_err());
+        assert!(validate_portal_shortcut("ctrl+shift").is_err());
+    }
+}
@@ -1,14 +1,15 @@
 use crate::actions::{ShortcutAction, TranscribeAction};
 use crate::managers::audio::AudioRecordingManager;
 use crate::overlay;
-use crate::settings::ActivationMode;
-use log::{debug, error, warn};
+use crate::settings::{get_settings, ActivationMode};
+use log::{debug, error, info, warn};
 use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Sender};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Singleton TranscribeAction used by the coordinator for all transcribe bindings.
 static TRANSCRIBE_ACTION: Lazy<TranscribeAction> = Lazy::new(|| TranscribeAction);
@@ -18,6 +19,9 @@ const DEBOUNCE: Duration = Duration::from_millis(30);
 /// How long a key must be held before it counts as a "hold" in HoldOrToggle mode.
 const HOLD_THRESHOLD: Duration = Duration::from_millis(300);
 
+/// Binding whose settings hands-free utterances are processed with.
+const HANDS_FREE_BINDING: &str = "transcribe";
+
 /// Commands processed sequentially by the coordinator thread.
 enum Command {
     Input {
@@ -29,9 +33,13 @@ enum Command {
     Cancel {
         recording_was_active: bool,
     },
-    /// Stop recording and process (triggered by overlay confirm button).
+    /// Stop recording and process (triggered by the overlay confirm button
+    /// or by auto-stop).
     Confirm,
     ProcessingFinished,
+    /// Enter or leave hands-free mode, where each utterance ended by the
+    /// VAD is processed while the next one is already being recorded.
+    SetHandsFree(bool),
 }
 
 /// Pipeline lifecycle, owned exclusively by the coordinator thread.
@@ -46,6 +54,7 @@ enum Stage {
 /// the async transcribe-paste pipeline.
 pub struct TranscriptionCoordinator {
     tx: Sender<Command>,
+    hands_free: Arc<AtomicBool>,
 }
 
 pub fn is_transcribe_binding(id: &str) -> bool {
@@ -55,14 +64,22 @@ pub fn is_transcribe_binding(id: &str) -> bool {
 impl TranscriptionCoordinator {
     pub fn new(app: AppHandle) -> Self {
         let (tx, rx) = mpsc::channel();
+        let hands_free = Arc::new(AtomicBool::new(false));
+        let hands_free_flag = Arc::clone(&hands_free);
 
         thread::spawn(move || {
             let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
                 let mut stage = Stage::Idle;
+                // Stopped recordings whose processing hasn't finished yet;
+                // hands-free mode records the next utterance meanwhile.
+                let mut in_flight = 0usize;
                 let mut last_press: Option<Instant> = None;
                 // HoldOrToggle state
                 let mut press_start: Option<Instant> = None;
                 let mut toggled = false;
+                // Whether the current recording is held by a key; auto-stop
+                // must not end a recording the user is still holding.
+                let mut key_held = false;
 
                 while let Ok(cmd) = rx.recv() {
                     match cmd {
@@ -86,11 +103,25 @@ impl TranscriptionCoordinator {
                             match activation_mode {
                                 ActivationMode::Hold => {
                                     if is_pressed && matches!(stage, Stage::Idle) {
-                                        start(&app, &mut stage, &binding_id, &hotkey_string);
+                                        start(
+                                            &app,
+                                            &mut stage,
+                                            &binding_id,
+                                            &hotkey_string,
+                                            activation_mode,
+                                        );
+                                        key_held = matches!(stage, Stage::Recording(_));
                                     } else if !is_pressed
                                         && matches!(&stage, Stage::Recording(id) if id == &binding_id)
                                     {
-                                        stop(&app, &mut stage, &binding_id, &hotkey_string);
+                                        stop(
+                                            &app,
+                                            &mut stage,
+                                            &mut in_flight,
+                                            &binding_id,
+                                            &hotkey_string,
+                                        );
+                                        key_held = false;
                                     }
                                 }
                                 ActivationMode::Toggle => {
@@ -102,12 +133,14 @@ impl TranscriptionCoordinator {
                                                     &mut stage,
                                                     &binding_id,
                                                     &hotkey_string,
+                                                    activation_mode,
                                                 );
                                             }
                                             Stage::Recording(id) if id == &binding_id => {
                                                 stop(
                                                     &app,
                                                     &mut stage,
+                                                    &mut in_flight,
                                                     &binding_id,
                                                     &hotkey_string,
                                                 );
@@ -124,23 +157,38 @@ impl TranscriptionCoordinator {
                                             && matches!(&stage, Stage::Recording(id) if id == &binding_id)
                                         {
                                             // In toggle state: next press stops recording.
-                                            stop(&app, &mut stage, &binding_id, &hotkey_string);
+                                            stop(
+                                                &app,
+                                                &mut stage,
+                                                &mut in_flight,
+                                                &binding_id,
+                                                &hotkey_string,
+                                            );
                                             toggled = false;
                                             press_start = None;
                                         } else if matches!(stage, Stage::Idle) {
                                             // Start recording and record timestamp.
                                             press_start = Some(Instant::now());
                                             toggled = false;
-                                            start(&app, &mut stage, &binding_id, &hotkey_string);
+                                            start(
+                                                &app,
+                                                &mut stage,
+                                                &binding_id,
+                                                &hotkey_string,
+                                                activation_mode,
+                                            );
+                                            key_held = matches!(stage, Stage::Recording(_));
                                         }
                                     } else if matches!(&stage, Stage::Recording(id) if id == &binding_id)
                                     {
+                                        key_held = false;
                                         if let Some(t) = press_start {
                                             if t.elapsed() >= HOLD_THRESHOLD {
                                                 // Held long enough: treat as hold → stop.
                                                 stop(
                                                     &app,
                                                     &mut stage,
+                                                    &mut in_flight,
                                                     &binding_id,
                                                     &hotkey_string,
                                                 );
@@ -159,11 +207,17 @@ impl TranscriptionCoordinator {
                             }
                         }
                         Command::Confirm => {
-                            if let Stage::Recording(ref id) = stage {
+                            if key_held {
+                                debug!("Ignoring confirm while the recording key is held");
+                            } else if let Stage::Recording(ref id) = stage {
                                 let bid = id.clone();
-                                stop(&app, &mut stage, &bid, "overlay-confirm");
+                                stop(&app, &mut stage, &mut in_flight, &bid, "overlay-confirm");
                                 press_start = None;
                                 toggled = false;
+                                // Keep listening while the utterance is processed
+                                if hands_free_flag.load(Ordering::SeqCst) {
+                                    start_hands_free_utterance(&app, &hands_free_flag, &mut stage);
+                                }
                             }
                         }
                         Command::Cancel {
@@ -173,15 +227,54 @@ impl TranscriptionCoordinator {
                             if !matches!(stage, Stage::Processing)
                                 && (recording_was_active || matches!(stage, Stage::Recording(_)))
                             {
-                                stage = Stage::Idle;
+                                // Earlier hands-free utterances still finish
+                                stage = if in_flight > 0 {
+                                    Stage::Processing
+                                } else {
+                                    Stage::Idle
+                                };
                                 press_start = None;
                                 toggled = false;
+                                key_held = false;
+                            }
+                            // Cancelling also ends a hands-free session
+                            if hands_free_flag.load(Ordering::SeqCst) {
+                                set_hands_free(&app, &hands_free_flag, false);
                             }
                         }
                         Command::ProcessingFinished => {
+                            in_flight = in_flight.saturating_sub(1);
+                            // A hands-free recording may already be running
+                            if in_flight > 0 || !matches!(stage, Stage::Processing) {
+                                continue;
+                            }
                             stage = Stage::Idle;
                             press_start = None;
                             toggled = false;
+                            key_held = false;
+                            if hands_free_flag.load(Ordering::SeqCst) {
+                                start_hands_free_utterance(&app, &hands_free_flag, &mut stage);
+                            }
+                        }
+                        Command::SetHandsFree(enabled) => {
+                            if hands_free_flag.load(Ordering::SeqCst) == enabled {
+                                continue;
+                            }
+                            if enabled {
+                                set_hands_free(&app, &hands_free_flag, true);
+                                if matches!(stage, Stage::Idle) {
+                                    start_hands_free_utterance(&app, &hands_free_flag, &mut stage);
+                                }
+                                // Otherwise the first utterance starts once
+                                // the current pipeline finishes.
+                            } else {
+                                // Process whatever was said before toggling off
+                                if let Stage::Recording(ref id) = stage {
+                                    let bid = id.clone();
+                                    stop(&app, &mut stage, &mut in_flight, &bid, "hands-free");
+                                }
+                                set_hands_free(&app, &hands_free_flag, false);
+                            }
                         }
                     }
                 }
@@ -192,7 +285,7 @@ impl TranscriptionCoordinator {
             }
         });
 
-        Self { tx }
+        Self { tx, hands_free }
     }
 
     /// Send a keyboard/signal input event for a transcribe binding.
@@ -242,9 +335,30 @@ impl TranscriptionCoordinator {
             warn!("Transcription coordinator channel closed");
         }
     }
+
+    pub fn set_hands_free(&self, enabled: bool) {
+        if self.tx.send(Command::SetHandsFree(enabled)).is_err() {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
+    pub fn toggle_hands_free(&self) {
+        self.set_hands_free(!self.is_hands_free());
+    }
+
+    pub fn is_hands_free(&self) -> bool {
+        self.hands_free.load(Ordering::SeqCst)
+    }
 }
 
-fn start(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &str) {
+fn start(
+    app: &AppHandle,
+    stage: &mut Stage,
+    binding_id: &str,
+    hotkey_string: &str,
+    activation_mode: ActivationMode,
+) {
+    overlay::update_overlay_activation_mode(app, activation_mode);
     TRANSCRIBE_ACTION.start(app, binding_id, hotkey_string);
     if app
         .try_state::<Arc<AudioRecordingManager>>()
@@ -256,7 +370,46 @@ fn start(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &s
     }
 }
 
-fn stop(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &str) {
+fn stop(
+    app: &AppHandle,
+    stage: &mut Stage,
+    in_flight: &mut usize,
+    binding_id: &str,
+    hotkey_string: &str,
+) {
     TRANSCRIBE_ACTION.stop(app, binding_id, hotkey_string);
     *stage = Stage::Processing;
+    *in_flight += 1;
+}
+
+/// Switch the audio manager in or out of hands-free capture and tell the
+/// frontend. Only called from the coordinator thread.
+fn set_hands_free(app: &AppHandle, flag: &AtomicBool, enabled: bool) {
+    flag.store(enabled, Ordering::SeqCst);
+    if let Some(rm) = app.try_state::<Arc<AudioRecordingManager>>() {
+        if let Err(e) = rm.set_hands_free(enabled) {
+            error!("Failed to switch microphone to hands-free mode: {e}");
+        }
+    }
+    info!(
+        "Hands-free mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    let _ = app.emit("hands-free-changed", enabled);
+}
+
+/// Start recording the next hands-free utterance. Leaves hands-free mode if
+/// the recording cannot start, rather than retrying on every finish.
+fn start_hands_free_utterance(app: &AppHandle, flag: &AtomicBool, stage: &mut Stage) {
+    let activation_mode = get_settings(app).activation_mode_for(HANDS_FREE_BINDING);
+    start(
+        app,
+        stage,
+        HANDS_FREE_BINDING,
+        "hands-free",
+        activation_mode,
+    );
+    if !matches!(stage, Stage::Recording(_)) {
+        set_hands_free(app, flag, false);
+    }
 }
@@ -180,7 +180,8 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
     let _ = tray.set_icon_as_template(true);
 }
 
-fn last_transcript_text(entry: &HistoryEntry) -> &str {
+/// Text of a history entry as it was pasted: post-processed when available.
+pub fn last_transcript_text(entry: &HistoryEntry) -> &str {
     entry
         .post_processed_text
         .as_deref()
@@ -201,7 +202,7 @@ pub fn copy_last_transcript(app: &AppHandle) {
     let entry = match history_manager.get_latest_entry() {
         Ok(Some(entry)) => entry,
         Ok(None) => {
-            warn!("No transcription history entries available for tray copy.");
+            warn!("No transcription history entries available to copy.");
             return;
         }
         Err(err) => {
@@ -215,13 +216,13 @@ pub fn copy_last_transcript(app: &AppHandle) {
         return;
     }
 
-    info!("Copied last transcript to clipboard via tray.");
+    info!("Copied last transcript to clipboard.");
 }
 
 #[cfg(test)]
 mod tests {
     use super::last_transcript_text;
-    use crate::managers::history::HistoryEntry;
+    use crate::managers::history::{HistoryEntry, TranscriptionStatus};
 
     fn build_entry(transcription: &str, post_processed: Option<&str>) -> HistoryEntry {
         HistoryEntry {
@@ -233,6 +234,11 @@ mod tests {
             transcription_text: transcription.to_string(),
             post_processed_text: post_processed.map(|text| text.to_string()),
             post_process_prompt: None,
+            status: TranscriptionStatus::Done,
+            error: None,
+            segments: None,
+            retracted: false,
+            binding_id: None,
         }
     }
 
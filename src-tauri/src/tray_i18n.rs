@@ -1,21 +1,23 @@
-//! Tray menu internationalization
+//! Tray menu and notification internationalization
 //!
 //! Everything is auto-generated at compile time by build.rs from the
 //! frontend locale files (src/i18n/locales/*/translation.json).
 //!
 //! The English translation.json is the single source of truth:
 //! - TrayStrings struct fields are derived from the English "tray" keys
+//! - NotificationStrings fields are derived from the "notifications" keys
 //! - All languages are auto-discovered from the locales directory
 //!
-//! To add a new tray menu item:
-//! 1. Add the key to en/translation.json under "tray"
+//! To add a new tray menu item or notification text:
+//! 1. Add the key to en/translation.json under "tray" or "notifications"
 //! 2. Add translations to other locale files
-//! 3. Update tray.rs to use the new field (e.g., strings.new_field)
+//! 3. Use the new field (e.g., strings.new_field)
 
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 
-// Include the auto-generated TrayStrings struct and TRANSLATIONS static
+// Include the auto-generated TrayStrings and NotificationStrings structs and
+// their TRANSLATIONS and NOTIFICATION_TRANSLATIONS statics
 include!(concat!(env!("OUT_DIR"), "/tray_translations.rs"));
 
 /// Get the language code from a locale string (e.g., "en-US" -> "en")
@@ -34,3 +36,12 @@ pub fn get_tray_translations(locale: Option<String>) -> TrayStrings {
         .cloned()
         .expect("English translations must exist")
 }
+
+/// Get localized notification strings for the app language
+pub fn get_notification_translations(locale: &str) -> NotificationStrings {
+    NOTIFICATION_TRANSLATIONS
+        .get(get_language_code(locale))
+        .or_else(|| NOTIFICATION_TRANSLATIONS.get("en"))
+        .cloned()
+        .expect("English translations must exist")
+}
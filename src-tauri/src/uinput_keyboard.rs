@@ -0,0 +1,475 @@
+//! Built-in virtual keyboard for typing on Linux without external tools
+//!
+//! Text is typed through a keyboard created with `/dev/uinput`, which works on
+//! X11 and every Wayland compositor. Since the compositor applies the user's
+//! keyboard layout to it, each character is mapped to the key and modifiers
+//! that produce it in that layout, compiled with xkbcommon from
+//! `XKB_DEFAULT_LAYOUT`, the session's input sources (GNOME) or `kxkbrc`
+//! (KDE), or else the system keyboard configuration. Characters the layout
+//! lacks are entered as Ctrl+Shift+U hex input when IBus is the input method
+//! and the layout has the keys for it; otherwise typing fails before any key
+//! is sent.
+
+use evdev::uinput::VirtualDevice;
+use evdev::{AttributeSet, EventType, InputEvent, KeyCode};
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use xkbcommon::xkb;
+
+/// Time for the compositor to pick up a newly created device, events sent
+/// before it did are lost.
+const DEVICE_SETTLE: Duration = Duration::from_millis(300);
+
+/// Pause between key events so applications don't drop or reorder them.
+const KEY_DELAY: Duration = Duration::from_millis(2);
+
+/// XKB keycodes are evdev key codes offset by 8.
+const XKB_KEYCODE_OFFSET: u32 = 8;
+
+/// Name of the virtual keyboard, so readers of key events can leave out
+/// what it types.
+pub const DEVICE_NAME: &str = "Handless Virtual Keyboard";
+
+/// Created on first use and kept, since every new device has to settle.
+static KEYBOARD: Mutex<Option<UinputKeyboard>> = Mutex::new(None);
+
+/// The key and modifiers producing a character.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct KeyPress {
+    code: KeyCode,
+    modifiers: &'static [KeyCode],
+}
+
+/// Modifier masks to look up characters with, from the fewest keys held.
+const LEVELS: [(bool, bool, &[KeyCode]); 4] = [
+    (false, false, &[]),
+    (true, false, &[KeyCode::KEY_LEFTSHIFT]),
+    (false, true, &[KeyCode::KEY_RIGHTALT]),
+    (true, true, &[KeyCode::KEY_LEFTSHIFT, KeyCode::KEY_RIGHTALT]),
+];
+
+/// Keys Ctrl+Shift+U hex input needs in the layout.
+const HEX_INPUT_KEYS: &str = "u0123456789abcdef";
+
+struct UinputKeyboard {
+    device: VirtualDevice,
+    keys: HashMap<char, KeyPress>,
+    /// Whether characters the layout lacks can be entered as hex input
+    hex_input: bool,
+}
+
+impl UinputKeyboard {
+    fn new() -> Result<Self, String> {
+        let keys = layout_keys()?;
+
+        let mut supported = AttributeSet::<KeyCode>::new();
+        for press in keys.values() {
+            supported.insert(press.code);
+            for modifier in press.modifiers {
+                supported.insert(*modifier);
+            }
+        }
+        for code in [
+            KeyCode::KEY_LEFTCTRL,
+            KeyCode::KEY_LEFTSHIFT,
+            KeyCode::KEY_BACKSPACE,
+            KeyCode::KEY_ENTER,
+            KeyCode::KEY_SPACE,
+        ] {
+            supported.insert(code);
+        }
+
+        let device = VirtualDevice::builder()
+            .and_then(|builder| builder.name(DEVICE_NAME).with_keys(&supported))
+            .and_then(|builder| builder.build())
+            .map_err(|e| format!("Failed to create virtual keyboard: {}", e))?;
+        info!("Created uinput virtual keyboard");
+        thread::sleep(DEVICE_SETTLE);
+
+        let hex_input = HEX_INPUT_KEYS.chars().all(|c| keys.contains_key(&c))
+            && ibus_is_input_method(|name| std::env::var(name).ok());
+        debug!("Unicode hex input available: {}", hex_input);
+
+        Ok(Self {
+            device,
+            keys,
+            hex_input,
+        })
+    }
+
+    fn emit(&mut self, codes: &[KeyCode], value: i32) -> Result<(), String> {
+        let events: Vec<InputEvent> = codes
+            .iter()
+            .map(|code| InputEvent::new(EventType::KEY.0, code.code(), value))
+            .collect();
+        self.device
+            .emit(&events)
+            .map_err(|e| format!("Failed to send key events: {}", e))?;
+        thread::sleep(KEY_DELAY);
+        Ok(())
+    }
+
+    /// Press and release a key while holding its modifiers.
+    fn tap(&mut self, press: KeyPress) -> Result<(), String> {
+        let mut down = press.modifiers.to_vec();
+        down.push(press.code);
+        self.emit(&down, 1)?;
+        down.reverse();
+        self.emit(&down, 0)
+    }
+
+    fn key_for(&self, c: char) -> Result<KeyPress, String> {
+        self.keys
+            .get(&c)
+            .copied()
+            .ok_or_else(|| format!("'{}' can't be typed with the keyboard layout", c))
+    }
+
+    fn can_type(&self, c: char) -> bool {
+        self.hex_input || self.keys.contains_key(&c)
+    }
+
+    fn type_char(&mut self, c: char) -> Result<(), String> {
+        match self.keys.get(&c).copied() {
+            Some(press) => self.tap(press),
+            None => self.type_unicode(c),
+        }
+    }
+
+    /// Enter a character by its code point with Ctrl+Shift+U, hex digits and
+    /// Space.
+    fn type_unicode(&mut self, c: char) -> Result<(), String> {
+        debug!("Typing U+{:04X} as hex input", c as u32);
+        let u = self.key_for('u')?;
+        self.tap(KeyPress {
+            code: u.code,
+            modifiers: &[KeyCode::KEY_LEFTCTRL, KeyCode::KEY_LEFTSHIFT],
+        })?;
+        for digit in format!("{:x}", c as u32).chars() {
+            let press = self.key_for(digit)?;
+            self.tap(press)?;
+        }
+        self.tap(KeyPress {
+            code: KeyCode::KEY_SPACE,
+            modifiers: &[],
+        })
+    }
+}
+
+fn with_keyboard(f: impl FnOnce(&mut UinputKeyboard) -> Result<(), String>) -> Result<(), String> {
+    let mut keyboard = KEYBOARD.lock().unwrap();
+    if keyboard.is_none() {
+        *keyboard = Some(UinputKeyboard::new()?);
+    }
+    f(keyboard.as_mut().unwrap())
+}
+
+/// Whether the current user may create virtual devices.
+pub fn is_available() -> bool {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/uinput")
+        .is_ok()
+}
+
+/// Type `text` on the virtual keyboard. Fails without typing anything if
+/// a character can't be typed.
+pub fn type_text(text: &str) -> Result<(), String> {
+    with_keyboard(|keyboard| {
+        if let Some(c) = text.chars().find(|c| !keyboard.can_type(*c)) {
+            return Err(format!(
+                "'{}' can't be typed with the keyboard layout, and Unicode input needs IBus",
+                c
+            ));
+        }
+        for c in text.chars() {
+            keyboard.type_char(c)?;
+        }
+        Ok(())
+    })
+}
+
+/// Press Backspace `count` times.
+pub fn press_backspaces(count: usize) -> Result<(), String> {
+    with_keyboard(|keyboard| {
+        for _ in 0..count {
+            keyboard.tap(KeyPress {
+                code: KeyCode::KEY_BACKSPACE,
+                modifiers: &[],
+            })?;
+        }
+        Ok(())
+    })
+}
+
+/// Press Ctrl with the key producing `c`, e.g. Ctrl+Z for undo.
+pub fn press_ctrl_with(c: char) -> Result<(), String> {
+    with_keyboard(|keyboard| {
+        let key = keyboard.key_for(c)?;
+        keyboard.tap(KeyPress {
+            code: key.code,
+            modifiers: &[KeyCode::KEY_LEFTCTRL],
+        })
+    })
+}
+
+/// Whether IBus handles input, which is what turns Ctrl+Shift+U and hex
+/// digits into a character in every toolkit. GNOME always runs it.
+fn ibus_is_input_method(var: impl Fn(&str) -> Option<String>) -> bool {
+    let uses_ibus = |name: &str| var(name).is_some_and(|value| value.contains("ibus"));
+    uses_ibus("GTK_IM_MODULE")
+        || uses_ibus("QT_IM_MODULE")
+        || uses_ibus("XMODIFIERS")
+        || var("XDG_CURRENT_DESKTOP").is_some_and(|desktop| desktop.contains("GNOME"))
+}
+
+/// Keys producing each character without modifiers in the user's layout.
+pub fn unmodified_keys() -> HashMap<char, KeyCode> {
+    match layout_keys() {
+        Ok(keys) => keys
+            .into_iter()
+            .filter(|(_, press)| press.modifiers.is_empty())
+            .map(|(c, press)| (c, press.code))
+            .collect(),
+        Err(e) => {
+            warn!("Failed to read the keyboard layout: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Map every character of the user's layout to the key producing it.
+fn layout_keys() -> Result<HashMap<char, KeyPress>, String> {
+    let (layout, variant) = session_layout();
+    info!(
+        "Compiling keymap for layout '{}' variant '{}'",
+        layout, variant
+    );
+
+    // Empty names fall back to XKB_DEFAULT_* and then the "us" layout
+    let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+    let keymap = xkb::Keymap::new_from_names(
+        &context,
+        "",
+        "",
+        &layout,
+        &variant,
+        None,
+        xkb::KEYMAP_COMPILE_NO_FLAGS,
+    )
+    .ok_or_else(|| format!("Failed to compile keymap for layout '{}'", layout))?;
+
+    // Shift and Mod5, which AltGr sets in nearly all layouts, are real
+    // modifiers that every keymap has
+    let shift = 1 << keymap.mod_get_index(xkb::MOD_NAME_SHIFT);
+    let level_three = 1 << keymap.mod_get_index("Mod5");
+
+    let mut keys = HashMap::new();
+    keys.insert(
+        '\n',
+        KeyPress {
+            code: KeyCode::KEY_ENTER,
+            modifiers: &[],
+        },
+    );
+    for (with_shift, with_level_three, modifiers) in LEVELS {
+        let mut mask = 0;
+        if with_shift {
+            mask |= shift;
+        }
+        if with_level_three {
+            mask |= level_three;
+        }
+        let mut state = xkb::State::new(&keymap);
+        state.update_mask(mask, 0, 0, 0, 0, 0);
+
+        for raw in keymap.min_keycode().raw()..=keymap.max_keycode().raw() {
+            let Some(c) = char::from_u32(state.key_get_utf32(xkb::Keycode::new(raw))) else {
+                continue;
+            };
+            if c == '\0' || (c.is_control() && c != '\t') || raw < XKB_KEYCODE_OFFSET {
+                continue;
+            }
+            keys.entry(c).or_insert(KeyPress {
+                code: KeyCode::new((raw - XKB_KEYCODE_OFFSET) as u16),
+                modifiers,
+            });
+        }
+    }
+    Ok(keys)
+}
+
+/// The layout and variant of the session, or empty strings to use
+/// xkbcommon's defaults. Desktops keep their own input sources, which can
+/// differ from the system configuration `localectl` reports.
+fn session_layout() -> (String, String) {
+    if std::env::var("XKB_DEFAULT_LAYOUT").is_ok() {
+        return (String::new(), String::new());
+    }
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    let from_desktop = if desktop.contains("GNOME") {
+        gnome_layout()
+    } else if desktop.contains("KDE") {
+        kde_layout()
+    } else {
+        None
+    };
+    from_desktop.unwrap_or_else(system_layout)
+}
+
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The first keyboard layout among GNOME's input sources, most recently
+/// used first.
+fn gnome_layout() -> Option<(String, String)> {
+    ["mru-sources", "sources"].into_iter().find_map(|key| {
+        let value = command_stdout(
+            "gsettings",
+            &["get", "org.gnome.desktop.input-sources", key],
+        )?;
+        parse_gnome_sources(&value)
+    })
+}
+
+/// Parse a list of input sources such as
+/// `[('ibus', 'mozc-jp'), ('xkb', 'de+nodeadkeys')]`.
+fn parse_gnome_sources(value: &str) -> Option<(String, String)> {
+    value.split('(').skip(1).find_map(|source| {
+        let mut quoted = source.split('\'').skip(1).step_by(2);
+        if quoted.next()? != "xkb" {
+            return None;
+        }
+        let name = quoted.next()?;
+        let (layout, variant) = name.split_once('+').unwrap_or((name, ""));
+        Some((layout.to_string(), variant.to_string()))
+    })
+}
+
+/// The layouts configured in KDE Plasma.
+fn kde_layout() -> Option<(String, String)> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    parse_kxkbrc(&std::fs::read_to_string(config_dir.join("kxkbrc")).ok()?)
+}
+
+/// Parse the `[Layout]` group of `kxkbrc`, which only applies with
+/// `Use=true`.
+fn parse_kxkbrc(config: &str) -> Option<(String, String)> {
+    let mut in_layout = false;
+    let mut used = false;
+    let mut layout = String::new();
+    let mut variant = String::new();
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_layout = line == "[Layout]";
+            continue;
+        }
+        if !in_layout {
+            continue;
+        }
+        match line.split_once('=') {
+            Some(("Use", value)) => used = value.trim() == "true",
+            Some(("LayoutList", value)) => layout = value.trim().to_string(),
+            Some(("VariantList", value)) => variant = value.trim().to_string(),
+            _ => {}
+        }
+    }
+    (used && !layout.is_empty()).then_some((layout, variant))
+}
+
+/// The system keyboard configuration.
+fn system_layout() -> (String, String) {
+    command_stdout("localectl", &["status"])
+        .map(|status| parse_localectl(&status))
+        .unwrap_or_default()
+}
+
+fn parse_localectl(status: &str) -> (String, String) {
+    let mut layout = String::new();
+    let mut variant = String::new();
+    for line in status.lines() {
+        match line.trim().split_once(':') {
+            Some(("X11 Layout", value)) => layout = value.trim().to_string(),
+            Some(("X11 Variant", value)) => variant = value.trim().to_string(),
+            _ => {}
+        }
+    }
+    (layout, variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_layout_and_variant_from_localectl() {
+        let status = "   System Locale: LANG=de_DE.UTF-8\n       VC Keymap: de-latin1\n      X11 Layout: de,us\n     X11 Variant: nodeadkeys,\n";
+        assert_eq!(
+            parse_localectl(status),
+            ("de,us".to_string(), "nodeadkeys,".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_the_first_keyboard_layout_of_gnome_input_sources() {
+        assert_eq!(
+            parse_gnome_sources("[('ibus', 'mozc-jp'), ('xkb', 'de+nodeadkeys'), ('xkb', 'us')]\n"),
+            Some(("de".to_string(), "nodeadkeys".to_string()))
+        );
+        assert_eq!(
+            parse_gnome_sources("[('xkb', 'fr')]"),
+            Some(("fr".to_string(), String::new()))
+        );
+        assert_eq!(parse_gnome_sources("@a(ss) []"), None);
+    }
+
+    #[test]
+    fn reads_kde_layouts_only_when_used() {
+        let config = "[$Version]\nupdate_info=kxkb.upd:remove-empty-lists\n\n[Layout]\nLayoutList=ru,us\nUse=true\nVariantList=,\n";
+        assert_eq!(
+            parse_kxkbrc(config),
+            Some(("ru,us".to_string(), ",".to_string()))
+        );
+        assert_eq!(parse_kxkbrc(&config.replace("Use=true", "Use=false")), None);
+    }
+
+    #[test]
+    fn hex_input_needs_ibus() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert!(ibus_is_input_method(env(&[("GTK_IM_MODULE", "ibus")])));
+        assert!(ibus_is_input_method(env(&[(
+            "XDG_CURRENT_DESKTOP",
+            "ubuntu:GNOME"
+        )])));
+        assert!(!ibus_is_input_method(env(&[("GTK_IM_MODULE", "fcitx")])));
+        assert!(!ibus_is_input_method(env(&[(
+            "XDG_CURRENT_DESKTOP",
+            "KDE"
+        )])));
+    }
+
+    #[test]
+    fn missing_layout_uses_defaults() {
+        let status = "   System Locale: LANG=C.UTF-8\n       VC Keymap: n/a\n";
+        assert_eq!(parse_localectl(status), (String::new(), String::new()));
+    }
+}
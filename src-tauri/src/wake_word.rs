@@ -0,0 +1,150 @@
+//! Offline wake-word activation.
+//!
+//! While the microphone is open and idle, the recorder cuts short, isolated
+//! utterances out of the stream (see `UtteranceSegmenter`) and checks each one
+//! on a spotting thread with a [`KeywordSpotter`], so inference never holds
+//! up the audio thread. [`PhraseSpotter`] runs a
+//! small Moonshine model of its own, loaded outside the transcription model
+//! pool, so listening never evicts the dictation model or holds its engine
+//! while the user dictates. When it hears the configured wake phrase, a
+//! recording starts through the coordinator like any other trigger. Nothing
+//! here talks to a cloud provider.
+
+use anyhow::{anyhow, bail, Result};
+use log::{debug, info};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use transcribe_rs::{
+    engines::moonshine::{
+        ModelVariant, MoonshineEngine, MoonshineModelParams, MoonshineStreamingEngine,
+        StreamingModelParams,
+    },
+    TranscriptionEngine,
+};
+
+use crate::audio_toolkit::{contains_wake_phrase, KeywordSpotter};
+use crate::managers::audio::AudioRecordingManager;
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::settings::AppSettings;
+use crate::signal_handle::send_transcription_input;
+
+/// Binding a wake phrase starts.
+const WAKE_WORD_BINDING: &str = "transcribe";
+
+/// Model used for spotting when none is configured.
+pub const DEFAULT_WAKE_WORD_MODEL: &str = "moonshine-tiny-streaming-en";
+
+enum SpotterEngine {
+    Moonshine(MoonshineEngine),
+    MoonshineStreaming(MoonshineStreamingEngine),
+}
+
+/// Spots the wake phrase by transcribing candidates with a dedicated small
+/// model.
+pub struct PhraseSpotter {
+    engine: SpotterEngine,
+    phrase: String,
+}
+
+impl PhraseSpotter {
+    /// Load the wake-word model from the settings.
+    pub fn load(model_manager: &ModelManager, settings: &AppSettings) -> Result<Self> {
+        let model_id = settings
+            .wake_word_model
+            .as_deref()
+            .unwrap_or(DEFAULT_WAKE_WORD_MODEL);
+        let model_info = check_model(model_manager, model_id)?;
+        let model_path = model_manager.get_model_path(model_id)?;
+
+        let load_error = |e: &dyn std::fmt::Display| {
+            anyhow!("Failed to load wake-word model {}: {}", model_id, e)
+        };
+        let engine = match model_info.engine_type {
+            EngineType::Moonshine => {
+                let mut engine = MoonshineEngine::new();
+                engine
+                    .load_model_with_params(
+                        &model_path,
+                        MoonshineModelParams::variant(ModelVariant::Base),
+                    )
+                    .map_err(|e| load_error(&e))?;
+                SpotterEngine::Moonshine(engine)
+            }
+            EngineType::MoonshineStreaming => {
+                let mut engine = MoonshineStreamingEngine::new();
+                engine
+                    .load_model_with_params(&model_path, StreamingModelParams::default())
+                    .map_err(|e| load_error(&e))?;
+                SpotterEngine::MoonshineStreaming(engine)
+            }
+            _ => bail!("{} can't listen for a wake phrase", model_info.name),
+        };
+
+        info!("Loaded wake-word model {}", model_id);
+        Ok(Self {
+            engine,
+            phrase: settings.wake_word_phrase.clone(),
+        })
+    }
+}
+
+/// Check that `model_id` can listen for the wake phrase. Only the Moonshine
+/// models are small and fast enough to keep up with speech.
+pub fn check_model(model_manager: &ModelManager, model_id: &str) -> Result<ModelInfo> {
+    let model_info = model_manager
+        .get_model_info(model_id)
+        .ok_or_else(|| anyhow!("Model not found: {}", model_id))?;
+    if !matches!(
+        model_info.engine_type,
+        EngineType::Moonshine | EngineType::MoonshineStreaming
+    ) {
+        bail!(
+            "{} is too large to listen for a wake phrase; choose a Moonshine model",
+            model_info.name
+        );
+    }
+    if !model_info.is_downloaded {
+        bail!("Wake-word model {} is not downloaded", model_info.name);
+    }
+    Ok(model_info)
+}
+
+impl KeywordSpotter for PhraseSpotter {
+    fn detect(&mut self, utterance: &[f32]) -> Result<bool> {
+        let result = match &mut self.engine {
+            SpotterEngine::Moonshine(engine) => engine.transcribe_samples(utterance.to_vec(), None),
+            SpotterEngine::MoonshineStreaming(engine) => {
+                engine.transcribe_samples(utterance.to_vec(), None)
+            }
+        }
+        .map_err(|e| anyhow!("Wake-word transcription failed: {}", e))?;
+        debug!("Wake-word candidate: {:?}", result.text);
+        Ok(contains_wake_phrase(&result.text, &self.phrase))
+    }
+}
+
+impl Drop for PhraseSpotter {
+    fn drop(&mut self) {
+        match &mut self.engine {
+            SpotterEngine::Moonshine(engine) => engine.unload_model(),
+            SpotterEngine::MoonshineStreaming(engine) => engine.unload_model(),
+        }
+    }
+}
+
+/// Start a recording after the wake phrase was heard. Called from the
+/// spotting thread, which must not wait on the recording manager.
+pub fn on_wake_phrase(app: &AppHandle) {
+    info!("Wake phrase detected");
+    let app = app.clone();
+    std::thread::spawn(move || {
+        // A recording may have started while the candidate was checked; a
+        // toggle input would stop it again.
+        let recording = app
+            .try_state::<Arc<AudioRecordingManager>>()
+            .is_some_and(|rm| rm.is_recording());
+        if !recording {
+            send_transcription_input(&app, WAKE_WORD_BINDING, "wake-word");
+        }
+    });
+}
@@ -77,6 +77,14 @@ async changeSelectedLanguageSetting(language: string) : Promise<Result<null, str
     else return { status: "error", error: e  as any };
 }
 },
+async changeLanguageRoutingSetting(enabled: boolean, routes: LanguageRoute[], languageIdModel: string | null, languageIdSeconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_language_routing_setting", { enabled, routes, languageIdModel, languageIdSeconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeOverlayPositionSetting(position: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_overlay_position_setting", { position }) };
@@ -85,6 +93,14 @@ async changeOverlayPositionSetting(position: string) : Promise<Result<null, stri
     else return { status: "error", error: e  as any };
 }
 },
+async changeErrorNotificationsSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_error_notifications_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeDebugModeSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_debug_mode_setting", { enabled }) };
@@ -136,6 +152,14 @@ async changeClipboardHandlingSetting(handling: string) : Promise<Result<null, st
     else return { status: "error", error: e  as any };
 }
 },
+async changeClipboardRestoreDelaySetting(delayMs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_clipboard_restore_delay_setting", { delayMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAutoSubmitSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_auto_submit_setting", { enabled }) };
@@ -176,6 +200,53 @@ async updateBindingPrompt(id: string, promptId: string | null) : Promise<Result<
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Set or clear the activation mode override of a binding. `None` makes the
+ * binding follow the global activation mode again.
+ */
+async updateBindingActivationMode(id: string, mode: ActivationMode | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_binding_activation_mode", { id, mode }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set or clear the mouse button or pedal that triggers a binding.
+ */
+async updateBindingDeviceTrigger(id: string, trigger: DeviceTrigger | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_binding_device_trigger", { id, trigger }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set where a binding's transcriptions are sent and whether they're still
+ * pasted.
+ */
+async updateBindingOutputSinks(id: string, sinks: OutputSink[], skipPaste: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_binding_output_sinks", { id, sinks, skipPaste }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Wait for the next button press on any input device and return it as a
+ * trigger. Triggers are paused meanwhile so grabbed devices can be captured.
+ */
+async captureDeviceTrigger() : Promise<Result<DeviceTrigger, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("capture_device_trigger") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePostProcessBaseUrlSetting(providerId: string, baseUrl: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_post_process_base_url_setting", { providerId, baseUrl }) };
@@ -403,6 +474,14 @@ async changeSttRealtimeEnabledSetting(providerId: string, enabled: boolean) : Pr
     else return { status: "error", error: e  as any };
 }
 },
+async changeSttFallbackSetting(chain: SttFallbackStep[], requestTimeoutSecs: number, maxRetries: number, retryBackoffMs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_stt_fallback_setting", { chain, requestTimeoutSecs, maxRetries, retryBackoffMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Start key recording mode
  */
@@ -442,6 +521,30 @@ async cancelOperation() : Promise<void> {
 async confirmRecording() : Promise<void> {
     await TAURI_INVOKE("confirm_recording");
 },
+/**
+ * Turn hands-free continuous dictation on or off.
+ */
+async setHandsFreeMode(enabled: boolean) : Promise<void> {
+    await TAURI_INVOKE("set_hands_free_mode", { enabled });
+},
+async getHandsFreeMode() : Promise<boolean> {
+    return await TAURI_INVOKE("get_hands_free_mode");
+},
+/**
+ * Start a meeting recording, or stop and transcribe the running one.
+ * Returns whether a meeting is running afterwards.
+ */
+async toggleMeeting() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_meeting") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async isMeetingActive() : Promise<boolean> {
+    return await TAURI_INVOKE("is_meeting_active");
+},
 async getAppDirPath() : Promise<Result<string, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_app_dir_path") };
@@ -532,6 +635,13 @@ async checkAppleIntelligenceAvailable() : Promise<boolean> {
 async isHomebrewInstall() : Promise<boolean> {
     return await TAURI_INVOKE("is_homebrew_install");
 },
+/**
+ * Whether Fcitx is the input method framework, which the input method paste
+ * method doesn't support.
+ */
+async isFcitxActive() : Promise<boolean> {
+    return await TAURI_INVOKE("is_fcitx_active");
+},
 /**
  * Try to initialize Enigo (keyboard/mouse simulation).
  * On macOS, this will return an error if accessibility permissions are not granted.
@@ -669,6 +779,43 @@ async updateMicrophoneMode(alwaysOn: boolean) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async updatePreRollSettings(preRollMs: number, warmStandbySecs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_pre_roll_settings", { preRollMs, warmStandbySecs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateAutoStopSettings(enabled: boolean, silenceMs: number, minSpeechMs: number, maxDurationSecs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_auto_stop_settings", { enabled, silenceMs, minSpeechMs, maxDurationSecs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateWakeWordSettings(enabled: boolean, phrase: string, modelId: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_wake_word_settings", { enabled, phrase, modelId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Configure meeting recording: the monitor source to capture (`None` for
+ * the monitor of the default output), whether to mix in the microphone,
+ * and how many seconds of audio each transcription chunk holds.
+ */
+async updateMeetingSettings(source: string | null, includeMicrophone: boolean, chunkSecs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_meeting_settings", { source, includeMicrophone, chunkSecs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getMicrophoneMode() : Promise<Result<boolean, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_microphone_mode") };
@@ -685,6 +832,39 @@ async getAvailableMicrophones() : Promise<Result<AudioDevice[], string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * System audio sources (PulseAudio/PipeWire monitors) that meeting
+ * recording can capture. Empty where loopback capture is unsupported.
+ */
+async getLoopbackSources() : Promise<AudioDevice[]> {
+    return await TAURI_INVOKE("get_loopback_sources");
+},
+/**
+ * Set the capture processing for one input device (`Default` for the
+ * system default). Applied the next time the microphone opens, or right
+ * away when it is open and idle.
+ */
+async setInputProcessing(deviceName: string, processing: InputProcessing) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_input_processing", { deviceName, processing }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Record a few seconds from `device` (the system default when `None`) and
+ * report levels, clipping, noise floor and how much of it the VAD heard as
+ * speech. With `playback`, the capture is played back afterwards.
+ */
+async testMicrophone(device: string | null, seconds: number, playback: boolean) : Promise<Result<MicrophoneTest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_microphone", { device, seconds, playback }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setMicrophonePriority(priority: string[]) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("set_microphone_priority", { priority }) };
@@ -789,6 +969,19 @@ async unloadModelManually() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Override the unload timeout for a single model. `None` reverts it to the global timeout.
+ */
+async setModelUnloadTimeoutOverride(modelId: string, timeout: ModelUnloadTimeout | null) : Promise<void> {
+    await TAURI_INVOKE("set_model_unload_timeout_override", { modelId, timeout });
+},
+/**
+ * Set how much memory (in MB) resident models may use before the least
+ * recently used ones are evicted. Zero keeps only the active model loaded.
+ */
+async setModelPoolBudget(budgetMb: number) : Promise<void> {
+    await TAURI_INVOKE("set_model_pool_budget", { budgetMb });
+},
 async getHistoryEntries() : Promise<Result<HistoryEntry[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_history_entries") };
@@ -829,6 +1022,42 @@ async deleteHistoryEntry(id: number) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Transcribe the stored audio of a history entry again, e.g. after the
+ * original attempt failed, and replace its transcription.
+ */
+async retryHistoryEntryTranscription(id: number) : Promise<Result<HistoryEntry, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("retry_history_entry_transcription", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Split the stored audio of a history entry into speaker turns and save
+ * them on the entry.
+ */
+async diarizeHistoryEntry(id: number) : Promise<Result<HistoryEntry, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diarize_history_entry", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether meeting recordings are diarized once transcribed, and how many
+ * speakers the experimental local diarization may tell apart.
+ */
+async updateDiarizationSettings(meetingDiarization: boolean, maxSpeakers: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_diarization_settings", { meetingDiarization, maxSpeakers }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateHistoryLimit(limit: number) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_history_limit", { limit }) };
@@ -912,7 +1141,7 @@ async isLaptop() : Promise<Result<boolean, string>> {
 /** user-defined types **/
 
 export type ActivationMode = "toggle" | "hold" | "hold_or_toggle"
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; activation_mode?: ActivationMode; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; microphone_priority?: string[]; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; auto_submit?: boolean; auto_submit_key?: AutoSubmitKey; stt_provider_id?: string; stt_providers?: SttProvider[]; stt_api_keys?: Partial<{ [key in string]: string }>; stt_cloud_models?: Partial<{ [key in string]: string }>; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; keyboard_implementation?: KeyboardImplementation; show_tray_icon?: boolean; paste_delay_ms?: number; typing_tool?: TypingTool; external_script_path: string | null; app_theme?: AppTheme; stt_verified_providers?: string[]; post_process_verified_providers?: string[]; post_process_input_prices?: Partial<{ [key in string]: number }>; post_process_output_prices?: Partial<{ [key in string]: number }>; stt_cloud_options?: Partial<{ [key in string]: string }>; stt_realtime_enabled?: Partial<{ [key in string]: boolean }>; stats_date_range?: StatsDateRange; dictionary_terms?: string[]; dictionary_context?: string }
+export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; activation_mode?: ActivationMode; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; pre_roll_ms?: number; warm_standby_secs?: number; auto_stop_enabled?: boolean; auto_stop_silence_ms?: number; auto_stop_min_speech_ms?: number; auto_stop_max_duration_secs?: number; wake_word_enabled?: boolean; wake_word_phrase?: string; wake_word_model?: string | null; meeting_source?: string | null; meeting_include_microphone?: boolean; meeting_chunk_secs?: number; meeting_diarization?: boolean; diarization_max_speakers?: number; selected_microphone?: string | null; microphone_priority?: string[]; clamshell_microphone?: string | null; input_processing?: Partial<{ [key in string]: InputProcessing }>; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; language_routing_enabled?: boolean; language_routes?: LanguageRoute[]; language_id_model?: string | null; language_id_seconds?: number; overlay_position?: OverlayPosition; error_notifications_enabled?: boolean; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; model_unload_timeouts?: Partial<{ [key in string]: ModelUnloadTimeout }>; model_pool_budget_mb?: number; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; auto_submit?: boolean; auto_submit_key?: AutoSubmitKey; stt_provider_id?: string; stt_providers?: SttProvider[]; stt_api_keys?: Partial<{ [key in string]: string }>; stt_cloud_models?: Partial<{ [key in string]: string }>; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; keyboard_implementation?: KeyboardImplementation; show_tray_icon?: boolean; paste_delay_ms?: number; clipboard_restore_delay_ms?: number; typing_tool?: TypingTool; external_script_path: string | null; app_theme?: AppTheme; stt_verified_providers?: string[]; post_process_verified_providers?: string[]; post_process_input_prices?: Partial<{ [key in string]: number }>; post_process_output_prices?: Partial<{ [key in string]: number }>; stt_cloud_options?: Partial<{ [key in string]: string }>; stt_realtime_enabled?: Partial<{ [key in string]: boolean }>; stt_fallback_chain?: SttFallbackStep[]; stt_request_timeout_secs?: number; stt_max_retries?: number; stt_retry_backoff_ms?: number; stats_date_range?: StatsDateRange; dictionary_terms?: string[]; dictionary_context?: string }
 export type AppTheme = "dark" | "light" | "system"
 export type AudioDevice = { index: string; name: string; is_default: boolean }
 export type AutoSubmitKey = "enter" | "ctrl_enter" | "cmd_enter"
@@ -922,8 +1151,42 @@ export type CloudOptionType = { type: "Text" } | { type: "Number"; min: number;
 export type CloudProviderOption = { key: string; label: string; option_type: CloudOptionType; description: string }
 export type CustomSounds = { start: boolean; stop: boolean }
 export type DailySpeakingStats = { date: string; total_word_count: number; total_duration_ms: number; transcription_count: number; avg_wpm: number }
+/**
+ * A key or button on one input device, such as an extra mouse button or a
+ * foot pedal.
+ */
+export type DeviceTrigger = { 
+/**
+ * Device name as reported by the kernel
+ */
+device: string; 
+/**
+ * evdev key code
+ */
+code: number; 
+/**
+ * Name of the key code, e.g. `BTN_SIDE`
+ */
+label: string; 
+/**
+ * Take the device exclusively so its events don't reach other apps
+ */
+grab?: boolean }
 export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | "MoonshineStreaming" | "SenseVoice"
-export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
+export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null; status?: TranscriptionStatus; error?: string | null; 
+/**
+ * Speaker turns, once the entry has been diarized.
+ */
+segments?: TranscriptSegment[] | null; 
+/**
+ * Whether the inserted text was taken back with "undo dictation".
+ */
+retracted?: boolean; 
+/**
+ * The shortcut binding the recording was made with, whose
+ * post-processing prompt also applies when it's transcribed again.
+ */
+binding_id?: string | null }
 export type HistoryPage = { entries: HistoryEntry[]; total_count: number }
 /**
  * Result of changing keyboard implementation
@@ -934,28 +1197,92 @@ export type ImplementationChangeResult = { success: boolean;
  */
 reset_bindings: string[] }
 export type ImportPreview = { export_version: number; app_version: string; platform: string; timestamp: number; has_settings: boolean; includes_recordings: boolean; history_count: number; stats_count: number; recording_files_count: number }
-export type KeyboardImplementation = "tauri" | "handy_keys"
+/**
+ * Capture processing for one input device. Stored per device name, with
+ * `Default` standing for the system default device.
+ */
+export type InputProcessing = { high_pass?: boolean; noise_suppression?: boolean; auto_gain?: boolean }
+export type KeyboardImplementation = "tauri" | "handy_keys" | "wayland"
+/**
+ * Sends transcriptions in a given language to a preferred provider/model.
+ * A `language` of `*` matches any language the default model cannot handle.
+ */
+export type LanguageRoute = { language: string; provider_id: string; model_id?: string | null }
 export type LLMPrompt = { id: string; name: string; prompt: string }
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
+/**
+ * Result of a short test capture from one input device.
+ */
+export type MicrophoneTest = { device_name: string; sample_rate: number; channels: number; sample_format: string; duration_secs: number; peak_dbfs: number; rms_dbfs: number; clipped_percent: number; noise_floor_dbfs: number; snr_db: number; 
+/**
+ * Share of 30 ms frames (0..=1) the VAD classified as speech.
+ */
+speech_ratio: number }
 export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; supports_translation: boolean; is_recommended: boolean; supported_languages: string[]; is_custom: boolean }
-export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
+export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null; 
+/**
+ * All resident models, most recently used first.
+ */
+loaded_models: string[] }
 /**
  * Pricing info returned to the frontend ($/M tokens).
  */
 export type ModelPricing = { input: number; output: number }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
+/**
+ * A destination transcriptions are delivered to after they're finished.
+ */
+export type OutputSink = { type: "file"; path: string; template: string } | { type: "webhook"; url: string } | { type: "command"; command: string }
 export type OverlayPosition = "none" | "top" | "bottom"
-export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v" | "external_script"
+export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v" | "external_script" | "input_method"
 export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null; supports_structured_output?: boolean }
 export type ProviderBackend = { type: "Local"; engine_type: EngineType; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; accuracy_score: number; speed_score: number; is_custom: boolean } | { type: "Cloud"; base_url: string; default_model: string; console_url: string | null }
 export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
-export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string; post_process_prompt_id?: string | null }
+export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string; post_process_prompt_id?: string | null; 
+/**
+ * Overrides the global `activation_mode` for this binding.
+ */
+activation_mode?: ActivationMode | null; 
+/**
+ * Mouse button or pedal that triggers this binding besides its shortcut.
+ */
+device_trigger?: DeviceTrigger | null; 
+/**
+ * Where this binding's transcriptions are sent besides being pasted.
+ */
+output_sinks?: OutputSink[]; 
+/**
+ * Only send transcriptions to the output sinks, without pasting them.
+ */
+skip_paste?: boolean }
 export type SoundTheme = "marimba" | "pop" | "custom"
 export type StatsDateRange = "today" | "3days" | "week" | "month" | "all" | "custom"
+/**
+ * One step of the transcription fallback chain, tried after the primary
+ * provider fails. `provider_id` is `local` or a cloud provider ID.
+ */
+export type SttFallbackStep = { provider_id: string; 
+/**
+ * Local model to use; `None` means the selected model.
+ */
+model_id?: string | null; 
+/**
+ * Per-attempt timeout for cloud providers; 0 disables it.
+ */
+timeout_secs?: number; max_retries?: number }
 export type SttProvider = { id: string; label: string; provider_type: SttProviderType; base_url: string; default_model: string }
 export type SttProviderInfo = { id: string; name: string; description: string; supported_languages: string[]; supports_translation: boolean; supports_realtime: boolean; is_recommended: boolean; backend: ProviderBackend; available_options?: CloudProviderOption[]; supports_dictionary_terms?: boolean; supports_dictionary_context?: boolean }
 export type SttProviderType = "local" | "cloud"
-export type TypingTool = "auto" | "wtype" | "kwtype" | "dotool" | "ydotool" | "xdotool"
+/**
+ * One speaker turn of a diarized transcription.
+ */
+export type TranscriptSegment = { speaker: string; start_ms: number; end_ms: number; text: string }
+/**
+ * Lifecycle of a history entry. The audio is stored as `pending` before
+ * transcription starts, so a dictation survives errors and crashes.
+ */
+export type TranscriptionStatus = "pending" | "failed" | "done"
+export type TypingTool = "auto" | "wtype" | "kwtype" | "dotool" | "ydotool" | "xdotool" | "uinput"
 
 /** tauri-specta globals **/
 
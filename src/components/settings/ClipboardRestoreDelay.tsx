@@ -0,0 +1,37 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { SliderSetting as Slider } from "../ui/SliderSetting";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ClipboardRestoreDelayProps {
+  descriptionMode?: "tooltip" | "inline";
+  grouped?: boolean;
+}
+
+export const ClipboardRestoreDelay: React.FC<ClipboardRestoreDelayProps> = ({
+  descriptionMode = "tooltip",
+  grouped = false,
+}) => {
+  const { t } = useTranslation();
+  const { settings, updateSetting } = useSettings();
+
+  // Restoring only happens when the clipboard is left unmodified
+  if ((settings?.clipboard_handling ?? "dont_modify") !== "dont_modify") {
+    return null;
+  }
+
+  return (
+    <Slider
+      value={settings?.clipboard_restore_delay_ms ?? 100}
+      onChange={(value) => updateSetting("clipboard_restore_delay_ms", value)}
+      min={50}
+      max={1000}
+      step={50}
+      label={t("settings.advanced.clipboardRestoreDelay.title")}
+      description={t("settings.advanced.clipboardRestoreDelay.description")}
+      descriptionMode={descriptionMode}
+      grouped={grouped}
+      formatValue={(v) => `${v}ms`}
+    />
+  );
+};
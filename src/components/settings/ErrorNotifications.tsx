@@ -0,0 +1,31 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ErrorNotificationsProps {
+  descriptionMode?: "inline" | "tooltip";
+  grouped?: boolean;
+}
+
+export const ErrorNotifications: React.FC<ErrorNotificationsProps> =
+  React.memo(({ descriptionMode = "tooltip", grouped = false }) => {
+    const { t } = useTranslation();
+    const { getSetting, updateSetting, isUpdating } = useSettings();
+
+    const enabled = getSetting("error_notifications_enabled") ?? true;
+
+    return (
+      <ToggleSwitch
+        checked={enabled}
+        onChange={(value) =>
+          updateSetting("error_notifications_enabled", value)
+        }
+        isUpdating={isUpdating("error_notifications_enabled")}
+        label={t("settings.advanced.errorNotifications.label")}
+        description={t("settings.advanced.errorNotifications.description")}
+        descriptionMode={descriptionMode}
+        grouped={grouped}
+      />
+    );
+  });
@@ -4,6 +4,9 @@ import {
   getKeyName,
   formatKeyCombination,
   normalizeKey,
+  MAX_SEQUENCE_STEPS,
+  SEQUENCE_SEPARATOR,
+  SEQUENCE_STEP_TIMEOUT_MS,
 } from "../../lib/utils/keyboard";
 import { SettingContainer } from "../ui/SettingContainer";
 import { ShortcutKeyBadge } from "./ShortcutKeyBadge";
@@ -36,6 +39,10 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
     useSettings();
   const [keyPressed, setKeyPressed] = useState<string[]>([]);
   const [recordedKeys, setRecordedKeys] = useState<string[]>([]);
+  // Finished steps of a sequence being recorded
+  const [recordedSteps, setRecordedSteps] = useState<string[]>([]);
+  // Commits the recorded steps unless another step follows in time
+  const commitTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const [editingShortcutId, setEditingShortcutId] = useState<string | null>(
     null,
   );
@@ -51,7 +58,15 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
 
     let cleanup = false;
 
+    const clearCommitTimer = () => {
+      if (commitTimerRef.current) {
+        clearTimeout(commitTimerRef.current);
+        commitTimerRef.current = null;
+      }
+    };
+
     const cancelRecording = async () => {
+      clearCommitTimer();
       if (autoRecord && onAutoRecordEnd) {
         onAutoRecordEnd(false);
       } else if (editingShortcutId && originalBinding) {
@@ -67,9 +82,49 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
       setEditingShortcutId(null);
       setKeyPressed([]);
       setRecordedKeys([]);
+      setRecordedSteps([]);
       setOriginalBinding("");
     };
 
+    const commitShortcut = async (newShortcut: string) => {
+      commitTimerRef.current = null;
+      if (!editingShortcutId || !bindings[editingShortcutId]) return;
+
+      let success = false;
+      try {
+        await updateBinding(editingShortcutId, newShortcut);
+        success = true;
+      } catch (error) {
+        console.error("Failed to change binding:", error);
+        toast.error(
+          t("settings.general.shortcut.errors.set", {
+            error: String(error),
+          }),
+        );
+
+        // Reset to original binding on error
+        if (originalBinding) {
+          try {
+            await updateBinding(editingShortcutId, originalBinding);
+          } catch (resetError) {
+            console.error("Failed to reset binding:", resetError);
+            toast.error(t("settings.general.shortcut.errors.reset"));
+          }
+        }
+      }
+
+      // Exit editing mode and reset states
+      setEditingShortcutId(null);
+      setKeyPressed([]);
+      setRecordedKeys([]);
+      setRecordedSteps([]);
+      setOriginalBinding("");
+
+      if (autoRecord && onAutoRecordEnd) {
+        onAutoRecordEnd(success);
+      }
+    };
+
     // Keyboard event listeners
     const handleKeyDown = async (e: KeyboardEvent) => {
       if (cleanup) return;
@@ -79,6 +134,8 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
         return;
       }
       e.preventDefault();
+      // Another combination follows: record it as the next step
+      clearCommitTimer();
 
       // Get the key with OS-specific naming and normalize it
       const rawKey = getKeyName(e, osType);
@@ -104,7 +161,7 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
       // Remove from currently pressed keys
       setKeyPressed((prev) => prev.filter((k) => k !== key));
 
-      // If no keys are pressed anymore, commit the shortcut
+      // If no keys are pressed anymore, the current step is complete
       const updatedKeyPressed = keyPressed.filter((k) => k !== key);
       if (updatedKeyPressed.length === 0 && recordedKeys.length > 0) {
         // Create the shortcut string from all recorded keys
@@ -129,41 +186,20 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
           if (!aIsModifier && bIsModifier) return 1;
           return 0;
         });
-        const newShortcut = sortedKeys.join("+");
-
-        if (editingShortcutId && bindings[editingShortcutId]) {
-          let success = false;
-          try {
-            await updateBinding(editingShortcutId, newShortcut);
-            success = true;
-          } catch (error) {
-            console.error("Failed to change binding:", error);
-            toast.error(
-              t("settings.general.shortcut.errors.set", {
-                error: String(error),
-              }),
-            );
-
-            // Reset to original binding on error
-            if (originalBinding) {
-              try {
-                await updateBinding(editingShortcutId, originalBinding);
-              } catch (resetError) {
-                console.error("Failed to reset binding:", resetError);
-                toast.error(t("settings.general.shortcut.errors.reset"));
-              }
-            }
-          }
-
-          // Exit editing mode and reset states
-          setEditingShortcutId(null);
-          setKeyPressed([]);
-          setRecordedKeys([]);
-          setOriginalBinding("");
-
-          if (autoRecord && onAutoRecordEnd) {
-            onAutoRecordEnd(success);
-          }
+        const steps = [...recordedSteps, sortedKeys.join("+")];
+
+        // Wait briefly for a further step before saving, so sequences such
+        // as a double tap can be recorded
+        setRecordedSteps(steps);
+        setRecordedKeys([]);
+        const newShortcut = steps.join(SEQUENCE_SEPARATOR);
+        if (steps.length >= MAX_SEQUENCE_STEPS) {
+          await commitShortcut(newShortcut);
+        } else {
+          commitTimerRef.current = setTimeout(
+            () => commitShortcut(newShortcut),
+            SEQUENCE_STEP_TIMEOUT_MS,
+          );
         }
       }
     };
@@ -190,6 +226,7 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
   }, [
     keyPressed,
     recordedKeys,
+    recordedSteps,
     editingShortcutId,
     bindings,
     originalBinding,
@@ -216,8 +253,17 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
     setEditingShortcutId(id);
     setKeyPressed([]);
     setRecordedKeys([]);
+    setRecordedSteps([]);
   };
 
+  // Drop a pending commit when the component goes away
+  useEffect(
+    () => () => {
+      if (commitTimerRef.current) clearTimeout(commitTimerRef.current);
+    },
+    [],
+  );
+
   // Auto-start recording when autoRecord prop is set
   useEffect(() => {
     if (autoRecord && !editingShortcutId && bindings[shortcutId]) {
@@ -227,11 +273,11 @@ export const GlobalShortcutInput: React.FC<GlobalShortcutInputProps> = ({
 
   // Format the current shortcut keys being recorded
   const formatCurrentKeys = (): string => {
-    if (recordedKeys.length === 0)
-      return t("settings.general.shortcut.pressKeys");
+    const steps = [...recordedSteps, recordedKeys.join("+")].filter(Boolean);
+    if (steps.length === 0) return t("settings.general.shortcut.pressKeys");
 
     // Use the same formatting as the display to ensure consistency
-    return formatKeyCombination(recordedKeys.join("+"), osType);
+    return formatKeyCombination(steps.join(SEQUENCE_SEPARATOR), osType);
   };
 
   // Store references to shortcut elements
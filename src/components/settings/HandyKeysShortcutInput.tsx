@@ -1,7 +1,12 @@
 import React, { useEffect, useState, useRef, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { listen } from "@tauri-apps/api/event";
-import { formatKeyCombination } from "../../lib/utils/keyboard";
+import {
+  formatKeyCombination,
+  MAX_SEQUENCE_STEPS,
+  SEQUENCE_SEPARATOR,
+  SEQUENCE_STEP_TIMEOUT_MS,
+} from "../../lib/utils/keyboard";
 import { SettingContainer } from "../ui/SettingContainer";
 import { ShortcutKeyBadge } from "./ShortcutKeyBadge";
 import { useSettings } from "../../hooks/useSettings";
@@ -45,6 +50,11 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
   const unlistenRef = useRef<(() => void) | null>(null);
   // Use a ref to track currentKeys for the event handler (avoids stale closure)
   const currentKeysRef = useRef<string>("");
+  // Finished steps of a sequence being recorded
+  const [recordedSteps, setRecordedSteps] = useState<string[]>([]);
+  const recordedStepsRef = useRef<string[]>([]);
+  // Commits the recorded steps unless another step follows in time
+  const commitTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const osType = useOsType();
 
   const bindings = getSetting("bindings") || {};
@@ -53,6 +63,11 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
   const cancelRecording = useCallback(async () => {
     if (!isRecording) return;
 
+    if (commitTimerRef.current) {
+      clearTimeout(commitTimerRef.current);
+      commitTimerRef.current = null;
+    }
+
     // Stop listening for backend events
     if (unlistenRef.current) {
       unlistenRef.current();
@@ -78,6 +93,8 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
     setIsRecording(false);
     setCurrentKeys("");
     currentKeysRef.current = "";
+    setRecordedSteps([]);
+    recordedStepsRef.current = [];
     setOriginalBinding("");
   }, [
     isRecording,
@@ -95,6 +112,49 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
 
     let cleanup = false;
 
+    const commitShortcut = async (keysToCommit: string) => {
+      commitTimerRef.current = null;
+      let success = false;
+      try {
+        await updateBinding(shortcutId, keysToCommit);
+        success = true;
+      } catch (error) {
+        console.error("Failed to change binding:", error);
+        toast.error(
+          t("settings.general.shortcut.errors.set", {
+            error: String(error),
+          }),
+        );
+
+        // Reset to original binding on error
+        if (originalBinding) {
+          try {
+            await updateBinding(shortcutId, originalBinding);
+          } catch (resetError) {
+            console.error("Failed to reset binding:", resetError);
+            toast.error(t("settings.general.shortcut.errors.reset"));
+          }
+        }
+      }
+
+      // Stop recording
+      if (unlistenRef.current) {
+        unlistenRef.current();
+        unlistenRef.current = null;
+      }
+      await commands.stopHandyKeysRecording().catch(console.error);
+      setIsRecording(false);
+      setCurrentKeys("");
+      currentKeysRef.current = "";
+      setRecordedSteps([]);
+      recordedStepsRef.current = [];
+      setOriginalBinding("");
+
+      if (autoRecord && onAutoRecordEnd) {
+        onAutoRecordEnd(success);
+      }
+    };
+
     const setupListener = async () => {
       // Listen for key events from backend
       const unlisten = await listen<HandyKeysEvent>(
@@ -105,48 +165,35 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
           const { hotkey_string, is_key_down } = event.payload;
 
           if (is_key_down && hotkey_string) {
+            // Another combination follows: record it as the next step
+            if (commitTimerRef.current) {
+              clearTimeout(commitTimerRef.current);
+              commitTimerRef.current = null;
+            }
             // Update both state (for display) and ref (for release handler)
             currentKeysRef.current = hotkey_string;
             setCurrentKeys(hotkey_string);
           } else if (!is_key_down && currentKeysRef.current) {
-            // Key released - commit the shortcut using the ref value
-            const keysToCommit = currentKeysRef.current;
-            let success = false;
-            try {
-              await updateBinding(shortcutId, keysToCommit);
-              success = true;
-            } catch (error) {
-              console.error("Failed to change binding:", error);
-              toast.error(
-                t("settings.general.shortcut.errors.set", {
-                  error: String(error),
-                }),
-              );
-
-              // Reset to original binding on error
-              if (originalBinding) {
-                try {
-                  await updateBinding(shortcutId, originalBinding);
-                } catch (resetError) {
-                  console.error("Failed to reset binding:", resetError);
-                  toast.error(t("settings.general.shortcut.errors.reset"));
-                }
-              }
-            }
-
-            // Stop recording
-            if (unlistenRef.current) {
-              unlistenRef.current();
-              unlistenRef.current = null;
-            }
-            await commands.stopHandyKeysRecording().catch(console.error);
-            setIsRecording(false);
-            setCurrentKeys("");
+            // Key released - the step is complete. Wait briefly for a
+            // further step before saving, so sequences such as a double
+            // tap can be recorded
+            const steps = [
+              ...recordedStepsRef.current,
+              currentKeysRef.current,
+            ];
+            recordedStepsRef.current = steps;
+            setRecordedSteps(steps);
             currentKeysRef.current = "";
-            setOriginalBinding("");
+            setCurrentKeys("");
 
-            if (autoRecord && onAutoRecordEnd) {
-              onAutoRecordEnd(success);
+            const keysToCommit = steps.join(SEQUENCE_SEPARATOR);
+            if (steps.length >= MAX_SEQUENCE_STEPS) {
+              await commitShortcut(keysToCommit);
+            } else {
+              commitTimerRef.current = setTimeout(
+                () => commitShortcut(keysToCommit),
+                SEQUENCE_STEP_TIMEOUT_MS,
+              );
             }
           }
         },
@@ -216,6 +263,8 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
       setIsRecording(true);
       setCurrentKeys("");
       currentKeysRef.current = "";
+      setRecordedSteps([]);
+      recordedStepsRef.current = [];
     } catch (error) {
       console.error("Failed to start recording:", error);
       toast.error(
@@ -224,6 +273,14 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
     }
   };
 
+  // Drop a pending commit when the component goes away
+  useEffect(
+    () => () => {
+      if (commitTimerRef.current) clearTimeout(commitTimerRef.current);
+    },
+    [],
+  );
+
   // Auto-start recording when autoRecord prop is set
   useEffect(() => {
     if (autoRecord && !isRecording && bindings[shortcutId]) {
@@ -233,8 +290,9 @@ export const HandyKeysShortcutInput: React.FC<HandyKeysShortcutInputProps> = ({
 
   // Format the current shortcut keys being recorded
   const formatCurrentKeys = (): string => {
-    if (!currentKeys) return t("settings.general.shortcut.pressKeys");
-    return formatKeyCombination(currentKeys, osType);
+    const steps = [...recordedSteps, currentKeys].filter(Boolean);
+    if (steps.length === 0) return t("settings.general.shortcut.pressKeys");
+    return formatKeyCombination(steps.join(SEQUENCE_SEPARATOR), osType);
   };
 
   // If still loading, show loading state
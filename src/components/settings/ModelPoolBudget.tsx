@@ -0,0 +1,36 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { SliderSetting as Slider } from "../ui/SliderSetting";
+import { useSettings } from "../../hooks/useSettings";
+
+interface ModelPoolBudgetProps {
+  descriptionMode?: "tooltip" | "inline";
+  grouped?: boolean;
+}
+
+export const ModelPoolBudget: React.FC<ModelPoolBudgetProps> = ({
+  descriptionMode = "tooltip",
+  grouped = false,
+}) => {
+  const { t } = useTranslation();
+  const { settings, updateSetting } = useSettings();
+
+  return (
+    <Slider
+      value={settings?.model_pool_budget_mb ?? 0}
+      onChange={(value) => updateSetting("model_pool_budget_mb", value)}
+      min={0}
+      max={16384}
+      step={512}
+      label={t("settings.advanced.modelPoolBudget.title")}
+      description={t("settings.advanced.modelPoolBudget.description")}
+      descriptionMode={descriptionMode}
+      grouped={grouped}
+      formatValue={(v) =>
+        v === 0
+          ? t("settings.advanced.modelPoolBudget.activeOnly")
+          : `${(v / 1024).toFixed(1)} GB`
+      }
+    />
+  );
+};
@@ -1,11 +1,11 @@
-import React from "react";
+import React, { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
-import { Dropdown } from "../ui/Dropdown";
+import { Dropdown, type DropdownOption } from "../ui/Dropdown";
 import { SettingContainer } from "../ui/SettingContainer";
 import { Input } from "../ui/Input";
 import { useSettings } from "../../hooks/useSettings";
 import { useOsType } from "../../hooks/useOsType";
-import type { PasteMethod } from "@/bindings";
+import { commands, type PasteMethod } from "@/bindings";
 
 interface PasteMethodProps {
   descriptionMode?: "inline" | "tooltip";
@@ -17,11 +17,22 @@ export const PasteMethodSetting: React.FC<PasteMethodProps> = React.memo(
     const { t } = useTranslation();
     const { getSetting, updateSetting, isUpdating } = useSettings();
     const osType = useOsType();
+    const [fcitxActive, setFcitxActive] = useState(false);
+
+    useEffect(() => {
+      if (osType !== "linux") return;
+      commands
+        .isFcitxActive()
+        .then(setFcitxActive)
+        .catch((error) =>
+          console.error("Failed to check the input method framework:", error),
+        );
+    }, [osType]);
 
     const getPasteMethodOptions = (osType: string) => {
       const mod = osType === "macos" ? "Cmd" : "Ctrl";
 
-      const options = [
+      const options: DropdownOption[] = [
         {
           value: "ctrl_v",
           label: t("settings.advanced.pasteMethod.options.clipboard", {
@@ -56,12 +67,20 @@ export const PasteMethodSetting: React.FC<PasteMethodProps> = React.memo(
         );
       }
 
-      // External script is only available on Linux
+      // External script and input method are only available on Linux
       if (osType === "linux") {
-        options.push({
-          value: "external_script",
-          label: t("settings.advanced.pasteMethod.options.externalScript"),
-        });
+        options.push(
+          {
+            value: "external_script",
+            label: t("settings.advanced.pasteMethod.options.externalScript"),
+          },
+          {
+            value: "input_method",
+            label: t("settings.advanced.pasteMethod.options.inputMethod"),
+            // Fcitx can't host the IBus engine this method registers
+            disabled: fcitxActive,
+          },
+        );
       }
 
       return options;
@@ -103,6 +122,13 @@ export const PasteMethodSetting: React.FC<PasteMethodProps> = React.memo(
               disabled={isUpdating("external_script_path")}
             />
           )}
+          {(selectedMethod === "input_method" || fcitxActive) && (
+            <p className="text-xs text-text/60">
+              {fcitxActive
+                ? t("settings.advanced.pasteMethod.inputMethodUnavailableFcitx")
+                : t("settings.advanced.pasteMethod.inputMethodHint")}
+            </p>
+          )}
         </div>
       </SettingContainer>
     );
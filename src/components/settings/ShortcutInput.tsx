@@ -21,6 +21,7 @@ interface ShortcutInputProps {
  *
  * - "tauri" (default): Uses GlobalShortcutInput with JS keyboard events
  * - "handy_keys": Uses HandyKeysShortcutInput with backend key events
+ * - "wayland": Uses GlobalShortcutInput; the window has focus while recording
  */
 export const ShortcutInput: React.FC<ShortcutInputProps> = (props) => {
   const { getSetting } = useSettings();
@@ -1,4 +1,5 @@
 import React from "react";
+import { useTranslation } from "react-i18next";
 import { ResetButton } from "../ui/ResetButton";
 
 interface ShortcutKeyBadgeProps {
@@ -19,23 +20,27 @@ export const ShortcutKeyBadge: React.FC<ShortcutKeyBadgeProps> = ({
   onStartRecording,
   onReset,
   resetDisabled,
-}) => (
-  <div className="flex items-center space-x-1">
-    {isEditing ? (
-      <div
-        ref={editingRef}
-        className="px-2 py-1 text-sm font-semibold border border-accent bg-accent/30 rounded"
-      >
-        {currentKeysDisplay}
-      </div>
-    ) : (
-      <div
-        className="px-2 py-1 text-sm font-semibold bg-muted/10 border border-muted/80 hover:bg-accent/10 rounded cursor-pointer hover:border-accent"
-        onClick={onStartRecording}
-      >
-        {bindingDisplay}
-      </div>
-    )}
-    <ResetButton onClick={onReset} disabled={resetDisabled} />
-  </div>
-);
+}) => {
+  const { t } = useTranslation();
+
+  return (
+    <div className="flex items-center space-x-1">
+      {isEditing ? (
+        <div
+          ref={editingRef}
+          className="px-2 py-1 text-sm font-semibold border border-accent bg-accent/30 rounded"
+        >
+          {currentKeysDisplay}
+        </div>
+      ) : (
+        <div
+          className="px-2 py-1 text-sm font-semibold bg-muted/10 border border-muted/80 hover:bg-accent/10 rounded cursor-pointer hover:border-accent"
+          onClick={onStartRecording}
+        >
+          {bindingDisplay || t("settings.general.shortcut.unbound")}
+        </div>
+      )}
+      <ResetButton onClick={onReset} disabled={resetDisabled} />
+    </div>
+  );
+};
@@ -50,10 +50,10 @@ export const TypingToolSetting: React.FC<TypingToolProps> = React.memo(
 
     const tools = availableTools ?? ["auto"];
     const typingToolOptions = tools.map((tool) =>
-      tool === "auto"
+      tool === "auto" || tool === "uinput"
         ? {
-            value: "auto",
-            label: t("settings.advanced.typingTool.options.auto"),
+            value: tool,
+            label: t(`settings.advanced.typingTool.options.${tool}`),
           }
         : { value: tool, label: allToolLabels[tool] ?? tool },
     );
@@ -0,0 +1,97 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Lock, LockOpen, Mouse, X } from "@phosphor-icons/react";
+import { useSettings } from "../../../hooks/useSettings";
+import { commands } from "@/bindings";
+import type { DeviceTrigger, ShortcutBinding } from "@/bindings";
+import { toast } from "sonner";
+import { SimpleTooltip } from "../../ui/Tooltip";
+
+interface DeviceTriggerButtonProps {
+  binding: ShortcutBinding;
+}
+
+/** Binds a mouse button or foot pedal to a shortcut binding */
+export const DeviceTriggerButton: React.FC<DeviceTriggerButtonProps> = ({
+  binding,
+}) => {
+  const { t } = useTranslation();
+  const { refreshSettings } = useSettings();
+  const [isCapturing, setIsCapturing] = useState(false);
+  const trigger = binding.device_trigger;
+
+  const updateTrigger = async (value: DeviceTrigger | null) => {
+    const result = await commands.updateBindingDeviceTrigger(
+      binding.id,
+      value,
+    );
+    if (result.status === "ok") {
+      await refreshSettings();
+    } else {
+      toast.error(result.error);
+    }
+  };
+
+  const handleCapture = async () => {
+    setIsCapturing(true);
+    try {
+      const result = await commands.captureDeviceTrigger();
+      if (result.status === "ok") {
+        await updateTrigger(result.data);
+      } else {
+        toast.error(result.error);
+      }
+    } catch (error) {
+      toast.error(String(error));
+    } finally {
+      setIsCapturing(false);
+    }
+  };
+
+  const buttonClass =
+    "p-1 text-muted/60 hover:text-text transition-colors rounded hover:bg-muted/20 disabled:opacity-50";
+
+  return (
+    <div className="flex items-center gap-0.5">
+      <SimpleTooltip
+        content={
+          trigger
+            ? `${trigger.device}: ${trigger.label}`
+            : t("settings.general.shortcuts.deviceTrigger.add")
+        }
+      >
+        <button
+          onClick={handleCapture}
+          disabled={isCapturing}
+          className={`${buttonClass} flex items-center gap-1 text-xs`}
+        >
+          <Mouse size={14} />
+          {isCapturing
+            ? t("settings.general.shortcuts.deviceTrigger.capturing")
+            : trigger?.label}
+        </button>
+      </SimpleTooltip>
+      {trigger && !isCapturing && (
+        <>
+          <SimpleTooltip
+            content={t("settings.general.shortcuts.deviceTrigger.grab")}
+          >
+            <button
+              onClick={() => updateTrigger({ ...trigger, grab: !trigger.grab })}
+              className={buttonClass}
+            >
+              {trigger.grab ? <Lock size={14} /> : <LockOpen size={14} />}
+            </button>
+          </SimpleTooltip>
+          <SimpleTooltip
+            content={t("settings.general.shortcuts.deviceTrigger.remove")}
+          >
+            <button onClick={() => updateTrigger(null)} className={buttonClass}>
+              <X size={14} />
+            </button>
+          </SimpleTooltip>
+        </>
+      )}
+    </div>
+  );
+};
@@ -10,7 +10,9 @@ import { VolumeSlider } from "../VolumeSlider";
 import { OutputDeviceSelector } from "../OutputDeviceSelector";
 import { MuteWhileRecording } from "../MuteWhileRecording";
 import { ShowOverlay } from "../ShowOverlay";
+import { ErrorNotifications } from "../ErrorNotifications";
 import { ModelUnloadTimeoutSetting } from "../ModelUnloadTimeout";
+import { ModelPoolBudget } from "../ModelPoolBudget";
 import { StartHidden } from "../StartHidden";
 import { AutostartToggle } from "../AutostartToggle";
 import { ShowTrayIcon } from "../ShowTrayIcon";
@@ -18,6 +20,7 @@ import { ThemeSelector } from "../ThemeSelector";
 import { PasteMethodSetting } from "../PasteMethod";
 import { TypingToolSetting } from "../TypingTool";
 import { ClipboardHandlingSetting } from "../ClipboardHandling";
+import { ClipboardRestoreDelay } from "../ClipboardRestoreDelay";
 import { AutoSubmit } from "../AutoSubmit";
 import { KeyboardImplementationSelector } from "../debug/KeyboardImplementationSelector";
 import { ExportImportSettings } from "../advanced/ExportImportSettings";
@@ -49,7 +52,9 @@ export const GeneralSettings: React.FC = () => {
         <AutostartToggle descriptionMode="tooltip" grouped={true} />
         <ShowTrayIcon descriptionMode="tooltip" grouped={true} />
         <ShowOverlay descriptionMode="tooltip" grouped={true} />
+        <ErrorNotifications descriptionMode="tooltip" grouped={true} />
         <ModelUnloadTimeoutSetting descriptionMode="tooltip" grouped={true} />
+        <ModelPoolBudget descriptionMode="tooltip" grouped={true} />
         <KeyboardImplementationSelector
           descriptionMode="tooltip"
           grouped={true}
@@ -60,6 +65,7 @@ export const GeneralSettings: React.FC = () => {
         <PasteMethodSetting descriptionMode="tooltip" grouped={true} />
         <TypingToolSetting descriptionMode="tooltip" grouped={true} />
         <ClipboardHandlingSetting descriptionMode="tooltip" grouped={true} />
+        <ClipboardRestoreDelay descriptionMode="tooltip" grouped={true} />
         <AutoSubmit descriptionMode="tooltip" grouped={true} />
         <AppendTrailingSpace descriptionMode="tooltip" grouped={true} />
       </SettingsGroup>
@@ -0,0 +1,147 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Plus, X } from "@phosphor-icons/react";
+import { useSettings } from "../../../hooks/useSettings";
+import { commands } from "@/bindings";
+import type { OutputSink, ShortcutBinding } from "@/bindings";
+import { toast } from "sonner";
+import { Input } from "../../ui/Input";
+import { Checkbox } from "../../ui/Checkbox";
+import { SimpleTooltip } from "../../ui/Tooltip";
+
+const NEW_SINKS: Record<OutputSink["type"], OutputSink> = {
+  file: {
+    type: "file",
+    path: "~/Notes/Daily/{date}.md",
+    template: "- {time} {text}",
+  },
+  webhook: { type: "webhook", url: "http://localhost:8080/transcription" },
+  command: { type: "command", command: "" },
+};
+
+interface OutputSinksEditorProps {
+  binding: ShortcutBinding;
+}
+
+/** Edits where a binding's transcriptions are sent besides being pasted */
+export const OutputSinksEditor: React.FC<OutputSinksEditorProps> = ({
+  binding,
+}) => {
+  const { t } = useTranslation();
+  const { refreshSettings } = useSettings();
+  const [sinks, setSinks] = useState<OutputSink[]>(binding.output_sinks ?? []);
+  const skipPaste = binding.skip_paste ?? false;
+
+  useEffect(() => {
+    setSinks(binding.output_sinks ?? []);
+  }, [binding.output_sinks]);
+
+  const save = async (value: OutputSink[], skip: boolean) => {
+    const result = await commands.updateBindingOutputSinks(
+      binding.id,
+      value,
+      skip,
+    );
+    if (result.status === "ok") {
+      await refreshSettings();
+    } else {
+      toast.error(result.error);
+    }
+  };
+
+  // Edits stay local until the field loses focus
+  const editSink = (index: number, sink: OutputSink) =>
+    setSinks(sinks.map((s, i) => (i === index ? sink : s)));
+
+  const renderFields = (sink: OutputSink, index: number) => {
+    const field = (
+      key: string,
+      value: string,
+      onChange: (value: string) => void,
+    ) => (
+      <Input
+        type="text"
+        variant="compact"
+        className="text-xs"
+        value={value}
+        placeholder={t(`settings.general.shortcuts.outputSinks.${key}`)}
+        onChange={(e) => onChange(e.target.value)}
+        onBlur={() => save(sinks, skipPaste)}
+        autoComplete="off"
+      />
+    );
+
+    switch (sink.type) {
+      case "file":
+        return (
+          <>
+            {field("path", sink.path, (path) =>
+              editSink(index, { ...sink, path }),
+            )}
+            {field("template", sink.template, (template) =>
+              editSink(index, { ...sink, template }),
+            )}
+          </>
+        );
+      case "webhook":
+        return field("url", sink.url, (url) =>
+          editSink(index, { ...sink, url }),
+        );
+      case "command":
+        return field("command", sink.command, (command) =>
+          editSink(index, { ...sink, command }),
+        );
+    }
+  };
+
+  return (
+    <div className="px-3 pb-2 space-y-1.5">
+      {sinks.map((sink, index) => (
+        <div key={index} className="flex items-center gap-2">
+          <span className="w-16 shrink-0 text-xs text-text/60">
+            {t(`settings.general.shortcuts.outputSinks.types.${sink.type}`)}
+          </span>
+          {renderFields(sink, index)}
+          <SimpleTooltip
+            content={t("settings.general.shortcuts.outputSinks.remove")}
+          >
+            <button
+              onClick={() =>
+                save(
+                  sinks.filter((_, i) => i !== index),
+                  skipPaste,
+                )
+              }
+              className="p-1 text-muted/40 hover:text-red-400 transition-colors rounded hover:bg-red-400/10"
+            >
+              <X size={14} />
+            </button>
+          </SimpleTooltip>
+        </div>
+      ))}
+      <div className="flex items-center gap-3">
+        {(Object.keys(NEW_SINKS) as OutputSink["type"][]).map((type) => (
+          <button
+            key={type}
+            onClick={() => save([...sinks, NEW_SINKS[type]], skipPaste)}
+            className="flex items-center gap-1 text-xs text-muted hover:text-text transition-colors cursor-pointer"
+          >
+            <Plus size={12} />
+            {t(`settings.general.shortcuts.outputSinks.types.${type}`)}
+          </button>
+        ))}
+        <label className="ml-auto flex items-center gap-1.5 text-xs text-text/60">
+          <Checkbox
+            checked={skipPaste}
+            disabled={sinks.length === 0}
+            onChange={(checked) => save(sinks, checked)}
+          />
+          {t("settings.general.shortcuts.outputSinks.skipPaste")}
+        </label>
+      </div>
+      <p className="text-xs text-text/60">
+        {t("settings.general.shortcuts.outputSinks.placeholders")}
+      </p>
+    </div>
+  );
+};
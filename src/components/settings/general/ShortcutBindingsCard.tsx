@@ -1,14 +1,17 @@
 import React, { useState } from "react";
 import { useTranslation } from "react-i18next";
-import { Plus, X } from "@phosphor-icons/react";
+import { Export, Plus, X } from "@phosphor-icons/react";
 import { SettingsGroup } from "../../ui/SettingsGroup";
 import { Dropdown } from "../../ui/Dropdown";
 import { ShortcutInput } from "../ShortcutInput";
 import { useSettings } from "../../../hooks/useSettings";
 import { commands } from "@/bindings";
-import type { ShortcutBinding } from "@/bindings";
+import type { ActivationMode, ShortcutBinding } from "@/bindings";
 import { toast } from "sonner";
 import { SimpleTooltip } from "../../ui/Tooltip";
+import { useOsType } from "../../../hooks/useOsType";
+import { DeviceTriggerButton } from "./DeviceTriggerButton";
+import { OutputSinksEditor } from "./OutputSinksEditor";
 
 const NONE_VALUE = "__none__";
 
@@ -28,6 +31,8 @@ export const ShortcutBindingsCard: React.FC = () => {
   const { settings, getSetting, refreshSettings } = useSettings();
   const [isAdding, setIsAdding] = useState(false);
   const [recordingNewId, setRecordingNewId] = useState<string | null>(null);
+  const [outputsOpenId, setOutputsOpenId] = useState<string | null>(null);
+  const osType = useOsType();
 
   const bindings = getSetting("bindings") || {};
   const prompts = getSetting("post_process_prompts") || [];
@@ -52,6 +57,28 @@ export const ShortcutBindingsCard: React.FC = () => {
     ...prompts.map((p) => ({ value: p.id, label: p.name })),
   ];
 
+  // Activation mode dropdown options; NONE_VALUE follows the global setting
+  const modeOptions = [
+    { value: NONE_VALUE, label: t("settings.general.shortcuts.modeDefault") },
+    {
+      value: "toggle",
+      label: t("settings.general.activationMode.options.toggle"),
+    },
+    { value: "hold", label: t("settings.general.activationMode.options.hold") },
+    {
+      value: "hold_or_toggle",
+      label: t("settings.general.activationMode.options.holdOrToggle"),
+    },
+  ];
+
+  const handleModeChange = async (bindingId: string, value: string) => {
+    const mode = value === NONE_VALUE ? null : (value as ActivationMode);
+    const result = await commands.updateBindingActivationMode(bindingId, mode);
+    if (result.status === "ok") {
+      await refreshSettings();
+    }
+  };
+
   const handleStrategyChange = async (bindingId: string, value: string) => {
     const promptId = value === NONE_VALUE ? null : value;
     const result = await commands.updateBindingPrompt(bindingId, promptId);
@@ -103,12 +130,19 @@ export const ShortcutBindingsCard: React.FC = () => {
   const canDelete = (id: string) => id !== "transcribe" && id !== "cancel";
 
   const showStrategyColumn = prompts.length > 0;
+  // Device triggers read /dev/input, so they're Linux only
+  const showTriggerColumn = osType === "linux";
+
+  // Full class names so Tailwind can find them
+  const gridColumns = [
+    "grid-cols-[auto_1fr_auto]",
+    "grid-cols-[auto_1fr_auto_auto]",
+    "grid-cols-[auto_1fr_auto_auto_auto]",
+  ][Number(showStrategyColumn) + Number(showTriggerColumn)];
 
   return (
     <SettingsGroup title={t("settings.general.shortcuts.title")}>
-      <div
-        className={`grid ${showStrategyColumn ? "grid-cols-[auto_1fr_auto]" : "grid-cols-[auto_auto]"} gap-x-2 items-center`}
-      >
+      <div className={`grid ${gridColumns} gap-x-2 items-center`}>
         {transcribeBindings.map((binding) => (
           <React.Fragment key={binding.id}>
             <div className="px-3 py-1.5">
@@ -144,7 +178,38 @@ export const ShortcutBindingsCard: React.FC = () => {
                 </div>
               </SimpleTooltip>
             )}
-            <div className="px-3 py-1.5 flex justify-center">
+            <div className="py-1.5">
+              <Dropdown
+                options={modeOptions}
+                selectedValue={binding.activation_mode || NONE_VALUE}
+                onSelect={(value) => handleModeChange(binding.id, value)}
+                className="w-full"
+              />
+            </div>
+            {showTriggerColumn && (
+              <div className="py-1.5">
+                <DeviceTriggerButton binding={binding} />
+              </div>
+            )}
+            <div className="px-3 py-1.5 flex justify-center gap-0.5">
+              <SimpleTooltip
+                content={t("settings.general.shortcuts.outputSinks.title")}
+              >
+                <button
+                  onClick={() =>
+                    setOutputsOpenId(
+                      outputsOpenId === binding.id ? null : binding.id,
+                    )
+                  }
+                  className={`p-1 transition-colors rounded hover:bg-muted/20 ${
+                    binding.output_sinks?.length
+                      ? "text-text"
+                      : "text-muted/40 hover:text-text"
+                  }`}
+                >
+                  <Export size={14} />
+                </button>
+              </SimpleTooltip>
               {canDelete(binding.id) ? (
                 <SimpleTooltip content={t("settings.general.shortcuts.remove")}>
                   <button
@@ -158,6 +223,11 @@ export const ShortcutBindingsCard: React.FC = () => {
                 <div className="w-6" />
               )}
             </div>
+            {outputsOpenId === binding.id && (
+              <div className="col-span-full">
+                <OutputSinksEditor binding={binding} />
+              </div>
+            )}
           </React.Fragment>
         ))}
       </div>
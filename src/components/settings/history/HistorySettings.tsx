@@ -20,6 +20,7 @@ import {
   FolderOpen,
   Microphone,
   Sparkle,
+  ArrowCounterClockwise,
 } from "@phosphor-icons/react";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
@@ -98,20 +99,42 @@ export const HistorySettings: React.FC = () => {
     loadPage(null, true).finally(() => setLoading(false));
   }, [loadPage]);
 
-  // Listen for new transcriptions from the backend
+  // Listen for new transcriptions from the backend. Entries are added as
+  // "pending" when recording stops and updated once transcription finishes.
   useEffect(() => {
-    const setupListener = async () => {
-      const unlisten = await listen<HistoryEntry>(
+    const setupListeners = async () => {
+      const unlistenAdded = await listen<HistoryEntry>(
         "history-entry-added",
         (event) => {
           setHistoryEntries((prev) => [event.payload, ...prev]);
           setTotalCount((prev) => prev + 1);
         },
       );
-      return unlisten;
+      const unlistenUpdated = await listen<HistoryEntry>(
+        "history-entry-updated",
+        (event) => {
+          setHistoryEntries((prev) =>
+            prev.map((e) => (e.id === event.payload.id ? event.payload : e)),
+          );
+        },
+      );
+      const unlistenRemoved = await listen<number>(
+        "history-entry-removed",
+        (event) => {
+          setHistoryEntries((prev) =>
+            prev.filter((e) => e.id !== event.payload),
+          );
+          setTotalCount((prev) => prev - 1);
+        },
+      );
+      return () => {
+        unlistenAdded();
+        unlistenUpdated();
+        unlistenRemoved();
+      };
     };
 
-    const unlistenPromise = setupListener();
+    const unlistenPromise = setupListeners();
 
     return () => {
       unlistenPromise.then((unlisten) => {
@@ -427,10 +450,26 @@ const HistoryEntryComponent: React.FC<HistoryEntryProps> = memo(
           </div>
         </div>
 
-        {/* Text content */}
-        <p className="text-[13px] leading-snug text-text/90 select-text cursor-text pr-28">
-          {displayText}
-        </p>
+        {/* Text content, split by speaker once diarized */}
+        {entry.segments?.length && !hasPostProcessed ? (
+          <div className="flex flex-col gap-1 pr-28">
+            {entry.segments.map((segment, index) => (
+              <p
+                key={index}
+                className="text-[13px] leading-snug text-text/90 select-text cursor-text"
+              >
+                <span className="text-xs font-medium text-muted mr-1.5">
+                  {segment.speaker}
+                </span>
+                {segment.text}
+              </p>
+            ))}
+          </div>
+        ) : (
+          <p className="text-[13px] leading-snug text-text/90 select-text cursor-text pr-28">
+            {displayText}
+          </p>
+        )}
         {hasPostProcessed && (
           <button
             onClick={() => setExpanded(!expanded)}
@@ -458,6 +497,12 @@ const HistoryEntryComponent: React.FC<HistoryEntryProps> = memo(
         {/* Audio player + timestamp */}
         <div className="flex items-center gap-2">
           <AudioPlayer onLoadRequest={handleLoadAudio} className="flex-1" />
+          {entry.retracted && (
+            <span className="flex items-center gap-1 text-xs text-muted/60 shrink-0">
+              <ArrowCounterClockwise size={10} />
+              {t("settings.history.retracted")}
+            </span>
+          )}
           <span className="text-xs text-muted/80 whitespace-nowrap shrink-0 tabular-nums">
             {formattedTime}
           </span>
@@ -22,6 +22,7 @@ export { PostProcessingSettingsApi } from "./PostProcessingSettingsApi";
 export { PostProcessingSettingsPrompts } from "./PostProcessingSettingsPrompts";
 export { AppDataDirectory } from "./AppDataDirectory";
 export { ModelUnloadTimeoutSetting } from "./ModelUnloadTimeout";
+export { ModelPoolBudget } from "./ModelPoolBudget";
 export { StartHidden } from "./StartHidden";
 
 export { RecordingRetentionPeriodSelector } from "./RecordingRetentionPeriod";
@@ -2,8 +2,20 @@ import React from "react";
 import { useTranslation } from "react-i18next";
 import { SettingsGroup } from "../../ui/SettingsGroup";
 import { ActivationModeSelector } from "../ActivationModeSelector";
+import { ShortcutInput } from "../ShortcutInput";
 import { ShortcutBindingsCard } from "../general/ShortcutBindingsCard";
 
+const ACTION_SHORTCUTS = [
+  "repaste_last",
+  "copy_last_transcript",
+  "undo_last_insertion",
+  "rerun_post_process",
+  "cycle_prompt",
+  "cycle_microphone",
+  "switch_model",
+  "toggle_always_on",
+];
+
 export const ShortcutsSettings: React.FC = () => {
   const { t } = useTranslation();
   return (
@@ -12,6 +24,26 @@ export const ShortcutsSettings: React.FC = () => {
       <ShortcutBindingsCard />
       <SettingsGroup title={t("settings.general.title")}>
         <ActivationModeSelector descriptionMode="tooltip" grouped={true} />
+        <ShortcutInput
+          shortcutId="toggle_hands_free"
+          descriptionMode="tooltip"
+          grouped={true}
+        />
+        <ShortcutInput
+          shortcutId="meeting"
+          descriptionMode="tooltip"
+          grouped={true}
+        />
+      </SettingsGroup>
+      <SettingsGroup title={t("settings.general.shortcuts.actions")}>
+        {ACTION_SHORTCUTS.map((id) => (
+          <ShortcutInput
+            key={id}
+            shortcutId={id}
+            descriptionMode="tooltip"
+            grouped={true}
+          />
+        ))}
       </SettingsGroup>
     </div>
   );
@@ -214,16 +214,32 @@ const formatKeyPart = (part: string, osType: OSType): string => {
   return capitalizeKey(trimmed);
 };
 
+/** Separates the steps of a multi-step shortcut, e.g. "ctrl+space, p" */
+export const SEQUENCE_SEPARATOR = ", ";
+
+/** Longest sequence the backend accepts */
+export const MAX_SEQUENCE_STEPS = 3;
+
+/** How long recording waits after a release for the next step of a sequence */
+export const SEQUENCE_STEP_TIMEOUT_MS = 800;
+
 /**
  * Get display-friendly key combination string for the current OS
  * Formats raw hotkey strings like "option_left+shift+space" into
  * human-readable form. On macOS, uses symbols: "⌥⇧Space"
+ * Sequences keep their steps apart: "⌃Space, P"
  */
 export const formatKeyCombination = (
   combination: string,
   osType: OSType,
 ): string => {
   if (!combination) return "";
+  if (combination.includes(",")) {
+    return combination
+      .split(",")
+      .map((step) => formatKeyCombination(step.trim(), osType))
+      .join(SEQUENCE_SEPARATOR);
+  }
   const parts = combination
     .split("+")
     .map((part) => formatKeyPart(part, osType));
@@ -11,15 +11,16 @@ import "./RecordingOverlay.css";
 import i18n, { syncLanguageFromSettings } from "@/i18n";
 import { getLanguageDirection } from "@/lib/utils/rtl";
 import { commands, type ActivationMode } from "@/bindings";
-import { X, Check } from "@phosphor-icons/react";
+import { X, Check, Warning } from "@phosphor-icons/react";
 
-type OverlayState = "recording" | "transcribing" | "processing";
+type OverlayState = "recording" | "transcribing" | "processing" | "error";
 
 const CHANNEL_COUNT =11;
 const THINKING_DOT_COUNT = 6;
 const ATTACK_SPEED = 0.22;
 const DECAY_SPEED = 0.07;
 const STREAMING_WIDTH = 300;
+const ERROR_WIDTH = 190;
 const STREAMING_LINE_HEIGHT = 18;
 const MAX_LINES = 5;
 const OVERLAY_PADDING = 12;
@@ -44,6 +45,7 @@ const RecordingOverlay: React.FC = () => {
   const [activationMode, setActivationMode] =
     useState<ActivationMode>("hold_or_toggle");
   const [streamingText, setStreamingText] = useState("");
+  const [errorReason, setErrorReason] = useState<string | null>(null);
   const [overlayPosition, setOverlayPosition] = useState<"top" | "bottom">(
     "top",
   );
@@ -95,6 +97,7 @@ const RecordingOverlay: React.FC = () => {
   const overlayWidth = (() => {
     if (!isVisible) return 33;
     if (hasStreamingText) return STREAMING_WIDTH + buttonsExtra;
+    if (state === "error") return ERROR_WIDTH;
     return 70 + buttonsExtra;
   })();
 
@@ -315,11 +318,13 @@ const RecordingOverlay: React.FC = () => {
           state: OverlayState;
           position: "top" | "bottom";
           activation_mode: ActivationMode;
+          reason?: string;
         }>("show-overlay", async (event) => {
           const {
             state: overlayState,
             position,
             activation_mode,
+            reason,
           } = event.payload;
           // Reset all state BEFORE any async work so the overlay never
           // renders stale content from the previous session.
@@ -327,6 +332,7 @@ const RecordingOverlay: React.FC = () => {
           setOverlayPosition(position);
           setActivationMode(activation_mode);
           setStreamingText("");
+          setErrorReason(reason ?? null);
           setProgress(0);
           // Cancel any pending hide from a previous session
           if (hideTimeoutRef.current) {
@@ -439,6 +445,15 @@ const RecordingOverlay: React.FC = () => {
           </>
         )}
 
+        {state === "error" && (
+          <div className="error-message">
+            <Warning size={14} weight="bold" />
+            <span>
+              {i18n.t(`overlay.error.${errorReason ?? "transcription"}`)}
+            </span>
+          </div>
+        )}
+
         {isPostProcessing && (
           <>
             <div
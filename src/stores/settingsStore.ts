@@ -150,6 +150,8 @@ const settingUpdaters: {
     commands.changeSelectedLanguageSetting(value as string),
   overlay_position: (value) =>
     commands.changeOverlayPositionSetting(value as string),
+  error_notifications_enabled: (value) =>
+    commands.changeErrorNotificationsSetting(value as boolean),
   debug_mode: (value) => commands.changeDebugModeSetting(value as boolean),
   custom_words: (value) => commands.updateCustomWords(value as string[]),
   word_correction_threshold: (value) =>
@@ -160,6 +162,10 @@ const settingUpdaters: {
     commands.changeExternalScriptPathSetting(value as string | null),
   clipboard_handling: (value) =>
     commands.changeClipboardHandlingSetting(value as string),
+  clipboard_restore_delay_ms: (value) =>
+    commands.changeClipboardRestoreDelaySetting(value as number),
+  model_pool_budget_mb: (value) =>
+    commands.setModelPoolBudget(value as number),
   auto_submit: (value) => commands.changeAutoSubmitSetting(value as boolean),
   auto_submit_key: (value) =>
     commands.changeAutoSubmitKeySetting(value as string),